@@ -1,4 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use itertools::Itertools;
 use rand::rngs::SmallRng;
 use rand::{Rng, SeedableRng};
 use stwo_prover::core::backend::cpu::CpuBackend;
@@ -8,6 +9,8 @@ use stwo_prover::core::fields::m31::BaseField;
 use stwo_prover::core::poly::circle::{CirclePoly, PolyOps};
 
 const LOG_SIZE: u32 = 20;
+const N_POLYS: usize = 16;
+const N_POINTS: usize = 4;
 
 fn bench_eval_at_secure_point<B: PolyOps>(c: &mut Criterion, id: &str) {
     let poly = CirclePoly::new((0..1 << LOG_SIZE).map(BaseField::from).collect());
@@ -23,9 +26,43 @@ fn bench_eval_at_secure_point<B: PolyOps>(c: &mut Criterion, id: &str) {
     );
 }
 
+fn bench_eval_at_secure_points<B: PolyOps>(c: &mut Criterion, id: &str) {
+    let polys = (0..N_POLYS)
+        .map(|i| CirclePoly::<B>::new((0..1 << LOG_SIZE).map(|j| BaseField::from(i + j)).collect()))
+        .collect_vec();
+    let poly_refs = polys.iter().collect_vec();
+    let mut rng = SmallRng::seed_from_u64(0);
+    let points = (0..N_POINTS)
+        .map(|_| CirclePoint {
+            x: rng.gen(),
+            y: rng.gen(),
+        })
+        .collect_vec();
+    c.bench_function(
+        &format!("{id} eval_at_secure_field_points {N_POLYS}x2^{LOG_SIZE} at {N_POINTS} points"),
+        |b| {
+            b.iter(|| B::eval_at_points(black_box(&poly_refs), black_box(&points)));
+        },
+    );
+    c.bench_function(
+        &format!("{id} eval_at_secure_field_point {N_POLYS}x2^{LOG_SIZE} at {N_POINTS} points"),
+        |b| {
+            b.iter(|| {
+                for poly in &polys {
+                    for &point in &points {
+                        black_box(B::eval_at_point(black_box(poly), black_box(point)));
+                    }
+                }
+            });
+        },
+    );
+}
+
 fn eval_at_secure_point_benches(c: &mut Criterion) {
     bench_eval_at_secure_point::<SimdBackend>(c, "simd");
     bench_eval_at_secure_point::<CpuBackend>(c, "cpu");
+    bench_eval_at_secure_points::<SimdBackend>(c, "simd");
+    bench_eval_at_secure_points::<CpuBackend>(c, "cpu");
 }
 
 criterion_group!(
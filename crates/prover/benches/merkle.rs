@@ -7,32 +7,62 @@ use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::backend::{Col, CpuBackend};
 use stwo_prover::core::fields::m31::{BaseField, N_BYTES_FELT};
 use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
-use stwo_prover::core::vcs::ops::MerkleOps;
+use stwo_prover::core::vcs::ops::{MerkleHasher, MerkleOps};
+use stwo_prover::core::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
 
 const LOG_N_ROWS: u32 = 16;
 
 const LOG_N_COLS: u32 = 8;
 
-fn bench_blake2s_merkle<B: MerkleOps<Blake2sMerkleHasher>>(c: &mut Criterion, id: &str) {
-    let col: Col<B, BaseField> = (0..1 << LOG_N_ROWS).map(|_| BaseField::zero()).collect();
+// Poseidon252 is much slower than Blake2s, so it is benched on smaller layers.
+const POSEIDON252_LOG_N_ROWS: u32 = 12;
+
+/// The number of columns added at once to a streamed layer, which doesn't fill whole Blake2s
+/// blocks.
+const STREAMED_BATCH_N_COLS: usize = 10;
+
+fn bench_merkle<H: MerkleHasher, B: MerkleOps<H>>(c: &mut Criterion, id: &str, log_n_rows: u32) {
+    let col: Col<B, BaseField> = (0..1 << log_n_rows).map(|_| BaseField::zero()).collect();
     let cols = (0..1 << LOG_N_COLS).map(|_| col.clone()).collect_vec();
     let col_refs = cols.iter().collect_vec();
     let mut group = c.benchmark_group("merkle throughput");
-    let n_elements = 1 << (LOG_N_COLS + LOG_N_ROWS);
+    let n_elements = 1 << (LOG_N_COLS + log_n_rows);
     group.throughput(Throughput::Elements(n_elements));
     group.throughput(Throughput::Bytes(N_BYTES_FELT as u64 * n_elements));
     group.bench_function(&format!("{id} merkle"), |b| {
-        b.iter_with_large_drop(|| B::commit_on_layer(LOG_N_ROWS, None, &col_refs))
+        b.iter_with_large_drop(|| B::commit_on_layer(log_n_rows, None, &col_refs))
+    });
+    group.bench_function(&format!("{id} streamed merkle"), |b| {
+        b.iter_with_large_drop(|| {
+            let mut state = B::start_layer(log_n_rows, None);
+            for batch in col_refs.chunks(STREAMED_BATCH_N_COLS) {
+                B::update_layer(&mut state, batch);
+            }
+            B::finish_layer(state)
+        })
     });
 }
 
 fn blake2s_merkle_benches(c: &mut Criterion) {
-    bench_blake2s_merkle::<SimdBackend>(c, "simd");
-    bench_blake2s_merkle::<CpuBackend>(c, "cpu");
+    bench_merkle::<Blake2sMerkleHasher, SimdBackend>(c, "simd blake2s", LOG_N_ROWS);
+    bench_merkle::<Blake2sMerkleHasher, CpuBackend>(c, "cpu blake2s", LOG_N_ROWS);
+}
+
+fn poseidon252_merkle_benches(c: &mut Criterion) {
+    bench_merkle::<Poseidon252MerkleHasher, SimdBackend>(
+        c,
+        "simd poseidon252",
+        POSEIDON252_LOG_N_ROWS,
+    );
+    bench_merkle::<Poseidon252MerkleHasher, CpuBackend>(
+        c,
+        "cpu poseidon252",
+        POSEIDON252_LOG_N_ROWS,
+    );
 }
 
 criterion_group!(
     name = benches;
     config = Criterion::default().sample_size(10);
-    targets = blake2s_merkle_benches);
+    targets = blake2s_merkle_benches, poseidon252_merkle_benches);
 criterion_main!(benches);
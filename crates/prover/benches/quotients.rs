@@ -23,10 +23,10 @@ fn bench_quotients<B: QuotientOps, const LOG_N_ROWS: u32, const LOG_N_COLS: u32>
     let col_refs = cols.iter().collect_vec();
     let random_coeff = SecureField::from_u32_unchecked(0, 1, 2, 3);
     let a = SecureField::from_u32_unchecked(5, 6, 7, 8);
-    let samples = vec![ColumnSampleBatch {
-        point: SECURE_FIELD_CIRCLE_GEN,
-        columns_and_values: (0..1 << LOG_N_COLS).map(|i| (i, a)).collect(),
-    }];
+    let samples = vec![ColumnSampleBatch::new(
+        SECURE_FIELD_CIRCLE_GEN,
+        (0..1 << LOG_N_COLS).map(|i| (i, a)).collect(),
+    )];
     c.bench_function(
         &format!("{id} quotients 2^{LOG_N_COLS} x 2^{LOG_N_ROWS}"),
         |b| {
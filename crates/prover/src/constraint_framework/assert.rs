@@ -1,26 +1,39 @@
 use num_traits::{One, Zero};
 
-use super::EvalAtRow;
-use crate::core::backend::{Backend, Column};
+use super::{EvalAtRow, PeriodicColumn, Selector};
+use crate::core::backend::Backend;
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
 use crate::core::pcs::TreeVec;
 use crate::core::poly::circle::{CanonicCoset, CirclePoly};
-use crate::core::utils::circle_domain_order_to_coset_order;
 
 /// Evaluates expressions at a trace domain row, and asserts constraints. Mainly used for testing.
 pub struct AssertEvaluator<'a> {
     pub trace: &'a TreeVec<Vec<Vec<BaseField>>>,
     pub col_index: TreeVec<usize>,
+    pub log_size: u32,
     pub row: usize,
+    pub periodic_columns: &'a [PeriodicColumn],
+    pub periodic_index: usize,
 }
 impl<'a> AssertEvaluator<'a> {
-    pub fn new(trace: &'a TreeVec<Vec<Vec<BaseField>>>, row: usize) -> Self {
+    pub fn new(trace: &'a TreeVec<Vec<Vec<BaseField>>>, log_size: u32, row: usize) -> Self {
         Self {
             trace,
             col_index: TreeVec::new(vec![0; trace.len()]),
+            log_size,
             row,
+            periodic_columns: &[],
+            periodic_index: 0,
+        }
+    }
+
+    /// Sets the periodic columns of the component.
+    pub fn with_periodic_columns(self, periodic_columns: &'a [PeriodicColumn]) -> Self {
+        Self {
+            periodic_columns,
+            ..self
         }
     }
 }
@@ -43,6 +56,16 @@ impl<'a> EvalAtRow for AssertEvaluator<'a> {
         })
     }
 
+    fn next_periodic_column(&mut self) -> Self::F {
+        let value = self.periodic_columns[self.periodic_index].at(self.row);
+        self.periodic_index += 1;
+        value
+    }
+
+    fn selector(&mut self, selector: Selector) -> Self::F {
+        selector.at(self.log_size, self.row)
+    }
+
     fn add_constraint<G>(&mut self, constraint: G)
     where
         Self::EF: std::ops::Mul<G, Output = Self::EF>,
@@ -63,22 +86,29 @@ pub fn assert_constraints<B: Backend>(
     trace_polys: &TreeVec<Vec<CirclePoly<B>>>,
     trace_domain: CanonicCoset,
     assert_func: impl Fn(AssertEvaluator<'_>),
+) {
+    assert_constraints_with_periodic_columns(trace_polys, trace_domain, &[], assert_func);
+}
+
+/// Like [assert_constraints], for components with periodic columns.
+pub fn assert_constraints_with_periodic_columns<B: Backend>(
+    trace_polys: &TreeVec<Vec<CirclePoly<B>>>,
+    trace_domain: CanonicCoset,
+    periodic_columns: &[PeriodicColumn],
+    assert_func: impl Fn(AssertEvaluator<'_>),
 ) {
     let traces = trace_polys.as_ref().map(|tree| {
         tree.iter()
             .map(|poly| {
-                circle_domain_order_to_coset_order(
-                    &poly
-                        .evaluate(trace_domain.circle_domain())
-                        .bit_reverse()
-                        .values
-                        .to_cpu(),
-                )
+                poly.evaluate(trace_domain.circle_domain())
+                    .natural_order()
+                    .to_vec()
             })
             .collect()
     });
     for row in 0..trace_domain.size() {
-        let eval = AssertEvaluator::new(&traces, row);
+        let eval = AssertEvaluator::new(&traces, trace_domain.log_size(), row)
+            .with_periodic_columns(periodic_columns);
         assert_func(eval);
     }
 }
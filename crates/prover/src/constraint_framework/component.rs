@@ -8,9 +8,13 @@ use rayon::prelude::*;
 use tracing::{span, Level};
 
 use super::cpu_domain::CpuDomainEvaluator;
-use super::{EvalAtRow, InfoEvaluator, PointEvaluator, SimdDomainEvaluator};
-use crate::core::air::accumulation::{DomainEvaluationAccumulator, PointEvaluationAccumulator};
+use super::{EvalAtRow, InfoEvaluator, PeriodicColumn, PointEvaluator, SimdDomainEvaluator};
+use crate::core::air::accumulation::{
+    ColumnAccumulator, DomainEvaluationAccumulator, PointEvaluationAccumulator,
+};
 use crate::core::air::{Component, ComponentProver, Trace};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::core::backend::disk::{DiskBackend, ScratchDir, Spill};
 use crate::core::backend::simd::column::VeryPackedSecureColumnByCoords;
 use crate::core::backend::simd::m31::LOG_N_LANES;
 use crate::core::backend::simd::very_packed_m31::{VeryPackedBaseField, LOG_N_VERY_PACKED_ELEMS};
@@ -22,7 +26,7 @@ use crate::core::fields::qm31::SecureField;
 use crate::core::fields::secure_column::SecureColumnByCoords;
 use crate::core::fields::FieldExpOps;
 use crate::core::pcs::{TreeSubspan, TreeVec};
-use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, PolyOps};
+use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, CirclePoly, PolyOps};
 use crate::core::poly::BitReversedOrder;
 use crate::core::{utils, ColumnVec};
 
@@ -72,6 +76,12 @@ pub trait FrameworkEval {
 
     fn max_constraint_log_degree_bound(&self) -> u32;
 
+    /// The periodic columns of the component, in the order they are requested by
+    /// [EvalAtRow::next_periodic_column].
+    fn periodic_columns(&self) -> &[PeriodicColumn] {
+        &[]
+    }
+
     fn evaluate<E: EvalAtRow>(&self, eval: E) -> E;
 }
 
@@ -82,8 +92,13 @@ pub struct FrameworkComponent<C: FrameworkEval> {
 
 impl<E: FrameworkEval> FrameworkComponent<E> {
     pub fn new(location_allocator: &mut TraceLocationAllocator, eval: E) -> Self {
-        let eval_tree_structure = eval.evaluate(InfoEvaluator::default()).mask_offsets;
-        let trace_locations = location_allocator.next_for_structure(&eval_tree_structure);
+        let info = eval.evaluate(InfoEvaluator::default());
+        assert_eq!(
+            info.n_periodic_columns,
+            eval.periodic_columns().len(),
+            "number of periodic columns used doesn't match the declared periodic columns"
+        );
+        let trace_locations = location_allocator.next_for_structure(&info.mask_offsets);
         Self {
             eval,
             trace_locations,
@@ -129,11 +144,27 @@ impl<E: FrameworkEval> Component for FrameworkComponent<E> {
         mask: &TreeVec<ColumnVec<Vec<SecureField>>>,
         evaluation_accumulator: &mut PointEvaluationAccumulator,
     ) {
-        self.eval.evaluate(PointEvaluator::new(
-            mask.sub_tree(&self.trace_locations),
-            evaluation_accumulator,
-            coset_vanishing(CanonicCoset::new(self.eval.log_size()).coset, point).inverse(),
-        ));
+        let log_size = self.eval.log_size();
+        let periodic_values = self
+            .eval
+            .periodic_columns()
+            .iter()
+            .map(|column| column.eval_at_point(log_size, point))
+            .collect();
+        let InfoEvaluator { selectors, .. } = self.eval.evaluate(InfoEvaluator::default());
+        let selector_values = selectors
+            .iter()
+            .map(|&selector| (selector, selector.eval_at_point(log_size, point)))
+            .collect();
+        self.eval.evaluate(
+            PointEvaluator::new(
+                mask.sub_tree(&self.trace_locations),
+                evaluation_accumulator,
+                coset_vanishing(CanonicCoset::new(log_size).coset, point).inverse(),
+            )
+            .with_periodic_values(periodic_values)
+            .with_selector_values(selector_values),
+        );
     }
 }
 
@@ -147,19 +178,88 @@ impl<E: FrameworkEval + Sync> ComponentProver<SimdBackend> for FrameworkComponen
             return;
         }
 
+        let [accum] = evaluation_accumulator
+            .columns([(self.max_constraint_log_degree_bound(), self.n_constraints())]);
+        self.evaluate_constraint_quotients_on_simd_domain(
+            trace
+                .polys
+                .sub_tree(&self.trace_locations)
+                .map_cols(|&poly| poly),
+            trace
+                .evals
+                .sub_tree(&self.trace_locations)
+                .map_cols(|&eval| eval),
+            accum,
+        );
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<E: FrameworkEval + Sync, D: ScratchDir> ComponentProver<DiskBackend<D>>
+    for FrameworkComponent<E>
+{
+    /// Loads the columns of the component and its accumulation column into memory, and evaluates
+    /// the constraints there as [SimdBackend] does.
+    fn evaluate_constraint_quotients_on_domain(
+        &self,
+        trace: &Trace<'_, DiskBackend<D>>,
+        evaluation_accumulator: &mut DomainEvaluationAccumulator<DiskBackend<D>>,
+    ) {
+        if self.n_constraints() == 0 {
+            return;
+        }
+
         let eval_domain = CanonicCoset::new(self.max_constraint_log_degree_bound()).circle_domain();
-        let trace_domain = CanonicCoset::new(self.eval.log_size());
+        let component_polys = trace
+            .polys
+            .sub_tree(&self.trace_locations)
+            .map_cols(|poly| poly.load());
+        // Evaluations on other domains are extended from the polynomials anyway.
+        let component_evals = trace
+            .evals
+            .sub_tree(&self.trace_locations)
+            .map_cols(|eval| {
+                eval.filter(|eval| eval.domain == eval_domain)
+                    .map(Spill::load)
+            });
+
+        let [accum] =
+            evaluation_accumulator.columns([(eval_domain.log_size(), self.n_constraints())]);
+        let mut col = accum.col.load();
+        self.evaluate_constraint_quotients_on_simd_domain(
+            component_polys.as_cols_ref(),
+            component_evals.as_cols_ref().map_cols(Option::as_ref),
+            ColumnAccumulator {
+                random_coeff_powers: accum.random_coeff_powers,
+                col: &mut col,
+            },
+        );
+        *accum.col = Spill::spill(&col);
+    }
+}
 
-        let component_polys = trace.polys.sub_tree(&self.trace_locations);
-        let component_evals = trace.evals.sub_tree(&self.trace_locations);
+impl<E: FrameworkEval + Sync> FrameworkComponent<E> {
+    /// Accumulates the constraint quotients of the component into `accum`, given its columns in
+    /// [SimdBackend].
+    #[allow(clippy::type_complexity)]
+    fn evaluate_constraint_quotients_on_simd_domain(
+        &self,
+        component_polys: TreeVec<ColumnVec<&CirclePoly<SimdBackend>>>,
+        component_evals: TreeVec<
+            ColumnVec<Option<&CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>>,
+        >,
+        mut accum: ColumnAccumulator<'_, SimdBackend>,
+    ) {
+        let eval_domain = CanonicCoset::new(self.max_constraint_log_degree_bound()).circle_domain();
+        let trace_domain = CanonicCoset::new(self.eval.log_size());
 
-        // Extend trace if necessary.
+        // Extend trace if necessary, or if the evaluations were dropped after commitment.
         // TODO: Don't extend when eval_size < committed_size. Instead, pick a good
         // subdomain. (For larger blowup factors).
         let need_to_extend = component_evals
             .iter()
             .flatten()
-            .any(|c| c.domain != eval_domain);
+            .any(|c| c.map_or(true, |c| c.domain != eval_domain));
         let trace: TreeVec<
             Vec<Cow<'_, CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>>,
         > = if need_to_extend {
@@ -169,7 +269,7 @@ impl<E: FrameworkEval + Sync> ComponentProver<SimdBackend> for FrameworkComponen
                 .as_cols_ref()
                 .map_cols(|col| Cow::Owned(col.evaluate_with_twiddles(eval_domain, &twiddles)))
         } else {
-            component_evals.clone().map_cols(|c| Cow::Borrowed(*c))
+            component_evals.map_cols(|c| Cow::Borrowed(c.unwrap()))
         };
 
         // Denom inverses.
@@ -179,9 +279,19 @@ impl<E: FrameworkEval + Sync> ComponentProver<SimdBackend> for FrameworkComponen
             .collect_vec();
         utils::bit_reverse(&mut denom_inv);
 
+        let periodic_evals = self
+            .eval
+            .periodic_columns()
+            .iter()
+            .map(|column| column.evaluate(trace_domain.log_size(), eval_domain.log_size()))
+            .collect_vec();
+        let InfoEvaluator { selectors, .. } = self.eval.evaluate(InfoEvaluator::default());
+        let selector_evals = selectors
+            .iter()
+            .map(|selector| selector.evaluate(trace_domain.log_size(), eval_domain.log_size()))
+            .collect_vec();
+
         // Accumulator.
-        let [mut accum] =
-            evaluation_accumulator.columns([(eval_domain.log_size(), self.n_constraints())]);
         accum.random_coeff_powers.reverse();
 
         let _span = span!(Level::INFO, "Constraint point-wise eval").entered();
@@ -201,7 +311,9 @@ impl<E: FrameworkEval + Sync> ComponentProver<SimdBackend> for FrameworkComponen
                     &accum.random_coeff_powers,
                     trace_domain.log_size(),
                     eval_domain.log_size(),
-                );
+                )
+                .with_periodic_evals(&periodic_evals)
+                .with_selector_evals(&selector_evals);
                 let row_res = self.eval.evaluate(eval).row_res;
 
                 // Finalize row.
@@ -238,7 +350,9 @@ impl<E: FrameworkEval + Sync> ComponentProver<SimdBackend> for FrameworkComponen
                     &accum.random_coeff_powers,
                     trace_domain.log_size(),
                     eval_domain.log_size(),
-                );
+                )
+                .with_periodic_evals(&periodic_evals)
+                .with_selector_evals(&selector_evals);
                 let row_res = self.eval.evaluate(eval).row_res;
 
                 // Finalize row.
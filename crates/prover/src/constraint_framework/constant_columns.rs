@@ -4,9 +4,12 @@ use crate::core::backend::{Backend, Col, Column};
 use crate::core::fields::m31::BaseField;
 use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
 use crate::core::poly::BitReversedOrder;
-use crate::core::utils::{bit_reverse_index, coset_index_to_circle_domain_index};
+use crate::core::utils::coset_index_to_bit_reversed_index;
 
 /// Generates a column with a single one at the first position, and zeros elsewhere.
+///
+/// Components of the constraint framework should use [`super::Selector::IsFirst`] instead, which
+/// isn't committed.
 pub fn gen_is_first<B: Backend>(log_size: u32) -> CircleEvaluation<B, BaseField, BitReversedOrder> {
     let mut col = Col::<B, BaseField>::zeros(1 << log_size);
     col.set(0, BaseField::one());
@@ -14,8 +17,8 @@ pub fn gen_is_first<B: Backend>(log_size: u32) -> CircleEvaluation<B, BaseField,
 }
 
 /// Generates a column with `1` at every `2^log_step` positions, `0` elsewhere, shifted by offset.
-// TODO(andrew): Consider optimizing. Is a quotients of two coset_vanishing (use succinct rep for
-// verifier).
+///
+/// See [`super::Selector::IsStepWithOffset`] for the succinct version.
 pub fn gen_is_step_with_offset<B: Backend>(
     log_size: u32,
     log_step: u32,
@@ -28,9 +31,10 @@ pub fn gen_is_step_with_offset<B: Backend>(
     let step_offset = offset % step;
 
     for i in (step_offset..size).step_by(step) {
-        let circle_domain_index = coset_index_to_circle_domain_index(i, log_size);
-        let circle_domain_index_bit_rev = bit_reverse_index(circle_domain_index, log_size);
-        col.set(circle_domain_index_bit_rev, BaseField::one());
+        col.set(
+            coset_index_to_bit_reversed_index(i, log_size),
+            BaseField::one(),
+        );
     }
 
     CircleEvaluation::new(CanonicCoset::new(log_size).circle_domain(), col)
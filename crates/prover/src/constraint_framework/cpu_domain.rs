@@ -2,7 +2,7 @@ use std::ops::Mul;
 
 use num_traits::Zero;
 
-use super::EvalAtRow;
+use super::{EvalAtRow, Selector, SelectorEvaluation};
 use crate::core::backend::CpuBackend;
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
@@ -22,6 +22,12 @@ pub struct CpuDomainEvaluator<'a> {
     pub constraint_index: usize,
     pub domain_log_size: u32,
     pub eval_domain_log_size: u32,
+    /// Evaluations of the periodic columns on the evaluation domain, in bit-reversed order.
+    pub periodic_evals: &'a [Vec<BaseField>],
+    pub periodic_index: usize,
+    /// Evaluations of the selectors on the evaluation domain, in the order they are used.
+    pub selector_evals: &'a [SelectorEvaluation],
+    pub selector_index: usize,
 }
 
 impl<'a> CpuDomainEvaluator<'a> {
@@ -42,6 +48,26 @@ impl<'a> CpuDomainEvaluator<'a> {
             constraint_index: 0,
             domain_log_size,
             eval_domain_log_size: eval_log_size,
+            periodic_evals: &[],
+            periodic_index: 0,
+            selector_evals: &[],
+            selector_index: 0,
+        }
+    }
+
+    /// Sets the evaluations of the component's periodic columns on the evaluation domain.
+    pub fn with_periodic_evals(self, periodic_evals: &'a [Vec<BaseField>]) -> Self {
+        Self {
+            periodic_evals,
+            ..self
+        }
+    }
+
+    /// Sets the evaluations of the component's selectors on the evaluation domain.
+    pub fn with_selector_evals(self, selector_evals: &'a [SelectorEvaluation]) -> Self {
+        Self {
+            selector_evals,
+            ..self
         }
     }
 }
@@ -77,6 +103,19 @@ impl<'a> EvalAtRow for CpuDomainEvaluator<'a> {
         })
     }
 
+    fn next_periodic_column(&mut self) -> Self::F {
+        let value = self.periodic_evals[self.periodic_index][self.row];
+        self.periodic_index += 1;
+        value
+    }
+
+    fn selector(&mut self, selector: Selector) -> Self::F {
+        let evaluation = &self.selector_evals[self.selector_index];
+        debug_assert_eq!(selector, evaluation.selector);
+        self.selector_index += 1;
+        evaluation.at(self.row)
+    }
+
     fn add_constraint<G>(&mut self, constraint: G)
     where
         Self::EF: Mul<G, Output = Self::EF>,
@@ -2,7 +2,7 @@ use std::ops::Mul;
 
 use num_traits::One;
 
-use super::EvalAtRow;
+use super::{EvalAtRow, Selector};
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::pcs::TreeVec;
@@ -13,6 +13,8 @@ use crate::core::pcs::TreeVec;
 pub struct InfoEvaluator {
     pub mask_offsets: TreeVec<Vec<Vec<isize>>>,
     pub n_constraints: usize,
+    pub n_periodic_columns: usize,
+    pub selectors: Vec<Selector>,
 }
 impl InfoEvaluator {
     pub fn new() -> Self {
@@ -35,6 +37,14 @@ impl EvalAtRow for InfoEvaluator {
         self.mask_offsets[interaction].push(offsets.into_iter().collect());
         [BaseField::one(); N]
     }
+    fn next_periodic_column(&mut self) -> Self::F {
+        self.n_periodic_columns += 1;
+        BaseField::one()
+    }
+    fn selector(&mut self, selector: Selector) -> Self::F {
+        self.selectors.push(selector);
+        BaseField::one()
+    }
     fn add_constraint<G>(&mut self, _constraint: G)
     where
         Self::EF: Mul<G, Output = Self::EF>,
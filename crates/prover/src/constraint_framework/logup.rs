@@ -14,11 +14,11 @@ use crate::core::channel::Channel;
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::fields::secure_column::SecureColumnByCoords;
-use crate::core::fields::FieldExpOps;
+use crate::core::fields::InverseOrZero;
 use crate::core::lookups::utils::Fraction;
 use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
 use crate::core::poly::BitReversedOrder;
-use crate::core::utils::{bit_reverse_index, coset_index_to_circle_domain_index};
+use crate::core::utils::coset_index_to_bit_reversed_index;
 use crate::core::ColumnVec;
 
 /// Represents the value of the prefix sum column at some index.
@@ -40,8 +40,8 @@ pub struct LogupAtRow<E: EvalAtRow> {
     pub prev_col_cumsum: E::EF,
     cur_frac: Option<Fraction<E::EF, E::EF>>,
     is_finalized: bool,
-    /// The value of the `is_first` constant column at current row.
-    /// See [`super::constant_columns::gen_is_first()`].
+    /// The value of the `is_first` selector at current row.
+    /// See [`super::Selector::IsFirst`].
     pub is_first: E::F,
 }
 impl<E: EvalAtRow> LogupAtRow<E> {
@@ -225,11 +225,7 @@ impl LogupTraceGenerator {
         };
         let returned_prefix_sums = indices.map(|idx| {
             // Prefix sum column is in bit-reversed circle domain order.
-            let fixed_index = bit_reverse_index(
-                coset_index_to_circle_domain_index(idx, self.log_size),
-                self.log_size,
-            );
-            secure_prefix_sum.at(fixed_index)
+            secure_prefix_sum.at(coset_index_to_bit_reversed_index(idx, self.log_size))
         });
         self.trace.push(secure_prefix_sum);
 
@@ -273,7 +269,7 @@ impl<'a> LogupColGenerator<'a> {
 
     /// Finalizes generating the column.
     pub fn finalize_col(mut self) {
-        FieldExpOps::batch_inverse(&self.gen.denom.data, &mut self.gen.denom_inv.data);
+        InverseOrZero::batch_inverse_or_zero(&self.gen.denom.data, &mut self.gen.denom_inv.data);
 
         for vec_row in 0..(1 << (self.gen.log_size - LOG_N_LANES)) {
             unsafe {
@@ -5,18 +5,22 @@ pub mod constant_columns;
 mod cpu_domain;
 mod info;
 pub mod logup;
+mod periodic;
 mod point;
+mod selector;
 mod simd_domain;
 
 use std::array;
 use std::fmt::Debug;
 use std::ops::{Add, AddAssign, Mul, Neg, Sub};
 
-pub use assert::{assert_constraints, AssertEvaluator};
+pub use assert::{assert_constraints, assert_constraints_with_periodic_columns, AssertEvaluator};
 pub use component::{FrameworkComponent, FrameworkEval, TraceLocationAllocator};
 pub use info::InfoEvaluator;
 use num_traits::{One, Zero};
+pub use periodic::PeriodicColumn;
 pub use point::PointEvaluator;
+pub use selector::{Selector, SelectorEvaluation};
 pub use simd_domain::SimdDomainEvaluator;
 
 use crate::core::fields::m31::BaseField;
@@ -91,6 +95,13 @@ pub trait EvalAtRow {
         })
     }
 
+    /// Returns the value of the next periodic column (see [FrameworkEval::periodic_columns]).
+    fn next_periodic_column(&mut self) -> Self::F;
+
+    /// Returns the value of a selector column, which is evaluated succinctly instead of being
+    /// committed.
+    fn selector(&mut self, selector: Selector) -> Self::F;
+
     /// Adds a constraint to the component.
     fn add_constraint<G>(&mut self, constraint: G)
     where
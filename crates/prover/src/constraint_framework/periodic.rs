@@ -0,0 +1,295 @@
+use itertools::Itertools;
+use num_traits::Zero;
+
+use crate::core::backend::cpu::{CpuCircleEvaluation, CpuCirclePoly};
+use crate::core::circle::CirclePoint;
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::qm31::SecureField;
+use crate::core::poly::circle::CanonicCoset;
+use crate::core::utils::coset_index_to_bit_reversed_index;
+
+/// A column whose values repeat every `2^log_period` rows of the trace.
+///
+/// Periodic columns are not committed. The value at trace row `i` is `values[i % 2^log_period]`,
+/// which is the evaluation of `h(2^m * p)` at the row's point `p`, where `h` is the polynomial of
+/// size `2^log_period` interpolating the values on [CanonicCoset] `log_period` and
+/// `m = trace_log_size - log_period`. Hence the verifier evaluates it succinctly in
+/// `O(2^log_period)`. A period of a single row gives a constant column.
+#[derive(Clone, Debug)]
+pub struct PeriodicColumn {
+    values: Vec<BaseField>,
+    poly: CpuCirclePoly,
+}
+
+impl PeriodicColumn {
+    /// Creates a periodic column from the values of a single period, in natural row order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values is not a power of two.
+    pub fn new(values: Vec<BaseField>) -> Self {
+        assert!(
+            values.len().is_power_of_two(),
+            "period must be a power of two"
+        );
+        let log_period = values.len().ilog2();
+        if log_period == 0 {
+            let poly = CpuCirclePoly::new(values.clone());
+            return Self { values, poly };
+        }
+        let mut bit_reversed_values = vec![BaseField::zero(); values.len()];
+        for (i, &value) in values.iter().enumerate() {
+            bit_reversed_values[coset_index_to_bit_reversed_index(i, log_period)] = value;
+        }
+        let domain = CanonicCoset::new(log_period).circle_domain();
+        let poly = CpuCircleEvaluation::new(domain, bit_reversed_values).interpolate();
+        Self { values, poly }
+    }
+
+    pub fn log_period(&self) -> u32 {
+        self.values.len().ilog2()
+    }
+
+    /// Returns the value at trace row `row`, in natural order.
+    pub fn at(&self, row: usize) -> BaseField {
+        self.values[row % self.values.len()]
+    }
+
+    /// Evaluates the column, as a column of a trace of log size `trace_log_size`, at `point`.
+    pub fn eval_at_point(
+        &self,
+        trace_log_size: u32,
+        point: CirclePoint<SecureField>,
+    ) -> SecureField {
+        let n_doublings = self.n_doublings(trace_log_size);
+        self.poly.eval_at_point(point.repeated_double(n_doublings))
+    }
+
+    /// Evaluates the column, as a column of a trace of log size `trace_log_size`, on the canonic
+    /// circle domain of log size `eval_log_size`. The values are in bit-reversed order.
+    pub fn evaluate(&self, trace_log_size: u32, eval_log_size: u32) -> Vec<BaseField> {
+        assert!(eval_log_size >= trace_log_size);
+        let n_doublings = self.n_doublings(trace_log_size);
+        if self.log_period() == 0 {
+            return vec![self.values[0]; 1 << eval_log_size];
+        }
+        let doubled_domain = CanonicCoset::new(eval_log_size - n_doublings).circle_domain();
+        let doubled_values = self.poly.evaluate(doubled_domain).values;
+        lift_doubled_evaluation(&doubled_values, n_doublings, eval_log_size)
+    }
+
+    fn n_doublings(&self, trace_log_size: u32) -> u32 {
+        assert!(
+            trace_log_size >= self.log_period(),
+            "period is larger than the trace"
+        );
+        trace_log_size - self.log_period()
+    }
+}
+
+/// Given the bit-reversed evaluation of `f` on the canonic circle domain of log size
+/// `eval_log_size - n_doublings`, returns the bit-reversed evaluation of `p -> f(2^n_doublings *
+/// p)` on the canonic circle domain of log size `eval_log_size`.
+pub(super) fn lift_doubled_evaluation(
+    doubled_values: &[BaseField],
+    n_doublings: u32,
+    eval_log_size: u32,
+) -> Vec<BaseField> {
+    assert_eq!(doubled_values.len(), 1 << (eval_log_size - n_doublings));
+    (0..1 << eval_log_size)
+        .map(|i| doubled_values[doubled_index(i, n_doublings)])
+        .collect_vec()
+}
+
+/// Returns the bit-reversed index of `2^n_doublings * p` in the doubled canonic circle domain,
+/// where `p` is at bit-reversed index `index` of a canonic circle domain.
+pub(super) fn doubled_index(index: usize, n_doublings: u32) -> usize {
+    // Doubling a point of a canonic circle domain maps bit-reversed index `i` to `i / 4 * 2 +
+    // i % 2`: the lowest bit selects the conjugate half and is kept, the second bit is dropped.
+    ((index >> (n_doublings + 1)) << 1) | (index & 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use num_traits::Zero;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::PeriodicColumn;
+    use crate::constraint_framework::{
+        assert_constraints_with_periodic_columns, EvalAtRow, FrameworkComponent, FrameworkEval,
+        TraceLocationAllocator,
+    };
+    use crate::core::air::Component;
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::CpuBackend;
+    use crate::core::channel::Blake2sChannel;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig, TreeVec};
+    use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
+    use crate::core::poly::twiddles::TwiddleCache;
+    use crate::core::poly::BitReversedOrder;
+    use crate::core::prover::{prove, verify};
+    use crate::core::utils::{bit_reverse, coset_index_to_bit_reversed_index};
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
+
+    const LOG_PERIOD: u32 = 3;
+
+    /// Returns random round constants that sum to zero over a period.
+    fn round_constants() -> PeriodicColumn {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut values = (0..1 << LOG_PERIOD).map(|_| rng.gen()).collect_vec();
+        values[0] = -values[1..].iter().copied().sum::<BaseField>();
+        PeriodicColumn::new(values)
+    }
+
+    /// Enforces that the trace column is the running sum of a periodic column.
+    struct RunningSumEval {
+        log_n_rows: u32,
+        round_constants: [PeriodicColumn; 1],
+    }
+    impl FrameworkEval for RunningSumEval {
+        fn log_size(&self) -> u32 {
+            self.log_n_rows
+        }
+        fn max_constraint_log_degree_bound(&self) -> u32 {
+            self.log_n_rows + 1
+        }
+        fn periodic_columns(&self) -> &[PeriodicColumn] {
+            &self.round_constants
+        }
+        fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+            let [prev, curr] = eval.next_interaction_mask(0, [-1, 0]);
+            let constant = eval.next_periodic_column();
+            eval.add_constraint(curr - prev - constant);
+            eval
+        }
+    }
+
+    /// Generates the running sum of `column`. It wraps around since a period sums to zero.
+    fn gen_trace(
+        log_n_rows: u32,
+        column: &PeriodicColumn,
+    ) -> CircleEvaluation<SimdBackend, BaseField, BitReversedOrder> {
+        let mut values = vec![BaseField::zero(); 1 << log_n_rows];
+        let mut acc = BaseField::zero();
+        for row in 0..1 << log_n_rows {
+            acc += column.at(row);
+            values[coset_index_to_bit_reversed_index(row, log_n_rows)] = acc;
+        }
+        let domain = CanonicCoset::new(log_n_rows).circle_domain();
+        CircleEvaluation::new(domain, values.into_iter().collect())
+    }
+
+    #[test]
+    fn test_periodic_column_at_trace_rows() {
+        const LOG_SIZE: u32 = 6;
+        let column = round_constants();
+        let coset = CanonicCoset::new(LOG_SIZE);
+
+        for row in 0..coset.size() {
+            let point = coset.at(row).into_ef();
+            assert_eq!(column.eval_at_point(LOG_SIZE, point), column.at(row).into());
+        }
+    }
+
+    #[test]
+    fn test_periodic_column_evaluate() {
+        const LOG_SIZE: u32 = 5;
+        const EVAL_LOG_SIZE: u32 = 7;
+        let column = round_constants();
+        let domain = CanonicCoset::new(EVAL_LOG_SIZE).circle_domain();
+
+        let values = column.evaluate(LOG_SIZE, EVAL_LOG_SIZE);
+
+        let mut expected = domain
+            .iter()
+            .map(|point| column.eval_at_point(LOG_SIZE, point.into_ef()))
+            .collect_vec();
+        bit_reverse(&mut expected);
+        assert_eq!(
+            values.into_iter().map(SecureField::from).collect_vec(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_constant_periodic_column() {
+        const LOG_SIZE: u32 = 4;
+        const EVAL_LOG_SIZE: u32 = 6;
+        let value = BaseField::from(7);
+        let column = PeriodicColumn::new(vec![value]);
+        let domain = CanonicCoset::new(EVAL_LOG_SIZE).circle_domain();
+
+        let values = column.evaluate(LOG_SIZE, EVAL_LOG_SIZE);
+
+        assert_eq!(values, vec![value; domain.size()]);
+        for point in domain.iter() {
+            assert_eq!(
+                column.eval_at_point(LOG_SIZE, point.into_ef()),
+                value.into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_periodic_column_constraints() {
+        const LOG_SIZE: u32 = 5;
+        let eval = RunningSumEval {
+            log_n_rows: LOG_SIZE,
+            round_constants: [round_constants()],
+        };
+        let trace = gen_trace(LOG_SIZE, &eval.round_constants[0]);
+        let trace_polys = TreeVec::new(vec![vec![trace.to_cpu().interpolate()]]);
+
+        assert_constraints_with_periodic_columns::<CpuBackend>(
+            &trace_polys,
+            CanonicCoset::new(LOG_SIZE),
+            eval.periodic_columns(),
+            |eval_at_row| {
+                eval.evaluate(eval_at_row);
+            },
+        );
+    }
+
+    #[test]
+    fn test_periodic_column_prove_and_verify() {
+        // The small trace falls back to the CPU domain evaluator.
+        for log_n_rows in [4, 6] {
+            let config = PcsConfig::default();
+            let eval = RunningSumEval {
+                log_n_rows,
+                round_constants: [round_constants()],
+            };
+            let trace = gen_trace(log_n_rows, &eval.round_constants[0]);
+
+            // Prove.
+            let prover_channel = &mut Blake2sChannel::default();
+            let commitment_scheme =
+                &mut CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+                    config,
+                    TwiddleCache::default(),
+                );
+            let mut tree_builder = commitment_scheme.tree_builder();
+            tree_builder.extend_evals([trace]);
+            tree_builder.commit(prover_channel);
+            let component = FrameworkComponent::new(&mut TraceLocationAllocator::default(), eval);
+            let proof = prove::<SimdBackend, Blake2sMerkleChannel>(
+                &[&component],
+                prover_channel,
+                commitment_scheme,
+            )
+            .unwrap();
+
+            // Verify.
+            let verifier_channel = &mut Blake2sChannel::default();
+            let commitment_scheme =
+                &mut CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+            let sizes = component.trace_log_degree_bounds();
+            commitment_scheme.commit(proof.commitments[0].clone(), &sizes[0], verifier_channel);
+            verify(&[&component], verifier_channel, commitment_scheme, proof).unwrap();
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::Mul;
 
-use super::EvalAtRow;
+use super::{EvalAtRow, Selector};
 use crate::core::air::accumulation::PointEvaluationAccumulator;
 use crate::core::fields::qm31::SecureField;
 use crate::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
@@ -13,6 +13,11 @@ pub struct PointEvaluator<'a> {
     pub evaluation_accumulator: &'a mut PointEvaluationAccumulator,
     pub col_index: Vec<usize>,
     pub denom_inverse: SecureField,
+    pub periodic_values: Vec<SecureField>,
+    pub periodic_index: usize,
+    /// The selectors of the component, in the order they are used, and their values at the point.
+    pub selector_values: Vec<(Selector, SecureField)>,
+    pub selector_index: usize,
 }
 impl<'a> PointEvaluator<'a> {
     pub fn new(
@@ -26,6 +31,26 @@ impl<'a> PointEvaluator<'a> {
             evaluation_accumulator,
             col_index,
             denom_inverse,
+            periodic_values: vec![],
+            periodic_index: 0,
+            selector_values: vec![],
+            selector_index: 0,
+        }
+    }
+
+    /// Sets the values of the component's periodic columns at the point.
+    pub fn with_periodic_values(self, periodic_values: Vec<SecureField>) -> Self {
+        Self {
+            periodic_values,
+            ..self
+        }
+    }
+
+    /// Sets the component's selectors and their values at the point, in the order they are used.
+    pub fn with_selector_values(self, selector_values: Vec<(Selector, SecureField)>) -> Self {
+        Self {
+            selector_values,
+            ..self
         }
     }
 }
@@ -44,6 +69,17 @@ impl<'a> EvalAtRow for PointEvaluator<'a> {
         assert_eq!(mask.len(), N);
         mask.try_into().unwrap()
     }
+    fn next_periodic_column(&mut self) -> Self::F {
+        let value = self.periodic_values[self.periodic_index];
+        self.periodic_index += 1;
+        value
+    }
+    fn selector(&mut self, selector: Selector) -> Self::F {
+        let (expected_selector, value) = self.selector_values[self.selector_index];
+        debug_assert_eq!(selector, expected_selector);
+        self.selector_index += 1;
+        value
+    }
     fn add_constraint<G>(&mut self, constraint: G)
     where
         Self::EF: Mul<G, Output = Self::EF>,
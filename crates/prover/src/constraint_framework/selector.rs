@@ -0,0 +1,277 @@
+use itertools::{zip_eq, Itertools};
+use num_traits::{One, Zero};
+
+use super::periodic::doubled_index;
+use crate::core::backend::simd::column::{BaseColumn, VeryPackedBaseColumn};
+use crate::core::backend::simd::domain::CircleDomainBitRevIterator;
+use crate::core::backend::simd::m31::{PackedBaseField, LOG_N_LANES};
+use crate::core::backend::simd::very_packed_m31::{VeryPackedBaseField, LOG_N_VERY_PACKED_ELEMS};
+use crate::core::circle::{CirclePoint, Coset};
+use crate::core::constraints::{coset_vanishing, point_vanishing};
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::{ExtensionOf, FieldExpOps};
+use crate::core::poly::circle::{CanonicCoset, CircleDomain};
+use crate::core::utils::bit_reverse;
+
+/// A selector column, which is one on some rows of the trace and zero on the others.
+///
+/// Selectors are not committed. They are evaluated in closed form, as a quotient of the vanishing
+/// polynomial of a coset of the trace by the vanishing polynomial of a point (see
+/// [Selector::eval_at_point]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Selector {
+    /// One on the first row.
+    IsFirst,
+    /// One on the last row.
+    IsLast,
+    /// One on every `2^log_step` rows, starting at row `offset`.
+    IsStepWithOffset { log_step: u32, offset: usize },
+}
+
+impl Selector {
+    /// Returns the value at trace row `row`, in natural order.
+    pub fn at(&self, trace_log_size: u32, row: usize) -> BaseField {
+        let (log_step, offset) = self.step_and_offset(trace_log_size);
+        if row % (1 << log_step) == offset {
+            BaseField::one()
+        } else {
+            BaseField::zero()
+        }
+    }
+
+    /// Evaluates the selector, as a column of a trace of log size `trace_log_size`, at `point`.
+    ///
+    /// The selector is the indicator of the trace rows `offset + k * 2^log_step`. Doubling the
+    /// trace `trace_log_size - log_step` times maps these rows to the single point `q` of
+    /// [CanonicCoset] `log_step` at `offset`, so the selector is `s(2^(trace_log_size - log_step)
+    /// * p)`, where `s` is the indicator of `q` in that coset:
+    ///
+    /// `s(p) = coset_vanishing(q + <step>, p) / (2^log_step * point_vanishing(q, p))`.
+    ///
+    /// The point must not be in the trace domain, where both vanishing polynomials are zero.
+    pub fn eval_at_point<F: ExtensionOf<BaseField>>(
+        &self,
+        trace_log_size: u32,
+        point: CirclePoint<F>,
+    ) -> F {
+        let (log_step, offset) = self.step_and_offset(trace_log_size);
+        point_indicator(
+            log_step,
+            offset,
+            point.repeated_double(trace_log_size - log_step),
+        )
+    }
+
+    /// Evaluates the selector, as a column of a trace of log size `trace_log_size`, on the canonic
+    /// circle domain of log size `eval_log_size`.
+    pub fn evaluate(&self, trace_log_size: u32, eval_log_size: u32) -> SelectorEvaluation {
+        assert!(eval_log_size >= trace_log_size);
+        let (log_step, offset) = self.step_and_offset(trace_log_size);
+        let n_doublings = trace_log_size - log_step;
+        let doubled_domain = CanonicCoset::new(eval_log_size - n_doublings).circle_domain();
+        let doubled_values = if log_step == 0 || doubled_domain.log_size() < LOG_N_LANES {
+            let mut values = doubled_domain
+                .iter()
+                .map(|point| point_indicator(log_step, offset, point))
+                .collect_vec();
+            bit_reverse(&mut values);
+            BaseColumn::from_cpu(values)
+        } else {
+            packed_point_indicator(log_step, offset, doubled_domain)
+        };
+        SelectorEvaluation {
+            selector: *self,
+            n_doublings,
+            doubled_values,
+        }
+    }
+
+    /// Returns `(log_step, offset)` such that the selector is one on the trace rows `offset + k *
+    /// 2^log_step`, with `offset < 2^log_step`.
+    fn step_and_offset(&self, trace_log_size: u32) -> (u32, usize) {
+        match *self {
+            Self::IsFirst => (trace_log_size, 0),
+            Self::IsLast => (trace_log_size, (1 << trace_log_size) - 1),
+            Self::IsStepWithOffset { log_step, offset } => {
+                assert!(log_step <= trace_log_size, "step is larger than the trace");
+                (log_step, offset % (1 << log_step))
+            }
+        }
+    }
+}
+
+/// Evaluates the indicator of the point at `offset` in [CanonicCoset] `log_size` at `point`.
+fn point_indicator<F: ExtensionOf<BaseField>>(
+    log_size: u32,
+    offset: usize,
+    point: CirclePoint<F>,
+) -> F {
+    if log_size == 0 {
+        return F::one();
+    }
+    let coset = Coset::new(CanonicCoset::new(log_size).index_at(offset), log_size);
+    let denom = point_vanishing(coset.initial, point) * BaseField::from(1 << log_size);
+    coset_vanishing(coset, point) * denom.inverse()
+}
+
+/// Evaluates the indicator of the point at `offset` in [CanonicCoset] `log_size` on `domain`, in
+/// bit-reversed order, a packed row at a time and with a single batch inversion.
+fn packed_point_indicator(log_size: u32, offset: usize, domain: CircleDomain) -> BaseColumn {
+    let coset = Coset::new(CanonicCoset::new(log_size).index_at(offset), log_size);
+    let broadcast = |p: CirclePoint<BaseField>| CirclePoint {
+        x: PackedBaseField::broadcast(p.x),
+        y: PackedBaseField::broadcast(p.y),
+    };
+    let initial = broadcast(coset.initial);
+    let shift = broadcast(coset.step_size.half().to_point()) - initial;
+    let scale = PackedBaseField::broadcast(BaseField::from(1 << log_size));
+
+    // As in [point_indicator], the numerator is `coset_vanishing(coset, p) * (1 + h.x)` and the
+    // denominator is `2^log_size * h.y`, where `h = p - coset.initial`.
+    let (numerators, denominators): (Vec<_>, Vec<_>) = CircleDomainBitRevIterator::new(domain)
+        .map(|point| {
+            let mut vanishing = (point + shift).x;
+            for _ in 1..log_size {
+                vanishing = CirclePoint::double_x(vanishing);
+            }
+            let h = point - initial;
+            (vanishing * (PackedBaseField::one() + h.x), scale * h.y)
+        })
+        .unzip();
+    let mut denominator_inverses = vec![PackedBaseField::zero(); denominators.len()];
+    PackedBaseField::batch_inverse(&denominators, &mut denominator_inverses);
+    BaseColumn {
+        data: zip_eq(numerators, denominator_inverses)
+            .map(|(numerator, denominator_inverse)| numerator * denominator_inverse)
+            .collect(),
+        length: domain.size(),
+    }
+}
+
+/// The evaluation of a [Selector] on a canonic circle domain, in bit-reversed order.
+///
+/// The selector at a point `p` is `s(2^n_doublings * p)`, so only the evaluation of `s` on the
+/// doubled domain is stored, which is `2^n_doublings` times smaller than the evaluation domain, and
+/// is read through the doubling map. Only the selectors of a single row of the trace, such as
+/// [Selector::IsFirst], store a value per point of the evaluation domain.
+#[derive(Clone, Debug)]
+pub struct SelectorEvaluation {
+    pub selector: Selector,
+    n_doublings: u32,
+    doubled_values: BaseColumn,
+}
+
+impl SelectorEvaluation {
+    /// Returns the value at bit-reversed index `index` of the evaluation domain.
+    pub fn at(&self, index: usize) -> BaseField {
+        self.doubled_values.as_slice()[doubled_index(index, self.n_doublings)]
+    }
+
+    /// Returns the values at the simd-vector row `vec_row` of the evaluation domain.
+    pub fn very_packed_at(&self, vec_row: usize) -> VeryPackedBaseField {
+        if self.n_doublings == 0 {
+            return unsafe { VeryPackedBaseColumn::transform_under_ref(&self.doubled_values) }.data
+                [vec_row];
+        }
+        let row_offset = vec_row << (LOG_N_LANES + LOG_N_VERY_PACKED_ELEMS);
+        let values = self.doubled_values.as_slice();
+        VeryPackedBaseField::from_array(std::array::from_fn(|i| {
+            values[doubled_index(row_offset + i, self.n_doublings)]
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Selector;
+    use crate::core::backend::cpu::CpuCircleEvaluation;
+    use crate::core::backend::simd::m31::LOG_N_LANES;
+    use crate::core::backend::simd::very_packed_m31::LOG_N_VERY_PACKED_ELEMS;
+    use crate::core::circle::CirclePoint;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::poly::circle::CanonicCoset;
+    use crate::core::poly::BitReversedOrder;
+
+    const SELECTORS: [Selector; 5] = [
+        Selector::IsFirst,
+        Selector::IsLast,
+        Selector::IsStepWithOffset {
+            log_step: 2,
+            offset: 3,
+        },
+        Selector::IsStepWithOffset {
+            log_step: 1,
+            offset: 0,
+        },
+        Selector::IsStepWithOffset {
+            log_step: 0,
+            offset: 0,
+        },
+    ];
+
+    #[test]
+    fn test_selector_evaluate_on_trace_rows() {
+        const LOG_SIZE: u32 = 5;
+        const EVAL_LOG_SIZE: u32 = 7;
+        let trace_coset = CanonicCoset::new(LOG_SIZE);
+        let eval_domain = CanonicCoset::new(EVAL_LOG_SIZE).circle_domain();
+
+        for selector in SELECTORS {
+            let evaluation = selector.evaluate(LOG_SIZE, EVAL_LOG_SIZE);
+            let values = (0..eval_domain.size()).map(|i| evaluation.at(i)).collect();
+            let poly = CpuCircleEvaluation::<BaseField, BitReversedOrder>::new(eval_domain, values)
+                .interpolate();
+
+            assert!(poly.is_in_fft_space(LOG_SIZE + 1));
+            for row in 0..trace_coset.size() {
+                let point = trace_coset.at(row).into_ef();
+                assert_eq!(
+                    poly.eval_at_point(point),
+                    selector.at(LOG_SIZE, row).into(),
+                    "{selector:?} at row {row}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_selector_eval_at_point_matches_evaluate() {
+        const LOG_SIZE: u32 = 4;
+        const EVAL_LOG_SIZE: u32 = 6;
+        let eval_domain = CanonicCoset::new(EVAL_LOG_SIZE).circle_domain();
+
+        for selector in SELECTORS {
+            let evaluation = selector.evaluate(LOG_SIZE, EVAL_LOG_SIZE);
+            let values = (0..eval_domain.size()).map(|i| evaluation.at(i)).collect();
+            let poly = CpuCircleEvaluation::<BaseField, BitReversedOrder>::new(eval_domain, values)
+                .interpolate();
+            let point = CirclePoint::<SecureField>::get_point(98989);
+
+            assert_eq!(
+                selector.eval_at_point(LOG_SIZE, point),
+                poly.eval_at_point(point),
+                "{selector:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_selector_very_packed_at_matches_at() {
+        const LOG_SIZE: u32 = 6;
+        const EVAL_LOG_SIZE: u32 = 8;
+        const LOG_N_VERY_PACKED_LANES: u32 = LOG_N_LANES + LOG_N_VERY_PACKED_ELEMS;
+
+        for selector in SELECTORS {
+            let evaluation = selector.evaluate(LOG_SIZE, EVAL_LOG_SIZE);
+
+            for vec_row in 0..1 << (EVAL_LOG_SIZE - LOG_N_VERY_PACKED_LANES) {
+                let values = evaluation.very_packed_at(vec_row).to_array();
+                for (i, value) in values.into_iter().enumerate() {
+                    let index = (vec_row << LOG_N_VERY_PACKED_LANES) + i;
+                    assert_eq!(value, evaluation.at(index), "{selector:?} at {index}");
+                }
+            }
+        }
+    }
+}
@@ -2,7 +2,7 @@ use std::ops::Mul;
 
 use num_traits::Zero;
 
-use super::EvalAtRow;
+use super::{EvalAtRow, Selector, SelectorEvaluation};
 use crate::core::backend::simd::column::VeryPackedBaseColumn;
 use crate::core::backend::simd::m31::LOG_N_LANES;
 use crate::core::backend::simd::very_packed_m31::{
@@ -30,6 +30,12 @@ pub struct SimdDomainEvaluator<'a> {
     pub constraint_index: usize,
     pub domain_log_size: u32,
     pub eval_domain_log_size: u32,
+    /// Evaluations of the periodic columns on the evaluation domain, in bit-reversed order.
+    pub periodic_evals: &'a [Vec<BaseField>],
+    pub periodic_index: usize,
+    /// Evaluations of the selectors on the evaluation domain, in the order they are used.
+    pub selector_evals: &'a [SelectorEvaluation],
+    pub selector_index: usize,
 }
 impl<'a> SimdDomainEvaluator<'a> {
     pub fn new(
@@ -48,6 +54,26 @@ impl<'a> SimdDomainEvaluator<'a> {
             constraint_index: 0,
             domain_log_size,
             eval_domain_log_size: eval_log_size,
+            periodic_evals: &[],
+            periodic_index: 0,
+            selector_evals: &[],
+            selector_index: 0,
+        }
+    }
+
+    /// Sets the evaluations of the component's periodic columns on the evaluation domain.
+    pub fn with_periodic_evals(self, periodic_evals: &'a [Vec<BaseField>]) -> Self {
+        Self {
+            periodic_evals,
+            ..self
+        }
+    }
+
+    /// Sets the evaluations of the component's selectors on the evaluation domain.
+    pub fn with_selector_evals(self, selector_evals: &'a [SelectorEvaluation]) -> Self {
+        Self {
+            selector_evals,
+            ..self
         }
     }
 }
@@ -90,6 +116,18 @@ impl<'a> EvalAtRow for SimdDomainEvaluator<'a> {
             }))
         })
     }
+    fn next_periodic_column(&mut self) -> Self::F {
+        let values = &self.periodic_evals[self.periodic_index];
+        self.periodic_index += 1;
+        let row_offset = self.vec_row << (LOG_N_LANES + LOG_N_VERY_PACKED_ELEMS);
+        VeryPackedBaseField::from_array(std::array::from_fn(|i| values[row_offset + i]))
+    }
+    fn selector(&mut self, selector: Selector) -> Self::F {
+        let evaluation = &self.selector_evals[self.selector_index];
+        debug_assert_eq!(selector, evaluation.selector);
+        self.selector_index += 1;
+        evaluation.very_packed_at(self.vec_row)
+    }
     fn add_constraint<G>(&mut self, constraint: G)
     where
         Self::EF: Mul<G, Output = Self::EF>,
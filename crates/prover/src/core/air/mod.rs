@@ -70,6 +70,8 @@ pub trait ComponentProver<B: Backend>: Component {
 pub struct Trace<'a, B: Backend> {
     /// Polynomials for each column.
     pub polys: TreeVec<ColumnVec<&'a CirclePoly<B>>>,
-    /// Evaluations for each column (evaluated on their commitment domains).
-    pub evals: TreeVec<ColumnVec<&'a CircleEvaluation<B, BaseField, BitReversedOrder>>>,
+    /// Evaluations for each column (evaluated on their commitment domains), or `None` if they were
+    /// dropped after commitment, in which case they are recomputed from the polynomials.
+    #[allow(clippy::type_complexity)]
+    pub evals: TreeVec<ColumnVec<Option<&'a CircleEvaluation<B, BaseField, BitReversedOrder>>>>,
 }
@@ -4,9 +4,13 @@ use crate::core::backend::CpuBackend;
 use crate::core::fields::m31::BaseField;
 use crate::core::vcs::blake2_hash::Blake2sHash;
 use crate::core::vcs::blake2_merkle::Blake2sMerkleHasher;
-use crate::core::vcs::ops::{MerkleHasher, MerkleOps};
+#[cfg(feature = "parallel")]
+use crate::core::vcs::ops::par_read_rows;
+use crate::core::vcs::ops::{MerkleHasher, MerkleOps, NodeStates};
 
 impl MerkleOps<Blake2sMerkleHasher> for CpuBackend {
+    type LayerState = NodeStates<Blake2sMerkleHasher>;
+
     fn commit_on_layer(
         log_size: u32,
         prev_layer: Option<&Vec<Blake2sHash>>,
@@ -21,4 +25,21 @@ impl MerkleOps<Blake2sMerkleHasher> for CpuBackend {
             })
             .collect()
     }
+
+    fn start_layer(log_size: u32, prev_layer: Option<&Vec<Blake2sHash>>) -> Self::LayerState {
+        NodeStates::start(log_size, prev_layer)
+    }
+
+    fn update_layer(state: &mut Self::LayerState, columns: &[&Vec<BaseField>]) {
+        state.update(columns);
+    }
+
+    fn finish_layer(state: Self::LayerState) -> Vec<Blake2sHash> {
+        state.finish()
+    }
+
+    #[cfg(feature = "parallel")]
+    fn read_rows(columns: &[&Vec<BaseField>], rows: &[usize]) -> Vec<Vec<BaseField>> {
+        par_read_rows(columns, rows)
+    }
 }
@@ -1,3 +1,6 @@
+use std::iter::zip;
+
+use itertools::Itertools;
 use num_traits::Zero;
 
 use super::CpuBackend;
@@ -6,7 +9,7 @@ use crate::core::circle::{CirclePoint, Coset};
 use crate::core::fft::{butterfly, ibutterfly};
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
-use crate::core::fields::{ExtensionOf, FieldExpOps};
+use crate::core::fields::{ExtensionOf, Field, FieldExpOps};
 use crate::core::poly::circle::{
     CanonicCoset, CircleDomain, CircleEvaluation, CirclePoly, PolyOps,
 };
@@ -100,6 +103,13 @@ impl PolyOps for CpuBackend {
         fold(&poly.coeffs, &mappings)
     }
 
+    fn eval_at_points(
+        polys: &[&CirclePoly<Self>],
+        points: &[CirclePoint<SecureField>],
+    ) -> Vec<Vec<SecureField>> {
+        eval_at_extension_points(polys, points)
+    }
+
     fn extend(poly: &CirclePoly<Self>, log_size: u32) -> CirclePoly<Self> {
         assert!(log_size >= poly.log_size());
         let mut coeffs = Vec::with_capacity(1 << log_size);
@@ -108,6 +118,16 @@ impl PolyOps for CpuBackend {
         CirclePoly::new(coeffs)
     }
 
+    fn truncate(poly: CirclePoly<Self>, log_size: u32) -> Option<CirclePoly<Self>> {
+        assert!(log_size <= poly.log_size());
+        let mut coeffs = poly.coeffs;
+        if !coeffs[1 << log_size..].iter().all(BaseField::is_zero) {
+            return None;
+        }
+        coeffs.truncate(1 << log_size);
+        Some(CirclePoly::new(coeffs))
+    }
+
     fn evaluate(
         poly: &CirclePoly<Self>,
         domain: CircleDomain,
@@ -180,6 +200,105 @@ impl PolyOps for CpuBackend {
             itwiddles,
         }
     }
+
+    fn add_scaled_column(
+        dst: &mut Col<Self, BaseField>,
+        src: &Col<Self, BaseField>,
+        alpha: BaseField,
+    ) {
+        assert_eq!(dst.len(), src.len());
+        zip(dst, src).for_each(|(d, &s)| *d += alpha * s);
+    }
+
+    fn mul_column(dst: &mut Col<Self, BaseField>, src: &Col<Self, BaseField>) {
+        assert_eq!(dst.len(), src.len());
+        zip(dst, src).for_each(|(d, &s)| *d *= s);
+    }
+}
+
+/// Evaluates each polynomial at each of the points, over any extension of [BaseField]. See
+/// [PolyOps::eval_at_points].
+pub fn eval_at_extension_points<E: ExtensionOf<BaseField>>(
+    polys: &[&CirclePoly<CpuBackend>],
+    points: &[CirclePoint<E>],
+) -> Vec<Vec<E>> {
+    let log_size = polys.iter().map(|p| p.log_size()).max().unwrap_or_default();
+    let log_low_size = log_size.div_ceil(2);
+    let tables = points
+        .iter()
+        .map(|&point| PointTwiddles::new(point, log_size, log_low_size))
+        .collect_vec();
+    polys
+        .iter()
+        .map(|poly| {
+            tables
+                .iter()
+                .map(|table| {
+                    zip(poly.coeffs.chunks(table.low.len()), &table.high)
+                        .map(|(chunk, &high)| {
+                            let low_sum: E = zip(chunk, &table.low).map(|(&c, &low)| low * c).sum();
+                            low_sum * high
+                        })
+                        .sum()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The values of the coefficient basis of circle polynomials at a point, split in two tables.
+///
+/// The `i`-th basis element of a polynomial in coefficient form evaluates at `(x, y)` to the
+/// product of `y, x, double(x), double(double(x)), ...` over the set bits of `i` (from the least
+/// significant). Its value is `low[i % low.len()] * high[i / low.len()]`, which keeps the tables
+/// at roughly the square root of the polynomial size.
+pub(crate) struct PointTwiddles<F = SecureField> {
+    pub low: Vec<F>,
+    pub high: Vec<F>,
+}
+
+impl<F: Field> PointTwiddles<F> {
+    /// Computes the tables for polynomials of log size up to `log_size`, with a low table of size
+    /// `2^log_low_size`.
+    pub fn new(point: CirclePoint<F>, log_size: u32, log_low_size: u32) -> Self {
+        let n_mappings = log_size.max(log_low_size) as usize;
+        let mut mappings = Vec::with_capacity(n_mappings);
+        let mut x = point.x;
+        for i in 0..n_mappings {
+            mappings.push(match i {
+                0 => point.y,
+                1 => x,
+                _ => {
+                    x = CirclePoint::double_x(x);
+                    x
+                }
+            });
+        }
+        Self::from_mappings(&mappings, log_low_size)
+    }
+
+    /// Computes the tables from the factors of the basis elements, given from the one of the
+    /// least significant bit.
+    pub fn from_mappings(mappings: &[F], log_low_size: u32) -> Self {
+        let (low_mappings, high_mappings) = mappings.split_at(log_low_size as usize);
+        Self {
+            low: subset_products(low_mappings),
+            high: subset_products(high_mappings),
+        }
+    }
+}
+
+/// Returns the products of all subsets of `factors`, where the subset of the `i`-th product is
+/// given by the set bits of `i`.
+fn subset_products<F: Field>(factors: &[F]) -> Vec<F> {
+    let mut res = Vec::with_capacity(1 << factors.len());
+    res.push(F::one());
+    for &factor in factors {
+        for i in 0..res.len() {
+            res.push(res[i] * factor);
+        }
+    }
+    res
 }
 
 pub fn slow_precompute_twiddles(mut coset: Coset) -> Vec<BaseField> {
@@ -260,13 +379,15 @@ impl<F: ExtensionOf<BaseField>, EvalOrder> IntoIterator
 mod tests {
     use std::iter::zip;
 
+    use itertools::Itertools;
     use num_traits::One;
 
     use crate::core::backend::cpu::CpuCirclePoly;
-    use crate::core::circle::CirclePoint;
+    use crate::core::backend::CpuBackend;
+    use crate::core::circle::{CirclePoint, SECURE_FIELD_CIRCLE_GEN};
     use crate::core::fields::m31::BaseField;
     use crate::core::fields::qm31::SecureField;
-    use crate::core::poly::circle::CanonicCoset;
+    use crate::core::poly::circle::{CanonicCoset, PolyOps};
 
     #[test]
     fn test_eval_at_point_with_4_coeffs() {
@@ -308,6 +429,21 @@ mod tests {
         assert_eq!(eval, SecureField::one());
     }
 
+    #[test]
+    fn test_eval_at_points() {
+        let polys = [0, 1, 3, 6, 7]
+            .map(|log_size| CpuCirclePoly::new((0..1 << log_size).map(BaseField::from).collect()));
+        let poly_refs = polys.iter().collect_vec();
+        let points = [1, 2, 9001].map(|i| SECURE_FIELD_CIRCLE_GEN.mul(i));
+
+        let evals = CpuBackend::eval_at_points(&poly_refs, &points);
+
+        for (poly, poly_evals) in zip(&polys, evals) {
+            let expected = points.map(|point| poly.eval_at_point(point));
+            assert_eq!(poly_evals, expected, "log_size={}", poly.log_size());
+        }
+    }
+
     #[test]
     fn test_evaluate_2_coeffs() {
         let domain = CanonicCoset::new(1).circle_domain();
@@ -1,36 +1,36 @@
 use super::CpuBackend;
 use crate::core::fields::m31::BaseField;
-use crate::core::fields::qm31::SecureField;
-use crate::core::fields::secure_column::SecureColumnByCoords;
+use crate::core::fields::secure_column::ExtensionColumn;
+use crate::core::fields::SecureExtension;
 use crate::core::fri::{fold_circle_into_line, fold_line, FriOps};
 use crate::core::poly::circle::SecureEvaluation;
 use crate::core::poly::line::LineEvaluation;
 use crate::core::poly::twiddles::TwiddleTree;
 use crate::core::poly::BitReversedOrder;
 
-impl FriOps for CpuBackend {
+impl<E: SecureExtension> FriOps<E> for CpuBackend {
     fn fold_line(
-        eval: &LineEvaluation<Self>,
-        alpha: SecureField,
+        eval: &LineEvaluation<Self, E>,
+        alpha: E,
         _twiddles: &TwiddleTree<Self>,
-    ) -> LineEvaluation<Self> {
+    ) -> LineEvaluation<Self, E> {
         fold_line(eval, alpha)
     }
 
     fn fold_circle_into_line(
-        dst: &mut LineEvaluation<Self>,
-        src: &SecureEvaluation<Self, BitReversedOrder>,
-        alpha: SecureField,
+        dst: &mut LineEvaluation<Self, E>,
+        src: &SecureEvaluation<Self, BitReversedOrder, E>,
+        alpha: E,
         _twiddles: &TwiddleTree<Self>,
     ) {
         fold_circle_into_line(dst, src, alpha)
     }
 
     fn decompose(
-        eval: &SecureEvaluation<Self, BitReversedOrder>,
-    ) -> (SecureEvaluation<Self, BitReversedOrder>, SecureField) {
+        eval: &SecureEvaluation<Self, BitReversedOrder, E>,
+    ) -> (SecureEvaluation<Self, BitReversedOrder, E>, E) {
         let lambda = Self::decomposition_coefficient(eval);
-        let mut g_values = unsafe { SecureColumnByCoords::<Self>::uninitialized(eval.len()) };
+        let mut g_values = E::Column::<Self>::zeros(eval.len());
 
         let domain_size = eval.len();
         let half_domain_size = domain_size / 2;
@@ -67,18 +67,18 @@ impl CpuBackend {
     /// This function assumes the blowupfactor is 2
     ///
     /// [`CirclePoly`]: crate::core::poly::circle::CirclePoly
-    fn decomposition_coefficient(eval: &SecureEvaluation<Self, BitReversedOrder>) -> SecureField {
+    fn decomposition_coefficient<E: SecureExtension>(
+        eval: &SecureEvaluation<Self, BitReversedOrder, E>,
+    ) -> E {
         let domain_size = 1 << eval.domain.log_size();
         let half_domain_size = domain_size / 2;
 
         // eval is in bit-reverse, hence all the positive factors are in the first half, opposite to
         // the latter.
-        let a_sum = (0..half_domain_size)
-            .map(|i| eval.values.at(i))
-            .sum::<SecureField>();
+        let a_sum = (0..half_domain_size).map(|i| eval.values.at(i)).sum::<E>();
         let b_sum = (half_domain_size..domain_size)
             .map(|i| eval.values.at(i))
-            .sum::<SecureField>();
+            .sum::<E>();
 
         // lambda = sum(+-f(p)) / 2N.
         (a_sum - b_sum) / BaseField::from_u32_unchecked(domain_size as u32)
@@ -0,0 +1,40 @@
+use super::CpuBackend;
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::secure_column::ExtensionColumn;
+use crate::core::fields::{FieldExpOps, SecureExtension};
+use crate::core::poly::line::{line_fft, line_ifft, LineDomain, LineEvaluation, LineOps};
+use crate::core::poly::twiddles::TwiddleTree;
+use crate::core::poly::utils::domain_line_twiddles_from_tree;
+
+impl<E: SecureExtension> LineOps<E> for CpuBackend {
+    fn interpolate_line(
+        eval: LineEvaluation<Self, E>,
+        twiddles: &TwiddleTree<Self>,
+    ) -> E::Column<Self> {
+        let domain = eval.domain();
+        assert!(twiddles.covers_coset(domain.coset()));
+        let mut values = eval.values.to_vec();
+        line_ifft(
+            &mut values,
+            &domain_line_twiddles_from_tree(domain, &twiddles.itwiddles),
+        );
+        // Normalize the coefficients.
+        let len_inv = BaseField::from(values.len()).inverse();
+        values.iter_mut().for_each(|v| *v *= len_inv);
+        E::Column::<Self>::from_values(&values)
+    }
+
+    fn evaluate_line(
+        coeffs: E::Column<Self>,
+        domain: LineDomain,
+        twiddles: &TwiddleTree<Self>,
+    ) -> LineEvaluation<Self, E> {
+        assert!(twiddles.covers_coset(domain.coset()));
+        let mut values = coeffs.to_vec();
+        line_fft(
+            &mut values,
+            &domain_line_twiddles_from_tree(domain, &twiddles.twiddles),
+        );
+        LineEvaluation::new(domain, E::Column::<Self>::from_values(&values))
+    }
+}
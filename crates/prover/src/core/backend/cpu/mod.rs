@@ -3,6 +3,7 @@ mod blake2s;
 pub mod circle;
 mod fri;
 mod grind;
+mod line;
 pub mod lookups;
 #[cfg(not(target_arch = "wasm32"))]
 mod poseidon252;
@@ -3,10 +3,14 @@ use starknet_ff::FieldElement as FieldElement252;
 
 use super::CpuBackend;
 use crate::core::fields::m31::BaseField;
-use crate::core::vcs::ops::{MerkleHasher, MerkleOps};
+#[cfg(feature = "parallel")]
+use crate::core::vcs::ops::par_read_rows;
+use crate::core::vcs::ops::{MerkleHasher, MerkleOps, NodeStates};
 use crate::core::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
 
 impl MerkleOps<Poseidon252MerkleHasher> for CpuBackend {
+    type LayerState = NodeStates<Poseidon252MerkleHasher>;
+
     fn commit_on_layer(
         log_size: u32,
         prev_layer: Option<&Vec<FieldElement252>>,
@@ -21,4 +25,21 @@ impl MerkleOps<Poseidon252MerkleHasher> for CpuBackend {
             })
             .collect()
     }
+
+    fn start_layer(log_size: u32, prev_layer: Option<&Vec<FieldElement252>>) -> Self::LayerState {
+        NodeStates::start(log_size, prev_layer)
+    }
+
+    fn update_layer(state: &mut Self::LayerState, columns: &[&Vec<BaseField>]) {
+        state.update(columns);
+    }
+
+    fn finish_layer(state: Self::LayerState) -> Vec<FieldElement252> {
+        state.finish()
+    }
+
+    #[cfg(feature = "parallel")]
+    fn read_rows(columns: &[&Vec<BaseField>], rows: &[usize]) -> Vec<Vec<BaseField>> {
+        par_read_rows(columns, rows)
+    }
 }
@@ -1,28 +1,28 @@
 use itertools::{izip, zip_eq};
-use num_traits::{One, Zero};
+use num_traits::Zero;
 
+use super::circle::eval_at_extension_points;
 use super::CpuBackend;
 use crate::core::circle::CirclePoint;
 use crate::core::constraints::complex_conjugate_line_coeffs;
-use crate::core::fields::cm31::CM31;
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
-use crate::core::fields::secure_column::SecureColumnByCoords;
-use crate::core::fields::FieldExpOps;
+use crate::core::fields::secure_column::ExtensionColumn;
+use crate::core::fields::{InverseOrZero, SecureExtension};
 use crate::core::pcs::quotients::{ColumnSampleBatch, PointSample, QuotientOps};
-use crate::core::poly::circle::{CircleDomain, CircleEvaluation, SecureEvaluation};
+use crate::core::poly::circle::{CircleDomain, CircleEvaluation, CirclePoly, SecureEvaluation};
 use crate::core::poly::BitReversedOrder;
 use crate::core::utils::{bit_reverse, bit_reverse_index};
 
-impl QuotientOps for CpuBackend {
+impl<E: SecureExtension> QuotientOps<E> for CpuBackend {
     fn accumulate_quotients(
         domain: CircleDomain,
         columns: &[&CircleEvaluation<Self, BaseField, BitReversedOrder>],
-        random_coeff: SecureField,
-        sample_batches: &[ColumnSampleBatch],
+        random_coeff: E,
+        sample_batches: &[ColumnSampleBatch<E>],
         _log_blowup_factor: u32,
-    ) -> SecureEvaluation<Self, BitReversedOrder> {
-        let mut values = unsafe { SecureColumnByCoords::uninitialized(domain.size()) };
+    ) -> SecureEvaluation<Self, BitReversedOrder, E> {
+        let mut values = E::Column::<Self>::zeros(domain.size());
         let quotient_constants = quotient_constants(sample_batches, random_coeff, domain);
 
         for row in 0..domain.size() {
@@ -38,27 +38,34 @@ impl QuotientOps for CpuBackend {
         }
         SecureEvaluation::new(domain, values)
     }
+
+    fn eval_at_extension_points(
+        polys: &[&CirclePoly<Self>],
+        points: &[CirclePoint<E>],
+    ) -> Vec<Vec<E>> {
+        eval_at_extension_points(polys, points)
+    }
 }
 
-pub fn accumulate_row_quotients(
-    sample_batches: &[ColumnSampleBatch],
+pub fn accumulate_row_quotients<E: SecureExtension>(
+    sample_batches: &[ColumnSampleBatch<E>],
     columns: &[&CircleEvaluation<CpuBackend, BaseField, BitReversedOrder>],
-    quotient_constants: &QuotientConstants,
+    quotient_constants: &QuotientConstants<E>,
     row: usize,
     domain_point: CirclePoint<BaseField>,
-) -> SecureField {
-    let mut row_accumulator = SecureField::zero();
+) -> E {
+    let mut row_accumulator = E::zero();
     for (sample_batch, line_coeffs, batch_coeff, denominator_inverses) in izip!(
         sample_batches,
         &quotient_constants.line_coeffs,
         &quotient_constants.batch_random_coeffs,
         &quotient_constants.denominator_inverses
     ) {
-        let mut numerator = SecureField::zero();
+        let mut numerator = E::zero();
         for ((column_index, _), (a, b, c)) in zip_eq(&sample_batch.columns_and_values, line_coeffs)
         {
             let column = &columns[*column_index];
-            let value = column[row] * *c;
+            let value = *c * column[row];
             // The numerator is a line equation passing through
             //   (sample_point.y, sample_value), (conj(sample_point), conj(sample_value))
             // evaluated at (domain_point.y, value).
@@ -70,23 +77,23 @@ pub fn accumulate_row_quotients(
         }
 
         row_accumulator =
-            row_accumulator * *batch_coeff + numerator.mul_cm31(denominator_inverses[row]);
+            row_accumulator * *batch_coeff + numerator.mul_subfield(denominator_inverses[row]);
     }
     row_accumulator
 }
 
 /// Precompute the complex conjugate line coefficients for each column in each sample batch.
-/// Specifically, for the i-th (in a sample batch) column's numerator term
+/// Specifically, for the i-th (in the whole sample batch) column's numerator term
 /// `alpha^i * (c * F(p) - (a * p.y + b))`, we precompute and return the constants:
 /// (`alpha^i * a`, `alpha^i * b`, `alpha^i * c`).
-pub fn column_line_coeffs(
-    sample_batches: &[ColumnSampleBatch],
-    random_coeff: SecureField,
-) -> Vec<Vec<(SecureField, SecureField, SecureField)>> {
+pub fn column_line_coeffs<E: SecureExtension>(
+    sample_batches: &[ColumnSampleBatch<E>],
+    random_coeff: E,
+) -> Vec<Vec<(E, E, E)>> {
     sample_batches
         .iter()
         .map(|sample_batch| {
-            let mut alpha = SecureField::one();
+            let mut alpha = random_coeff.pow(sample_batch.n_preceding_columns as u128);
             sample_batch
                 .columns_and_values
                 .iter()
@@ -104,39 +111,38 @@ pub fn column_line_coeffs(
 }
 
 /// Precompute the random coefficients used to linearly combine the batched quotients.
-/// Specifically, for each sample batch we compute random_coeff^(number of columns in the batch),
-/// which is used to linearly combine the batch with the next one.
-pub fn batch_random_coeffs(
-    sample_batches: &[ColumnSampleBatch],
-    random_coeff: SecureField,
-) -> Vec<SecureField> {
+/// Specifically, for each sample batch we compute random_coeff^(number of columns in the whole
+/// batch), which is used to linearly combine the batch with the next one.
+pub fn batch_random_coeffs<E: SecureExtension>(
+    sample_batches: &[ColumnSampleBatch<E>],
+    random_coeff: E,
+) -> Vec<E> {
     sample_batches
         .iter()
-        .map(|sb| random_coeff.pow(sb.columns_and_values.len() as u128))
+        .map(|sb| random_coeff.pow(sb.n_batch_columns as u128))
         .collect()
 }
 
-fn denominator_inverses(
-    sample_batches: &[ColumnSampleBatch],
+fn denominator_inverses<E: SecureExtension>(
+    sample_batches: &[ColumnSampleBatch<E>],
     domain: CircleDomain,
-) -> Vec<Vec<CM31>> {
+) -> Vec<Vec<E::Subfield>> {
     let mut flat_denominators = Vec::with_capacity(sample_batches.len() * domain.size());
-    // We want a P to be on a line that passes through a point Pr + uPi in QM31^2, and its conjugate
-    // Pr - uPi. Thus, Pr - P is parallel to Pi. Or, (Pr - P).x * Pi.y - (Pr - P).y * Pi.x = 0.
+    // We want a P to be on a line that passes through a point Pr + uPi in E^2, and its conjugate
+    // Pr - uPi, where Pr and Pi are over the subfield. Thus, Pr - P is parallel to Pi. Or,
+    // (Pr - P).x * Pi.y - (Pr - P).y * Pi.x = 0.
     for sample_batch in sample_batches {
         // Extract Pr, Pi.
-        let prx = sample_batch.point.x.0;
-        let pry = sample_batch.point.y.0;
-        let pix = sample_batch.point.x.1;
-        let piy = sample_batch.point.y.1;
+        let (prx, pix) = sample_batch.point.x.to_subfield_parts();
+        let (pry, piy) = sample_batch.point.y.to_subfield_parts();
         for row in 0..domain.size() {
             let domain_point = domain.at(row);
             flat_denominators.push((prx - domain_point.x) * piy - (pry - domain_point.y) * pix);
         }
     }
 
-    let mut flat_denominator_inverses = vec![CM31::zero(); flat_denominators.len()];
-    CM31::batch_inverse(&flat_denominators, &mut flat_denominator_inverses);
+    let mut flat_denominator_inverses = vec![E::Subfield::zero(); flat_denominators.len()];
+    E::Subfield::batch_inverse_or_zero(&flat_denominators, &mut flat_denominator_inverses);
 
     flat_denominator_inverses
         .chunks_mut(domain.size())
@@ -147,11 +153,11 @@ fn denominator_inverses(
         .collect()
 }
 
-pub fn quotient_constants(
-    sample_batches: &[ColumnSampleBatch],
-    random_coeff: SecureField,
+pub fn quotient_constants<E: SecureExtension>(
+    sample_batches: &[ColumnSampleBatch<E>],
+    random_coeff: E,
     domain: CircleDomain,
-) -> QuotientConstants {
+) -> QuotientConstants<E> {
     let line_coeffs = column_line_coeffs(sample_batches, random_coeff);
     let batch_random_coeffs = batch_random_coeffs(sample_batches, random_coeff);
     let denominator_inverses = denominator_inverses(sample_batches, domain);
@@ -163,15 +169,15 @@ pub fn quotient_constants(
 }
 
 /// Holds the precomputed constant values used in each quotient evaluation.
-pub struct QuotientConstants {
+pub struct QuotientConstants<E: SecureExtension = SecureField> {
     /// The line coefficients for each quotient numerator term. For more details see
     /// [self::column_line_coeffs].
-    pub line_coeffs: Vec<Vec<(SecureField, SecureField, SecureField)>>,
+    pub line_coeffs: Vec<Vec<(E, E, E)>>,
     /// The random coefficients used to linearly combine the batched quotients For more details see
     /// [self::batch_random_coeffs].
-    pub batch_random_coeffs: Vec<SecureField>,
-    /// The inverses of the denominators of the quotients.
-    pub denominator_inverses: Vec<Vec<CM31>>,
+    pub batch_random_coeffs: Vec<E>,
+    /// The inverses of the denominators of the quotients, in [SecureExtension::Subfield].
+    pub denominator_inverses: Vec<Vec<E::Subfield>>,
 }
 
 #[cfg(test)]
@@ -197,10 +203,7 @@ mod tests {
             eval_domain,
             &[&eval],
             coeff,
-            &[ColumnSampleBatch {
-                point,
-                columns_and_values: vec![(0, value)],
-            }],
+            &[ColumnSampleBatch::new(point, vec![(0, value)])],
             LOG_BLOWUP_FACTOR,
         );
         let quot_poly_base_field =
@@ -0,0 +1,13 @@
+use std::iter::zip;
+
+use super::{DiskBackend, ScratchDir};
+use crate::core::air::accumulation::AccumulationOps;
+use crate::core::fields::secure_column::SecureColumnByCoords;
+
+impl<D: ScratchDir> AccumulationOps for DiskBackend<D> {
+    fn accumulate(column: &mut SecureColumnByCoords<Self>, other: &SecureColumnByCoords<Self>) {
+        for (column, other) in zip(&mut column.columns, &other.columns) {
+            zip(column.packed_mut(), other.packed()).for_each(|(c, &o)| *c += o);
+        }
+    }
+}
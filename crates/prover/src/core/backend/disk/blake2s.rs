@@ -0,0 +1,302 @@
+use itertools::Itertools;
+
+use super::{
+    chunk_log_size, commit_on_layer_in_chunks, DiskBackend, DiskColumn, ScratchDir, Spill,
+    N_CHILDREN_WORDS,
+};
+use crate::core::backend::simd::blake2s::{
+    finalize_states, n_columns_to_compress, start_states, states_from_hashes, Blake2sLayerState,
+};
+use crate::core::backend::simd::m31::LOG_N_LANES;
+use crate::core::backend::simd::SimdBackend;
+use crate::core::backend::{Column, ColumnOps};
+use crate::core::fields::m31::BaseField;
+use crate::core::utils::bit_reverse;
+use crate::core::vcs::blake2_hash::Blake2sHash;
+use crate::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+#[cfg(feature = "parallel")]
+use crate::core::vcs::ops::par_read_rows;
+use crate::core::vcs::ops::MerkleOps;
+
+impl<D: ScratchDir> ColumnOps<Blake2sHash> for DiskBackend<D> {
+    type Column = DiskColumn<Blake2sHash, D>;
+
+    fn bit_reverse_column(column: &mut Self::Column) {
+        bit_reverse(column.as_mut_slice());
+    }
+}
+
+impl<D: ScratchDir> MerkleOps<Blake2sMerkleHasher> for DiskBackend<D> {
+    type LayerState = DiskBlake2sLayerState<D>;
+
+    fn commit_on_layer(
+        log_size: u32,
+        prev_layer: Option<&DiskColumn<Blake2sHash, D>>,
+        columns: &[&DiskColumn<BaseField, D>],
+    ) -> DiskColumn<Blake2sHash, D> {
+        let log_chunk_size = chunk_log_size(log_size, columns.len() + N_CHILDREN_WORDS);
+        commit_on_layer_in_chunks::<Blake2sMerkleHasher, D>(
+            log_size,
+            prev_layer,
+            columns,
+            log_chunk_size,
+        )
+    }
+
+    fn start_layer(
+        log_size: u32,
+        prev_layer: Option<&DiskColumn<Blake2sHash, D>>,
+    ) -> DiskBlake2sLayerState<D> {
+        if log_size < LOG_N_LANES {
+            let prev_layer = prev_layer.map(|prev_layer| prev_layer.to_cpu());
+            return DiskBlake2sLayerState::Small(
+                <SimdBackend as MerkleOps<Blake2sMerkleHasher>>::start_layer(
+                    log_size,
+                    prev_layer.as_ref(),
+                ),
+            );
+        }
+
+        // Without children, the compression states start at zero.
+        let mut states = DiskColumn::zeros(1 << log_size);
+        if let Some(prev_layer) = prev_layer {
+            let log_chunk_size = chunk_log_size(log_size, N_CHILDREN_WORDS);
+            let chunk_size = 1 << log_chunk_size;
+            for (chunk_index, chunk) in states.as_mut_slice().chunks_mut(chunk_size).enumerate() {
+                let rows = chunk_index * chunk_size..(chunk_index + 1) * chunk_size;
+                let prev_chunk = &prev_layer.as_slice()[2 * rows.start..2 * rows.end];
+                let chunk_states = start_states(log_chunk_size, Some(prev_chunk));
+                chunk.copy_from_slice(&finalize_states(&chunk_states, &[]));
+            }
+        }
+        DiskBlake2sLayerState::Chunked {
+            states,
+            pending_columns: Vec::new(),
+        }
+    }
+
+    fn update_layer(state: &mut DiskBlake2sLayerState<D>, columns: &[&DiskColumn<BaseField, D>]) {
+        match state {
+            DiskBlake2sLayerState::Small(state) => {
+                let columns = columns.iter().map(|column| column.load()).collect_vec();
+                <SimdBackend as MerkleOps<Blake2sMerkleHasher>>::update_layer(
+                    state,
+                    &columns.iter().collect_vec(),
+                );
+            }
+            DiskBlake2sLayerState::Chunked {
+                states,
+                pending_columns,
+            } => {
+                let n_compressed = n_columns_to_compress(pending_columns.len(), columns.len());
+                let (compressed, pending) = columns.split_at(n_compressed);
+                if !compressed.is_empty() {
+                    let compressed = pending_columns
+                        .iter()
+                        .chain(compressed.iter().copied())
+                        .collect_vec();
+                    let log_chunk_size =
+                        chunk_log_size(states.len().ilog2(), compressed.len() + N_STATE_WORDS);
+                    compress_columns_in_chunks(states, &compressed, log_chunk_size);
+                    pending_columns.clear();
+                }
+                pending_columns.extend(pending.iter().map(|&column| column.clone()));
+            }
+        }
+    }
+
+    fn finish_layer(state: DiskBlake2sLayerState<D>) -> DiskColumn<Blake2sHash, D> {
+        match state {
+            DiskBlake2sLayerState::Small(state) => {
+                <SimdBackend as MerkleOps<Blake2sMerkleHasher>>::finish_layer(state)
+                    .into_iter()
+                    .collect()
+            }
+            DiskBlake2sLayerState::Chunked {
+                mut states,
+                pending_columns,
+            } => {
+                // The hashes of the nodes are their compression states after the last block.
+                let log_chunk_size =
+                    chunk_log_size(states.len().ilog2(), pending_columns.len() + N_STATE_WORDS);
+                compress_columns_in_chunks(
+                    &mut states,
+                    &pending_columns.iter().collect_vec(),
+                    log_chunk_size,
+                );
+                states
+            }
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    fn read_rows(columns: &[&DiskColumn<BaseField, D>], rows: &[usize]) -> Vec<Vec<BaseField>> {
+        par_read_rows(columns, rows)
+    }
+}
+
+/// The partial hashes of a layer of [DiskBackend] nodes. See [MerkleOps::start_layer].
+///
+/// The compression states of the nodes are stored in a [DiskColumn], 32 bytes per node, and
+/// updated by the kernels of [SimdBackend], loading `2^22` values at a time as when a layer is
+/// committed at once. Up to 15 columns that don't fill a block yet are copied to files of their own
+/// until the next block is filled.
+#[derive(Debug)]
+pub enum DiskBlake2sLayerState<D: ScratchDir> {
+    /// A layer smaller than a packed row, which is hashed in memory.
+    Small(Blake2sLayerState),
+    Chunked {
+        /// The compression states of the nodes, in the layout of a [Blake2sHash].
+        states: DiskColumn<Blake2sHash, D>,
+        /// The columns added since the last compression, fewer than a block.
+        pending_columns: Vec<DiskColumn<BaseField, D>>,
+    },
+}
+
+/// The number of words of the compression state of a node.
+const N_STATE_WORDS: usize = 8;
+
+/// Adds `columns` to the compression states of the nodes, in chunks of `2^log_chunk_size`
+/// consecutive nodes, of at least a packed row. The last block is padded with zeros, so `columns`
+/// fill whole blocks unless they are the last columns of the layer.
+fn compress_columns_in_chunks<D: ScratchDir>(
+    states: &mut DiskColumn<Blake2sHash, D>,
+    columns: &[&DiskColumn<BaseField, D>],
+    log_chunk_size: u32,
+) {
+    if columns.is_empty() {
+        return;
+    }
+    let chunk_size = 1 << log_chunk_size;
+    for (chunk_index, chunk) in states.as_mut_slice().chunks_mut(chunk_size).enumerate() {
+        let rows = chunk_index * chunk_size..(chunk_index + 1) * chunk_size;
+        let column_chunks = columns
+            .iter()
+            .map(|column| column.load_rows(rows.clone()))
+            .collect_vec();
+        let hashes = finalize_states(
+            &states_from_hashes(chunk),
+            &column_chunks.iter().collect_vec(),
+        );
+        chunk.copy_from_slice(&hashes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::compress_columns_in_chunks;
+    use crate::core::backend::disk::{commit_on_layer_in_chunks, DiskBackend, DiskColumn, Spill};
+    use crate::core::backend::simd::blake2s::{finalize_states, states_from_hashes};
+    use crate::core::backend::simd::column::BaseColumn;
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::Column;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::vcs::blake2_hash::Blake2sHash;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+    use crate::core::vcs::ops::MerkleOps;
+    use crate::core::vcs::prover::MerkleProver;
+    use crate::core::vcs::test_utils::assert_layer_ops_match_commit_on_layer;
+
+    #[test]
+    fn test_commit_on_layer_in_chunks() {
+        const LOG_SIZE: u32 = 7;
+        let mut rng = SmallRng::seed_from_u64(0);
+        let columns = (0..3)
+            .map(|_| {
+                (0..1 << LOG_SIZE)
+                    .map(|_| rng.gen())
+                    .collect::<BaseColumn>()
+            })
+            .collect_vec();
+        let prev_layer = <SimdBackend as MerkleOps<Blake2sMerkleHasher>>::commit_on_layer(
+            LOG_SIZE + 1,
+            None,
+            &[],
+        );
+        let expected = <SimdBackend as MerkleOps<Blake2sMerkleHasher>>::commit_on_layer(
+            LOG_SIZE,
+            Some(&prev_layer),
+            &columns.iter().collect_vec(),
+        );
+
+        let disk_columns = columns
+            .iter()
+            .map(DiskColumn::<BaseField>::spill)
+            .collect_vec();
+        let disk_prev_layer = prev_layer.iter().copied().collect::<DiskColumn<_>>();
+        for log_chunk_size in [0, 3, LOG_SIZE] {
+            let layer = commit_on_layer_in_chunks::<Blake2sMerkleHasher, _>(
+                LOG_SIZE,
+                Some(&disk_prev_layer),
+                &disk_columns.iter().collect_vec(),
+                log_chunk_size,
+            );
+            assert_eq!(layer.to_cpu(), expected, "log chunk size {log_chunk_size}");
+        }
+    }
+
+    #[test]
+    fn test_disk_merkle_root_matches_simd() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let columns = [8, 8, 5, 3].map(|log_size| {
+            (0..1 << log_size)
+                .map(|_| rng.gen())
+                .collect::<BaseColumn>()
+        });
+
+        let root =
+            MerkleProver::<SimdBackend, Blake2sMerkleHasher>::commit(columns.iter().collect())
+                .root();
+        let disk_columns = columns.each_ref().map(DiskColumn::<BaseField>::spill);
+        let disk_root =
+            MerkleProver::<DiskBackend, Blake2sMerkleHasher>::commit(disk_columns.iter().collect())
+                .root();
+
+        assert_eq!(disk_root, root);
+    }
+
+    #[test]
+    fn test_compress_columns_in_chunks() {
+        const LOG_SIZE: u32 = 7;
+        let mut rng = SmallRng::seed_from_u64(0);
+        let states = (0..1 << LOG_SIZE)
+            .map(|_| Blake2sHash(rng.gen()))
+            .collect_vec();
+        // A whole block and the padded last one.
+        let columns = (0..19)
+            .map(|_| {
+                (0..1 << LOG_SIZE)
+                    .map(|_| rng.gen())
+                    .collect::<BaseColumn>()
+            })
+            .collect_vec();
+        let expected = finalize_states(&states_from_hashes(&states), &columns.iter().collect_vec());
+
+        let disk_columns = columns
+            .iter()
+            .map(DiskColumn::<BaseField>::spill)
+            .collect_vec();
+        for log_chunk_size in [4, 6, LOG_SIZE] {
+            let mut disk_states = states.iter().copied().collect::<DiskColumn<_>>();
+            compress_columns_in_chunks(
+                &mut disk_states,
+                &disk_columns.iter().collect_vec(),
+                log_chunk_size,
+            );
+            assert_eq!(
+                disk_states.to_cpu(),
+                expected,
+                "log chunk size {log_chunk_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_layer_ops_match_commit_on_layer() {
+        assert_layer_ops_match_commit_on_layer::<DiskBackend, Blake2sMerkleHasher>();
+    }
+}
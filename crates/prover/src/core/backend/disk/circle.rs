@@ -0,0 +1,185 @@
+use std::iter::zip;
+use std::sync::Arc;
+
+use num_traits::Zero;
+
+use super::{DiskBackend, DiskColumn, ScratchDir, Spill};
+use crate::core::backend::simd::circle::{
+    eval_packed_at_points, evaluate_packed, interpolate_packed,
+};
+use crate::core::backend::simd::fft::MIN_FFT_LOG_SIZE;
+use crate::core::backend::simd::m31::PackedBaseField;
+use crate::core::backend::simd::SimdBackend;
+use crate::core::backend::{Col, Column};
+use crate::core::circle::{CirclePoint, Coset};
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::qm31::SecureField;
+use crate::core::poly::circle::{
+    CanonicCoset, CircleDomain, CircleEvaluation, CirclePoly, PolyOps,
+};
+use crate::core::poly::twiddles::TwiddleTree;
+use crate::core::poly::BitReversedOrder;
+use crate::core::utils::{bit_reverse_index, circle_domain_index_to_coset_index};
+
+/// The FFTs run on the memory maps of the columns, like [SimdBackend] runs them on its vectors.
+impl<D: ScratchDir> PolyOps for DiskBackend<D> {
+    /// The twiddles of [SimdBackend], which are as large as a single column and stay in memory.
+    type Twiddles = Arc<TwiddleTree<SimdBackend>>;
+
+    fn new_canonical_ordered(
+        coset: CanonicCoset,
+        values: Col<Self, BaseField>,
+    ) -> CircleEvaluation<Self, BaseField, BitReversedOrder> {
+        let domain = coset.circle_domain();
+        assert_eq!(values.len(), domain.size());
+        let log_size = domain.log_size();
+        let values = (0..domain.size())
+            .map(|i| {
+                let circle_index = bit_reverse_index(i, log_size);
+                values.at(circle_domain_index_to_coset_index(circle_index, log_size))
+            })
+            .collect();
+        CircleEvaluation::new(domain, values)
+    }
+
+    fn interpolate(
+        eval: CircleEvaluation<Self, BaseField, BitReversedOrder>,
+        itwiddles: &TwiddleTree<Self>,
+    ) -> CirclePoly<Self> {
+        if eval.domain.log_size() < MIN_FFT_LOG_SIZE {
+            return Spill::spill(&SimdBackend::interpolate(eval.load(), &itwiddles.itwiddles));
+        }
+        let mut values = eval.values;
+        interpolate_packed(values.packed_mut(), eval.domain, &itwiddles.itwiddles);
+        CirclePoly::new(values)
+    }
+
+    fn eval_at_point(poly: &CirclePoly<Self>, point: CirclePoint<SecureField>) -> SecureField {
+        Self::eval_at_points(&[poly], &[point])[0][0]
+    }
+
+    fn eval_at_points(
+        polys: &[&CirclePoly<Self>],
+        points: &[CirclePoint<SecureField>],
+    ) -> Vec<Vec<SecureField>> {
+        let polys = polys
+            .iter()
+            .map(|poly| (poly.log_size(), poly.coeffs.packed()))
+            .collect::<Vec<_>>();
+        eval_packed_at_points(&polys, points)
+    }
+
+    fn extend(poly: &CirclePoly<Self>, log_size: u32) -> CirclePoly<Self> {
+        poly.evaluate(CanonicCoset::new(log_size).circle_domain())
+            .interpolate()
+    }
+
+    fn truncate(poly: CirclePoly<Self>, log_size: u32) -> Option<CirclePoly<Self>> {
+        assert!(log_size <= poly.log_size());
+        let mut coeffs = poly.coeffs;
+        if !coeffs.as_slice()[1 << log_size..]
+            .iter()
+            .all(BaseField::is_zero)
+        {
+            return None;
+        }
+        coeffs.truncate(1 << log_size);
+        Some(CirclePoly::new(coeffs))
+    }
+
+    fn evaluate(
+        poly: &CirclePoly<Self>,
+        domain: CircleDomain,
+        twiddles: &TwiddleTree<Self>,
+    ) -> CircleEvaluation<Self, BaseField, BitReversedOrder> {
+        if poly.log_size() < MIN_FFT_LOG_SIZE {
+            return Spill::spill(&SimdBackend::evaluate(
+                &poly.load(),
+                domain,
+                &twiddles.twiddles,
+            ));
+        }
+        let mut values = DiskColumn::zeros(domain.size());
+        evaluate_packed(
+            poly.coeffs.packed(),
+            poly.log_size(),
+            domain,
+            &twiddles.twiddles,
+            values.packed_mut(),
+        );
+        CircleEvaluation::new(domain, values)
+    }
+
+    fn precompute_twiddles(coset: Coset) -> TwiddleTree<Self> {
+        let twiddles = Arc::new(SimdBackend::precompute_twiddles(coset));
+        TwiddleTree {
+            root_coset: coset,
+            twiddles: Arc::clone(&twiddles),
+            itwiddles: twiddles,
+        }
+    }
+
+    fn add_scaled_column(
+        dst: &mut Col<Self, BaseField>,
+        src: &Col<Self, BaseField>,
+        alpha: BaseField,
+    ) {
+        assert_eq!(dst.len(), src.len());
+        let alpha = PackedBaseField::broadcast(alpha);
+        zip(dst.packed_mut(), src.packed()).for_each(|(d, &s)| *d += alpha * s);
+    }
+
+    fn mul_column(dst: &mut Col<Self, BaseField>, src: &Col<Self, BaseField>) {
+        assert_eq!(dst.len(), src.len());
+        zip(dst.packed_mut(), src.packed()).for_each(|(d, &s)| *d *= s);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::core::backend::disk::{DiskBackend, Spill};
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::Column;
+    use crate::core::circle::SECURE_FIELD_CIRCLE_GEN;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, CirclePoly, PolyOps};
+    use crate::core::poly::BitReversedOrder;
+
+    #[test]
+    fn test_disk_fft_matches_simd() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for log_size in [3, 5, 8, 17] {
+            let coeffs = (0..1 << log_size).map(|_| rng.gen()).collect();
+            let poly = CirclePoly::<SimdBackend>::new(coeffs);
+            let domain = CanonicCoset::new(log_size + 2).circle_domain();
+            let disk_poly = CirclePoly::<DiskBackend>::spill(&poly);
+
+            let eval = poly.evaluate(domain);
+            let disk_eval = disk_poly.evaluate(domain);
+            let point = SECURE_FIELD_CIRCLE_GEN;
+
+            assert_eq!(disk_eval.values.to_cpu(), eval.values.to_cpu());
+            assert_eq!(
+                disk_eval.interpolate().coeffs.to_cpu(),
+                eval.interpolate().coeffs.to_cpu()
+            );
+            assert_eq!(disk_poly.eval_at_point(point), poly.eval_at_point(point));
+        }
+    }
+
+    #[test]
+    fn test_disk_new_canonical_ordered_matches_simd() {
+        let coset = CanonicCoset::new(6);
+        let values = (0..coset.size()).map(BaseField::from).collect_vec();
+
+        let eval = SimdBackend::new_canonical_ordered(coset, values.iter().copied().collect());
+        let disk_eval: CircleEvaluation<DiskBackend, BaseField, BitReversedOrder> =
+            <DiskBackend>::new_canonical_ordered(coset, values.into_iter().collect());
+
+        assert_eq!(disk_eval.values.to_cpu(), eval.values.to_cpu());
+    }
+}
@@ -0,0 +1,365 @@
+use std::fmt::{self, Debug};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bytemuck::{bytes_of, cast_slice, cast_slice_mut, Pod};
+use memmap2::MmapMut;
+use starknet_ff::FieldElement as FieldElement252;
+
+use super::{DiskBackend, ScratchDir, Spill, TempDir, LOG_CHUNK_N_VALUES};
+use crate::core::backend::simd::bit_reverse::{bit_reverse_m31, MIN_LOG_SIZE};
+use crate::core::backend::simd::column::{BaseColumn, SecureColumn};
+use crate::core::backend::simd::m31::{PackedBaseField, N_LANES};
+use crate::core::backend::{Column, ColumnOps};
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::qm31::SecureField;
+use crate::core::fields::{FieldExpOps, FieldOps};
+use crate::core::utils::bit_reverse;
+use crate::core::vcs::blake2_hash::Blake2sHash;
+
+/// A type whose values can be stored in a [DiskColumn].
+pub trait DiskElement: Copy + Debug + Send + Sync + 'static {
+    /// The plain-old-data representation of a value in the file.
+    type Repr: Pod + Send + Sync;
+
+    fn into_repr(self) -> Self::Repr;
+
+    fn from_repr(repr: Self::Repr) -> Self;
+}
+
+impl DiskElement for BaseField {
+    type Repr = BaseField;
+
+    fn into_repr(self) -> BaseField {
+        self
+    }
+
+    fn from_repr(repr: BaseField) -> Self {
+        repr
+    }
+}
+
+impl DiskElement for SecureField {
+    type Repr = [BaseField; 4];
+
+    fn into_repr(self) -> [BaseField; 4] {
+        self.to_m31_array()
+    }
+
+    fn from_repr(repr: [BaseField; 4]) -> Self {
+        SecureField::from_m31_array(repr)
+    }
+}
+
+impl DiskElement for Blake2sHash {
+    type Repr = Blake2sHash;
+
+    fn into_repr(self) -> Blake2sHash {
+        self
+    }
+
+    fn from_repr(repr: Blake2sHash) -> Self {
+        repr
+    }
+}
+
+impl DiskElement for FieldElement252 {
+    type Repr = [u8; 32];
+
+    fn into_repr(self) -> [u8; 32] {
+        self.to_bytes_be()
+    }
+
+    fn from_repr(repr: [u8; 32]) -> Self {
+        // The representation was written by `into_repr`, so it is in range.
+        FieldElement252::from_bytes_be(&repr).unwrap()
+    }
+}
+
+/// A column stored in a memory-mapped file in the scratch directory `D` of [DiskBackend].
+///
+/// The operating system pages the values in and out of memory as they are accessed, so the total
+/// size of the columns is bounded by the disk rather than by the memory. The file is removed when
+/// the column is dropped.
+///
+/// The values are padded with zeros to a multiple of [N_LANES], so a column of [BaseField]s can be
+/// viewed as [PackedBaseField]s.
+pub struct DiskColumn<T: DiskElement, D: ScratchDir = TempDir> {
+    // Declared before `file`, so the mapping is dropped before the file is removed.
+    mmap: MmapMut,
+    file: ScratchFile,
+    length: usize,
+    _marker: PhantomData<(T, D)>,
+}
+
+impl<T: DiskElement, D: ScratchDir> DiskColumn<T, D> {
+    /// Creates a column of `length` zeros.
+    fn new(length: usize) -> Self {
+        let (file, handle) = ScratchFile::create(D::path());
+        Self::map(file, handle, length)
+    }
+
+    /// Maps `handle` as a column of `length` values, after padding it with zeros.
+    fn map(file: ScratchFile, handle: File, length: usize) -> Self {
+        // Never map an empty file, which fails on some platforms.
+        let capacity = length.next_multiple_of(N_LANES).max(N_LANES);
+        handle
+            .set_len((capacity * size_of::<T::Repr>()) as u64)
+            .unwrap_or_else(|e| panic!("failed to resize {}: {e}", file.path.display()));
+        // SAFETY: The file was created for this column only, and is not modified other than
+        // through the mapping.
+        let mmap = unsafe { MmapMut::map_mut(&handle) }
+            .unwrap_or_else(|e| panic!("failed to map {}: {e}", file.path.display()));
+        Self {
+            mmap,
+            file,
+            length,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.file.path
+    }
+
+    /// Returns the values, in their representation in the file.
+    pub fn as_slice(&self) -> &[T::Repr] {
+        &cast_slice(&self.mmap)[..self.length]
+    }
+
+    /// Returns the values mutably, in their representation in the file.
+    pub fn as_mut_slice(&mut self) -> &mut [T::Repr] {
+        &mut cast_slice_mut(&mut self.mmap)[..self.length]
+    }
+
+    /// Shortens the column to its first `length` values. The file keeps its size.
+    pub fn truncate(&mut self, length: usize) {
+        self.length = self.length.min(length);
+    }
+}
+
+impl<D: ScratchDir> DiskColumn<BaseField, D> {
+    /// Returns the values as [PackedBaseField]s, including the padding of the last one.
+    pub fn packed(&self) -> &[PackedBaseField] {
+        &cast_slice(&self.mmap)[..self.length.div_ceil(N_LANES)]
+    }
+
+    /// Returns the values mutably as [PackedBaseField]s, including the padding of the last one.
+    pub fn packed_mut(&mut self) -> &mut [PackedBaseField] {
+        &mut cast_slice_mut(&mut self.mmap)[..self.length.div_ceil(N_LANES)]
+    }
+
+    /// Copies the values of `rows` into memory.
+    pub fn load_rows(&self, rows: Range<usize>) -> BaseColumn {
+        assert!(rows.end <= self.length);
+        if rows.start % N_LANES != 0 {
+            return self.as_slice()[rows].iter().copied().collect();
+        }
+        BaseColumn {
+            data: self.packed()[rows.start / N_LANES..rows.end.div_ceil(N_LANES)].to_vec(),
+            length: rows.len(),
+        }
+    }
+
+    /// Overwrites the rows starting at `start` with `values`.
+    pub fn store_rows(&mut self, start: usize, values: &BaseColumn) {
+        self.as_mut_slice()[start..start + values.len()].copy_from_slice(values.as_slice());
+    }
+}
+
+impl<D: ScratchDir> Spill for DiskColumn<BaseField, D> {
+    type Resident = BaseColumn;
+
+    fn load(&self) -> BaseColumn {
+        self.load_rows(0..self.length)
+    }
+
+    fn spill(resident: &BaseColumn) -> Self {
+        let mut column = Self::new(resident.len());
+        column.store_rows(0, resident);
+        column
+    }
+}
+
+impl<D: ScratchDir> DiskColumn<SecureField, D> {
+    /// Copies the values of `rows` into memory.
+    pub fn load_rows(&self, rows: Range<usize>) -> SecureColumn {
+        self.as_slice()[rows]
+            .iter()
+            .map(|&repr| SecureField::from_repr(repr))
+            .collect()
+    }
+
+    /// Overwrites the rows starting at `start` with `values`.
+    pub fn store_rows(&mut self, start: usize, values: &SecureColumn) {
+        let rows = &mut self.as_mut_slice()[start..start + values.len()];
+        for (i, row) in rows.iter_mut().enumerate() {
+            *row = values.at(i).into_repr();
+        }
+    }
+}
+
+impl<D: ScratchDir> Spill for DiskColumn<SecureField, D> {
+    type Resident = SecureColumn;
+
+    fn load(&self) -> SecureColumn {
+        self.load_rows(0..self.length)
+    }
+
+    fn spill(resident: &SecureColumn) -> Self {
+        let mut column = Self::new(resident.len());
+        column.store_rows(0, resident);
+        column
+    }
+}
+
+impl<T: DiskElement, D: ScratchDir> Column<T> for DiskColumn<T, D> {
+    fn zeros(length: usize) -> Self {
+        Self::new(length)
+    }
+
+    unsafe fn uninitialized(length: usize) -> Self {
+        Self::new(length)
+    }
+
+    fn to_cpu(&self) -> Vec<T> {
+        self.as_slice()
+            .iter()
+            .map(|&repr| T::from_repr(repr))
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    fn at(&self, index: usize) -> T {
+        T::from_repr(self.as_slice()[index])
+    }
+
+    fn set(&mut self, index: usize, value: T) {
+        self.as_mut_slice()[index] = value.into_repr();
+    }
+}
+
+impl<T: DiskElement, D: ScratchDir> Clone for DiskColumn<T, D> {
+    fn clone(&self) -> Self {
+        let mut column = Self::new(self.length);
+        column.mmap.copy_from_slice(&self.mmap);
+        column
+    }
+}
+
+impl<T: DiskElement, D: ScratchDir> Debug for DiskColumn<T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DiskColumn")
+            .field("path", &self.file.path)
+            .field("length", &self.length)
+            .finish()
+    }
+}
+
+impl<T: DiskElement, D: ScratchDir> FromIterator<T> for DiskColumn<T, D> {
+    /// Writes the values to the file as they are produced, without holding them in memory.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let (file, handle) = ScratchFile::create(D::path());
+        let mut writer = BufWriter::new(handle);
+        let mut length = 0;
+        for value in iter {
+            writer
+                .write_all(bytes_of(&value.into_repr()))
+                .unwrap_or_else(|e| panic!("failed to write {}: {e}", file.path.display()));
+            length += 1;
+        }
+        let handle = writer
+            .into_inner()
+            .unwrap_or_else(|e| panic!("failed to write {}: {e}", file.path.display()));
+        Self::map(file, handle, length)
+    }
+}
+
+impl<D: ScratchDir> ColumnOps<BaseField> for DiskBackend<D> {
+    type Column = DiskColumn<BaseField, D>;
+
+    fn bit_reverse_column(column: &mut Self::Column) {
+        // Fallback to cpu bit_reverse.
+        if column.len() < N_LANES << MIN_LOG_SIZE {
+            bit_reverse(column.as_mut_slice());
+            return;
+        }
+        bit_reverse_m31(column.packed_mut());
+    }
+}
+
+impl<D: ScratchDir> ColumnOps<SecureField> for DiskBackend<D> {
+    type Column = DiskColumn<SecureField, D>;
+
+    fn bit_reverse_column(column: &mut Self::Column) {
+        bit_reverse(column.as_mut_slice());
+    }
+}
+
+impl<D: ScratchDir> FieldOps<BaseField> for DiskBackend<D> {
+    fn batch_inverse(column: &Self::Column, dst: &mut Self::Column) {
+        PackedBaseField::batch_inverse(column.packed(), dst.packed_mut());
+    }
+}
+
+impl<D: ScratchDir> FieldOps<SecureField> for DiskBackend<D> {
+    fn batch_inverse(column: &Self::Column, dst: &mut Self::Column) {
+        let chunk_size = 1 << (LOG_CHUNK_N_VALUES - 2);
+        for (chunk, dst_chunk) in column
+            .as_slice()
+            .chunks(chunk_size)
+            .zip(dst.as_mut_slice().chunks_mut(chunk_size))
+        {
+            let values = chunk
+                .iter()
+                .map(|&repr| SecureField::from_repr(repr))
+                .collect::<Vec<_>>();
+            let mut inverses = values.clone();
+            SecureField::batch_inverse(&values, &mut inverses);
+            for (dst, inverse) in dst_chunk.iter_mut().zip(inverses) {
+                *dst = inverse.into_repr();
+            }
+        }
+    }
+}
+
+/// The number of scratch files created by this process, used to name them uniquely.
+static N_SCRATCH_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// A file in the scratch directory, which is removed when dropped.
+struct ScratchFile {
+    path: PathBuf,
+}
+
+impl ScratchFile {
+    fn create(dir: PathBuf) -> (Self, File) {
+        let path = dir.join(format!(
+            "stwo-{}-{}.col",
+            std::process::id(),
+            N_SCRATCH_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let handle = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .unwrap_or_else(|e| panic!("failed to create {}: {e}", path.display()));
+        (Self { path }, handle)
+    }
+}
+
+impl Drop for ScratchFile {
+    fn drop(&mut self) {
+        // The file may already be gone if the scratch directory was cleaned up.
+        let _ = fs::remove_file(&self.path);
+    }
+}
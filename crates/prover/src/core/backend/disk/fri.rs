@@ -0,0 +1,166 @@
+use super::{DiskBackend, DiskColumn, ScratchDir, Spill};
+use crate::core::backend::simd::fri::{
+    decompose_packed, fold_circle_into_line_packed, fold_line_packed,
+};
+use crate::core::backend::simd::m31::{PackedBaseField, LOG_N_LANES};
+use crate::core::backend::simd::SimdBackend;
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::qm31::SecureField;
+use crate::core::fields::secure_column::SecureColumnByCoords;
+use crate::core::fri::FriOps;
+use crate::core::poly::circle::SecureEvaluation;
+use crate::core::poly::line::LineEvaluation;
+use crate::core::poly::twiddles::TwiddleTree;
+use crate::core::poly::utils::domain_line_twiddles_from_tree;
+use crate::core::poly::BitReversedOrder;
+
+/// The folds run on the memory maps of the coordinates, like [SimdBackend] runs them on its
+/// vectors.
+impl<D: ScratchDir> FriOps for DiskBackend<D> {
+    fn fold_line(
+        eval: &LineEvaluation<Self>,
+        alpha: SecureField,
+        twiddles: &TwiddleTree<Self>,
+    ) -> LineEvaluation<Self> {
+        let log_size = eval.len().ilog2();
+        if log_size <= LOG_N_LANES {
+            return Spill::spill(&SimdBackend::fold_line(
+                &eval.load(),
+                alpha,
+                &twiddles.twiddles,
+            ));
+        }
+
+        let domain = eval.domain();
+        let itwiddles = domain_line_twiddles_from_tree(domain, &twiddles.itwiddles.itwiddles)[0];
+        let mut folded_values = SecureColumnByCoords::<Self>::zeros(1 << (log_size - 1));
+        fold_line_packed(
+            packed_coordinates(&eval.values),
+            itwiddles,
+            alpha,
+            packed_coordinates_mut(&mut folded_values),
+        );
+        LineEvaluation::new(domain.double(), folded_values)
+    }
+
+    fn fold_circle_into_line(
+        dst: &mut LineEvaluation<Self>,
+        src: &SecureEvaluation<Self, BitReversedOrder>,
+        alpha: SecureField,
+        twiddles: &TwiddleTree<Self>,
+    ) {
+        let log_size = src.len().ilog2();
+        if log_size <= LOG_N_LANES {
+            let mut resident = dst.load();
+            SimdBackend::fold_circle_into_line(
+                &mut resident,
+                &src.load(),
+                alpha,
+                &twiddles.twiddles,
+            );
+            *dst = Spill::spill(&resident);
+            return;
+        }
+
+        let itwiddles =
+            domain_line_twiddles_from_tree(src.domain, &twiddles.itwiddles.itwiddles)[0];
+        fold_circle_into_line_packed(
+            packed_coordinates_mut(&mut dst.values),
+            packed_coordinates(&src.values),
+            itwiddles,
+            alpha,
+        );
+    }
+
+    fn decompose(
+        eval: &SecureEvaluation<Self, BitReversedOrder>,
+    ) -> (SecureEvaluation<Self, BitReversedOrder>, SecureField) {
+        if eval.domain.log_size() <= LOG_N_LANES {
+            let (g, lambda) = SimdBackend::decompose(&eval.load());
+            return (Spill::spill(&g), lambda);
+        }
+
+        let mut g_values = SecureColumnByCoords::<Self>::zeros(eval.len());
+        let lambda = decompose_packed(
+            packed_coordinates(&eval.values),
+            eval.domain.log_size(),
+            packed_coordinates_mut(&mut g_values),
+        );
+        (SecureEvaluation::new(eval.domain, g_values), lambda)
+    }
+}
+
+fn packed_coordinates<D: ScratchDir>(
+    values: &SecureColumnByCoords<DiskBackend<D>>,
+) -> [&[PackedBaseField]; 4] {
+    values
+        .columns
+        .each_ref()
+        .map(DiskColumn::<BaseField, D>::packed)
+}
+
+fn packed_coordinates_mut<D: ScratchDir>(
+    values: &mut SecureColumnByCoords<DiskBackend<D>>,
+) -> [&mut [PackedBaseField]; 4] {
+    values
+        .columns
+        .each_mut()
+        .map(DiskColumn::<BaseField, D>::packed_mut)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::core::backend::disk::{DiskBackend, Spill};
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::fields::secure_column::SecureColumnByCoords;
+    use crate::core::fri::FriOps;
+    use crate::core::poly::circle::{CanonicCoset, PolyOps, SecureEvaluation};
+    use crate::core::poly::line::{LineDomain, LineEvaluation};
+
+    #[test]
+    fn test_disk_folds_match_simd() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for log_size in [4, 7, 10] {
+            let circle_domain = CanonicCoset::new(log_size).circle_domain();
+            let line_domain = LineDomain::new(circle_domain.half_coset);
+            let values = (0..1 << log_size)
+                .map(|_| rng.gen::<SecureField>())
+                .collect::<SecureColumnByCoords<SimdBackend>>();
+            let line_values = (0..1 << (log_size - 1))
+                .map(|_| rng.gen::<SecureField>())
+                .collect::<SecureColumnByCoords<SimdBackend>>();
+            let alpha = rng.gen();
+            let twiddles = SimdBackend::precompute_twiddles(line_domain.coset());
+            let disk_twiddles = <DiskBackend>::precompute_twiddles(line_domain.coset());
+
+            let eval = SecureEvaluation::new(circle_domain, values);
+            let disk_eval = SecureEvaluation::<DiskBackend, _>::spill(&eval);
+            let (g, lambda) = SimdBackend::decompose(&eval);
+            let (disk_g, disk_lambda) = <DiskBackend>::decompose(&disk_eval);
+            assert_eq!(disk_lambda, lambda);
+            assert_eq!(disk_g.values.load().to_vec(), g.values.to_vec());
+
+            let mut line_eval = LineEvaluation::new(line_domain, line_values);
+            let mut disk_line_eval = LineEvaluation::<DiskBackend>::spill(&line_eval);
+            SimdBackend::fold_circle_into_line(&mut line_eval, &eval, alpha, &twiddles);
+            <DiskBackend>::fold_circle_into_line(
+                &mut disk_line_eval,
+                &disk_eval,
+                alpha,
+                &disk_twiddles,
+            );
+            assert_eq!(
+                disk_line_eval.values.load().to_vec(),
+                line_eval.values.to_vec()
+            );
+
+            let folded = SimdBackend::fold_line(&line_eval, alpha, &twiddles);
+            let disk_folded = <DiskBackend>::fold_line(&disk_line_eval, alpha, &disk_twiddles);
+            assert_eq!(disk_folded.values.load().to_vec(), folded.values.to_vec());
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use super::{DiskBackend, ScratchDir};
+use crate::core::backend::simd::SimdBackend;
+use crate::core::channel::{Blake2sChannel, Poseidon252Channel};
+use crate::core::proof_of_work::GrindOps;
+
+impl<D: ScratchDir> GrindOps<Blake2sChannel> for DiskBackend<D> {
+    fn grind(channel: &Blake2sChannel, pow_bits: u32) -> u64 {
+        SimdBackend::grind(channel, pow_bits)
+    }
+}
+
+impl<D: ScratchDir> GrindOps<Poseidon252Channel> for DiskBackend<D> {
+    fn grind(channel: &Poseidon252Channel, pow_bits: u32) -> u64 {
+        SimdBackend::grind(channel, pow_bits)
+    }
+}
@@ -0,0 +1,98 @@
+use super::{DiskBackend, ScratchDir, Spill};
+use crate::core::backend::simd::line::{evaluate_line_packed, interpolate_line_packed};
+use crate::core::backend::simd::m31::LOG_N_LANES;
+use crate::core::backend::simd::SimdBackend;
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::secure_column::SecureColumnByCoords;
+use crate::core::fields::FieldExpOps;
+use crate::core::poly::line::{LineDomain, LineEvaluation, LineOps};
+use crate::core::poly::twiddles::TwiddleTree;
+use crate::core::poly::utils::domain_line_twiddles_from_tree;
+
+/// The line FFTs run on the memory maps of the coordinates, like [SimdBackend] runs them on its
+/// vectors.
+impl<D: ScratchDir> LineOps for DiskBackend<D> {
+    fn interpolate_line(
+        eval: LineEvaluation<Self>,
+        twiddles: &TwiddleTree<Self>,
+    ) -> SecureColumnByCoords<Self> {
+        let domain = eval.domain();
+        if domain.log_size() <= LOG_N_LANES {
+            return Spill::spill(&SimdBackend::interpolate_line(
+                eval.load(),
+                &twiddles.itwiddles,
+            ));
+        }
+        assert!(twiddles.covers_coset(domain.coset()));
+        let itwiddles = domain_line_twiddles_from_tree(domain, &twiddles.itwiddles.itwiddles);
+        let len_inv = BaseField::from(domain.size()).inverse();
+        let mut values = eval.values;
+        for column in &mut values.columns {
+            interpolate_line_packed(column.packed_mut(), &itwiddles, len_inv);
+        }
+        values
+    }
+
+    fn evaluate_line(
+        coeffs: SecureColumnByCoords<Self>,
+        domain: LineDomain,
+        twiddles: &TwiddleTree<Self>,
+    ) -> LineEvaluation<Self> {
+        if domain.log_size() <= LOG_N_LANES {
+            return Spill::spill(&SimdBackend::evaluate_line(
+                coeffs.load(),
+                domain,
+                &twiddles.twiddles,
+            ));
+        }
+        assert_eq!(coeffs.len(), domain.size());
+        assert!(twiddles.covers_coset(domain.coset()));
+        let twiddles = domain_line_twiddles_from_tree(domain, &twiddles.twiddles.twiddles);
+        let mut values = coeffs;
+        for column in &mut values.columns {
+            evaluate_line_packed(column.packed_mut(), &twiddles);
+        }
+        LineEvaluation::new(domain, values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::core::backend::disk::{DiskBackend, Spill};
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::circle::Coset;
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::fields::secure_column::SecureColumnByCoords;
+    use crate::core::poly::circle::PolyOps;
+    use crate::core::poly::line::{LineDomain, LineEvaluation, LineOps};
+
+    #[test]
+    fn test_disk_line_fft_matches_simd() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for log_size in [3, 6, 12] {
+            let domain = LineDomain::new(Coset::half_odds(log_size));
+            let values = (0..1 << log_size)
+                .map(|_| rng.gen::<SecureField>())
+                .collect::<SecureColumnByCoords<SimdBackend>>();
+            let twiddles = SimdBackend::precompute_twiddles(domain.coset());
+            let disk_twiddles = <DiskBackend>::precompute_twiddles(domain.coset());
+
+            let coeffs = SimdBackend::interpolate_line(
+                LineEvaluation::new(domain, values.clone()),
+                &twiddles,
+            );
+            let disk_coeffs = <DiskBackend>::interpolate_line(
+                LineEvaluation::new(domain, Spill::spill(&values)),
+                &disk_twiddles,
+            );
+            assert_eq!(disk_coeffs.load().to_vec(), coeffs.to_vec());
+
+            let eval = SimdBackend::evaluate_line(coeffs, domain, &twiddles);
+            let disk_eval = <DiskBackend>::evaluate_line(disk_coeffs, domain, &disk_twiddles);
+            assert_eq!(disk_eval.values.load().to_vec(), eval.values.to_vec());
+        }
+    }
+}
@@ -0,0 +1,487 @@
+use std::borrow::Cow;
+use std::ops::Range;
+use std::slice;
+
+use num_traits::{One, Zero};
+
+use super::{chunk_log_size, DiskBackend, DiskColumn, DiskElement, ScratchDir, Spill};
+use crate::core::backend::simd::lookups::gkr::sum_at_0_and_2;
+use crate::core::backend::simd::m31::N_LANES;
+use crate::core::backend::simd::SimdBackend;
+use crate::core::backend::{Col, Column, ColumnOps};
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::qm31::SecureField;
+use crate::core::fields::Field;
+use crate::core::lookups::gkr_prover::{
+    correct_sum_as_poly_in_first_variable, EqEvals, GkrMultivariatePolyOracle, GkrOps, Layer,
+};
+use crate::core::lookups::mle::{Mle, MleOps};
+use crate::core::lookups::sumcheck::MultivariatePolyOracle;
+use crate::core::lookups::utils::{eq, UnivariatePoly};
+
+impl<D: ScratchDir> MleOps<BaseField> for DiskBackend<D> {
+    fn fix_first_variable(
+        mle: Mle<Self, BaseField>,
+        assignment: SecureField,
+    ) -> Mle<Self, SecureField> {
+        let log_chunk_size = chunk_log_size(mle.n_variables().saturating_sub(1) as u32, 2 + 4);
+        Mle::new(fix_first_variable_in_chunks(
+            &mle,
+            assignment,
+            log_chunk_size,
+        ))
+    }
+}
+
+impl<D: ScratchDir> MleOps<SecureField> for DiskBackend<D> {
+    fn fix_first_variable(
+        mle: Mle<Self, SecureField>,
+        assignment: SecureField,
+    ) -> Mle<Self, SecureField> {
+        let log_chunk_size = chunk_log_size(mle.n_variables().saturating_sub(1) as u32, 2 * 4 + 4);
+        Mle::new(fix_first_variable_in_chunks(
+            &mle,
+            assignment,
+            log_chunk_size,
+        ))
+    }
+}
+
+/// The lookup operations load `2^log_chunk_size` rows of each column they read at a time, and
+/// hand them to [SimdBackend].
+impl<D: ScratchDir> GkrOps for DiskBackend<D> {
+    fn gen_eq_evals(y: &[SecureField], v: SecureField) -> Mle<Self, SecureField> {
+        let log_chunk_size = chunk_log_size(y.len() as u32, 4);
+        Mle::new(gen_eq_evals_in_chunks(y, v, log_chunk_size))
+    }
+
+    fn next_layer(layer: &Layer<Self>) -> Layer<Self> {
+        let log_chunk_size = chunk_log_size(
+            layer.n_variables() as u32 - 1,
+            2 * n_base_columns(layer) + 2 * 4,
+        );
+        next_layer_in_chunks(layer, log_chunk_size)
+    }
+
+    fn sum_as_poly_in_first_variable(
+        h: &GkrMultivariatePolyOracle<'_, Self>,
+        claim: SecureField,
+    ) -> UnivariatePoly<SecureField> {
+        let n_variables = h.n_variables();
+        let log_n_terms = n_variables.saturating_sub(1) as u32;
+        // Offload small instances to SimdBackend, which offloads them to CpuBackend.
+        if 1 << log_n_terms < N_LANES {
+            let h = GkrMultivariatePolyOracle {
+                // The evaluations only depend on `y`, so they are regenerated rather than loaded.
+                eq_evals: Cow::Owned(EqEvals::generate(h.eq_evals.y())),
+                input_layer: h.input_layer.load(),
+                eq_fixed_var_correction: h.eq_fixed_var_correction,
+                lambda: h.lambda,
+            };
+            return SimdBackend::sum_as_poly_in_first_variable(&h, claim);
+        }
+        let log_chunk_size = chunk_log_size(log_n_terms, 4 * n_base_columns(&h.input_layer) + 4);
+        sum_as_poly_in_first_variable_in_chunks(h, claim, log_chunk_size)
+    }
+}
+
+/// Returns the number of [BaseField] columns that make up the columns of `layer`.
+fn n_base_columns<D: ScratchDir>(layer: &Layer<DiskBackend<D>>) -> usize {
+    match layer {
+        Layer::GrandProduct(_) | Layer::LogUpSingles { .. } => 4,
+        Layer::LogUpMultiplicities { .. } => 1 + 4,
+        Layer::LogUpGeneric { .. } => 2 * 4,
+    }
+}
+
+/// Copies the rows of each of `ranges` of `column` into memory, one range after the other.
+fn load_ranges<T, D>(column: &DiskColumn<T, D>, ranges: &[Range<usize>]) -> Col<SimdBackend, T>
+where
+    T: DiskElement,
+    D: ScratchDir,
+    SimdBackend: ColumnOps<T>,
+{
+    ranges
+        .iter()
+        .flat_map(|rows| &column.as_slice()[rows.clone()])
+        .map(|&repr| T::from_repr(repr))
+        .collect()
+}
+
+/// Copies the rows of each of `ranges` of the columns of `layer` into memory, one range after the
+/// other.
+fn load_layer_ranges<D: ScratchDir>(
+    layer: &Layer<DiskBackend<D>>,
+    ranges: &[Range<usize>],
+) -> Layer<SimdBackend> {
+    match layer {
+        Layer::GrandProduct(mle) => Layer::GrandProduct(Mle::new(load_ranges(mle, ranges))),
+        Layer::LogUpGeneric {
+            numerators,
+            denominators,
+        } => Layer::LogUpGeneric {
+            numerators: Mle::new(load_ranges(numerators, ranges)),
+            denominators: Mle::new(load_ranges(denominators, ranges)),
+        },
+        Layer::LogUpMultiplicities {
+            numerators,
+            denominators,
+        } => Layer::LogUpMultiplicities {
+            numerators: Mle::new(load_ranges(numerators, ranges)),
+            denominators: Mle::new(load_ranges(denominators, ranges)),
+        },
+        Layer::LogUpSingles { denominators } => Layer::LogUpSingles {
+            denominators: Mle::new(load_ranges(denominators, ranges)),
+        },
+    }
+}
+
+/// Fixes the first variable of the multilinear extension with evaluations `evals`, in chunks of
+/// `2^log_chunk_size` rows of each half, which are folded into the same rows of the result.
+fn fix_first_variable_in_chunks<F, D>(
+    evals: &DiskColumn<F, D>,
+    assignment: SecureField,
+    log_chunk_size: u32,
+) -> DiskColumn<SecureField, D>
+where
+    F: Field + DiskElement,
+    D: ScratchDir,
+    SimdBackend: MleOps<F>,
+{
+    let midpoint = evals.len() / 2;
+    let mut res = DiskColumn::<SecureField, D>::zeros(midpoint);
+    for start in (0..midpoint).step_by(1 << log_chunk_size) {
+        let end = (start + (1 << log_chunk_size)).min(midpoint);
+        let chunk = Mle::<SimdBackend, F>::new(load_ranges(
+            evals,
+            &[start..end, midpoint + start..midpoint + end],
+        ));
+        res.store_rows(start, &chunk.fix_first_variable(assignment));
+    }
+    res
+}
+
+/// Generates the evaluations of `v * eq(x, y)` in chunks of `2^log_chunk_size` evaluations.
+///
+/// The bits of `x` are ordered like `y`, from the most significant bit, so the evaluations of
+/// chunk `k` are those of `v * eq(bits(k), y_high)` times `eq(x_low, y_low)`, where `y_high` holds
+/// the variables of the bits of `k`.
+fn gen_eq_evals_in_chunks<D: ScratchDir>(
+    y: &[SecureField],
+    v: SecureField,
+    log_chunk_size: u32,
+) -> DiskColumn<SecureField, D> {
+    let n_high_variables = y.len() - log_chunk_size as usize;
+    let (y_high, y_low) = y.split_at(n_high_variables);
+    let mut evals = DiskColumn::<SecureField, D>::zeros(1 << y.len());
+    for chunk_index in 0..1 << n_high_variables {
+        let chunk_bits = (0..n_high_variables)
+            .rev()
+            .map(|i| match chunk_index >> i & 1 {
+                0 => SecureField::zero(),
+                _ => SecureField::one(),
+            })
+            .collect::<Vec<_>>();
+        let chunk = SimdBackend::gen_eq_evals(y_low, v * eq(&chunk_bits, y_high));
+        evals.store_rows(chunk_index << log_chunk_size, &chunk);
+    }
+    evals
+}
+
+/// Generates the next layer in chunks of `2^log_chunk_size` rows, each produced from twice as
+/// many rows of `layer`.
+fn next_layer_in_chunks<D: ScratchDir>(
+    layer: &Layer<DiskBackend<D>>,
+    log_chunk_size: u32,
+) -> Layer<DiskBackend<D>> {
+    let next_len = 1 << (layer.n_variables() - 1);
+    let mut next_layer: Layer<DiskBackend<D>> = match layer {
+        Layer::GrandProduct(_) => Layer::GrandProduct(Mle::new(DiskColumn::zeros(next_len))),
+        _ => Layer::LogUpGeneric {
+            numerators: Mle::new(DiskColumn::zeros(next_len)),
+            denominators: Mle::new(DiskColumn::zeros(next_len)),
+        },
+    };
+    for start in (0..next_len).step_by(1 << log_chunk_size) {
+        let end = (start + (1 << log_chunk_size)).min(next_len);
+        let chunk = load_layer_ranges(layer, slice::from_ref(&(2 * start..2 * end)));
+        match (&mut next_layer, SimdBackend::next_layer(&chunk)) {
+            (Layer::GrandProduct(mle), Layer::GrandProduct(chunk_mle)) => {
+                mle.store_rows(start, &chunk_mle)
+            }
+            (
+                Layer::LogUpGeneric {
+                    numerators,
+                    denominators,
+                },
+                Layer::LogUpGeneric {
+                    numerators: chunk_numerators,
+                    denominators: chunk_denominators,
+                },
+            ) => {
+                numerators.store_rows(start, &chunk_numerators);
+                denominators.store_rows(start, &chunk_denominators);
+            }
+            _ => unreachable!(),
+        }
+    }
+    next_layer
+}
+
+/// Sums the terms of `h` in chunks of `2^log_chunk_size` terms.
+///
+/// Term `i` reads rows `2i` and `2i + 1` of each half of the input layer, so the rows of a chunk
+/// of terms, once loaded one half after the other, form an input layer of their own.
+fn sum_as_poly_in_first_variable_in_chunks<D: ScratchDir>(
+    h: &GkrMultivariatePolyOracle<'_, DiskBackend<D>>,
+    claim: SecureField,
+    log_chunk_size: u32,
+) -> UnivariatePoly<SecureField> {
+    assert!(1 << log_chunk_size >= N_LANES);
+    let n_variables = h.n_variables();
+    let n_terms = 1 << (n_variables - 1);
+    let midpoint = 2 * n_terms;
+
+    let mut eval_at_0 = SecureField::zero();
+    let mut eval_at_2 = SecureField::zero();
+    for start in (0..n_terms).step_by(1 << log_chunk_size) {
+        let end = start + (1 << log_chunk_size);
+        let chunk = load_layer_ranges(
+            &h.input_layer,
+            &[2 * start..2 * end, midpoint + 2 * start..midpoint + 2 * end],
+        );
+        let eq_evals = h.eq_evals.load_rows(start..end);
+        let (chunk_eval_at_0, chunk_eval_at_2) = sum_at_0_and_2(&eq_evals, &chunk, h.lambda);
+        eval_at_0 += chunk_eval_at_0;
+        eval_at_2 += chunk_eval_at_2;
+    }
+
+    eval_at_0 *= h.eq_fixed_var_correction;
+    eval_at_2 *= h.eq_fixed_var_correction;
+    correct_sum_as_poly_in_first_variable(eval_at_0, eval_at_2, claim, h.eq_evals.y(), n_variables)
+}
+
+impl<D: ScratchDir> Spill for Mle<DiskBackend<D>, BaseField> {
+    type Resident = Mle<SimdBackend, BaseField>;
+
+    fn load(&self) -> Self::Resident {
+        Mle::new(Spill::load(&**self))
+    }
+
+    fn spill(resident: &Self::Resident) -> Self {
+        Mle::new(DiskColumn::spill(&**resident))
+    }
+}
+
+impl<D: ScratchDir> Spill for Mle<DiskBackend<D>, SecureField> {
+    type Resident = Mle<SimdBackend, SecureField>;
+
+    fn load(&self) -> Self::Resident {
+        Mle::new(Spill::load(&**self))
+    }
+
+    fn spill(resident: &Self::Resident) -> Self {
+        Mle::new(DiskColumn::spill(&**resident))
+    }
+}
+
+impl<D: ScratchDir> Spill for Layer<DiskBackend<D>> {
+    type Resident = Layer<SimdBackend>;
+
+    fn load(&self) -> Self::Resident {
+        match self {
+            Layer::GrandProduct(mle) => Layer::GrandProduct(mle.load()),
+            Layer::LogUpGeneric {
+                numerators,
+                denominators,
+            } => Layer::LogUpGeneric {
+                numerators: numerators.load(),
+                denominators: denominators.load(),
+            },
+            Layer::LogUpMultiplicities {
+                numerators,
+                denominators,
+            } => Layer::LogUpMultiplicities {
+                numerators: numerators.load(),
+                denominators: denominators.load(),
+            },
+            Layer::LogUpSingles { denominators } => Layer::LogUpSingles {
+                denominators: denominators.load(),
+            },
+        }
+    }
+
+    fn spill(resident: &Self::Resident) -> Self {
+        match resident {
+            Layer::GrandProduct(mle) => Layer::GrandProduct(Spill::spill(mle)),
+            Layer::LogUpGeneric {
+                numerators,
+                denominators,
+            } => Layer::LogUpGeneric {
+                numerators: Spill::spill(numerators),
+                denominators: Spill::spill(denominators),
+            },
+            Layer::LogUpMultiplicities {
+                numerators,
+                denominators,
+            } => Layer::LogUpMultiplicities {
+                numerators: Spill::spill(numerators),
+                denominators: Spill::spill(denominators),
+            },
+            Layer::LogUpSingles { denominators } => Layer::LogUpSingles {
+                denominators: Spill::spill(denominators),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::{
+        fix_first_variable_in_chunks, gen_eq_evals_in_chunks, next_layer_in_chunks,
+        sum_as_poly_in_first_variable_in_chunks,
+    };
+    use crate::core::backend::disk::{DiskBackend, DiskColumn, Spill, TempDir};
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::{Column, CpuBackend};
+    use crate::core::fields::m31::BaseField;
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::lookups::gkr_prover::{EqEvals, GkrMultivariatePolyOracle, GkrOps, Layer};
+    use crate::core::lookups::mle::Mle;
+    use crate::core::lookups::sumcheck::MultivariatePolyOracle;
+
+    const LOG_SIZE: u32 = 8;
+
+    fn layer_values(layer: &Layer<CpuBackend>) -> Vec<Vec<SecureField>> {
+        match layer {
+            Layer::GrandProduct(mle) => vec![mle.to_vec()],
+            Layer::LogUpGeneric {
+                numerators,
+                denominators,
+            } => vec![numerators.to_vec(), denominators.to_vec()],
+            Layer::LogUpMultiplicities {
+                numerators,
+                denominators,
+            } => vec![
+                numerators.iter().map(|&v| v.into()).collect(),
+                denominators.to_vec(),
+            ],
+            Layer::LogUpSingles { denominators } => vec![denominators.to_vec()],
+        }
+    }
+
+    fn random_layers(rng: &mut SmallRng) -> Vec<Layer<SimdBackend>> {
+        let mut secure_mle = || {
+            Mle::new(
+                (0..1 << LOG_SIZE)
+                    .map(|_| rng.gen::<SecureField>())
+                    .collect(),
+            )
+        };
+        let (a, b, c, d, e) = (
+            secure_mle(),
+            secure_mle(),
+            secure_mle(),
+            secure_mle(),
+            secure_mle(),
+        );
+        let base_mle = Mle::new((0..1 << LOG_SIZE).map(|_| rng.gen()).collect());
+        vec![
+            Layer::GrandProduct(a),
+            Layer::LogUpGeneric {
+                numerators: b,
+                denominators: c,
+            },
+            Layer::LogUpMultiplicities {
+                numerators: base_mle,
+                denominators: d,
+            },
+            Layer::LogUpSingles { denominators: e },
+        ]
+    }
+
+    #[test]
+    fn test_fix_first_variable_in_chunks() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let evals = (0..1 << LOG_SIZE).map(|_| rng.gen()).collect_vec();
+        let base_evals = (0..1 << LOG_SIZE).map(|_| rng.gen()).collect_vec();
+        let assignment = rng.gen();
+        let expected = Mle::<SimdBackend, SecureField>::new(evals.iter().copied().collect())
+            .fix_first_variable(assignment);
+        let base_expected =
+            Mle::<SimdBackend, BaseField>::new(base_evals.iter().copied().collect())
+                .fix_first_variable(assignment);
+
+        let column = evals.into_iter().collect::<DiskColumn<SecureField>>();
+        let base_column = base_evals.into_iter().collect::<DiskColumn<BaseField>>();
+        for log_chunk_size in [1, 5, LOG_SIZE - 1] {
+            let res = fix_first_variable_in_chunks(&column, assignment, log_chunk_size);
+            let base_res = fix_first_variable_in_chunks(&base_column, assignment, log_chunk_size);
+            assert_eq!(res.to_cpu(), expected.to_cpu());
+            assert_eq!(base_res.to_cpu(), base_expected.to_cpu());
+        }
+    }
+
+    #[test]
+    fn test_gen_eq_evals_in_chunks() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let y = (0..LOG_SIZE).map(|_| rng.gen()).collect_vec();
+        let v = rng.gen();
+        let expected = SimdBackend::gen_eq_evals(&y, v);
+
+        for log_chunk_size in [0, 5, LOG_SIZE] {
+            let evals = gen_eq_evals_in_chunks::<TempDir>(&y, v, log_chunk_size);
+            assert_eq!(evals.to_cpu(), expected.to_cpu());
+        }
+    }
+
+    #[test]
+    fn test_next_layer_in_chunks() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for layer in random_layers(&mut rng) {
+            let expected = layer.next_layer().unwrap().to_cpu();
+            let disk_layer = Layer::<DiskBackend>::spill(&layer);
+            for log_chunk_size in [0, 5, LOG_SIZE - 1] {
+                let next_layer = next_layer_in_chunks(&disk_layer, log_chunk_size);
+                assert_eq!(
+                    layer_values(&next_layer.load().to_cpu()),
+                    layer_values(&expected)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sum_as_poly_in_first_variable_in_chunks() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let y = (0..LOG_SIZE - 1).map(|_| rng.gen()).collect_vec();
+        let (claim, lambda, eq_fixed_var_correction) = (rng.gen(), rng.gen(), rng.gen());
+        for layer in random_layers(&mut rng) {
+            let eq_evals = EqEvals::<SimdBackend>::generate(&y);
+            let disk_eq_evals = EqEvals::<DiskBackend>::generate(&y);
+            let h = GkrMultivariatePolyOracle {
+                eq_evals: Cow::Borrowed(&eq_evals),
+                input_layer: layer.clone(),
+                eq_fixed_var_correction,
+                lambda,
+            };
+            let expected = h.sum_as_poly_in_first_variable(claim);
+            let disk_h = GkrMultivariatePolyOracle {
+                eq_evals: Cow::Borrowed(&disk_eq_evals),
+                input_layer: Layer::<DiskBackend>::spill(&layer),
+                eq_fixed_var_correction,
+                lambda,
+            };
+            for log_chunk_size in [4, 5, LOG_SIZE - 2] {
+                let poly = sum_as_poly_in_first_variable_in_chunks(&disk_h, claim, log_chunk_size);
+                assert_eq!(*poly, *expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+mod accumulation;
+mod blake2s;
+mod circle;
+mod column;
+mod fri;
+mod grind;
+mod line;
+mod lookups;
+mod poseidon252;
+mod quotients;
+
+use std::fmt::Debug;
+use std::iter::zip;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+pub use self::blake2s::DiskBlake2sLayerState;
+pub use self::column::{DiskColumn, DiskElement};
+use super::simd::m31::LOG_N_LANES;
+use super::simd::SimdBackend;
+use super::{Backend, BackendForChannel, Column, ColumnOps};
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::secure_column::SecureColumnByCoords;
+use crate::core::poly::circle::{CircleDomain, CircleEvaluation, CirclePoly, SecureEvaluation};
+use crate::core::poly::line::LineEvaluation;
+use crate::core::utils::bit_reverse;
+use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
+use crate::core::vcs::ops::{MerkleHasher, MerkleOps};
+use crate::core::vcs::poseidon252_merkle::Poseidon252MerkleChannel;
+
+/// The log of the number of values that the operations reading many columns row by row (Merkle
+/// hashing, quotient accumulation and the lookup operations) load into memory at once.
+const LOG_CHUNK_N_VALUES: u32 = 22;
+
+/// The log of the smallest number of rows such an operation loads at once.
+const MIN_LOG_CHUNK_SIZE: u32 = LOG_N_LANES + 2;
+
+/// A backend whose columns are memory-mapped files (see [DiskColumn]) in the directory `D`, for
+/// traces that don't fit in memory.
+///
+/// The operations reuse the kernels of [SimdBackend] without copying whole columns into memory:
+/// * FFTs and FRI folds run on the memory maps directly, so the operating system pages the values
+///   in and out as they are accessed.
+/// * Merkle hashing, quotient accumulation and the lookup operations load `2^22` values at a time,
+///   across all the columns they read.
+/// * Constraint evaluation loads the columns of one component at a time.
+///
+/// The peak memory is then bounded by the largest component rather than by the whole trace, given
+/// that the evaluations are recomputed (see [crate::core::pcs::EvaluationStorage]) and that the
+/// trace is committed as it is generated (see [crate::core::pcs::StreamingTreeBuilder]).
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+#[serde(bound = "")]
+pub struct DiskBackend<D: ScratchDir = TempDir>(PhantomData<D>);
+
+impl<D: ScratchDir> Backend for DiskBackend<D> {}
+impl<D: ScratchDir> BackendForChannel<Blake2sMerkleChannel> for DiskBackend<D> {}
+impl<D: ScratchDir> BackendForChannel<Poseidon252MerkleChannel> for DiskBackend<D> {}
+
+/// The directory in which a [DiskBackend] creates its column files.
+///
+/// Each directory is a type, so that backends with different directories can be used side by side,
+/// e.g. `DiskBackend<MyDir>` with:
+/// ```
+/// use std::path::PathBuf;
+///
+/// use stwo_prover::core::backend::disk::ScratchDir;
+///
+/// #[derive(Copy, Clone, Debug)]
+/// struct MyDir;
+///
+/// impl ScratchDir for MyDir {
+///     fn path() -> PathBuf {
+///         PathBuf::from("/mnt/scratch")
+///     }
+/// }
+/// ```
+pub trait ScratchDir: Copy + Debug + Send + Sync + 'static {
+    /// Returns the directory. It must exist by the time a column is created.
+    fn path() -> PathBuf;
+}
+
+/// The [ScratchDir] of [std::env::temp_dir].
+#[derive(Copy, Clone, Debug)]
+pub struct TempDir;
+
+impl ScratchDir for TempDir {
+    fn path() -> PathBuf {
+        std::env::temp_dir()
+    }
+}
+
+/// A value of [DiskBackend] that the kernels of [SimdBackend] operate on once it is loaded into
+/// memory.
+pub trait Spill {
+    /// The in-memory counterpart of the value, in [SimdBackend].
+    type Resident;
+
+    /// Copies the value into memory.
+    fn load(&self) -> Self::Resident;
+
+    /// Copies `resident` to disk.
+    fn spill(resident: &Self::Resident) -> Self;
+}
+
+impl<D: ScratchDir> Spill for CirclePoly<DiskBackend<D>> {
+    type Resident = CirclePoly<SimdBackend>;
+
+    fn load(&self) -> Self::Resident {
+        CirclePoly::new(self.coeffs.load())
+    }
+
+    fn spill(resident: &Self::Resident) -> Self {
+        CirclePoly::new(DiskColumn::spill(&resident.coeffs))
+    }
+}
+
+impl<D: ScratchDir, EvalOrder> Spill for CircleEvaluation<DiskBackend<D>, BaseField, EvalOrder> {
+    type Resident = CircleEvaluation<SimdBackend, BaseField, EvalOrder>;
+
+    fn load(&self) -> Self::Resident {
+        CircleEvaluation::new(self.domain, self.values.load())
+    }
+
+    fn spill(resident: &Self::Resident) -> Self {
+        CircleEvaluation::new(resident.domain, DiskColumn::spill(&resident.values))
+    }
+}
+
+impl<D: ScratchDir> Spill for SecureColumnByCoords<DiskBackend<D>> {
+    type Resident = SecureColumnByCoords<SimdBackend>;
+
+    fn load(&self) -> Self::Resident {
+        SecureColumnByCoords {
+            columns: self.columns.each_ref().map(|column| column.load()),
+        }
+    }
+
+    fn spill(resident: &Self::Resident) -> Self {
+        SecureColumnByCoords {
+            columns: resident.columns.each_ref().map(DiskColumn::spill),
+        }
+    }
+}
+
+impl<D: ScratchDir, EvalOrder> Spill for SecureEvaluation<DiskBackend<D>, EvalOrder> {
+    type Resident = SecureEvaluation<SimdBackend, EvalOrder>;
+
+    fn load(&self) -> Self::Resident {
+        SecureEvaluation::new(self.domain, self.values.load())
+    }
+
+    fn spill(resident: &Self::Resident) -> Self {
+        SecureEvaluation::new(resident.domain, Spill::spill(&resident.values))
+    }
+}
+
+impl<D: ScratchDir> Spill for LineEvaluation<DiskBackend<D>> {
+    type Resident = LineEvaluation<SimdBackend>;
+
+    fn load(&self) -> Self::Resident {
+        LineEvaluation::new(self.domain(), self.values.load())
+    }
+
+    fn spill(resident: &Self::Resident) -> Self {
+        LineEvaluation::new(resident.domain(), Spill::spill(&resident.values))
+    }
+}
+
+/// The number of words of the two children hashes of a Merkle node.
+const N_CHILDREN_WORDS: usize = 16;
+
+/// Returns the log number of rows to load at once from `n_columns` columns of log size
+/// `log_size`.
+fn chunk_log_size(log_size: u32, n_columns: usize) -> u32 {
+    let log_n_columns = n_columns.max(1).next_power_of_two().ilog2();
+    LOG_CHUNK_N_VALUES
+        .saturating_sub(log_n_columns)
+        .max(MIN_LOG_CHUNK_SIZE)
+        .min(log_size)
+}
+
+/// Splits `domain` into the domains of its chunks of `2^log_chunk_size` consecutive rows in
+/// bit-reversed order, which are circle domains themselves.
+fn row_chunks(domain: CircleDomain, log_chunk_size: u32) -> Vec<CircleDomain> {
+    let (subdomain, mut shifts) = domain.split(domain.log_size() - log_chunk_size);
+    // Traversing the domain in bit-reversed order goes over the subdomain before changing the
+    // shift, whose bits are traversed in reverse.
+    bit_reverse(&mut shifts);
+    shifts
+        .into_iter()
+        .map(|shift| subdomain.shift(shift))
+        .collect_vec()
+}
+
+/// Commits on a Merkle layer in chunks of `2^log_chunk_size` consecutive nodes, which
+/// [SimdBackend] hashes from the rows of the chunk. The hash of a node doesn't depend on its index,
+/// so each chunk is committed as a layer of its own.
+fn commit_on_layer_in_chunks<H: MerkleHasher, D: ScratchDir>(
+    log_size: u32,
+    prev_layer: Option<&DiskColumn<H::Hash, D>>,
+    columns: &[&DiskColumn<BaseField, D>],
+    log_chunk_size: u32,
+) -> DiskColumn<H::Hash, D>
+where
+    H::Hash: DiskElement,
+    SimdBackend: MerkleOps<H> + ColumnOps<H::Hash, Column = Vec<H::Hash>>,
+{
+    let mut layer = DiskColumn::<H::Hash, D>::zeros(1 << log_size);
+    let chunk_size = 1 << log_chunk_size;
+    for (chunk_index, chunk) in layer.as_mut_slice().chunks_mut(chunk_size).enumerate() {
+        let rows = chunk_index * chunk_size..(chunk_index + 1) * chunk_size;
+        let prev_chunk = prev_layer.map(|prev_layer| {
+            prev_layer.as_slice()[2 * rows.start..2 * rows.end]
+                .iter()
+                .map(|&repr| H::Hash::from_repr(repr))
+                .collect_vec()
+        });
+        let column_chunks = columns
+            .iter()
+            .map(|column| column.load_rows(rows.clone()))
+            .collect_vec();
+        let hashes = <SimdBackend as MerkleOps<H>>::commit_on_layer(
+            log_chunk_size,
+            prev_chunk.as_ref(),
+            &column_chunks.iter().collect_vec(),
+        );
+        for (repr, hash) in zip(chunk, hashes) {
+            *repr = hash.into_repr();
+        }
+    }
+    layer
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::{row_chunks, DiskColumn, Spill};
+    use crate::core::backend::simd::column::BaseColumn;
+    use crate::core::backend::Column;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::poly::circle::CanonicCoset;
+    use crate::core::utils::bit_reverse;
+
+    #[test]
+    fn test_row_chunks() {
+        let domain = CanonicCoset::new(6).circle_domain();
+        let mut points = domain.iter().collect_vec();
+        bit_reverse(&mut points);
+
+        for log_chunk_size in 1..=6 {
+            let chunk_points = row_chunks(domain, log_chunk_size)
+                .into_iter()
+                .flat_map(|chunk| {
+                    let mut chunk_points = chunk.iter().collect_vec();
+                    bit_reverse(&mut chunk_points);
+                    chunk_points
+                })
+                .collect_vec();
+            assert_eq!(chunk_points, points, "log chunk size {log_chunk_size}");
+        }
+    }
+
+    #[test]
+    fn test_disk_column() {
+        let values = (0..100).map(BaseField::from).collect_vec();
+
+        let mut column = values.iter().copied().collect::<DiskColumn<BaseField>>();
+        let path = column.path().to_path_buf();
+
+        assert_eq!(column.to_cpu(), values);
+        assert_eq!(column.load_rows(16..40).to_cpu(), values[16..40]);
+        assert_eq!(column.load_rows(3..5).to_cpu(), values[3..5]);
+        column.set(3, BaseField::from(7));
+        let clone = column.clone();
+        column.store_rows(0, &BaseColumn::from_cpu(vec![BaseField::from(1); 2]));
+        assert_eq!(clone.at(3), BaseField::from(7));
+        assert_eq!(clone.at(0), BaseField::from(0));
+        assert_eq!(column.at(0), BaseField::from(1));
+        assert_eq!(
+            DiskColumn::<BaseField>::spill(&column.load()).to_cpu(),
+            column.to_cpu()
+        );
+        assert!(path.exists());
+        drop(column);
+        assert!(!path.exists());
+    }
+}
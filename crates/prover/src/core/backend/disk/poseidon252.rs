@@ -0,0 +1,95 @@
+use starknet_ff::FieldElement as FieldElement252;
+
+use super::{
+    chunk_log_size, commit_on_layer_in_chunks, DiskBackend, DiskColumn, ScratchDir,
+    N_CHILDREN_WORDS,
+};
+use crate::core::backend::ColumnOps;
+use crate::core::fields::m31::BaseField;
+use crate::core::utils::bit_reverse;
+#[cfg(feature = "parallel")]
+use crate::core::vcs::ops::par_read_rows;
+use crate::core::vcs::ops::{MerkleOps, NodeStates};
+use crate::core::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
+
+impl<D: ScratchDir> ColumnOps<FieldElement252> for DiskBackend<D> {
+    type Column = DiskColumn<FieldElement252, D>;
+
+    fn bit_reverse_column(column: &mut Self::Column) {
+        bit_reverse(column.as_mut_slice());
+    }
+}
+
+impl<D: ScratchDir> MerkleOps<Poseidon252MerkleHasher> for DiskBackend<D> {
+    // The Poseidon sponge of a node is opaque, so the partial hashes are kept in memory rather than
+    // in a column.
+    type LayerState = NodeStates<Poseidon252MerkleHasher>;
+
+    fn commit_on_layer(
+        log_size: u32,
+        prev_layer: Option<&DiskColumn<FieldElement252, D>>,
+        columns: &[&DiskColumn<BaseField, D>],
+    ) -> DiskColumn<FieldElement252, D> {
+        let log_chunk_size = chunk_log_size(log_size, columns.len() + N_CHILDREN_WORDS);
+        commit_on_layer_in_chunks::<Poseidon252MerkleHasher, D>(
+            log_size,
+            prev_layer,
+            columns,
+            log_chunk_size,
+        )
+    }
+
+    fn start_layer(
+        log_size: u32,
+        prev_layer: Option<&DiskColumn<FieldElement252, D>>,
+    ) -> Self::LayerState {
+        NodeStates::start(log_size, prev_layer)
+    }
+
+    fn update_layer(state: &mut Self::LayerState, columns: &[&DiskColumn<BaseField, D>]) {
+        state.update(columns);
+    }
+
+    fn finish_layer(state: Self::LayerState) -> DiskColumn<FieldElement252, D> {
+        state.finish()
+    }
+
+    #[cfg(feature = "parallel")]
+    fn read_rows(columns: &[&DiskColumn<BaseField, D>], rows: &[usize]) -> Vec<Vec<BaseField>> {
+        par_read_rows(columns, rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::core::backend::disk::{DiskBackend, DiskColumn, Spill};
+    use crate::core::backend::simd::column::BaseColumn;
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
+    use crate::core::vcs::prover::MerkleProver;
+
+    #[test]
+    fn test_disk_merkle_root_matches_simd() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let columns = [7, 7, 4, 2].map(|log_size| {
+            (0..1 << log_size)
+                .map(|_| rng.gen())
+                .collect::<BaseColumn>()
+        });
+
+        let root =
+            MerkleProver::<SimdBackend, Poseidon252MerkleHasher>::commit(columns.iter().collect())
+                .root();
+        let disk_columns = columns.each_ref().map(DiskColumn::<BaseField>::spill);
+        let disk_root = MerkleProver::<DiskBackend, Poseidon252MerkleHasher>::commit(
+            disk_columns.iter().collect(),
+        )
+        .root();
+
+        assert_eq!(disk_root, root);
+    }
+}
@@ -0,0 +1,170 @@
+use itertools::Itertools;
+
+use super::{chunk_log_size, row_chunks, DiskBackend, ScratchDir};
+use crate::core::backend::simd::SimdBackend;
+use crate::core::circle::CirclePoint;
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::qm31::SecureField;
+use crate::core::fields::secure_column::SecureColumnByCoords;
+use crate::core::pcs::quotients::{ColumnSampleBatch, QuotientOps};
+use crate::core::poly::circle::{
+    CircleDomain, CircleEvaluation, CirclePoly, PolyOps, SecureEvaluation,
+};
+use crate::core::poly::BitReversedOrder;
+use crate::core::utils::bit_reverse;
+
+impl<D: ScratchDir> QuotientOps for DiskBackend<D> {
+    fn accumulate_quotients(
+        domain: CircleDomain,
+        columns: &[&CircleEvaluation<Self, BaseField, BitReversedOrder>],
+        random_coeff: SecureField,
+        sample_batches: &[ColumnSampleBatch],
+        log_blowup_factor: u32,
+    ) -> SecureEvaluation<Self, BitReversedOrder> {
+        let subdomain_log_size = domain.log_size() - log_blowup_factor;
+        let log_chunk_size = chunk_log_size(subdomain_log_size, columns.len());
+        accumulate_quotients_in_chunks(
+            domain,
+            columns,
+            random_coeff,
+            sample_batches,
+            log_blowup_factor,
+            log_chunk_size,
+        )
+    }
+
+    fn eval_at_extension_points(
+        polys: &[&CirclePoly<Self>],
+        points: &[CirclePoint<SecureField>],
+    ) -> Vec<Vec<SecureField>> {
+        Self::eval_at_points(polys, points)
+    }
+}
+
+/// Accumulates the quotients in chunks of `2^log_chunk_size` rows.
+///
+/// The quotients are of low degree, so they are accumulated on the subdomain made of the first
+/// `1 / 2^log_blowup_factor` rows only, one chunk at a time, and then interpolated and evaluated on
+/// the rest of the domain, like [SimdBackend] does, with the FFTs of [DiskBackend].
+fn accumulate_quotients_in_chunks<D: ScratchDir>(
+    domain: CircleDomain,
+    columns: &[&CircleEvaluation<DiskBackend<D>, BaseField, BitReversedOrder>],
+    random_coeff: SecureField,
+    sample_batches: &[ColumnSampleBatch],
+    log_blowup_factor: u32,
+    log_chunk_size: u32,
+) -> SecureEvaluation<DiskBackend<D>, BitReversedOrder> {
+    let (subdomain, mut subdomain_shifts) = domain.split(log_blowup_factor);
+    // See [SimdBackend::accumulate_quotients] for the order of the shifts.
+    bit_reverse(&mut subdomain_shifts);
+
+    let mut subdomain_values = SecureColumnByCoords::<DiskBackend<D>>::zeros(subdomain.size());
+    for (chunk_index, chunk_domain) in row_chunks(subdomain, log_chunk_size)
+        .into_iter()
+        .enumerate()
+    {
+        let start = chunk_index << log_chunk_size;
+        let rows = start..start + chunk_domain.size();
+        let chunk_columns = columns
+            .iter()
+            .map(|column| {
+                CircleEvaluation::new(chunk_domain, column.values.load_rows(rows.clone()))
+            })
+            .collect_vec();
+        // The chunk is a subset of the subdomain, on which the quotients are not of low degree.
+        let chunk = SimdBackend::accumulate_quotients(
+            chunk_domain,
+            &chunk_columns.iter().collect_vec(),
+            random_coeff,
+            sample_batches,
+            0,
+        );
+        for (values, chunk_values) in subdomain_values.columns.iter_mut().zip(&chunk.columns) {
+            values.store_rows(rows.start, chunk_values);
+        }
+    }
+
+    let twiddles = DiskBackend::<D>::precompute_twiddles(subdomain.half_coset);
+    let subdomain_polys = subdomain_values.columns.map(|values| {
+        CircleEvaluation::<DiskBackend<D>, BaseField, BitReversedOrder>::new(subdomain, values)
+            .interpolate_with_twiddles(&twiddles)
+    });
+    let mut values = SecureColumnByCoords::<DiskBackend<D>>::zeros(domain.size());
+    for (shift_index, &shift) in subdomain_shifts.iter().enumerate() {
+        let shifted_subdomain = subdomain.shift(shift);
+        let twiddles = DiskBackend::<D>::precompute_twiddles(shifted_subdomain.half_coset);
+        let rows = shift_index * subdomain.size()..(shift_index + 1) * subdomain.size();
+        for (column, poly) in values.columns.iter_mut().zip(&subdomain_polys) {
+            let eval = poly.evaluate_with_twiddles(shifted_subdomain, &twiddles);
+            column.as_mut_slice()[rows.clone()].copy_from_slice(eval.values.as_slice());
+        }
+    }
+    SecureEvaluation::new(domain, values)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::accumulate_quotients_in_chunks;
+    use crate::core::backend::disk::{DiskBackend, Spill};
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::circle::SECURE_FIELD_CIRCLE_GEN;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::pcs::quotients::{ColumnSampleBatch, QuotientOps};
+    use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, CirclePoly};
+    use crate::core::poly::BitReversedOrder;
+
+    #[test]
+    fn test_accumulate_quotients_in_chunks() {
+        const LOG_SIZE: u32 = 8;
+        const LOG_BLOWUP_FACTOR: u32 = 1;
+        let mut rng = SmallRng::seed_from_u64(0);
+        let domain = CanonicCoset::new(LOG_SIZE + LOG_BLOWUP_FACTOR).circle_domain();
+        let polys = (0..3)
+            .map(|_| {
+                CirclePoly::<SimdBackend>::new((0..1 << LOG_SIZE).map(|_| rng.gen()).collect())
+            })
+            .collect_vec();
+        let columns = polys.iter().map(|poly| poly.evaluate(domain)).collect_vec();
+        let point = SECURE_FIELD_CIRCLE_GEN;
+        let sample_batches = vec![ColumnSampleBatch::new(
+            point,
+            polys
+                .iter()
+                .enumerate()
+                .map(|(i, poly)| (i, poly.eval_at_point(point)))
+                .collect(),
+        )];
+        let random_coeff = rng.gen();
+        let expected = SimdBackend::accumulate_quotients(
+            domain,
+            &columns.iter().collect_vec(),
+            random_coeff,
+            &sample_batches,
+            LOG_BLOWUP_FACTOR,
+        );
+
+        let disk_columns = columns
+            .iter()
+            .map(CircleEvaluation::<DiskBackend, BaseField, BitReversedOrder>::spill)
+            .collect_vec();
+        for log_chunk_size in [6, 7, LOG_SIZE] {
+            let quotients = accumulate_quotients_in_chunks(
+                domain,
+                &disk_columns.iter().collect_vec(),
+                random_coeff,
+                &sample_batches,
+                LOG_BLOWUP_FACTOR,
+                log_chunk_size,
+            );
+            assert_eq!(
+                quotients.values.load().to_vec(),
+                expected.values.to_vec(),
+                "log chunk size {log_chunk_size}"
+            );
+        }
+    }
+}
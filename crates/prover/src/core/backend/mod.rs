@@ -15,6 +15,8 @@ use super::proof_of_work::GrindOps;
 use super::vcs::ops::MerkleOps;
 
 pub mod cpu;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod disk;
 pub mod simd;
 
 pub trait Backend:
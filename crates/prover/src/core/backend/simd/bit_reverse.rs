@@ -1,5 +1,6 @@
 use std::array;
 
+use bytemuck::cast_slice_mut;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
@@ -41,6 +42,16 @@ impl ColumnOps<SecureField> for SimdBackend {
     }
 }
 
+/// Bit reverses the M31 values of `data`, whose length is a power of two, falling back to cpu
+/// bit_reverse for small sizes.
+pub(crate) fn bit_reverse_packed(data: &mut [PackedBaseField]) {
+    if data.len().ilog2() < MIN_LOG_SIZE {
+        cpu_bit_reverse(cast_slice_mut::<_, BaseField>(data));
+        return;
+    }
+    bit_reverse_m31(data);
+}
+
 /// Bit reverses M31 values.
 ///
 /// Given an array `A[0..2^n)`, computes `B[i] = A[bit_reverse(i)]`.
@@ -11,15 +11,21 @@ use itertools::Itertools;
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use super::m31::{LOG_N_LANES, N_LANES};
+use super::column::BaseColumn;
+use super::m31::LOG_N_LANES;
 use super::SimdBackend;
 use crate::core::backend::{Col, Column, ColumnOps};
 use crate::core::fields::m31::BaseField;
 use crate::core::vcs::blake2_hash::Blake2sHash;
 use crate::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+#[cfg(feature = "parallel")]
+use crate::core::vcs::ops::par_read_rows;
 use crate::core::vcs::ops::{MerkleHasher, MerkleOps};
 use crate::parallel_iter;
 
+/// The number of words in a message block of the compression function.
+const N_WORDS_PER_BLOCK: usize = 16;
+
 const IV: [u32; 8] = [
     0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
 ];
@@ -46,6 +52,8 @@ impl ColumnOps<Blake2sHash> for SimdBackend {
 }
 
 impl MerkleOps<Blake2sMerkleHasher> for SimdBackend {
+    type LayerState = Blake2sLayerState;
+
     fn commit_on_layer(
         log_size: u32,
         prev_layer: Option<&Vec<Blake2sHash>>,
@@ -66,8 +74,6 @@ impl MerkleOps<Blake2sMerkleHasher> for SimdBackend {
             assert_eq!(prev_layer.len(), 1 << (log_size + 1));
         }
 
-        let zeros = u32x16::splat(0);
-
         // Commit to columns.
         let mut res = vec![Blake2sHash::default(); 1 << log_size];
         #[cfg(not(feature = "parallel"))]
@@ -77,42 +83,214 @@ impl MerkleOps<Blake2sMerkleHasher> for SimdBackend {
         let iter = res.par_chunks_mut(1 << LOG_N_LANES);
 
         iter.enumerate().for_each(|(i, chunk)| {
-            let mut state: [u32x16; 8] = unsafe { std::mem::zeroed() };
-            // Hash prev_layer, if exists.
-            if let Some(prev_layer) = prev_layer {
-                let prev_chunk_u32s = cast_slice::<_, u32>(&prev_layer[(i << 5)..((i + 1) << 5)]);
-                // Note: prev_layer might be unaligned.
-                let msgs: [u32x16; 16] = array::from_fn(|j| {
-                    u32x16::from_array(std::array::from_fn(|k| prev_chunk_u32s[16 * j + k]))
-                });
-                state = compress16(state, transpose_msgs(msgs), zeros, zeros, zeros, zeros);
-            }
-
-            // Hash columns in chunks of 16.
-            let mut col_chunk_iter = columns.array_chunks();
-            for col_chunk in &mut col_chunk_iter {
-                let msgs = col_chunk.map(|column| column.data[i].into_simd());
-                state = compress16(state, msgs, zeros, zeros, zeros, zeros);
-            }
-
-            // Hash remaining columns.
-            let remainder = col_chunk_iter.remainder();
-            if !remainder.is_empty() {
-                let msgs = remainder
-                    .iter()
-                    .map(|column| column.data[i].into_simd())
-                    .chain(repeat(zeros))
-                    .take(N_LANES)
-                    .collect_vec()
-                    .try_into()
-                    .unwrap();
-                state = compress16(state, msgs, zeros, zeros, zeros, zeros);
-            }
+            let state = hash_children16(prev_layer.map(Vec::as_slice), i);
+            let state = compress_columns16(state, columns, i);
             let state: [Blake2sHash; 16] = unsafe { transmute(untranspose_states(state)) };
             chunk.copy_from_slice(&state);
         });
         res
     }
+
+    fn start_layer(log_size: u32, prev_layer: Option<&Vec<Blake2sHash>>) -> Blake2sLayerState {
+        if log_size < LOG_N_LANES {
+            return Blake2sLayerState::Small {
+                log_size,
+                prev_layer: prev_layer.cloned(),
+                columns: Vec::new(),
+            };
+        }
+        Blake2sLayerState::Packed {
+            states: start_states(log_size, prev_layer.map(Vec::as_slice)),
+            pending_columns: Vec::new(),
+        }
+    }
+
+    fn update_layer(state: &mut Blake2sLayerState, columns: &[&BaseColumn]) {
+        match state {
+            Blake2sLayerState::Small {
+                columns: layer_columns,
+                ..
+            } => layer_columns.extend(columns.iter().map(|&column| column.clone())),
+            Blake2sLayerState::Packed {
+                states,
+                pending_columns,
+            } => {
+                let n_compressed = n_columns_to_compress(pending_columns.len(), columns.len());
+                let (compressed, pending) = columns.split_at(n_compressed);
+                if !compressed.is_empty() {
+                    let compressed = pending_columns
+                        .iter()
+                        .chain(compressed.iter().copied())
+                        .collect_vec();
+                    compress_columns(states, &compressed);
+                    pending_columns.clear();
+                }
+                pending_columns.extend(pending.iter().map(|&column| column.clone()));
+            }
+        }
+    }
+
+    fn finish_layer(state: Blake2sLayerState) -> Vec<Blake2sHash> {
+        match state {
+            Blake2sLayerState::Small {
+                log_size,
+                prev_layer,
+                columns,
+            } => <Self as MerkleOps<Blake2sMerkleHasher>>::commit_on_layer(
+                log_size,
+                prev_layer.as_ref(),
+                &columns.iter().collect_vec(),
+            ),
+            Blake2sLayerState::Packed {
+                states,
+                pending_columns,
+            } => finalize_states(&states, &pending_columns.iter().collect_vec()),
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    fn read_rows(columns: &[&Col<Self, BaseField>], rows: &[usize]) -> Vec<Vec<BaseField>> {
+        par_read_rows(columns, rows)
+    }
+}
+
+/// The partial hashes of a layer of [SimdBackend] nodes. See [MerkleOps::start_layer].
+///
+/// The compression states of the nodes are kept transposed, 16 nodes per [u32x16] word, which
+/// takes 32 bytes per node. The columns are compressed 16 at a time, so up to 15 columns that don't
+/// fill a block yet are copied, which takes up to 60 more bytes per node.
+#[derive(Debug)]
+pub enum Blake2sLayerState {
+    /// A layer smaller than a packed row, which is committed as a whole when it is finished.
+    Small {
+        log_size: u32,
+        prev_layer: Option<Vec<Blake2sHash>>,
+        columns: Vec<BaseColumn>,
+    },
+    Packed {
+        /// The compression states of the nodes of each packed row.
+        states: Vec<[u32x16; 8]>,
+        /// The columns added since the last compression, fewer than a block.
+        pending_columns: Vec<BaseColumn>,
+    },
+}
+
+/// Returns how many of `n_columns` columns, added to a layer whose nodes have `n_pending` column
+/// values that don't fill a block, to compress right away along with the pending ones, so that
+/// they fill whole blocks. The other columns become pending.
+pub(crate) fn n_columns_to_compress(n_pending: usize, n_columns: usize) -> usize {
+    ((n_pending + n_columns) / N_WORDS_PER_BLOCK * N_WORDS_PER_BLOCK).saturating_sub(n_pending)
+}
+
+/// Returns the compression states of the nodes of a layer of `2^log_size` nodes, at least a packed
+/// row, after hashing their children in `prev_layer`, if it exists.
+pub(crate) fn start_states(log_size: u32, prev_layer: Option<&[Blake2sHash]>) -> Vec<[u32x16; 8]> {
+    assert!(log_size >= LOG_N_LANES);
+    if let Some(prev_layer) = prev_layer {
+        assert_eq!(prev_layer.len(), 1 << (log_size + 1));
+    }
+    parallel_iter!(0..1 << (log_size - LOG_N_LANES))
+        .map(|i| hash_children16(prev_layer, i))
+        .collect()
+}
+
+/// Adds `columns`, which fill whole blocks, to the compression states of the nodes of each packed
+/// row.
+pub(crate) fn compress_columns(states: &mut [[u32x16; 8]], columns: &[&BaseColumn]) {
+    debug_assert_eq!(columns.len() % N_WORDS_PER_BLOCK, 0);
+
+    #[cfg(not(feature = "parallel"))]
+    let iter = states.iter_mut();
+
+    #[cfg(feature = "parallel")]
+    let iter = states.par_iter_mut();
+
+    iter.enumerate().for_each(|(i, state)| {
+        *state = compress_columns16(*state, columns, i);
+    });
+}
+
+/// Returns the hashes of the nodes, after adding the last `columns` to their compression states.
+/// The last block is padded with zeros.
+///
+/// With no columns, the hashes are the compression states themselves, which
+/// [states_from_hashes] reads back.
+pub(crate) fn finalize_states(states: &[[u32x16; 8]], columns: &[&BaseColumn]) -> Vec<Blake2sHash> {
+    let mut res = vec![Blake2sHash::default(); states.len() << LOG_N_LANES];
+    #[cfg(not(feature = "parallel"))]
+    let iter = res.chunks_mut(1 << LOG_N_LANES);
+
+    #[cfg(feature = "parallel")]
+    let iter = res.par_chunks_mut(1 << LOG_N_LANES);
+
+    iter.enumerate().for_each(|(i, chunk)| {
+        let state = compress_columns16(states[i], columns, i);
+        let state: [Blake2sHash; 16] = unsafe { transmute(untranspose_states(state)) };
+        chunk.copy_from_slice(&state);
+    });
+    res
+}
+
+/// Returns the compression states that [finalize_states] wrote as hashes, with no columns.
+pub(crate) fn states_from_hashes(hashes: &[Blake2sHash]) -> Vec<[u32x16; 8]> {
+    #[cfg(not(feature = "parallel"))]
+    let iter = hashes.chunks(1 << LOG_N_LANES);
+
+    #[cfg(feature = "parallel")]
+    let iter = hashes.par_chunks(1 << LOG_N_LANES);
+
+    iter.map(|chunk| {
+        let chunk_u32s = cast_slice::<_, u32>(chunk);
+        // Note: hashes might be unaligned.
+        transpose_states(array::from_fn(|j| {
+            u32x16::from_array(std::array::from_fn(|k| chunk_u32s[16 * j + k]))
+        }))
+    })
+    .collect()
+}
+
+/// Returns the compression states of the nodes of packed row `i`, after hashing their children
+/// in `prev_layer`, if it exists.
+fn hash_children16(prev_layer: Option<&[Blake2sHash]>, i: usize) -> [u32x16; 8] {
+    let zeros = u32x16::splat(0);
+    let state: [u32x16; 8] = unsafe { std::mem::zeroed() };
+    let Some(prev_layer) = prev_layer else {
+        return state;
+    };
+    let prev_chunk_u32s = cast_slice::<_, u32>(&prev_layer[(i << 5)..((i + 1) << 5)]);
+    // Note: prev_layer might be unaligned.
+    let msgs: [u32x16; 16] = array::from_fn(|j| {
+        u32x16::from_array(std::array::from_fn(|k| prev_chunk_u32s[16 * j + k]))
+    });
+    compress16(state, transpose_msgs(msgs), zeros, zeros, zeros, zeros)
+}
+
+/// Adds the values of packed row `i` of `columns` to the compression states of its nodes, in
+/// blocks of 16 columns. The last block is padded with zeros.
+fn compress_columns16(mut state: [u32x16; 8], columns: &[&BaseColumn], i: usize) -> [u32x16; 8] {
+    let zeros = u32x16::splat(0);
+
+    // Hash columns in chunks of 16.
+    let mut col_chunk_iter = columns.array_chunks();
+    for col_chunk in &mut col_chunk_iter {
+        let msgs = col_chunk.map(|column| column.data[i].into_simd());
+        state = compress16(state, msgs, zeros, zeros, zeros, zeros);
+    }
+
+    // Hash remaining columns.
+    let remainder = col_chunk_iter.remainder();
+    if !remainder.is_empty() {
+        let msgs = remainder
+            .iter()
+            .map(|column| column.data[i].into_simd())
+            .chain(repeat(zeros))
+            .take(N_WORDS_PER_BLOCK)
+            .collect_vec()
+            .try_into()
+            .unwrap();
+        state = compress16(state, msgs, zeros, zeros, zeros, zeros);
+    }
+    state
 }
 
 /// Applies [`u32::rotate_right(N)`] to each element of the vector
@@ -284,6 +462,25 @@ fn untranspose_states(mut states: [u32x16; 8]) -> [u32x16; 8] {
     states
 }
 
+/// Transposes states, from 8 packed words, to get 16 results, each of size 32B.
+fn transpose_states(mut states: [u32x16; 8]) -> [u32x16; 8] {
+    // Index abc:xyzw, refers to a specific word in data as follows:
+    //   abc - chunk index (in base 2)
+    //   xyzw - word offset (in base 2)
+    // Transpose by applying 3 times the index permutation:
+    //   abc:xyzw => wab:cxyz
+    // In other words, rotate the index to the right by 1.
+    for _ in 0..3 {
+        let (s0, s4) = states[0].deinterleave(states[1]);
+        let (s1, s5) = states[2].deinterleave(states[3]);
+        let (s2, s6) = states[4].deinterleave(states[5]);
+        let (s3, s7) = states[6].deinterleave(states[7]);
+        states = [s0, s1, s2, s3, s4, s5, s6, s7];
+    }
+
+    states
+}
+
 /// Compresses 16 blake2s instances.
 pub fn compress16(
     h_vecs: [u32x16; 8],
@@ -343,8 +540,13 @@ mod tests {
 
     use aligned::{Aligned, A64};
 
-    use super::{compress16, transpose_msgs, untranspose_states};
+    use super::{compress16, transpose_msgs, transpose_states, untranspose_states};
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleHasher;
     use crate::core::vcs::blake2s_ref::compress;
+    use crate::core::vcs::test_utils::{
+        assert_layer_ops_match_commit_on_layer, assert_merkle_matches_cpu,
+    };
 
     #[test]
     fn compress16_works() {
@@ -386,22 +588,13 @@ mod tests {
         assert_eq!(untrasponsed_transposed_states, states)
     }
 
-    /// Transposes states, from 8 packed words, to get 16 results, each of size 32B.
-    fn transpose_states(mut states: [u32x16; 8]) -> [u32x16; 8] {
-        // Index abc:xyzw, refers to a specific word in data as follows:
-        //   abc - chunk index (in base 2)
-        //   xyzw - word offset (in base 2)
-        // Transpose by applying 3 times the index permutation:
-        //   abc:xyzw => wab:cxyz
-        // In other words, rotate the index to the right by 1.
-        for _ in 0..3 {
-            let (s0, s4) = states[0].deinterleave(states[1]);
-            let (s1, s5) = states[2].deinterleave(states[3]);
-            let (s2, s6) = states[4].deinterleave(states[5]);
-            let (s3, s7) = states[6].deinterleave(states[7]);
-            states = [s0, s1, s2, s3, s4, s5, s6, s7];
-        }
+    #[test]
+    fn test_merkle_matches_cpu() {
+        assert_merkle_matches_cpu::<SimdBackend, Blake2sMerkleHasher>();
+    }
 
-        states
+    #[test]
+    fn test_layer_ops_match_commit_on_layer() {
+        assert_layer_ops_match_commit_on_layer::<SimdBackend, Blake2sMerkleHasher>();
     }
 }
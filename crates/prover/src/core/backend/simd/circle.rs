@@ -1,15 +1,17 @@
+use std::collections::BTreeMap;
 use std::iter::zip;
 use std::mem::transmute;
 use std::simd::Simd;
 
-use bytemuck::Zeroable;
-use num_traits::One;
+use bytemuck::{cast_slice, Zeroable};
+use itertools::Itertools;
+use num_traits::{One, Zero};
 
 use super::fft::{ifft, rfft, CACHED_FFT_LOG_SIZE, MIN_FFT_LOG_SIZE};
 use super::m31::{PackedBaseField, LOG_N_LANES, N_LANES};
 use super::qm31::PackedSecureField;
 use super::SimdBackend;
-use crate::core::backend::cpu::circle::slow_precompute_twiddles;
+use crate::core::backend::cpu::circle::{slow_precompute_twiddles, PointTwiddles};
 use crate::core::backend::simd::column::BaseColumn;
 use crate::core::backend::simd::m31::PackedM31;
 use crate::core::backend::{Col, Column, CpuBackend};
@@ -154,21 +156,7 @@ impl PolyOps for SimdBackend {
         }
 
         let mut values = eval.values;
-        let twiddles = domain_line_twiddles_from_tree(eval.domain, &twiddles.itwiddles);
-
-        // Safe because [PackedBaseField] is aligned on 64 bytes.
-        unsafe {
-            ifft::ifft(
-                transmute(values.data.as_mut_ptr()),
-                &twiddles,
-                log_size as usize,
-            );
-        }
-
-        // TODO(alont): Cache this inversion.
-        let inv = PackedBaseField::broadcast(BaseField::from(eval.domain.size()).inverse());
-        values.data.iter_mut().for_each(|x| *x *= inv);
-
+        interpolate_packed(&mut values.data, eval.domain, twiddles);
         CirclePoly::new(values)
     }
 
@@ -176,7 +164,7 @@ impl PolyOps for SimdBackend {
         // If the polynomial is small, fallback to evaluate directly.
         // TODO(Ohad): it's possible to avoid falling back. Consider fixing.
         if poly.log_size() <= 8 {
-            return slow_eval_at_point(poly, point);
+            return slow_eval_at_point(poly.coeffs.as_slice(), point);
         }
 
         let mappings = Self::generate_evaluation_mappings(point, poly.log_size());
@@ -218,12 +206,38 @@ impl PolyOps for SimdBackend {
         (sum * twiddle_lows).pointwise_sum()
     }
 
+    fn eval_at_points(
+        polys: &[&CirclePoly<Self>],
+        points: &[CirclePoint<SecureField>],
+    ) -> Vec<Vec<SecureField>> {
+        let polys = polys
+            .iter()
+            .map(|poly| (poly.log_size(), &poly.coeffs.data[..]))
+            .collect_vec();
+        eval_packed_at_points(&polys, points)
+    }
+
     fn extend(poly: &CirclePoly<Self>, log_size: u32) -> CirclePoly<Self> {
         // TODO(shahars): Get rid of extends.
         poly.evaluate(CanonicCoset::new(log_size).circle_domain())
             .interpolate()
     }
 
+    fn truncate(poly: CirclePoly<Self>, log_size: u32) -> Option<CirclePoly<Self>> {
+        assert!(log_size <= poly.log_size());
+        let mut coeffs = poly.coeffs;
+        if !coeffs.as_slice()[1 << log_size..]
+            .iter()
+            .all(BaseField::is_zero)
+        {
+            return None;
+        }
+        // The dropped coefficients are zero, so the padding of the last vector already is.
+        coeffs.data.truncate((1usize << log_size).div_ceil(N_LANES));
+        coeffs.length = 1 << log_size;
+        Some(CirclePoly::new(coeffs))
+    }
+
     fn evaluate(
         poly: &CirclePoly<Self>,
         domain: CircleDomain,
@@ -245,41 +259,19 @@ impl PolyOps for SimdBackend {
             );
         }
 
-        let twiddles = domain_line_twiddles_from_tree(domain, &twiddles.twiddles);
-
-        // Evaluate on a big domains by evaluating on several subdomains.
-        let log_subdomains = log_size - fft_log_size;
-
         // Allocate the destination buffer without initializing.
         let mut values = Vec::with_capacity(domain.size() >> LOG_N_LANES);
         #[allow(clippy::uninit_vec)]
         unsafe {
             values.set_len(domain.size() >> LOG_N_LANES)
         };
-
-        for i in 0..(1 << log_subdomains) {
-            // The subdomain twiddles are a slice of the large domain twiddles.
-            let subdomain_twiddles = (0..(fft_log_size - 1))
-                .map(|layer_i| {
-                    &twiddles[layer_i as usize]
-                        [i << (fft_log_size - 2 - layer_i)..(i + 1) << (fft_log_size - 2 - layer_i)]
-                })
-                .collect::<Vec<_>>();
-
-            // FFT from the coefficients buffer to the values chunk.
-            unsafe {
-                rfft::fft(
-                    transmute(poly.coeffs.data.as_ptr()),
-                    transmute(
-                        values[i << (fft_log_size - LOG_N_LANES)
-                            ..(i + 1) << (fft_log_size - LOG_N_LANES)]
-                            .as_mut_ptr(),
-                    ),
-                    &subdomain_twiddles,
-                    fft_log_size as usize,
-                );
-            }
-        }
+        evaluate_packed(
+            &poly.coeffs.data,
+            fft_log_size,
+            domain,
+            twiddles,
+            &mut values,
+        );
 
         CircleEvaluation::new(
             domain,
@@ -332,6 +324,155 @@ impl PolyOps for SimdBackend {
             itwiddles: dbl_itwiddles,
         }
     }
+
+    fn add_scaled_column(
+        dst: &mut Col<Self, BaseField>,
+        src: &Col<Self, BaseField>,
+        alpha: BaseField,
+    ) {
+        assert_eq!(dst.len(), src.len());
+        let alpha = PackedBaseField::broadcast(alpha);
+        zip(&mut dst.data, &src.data).for_each(|(d, &s)| *d += alpha * s);
+    }
+
+    fn mul_column(dst: &mut Col<Self, BaseField>, src: &Col<Self, BaseField>) {
+        assert_eq!(dst.len(), src.len());
+        zip(&mut dst.data, &src.data).for_each(|(d, &s)| *d *= s);
+    }
+}
+
+/// Interpolates the packed `values` of an evaluation on `domain` in place, leaving the packed
+/// coefficients. See [PolyOps::interpolate].
+///
+/// The domain must be of log size at least [MIN_FFT_LOG_SIZE].
+pub(crate) fn interpolate_packed(
+    values: &mut [PackedBaseField],
+    domain: CircleDomain,
+    twiddles: &TwiddleTree<SimdBackend>,
+) {
+    let log_size = domain.log_size();
+    assert!(log_size >= MIN_FFT_LOG_SIZE);
+    assert_eq!(values.len() << LOG_N_LANES, domain.size());
+    let twiddles = domain_line_twiddles_from_tree(domain, &twiddles.itwiddles);
+
+    // Safe because [PackedBaseField] is aligned on 64 bytes.
+    unsafe {
+        ifft::ifft(transmute(values.as_mut_ptr()), &twiddles, log_size as usize);
+    }
+
+    // TODO(alont): Cache this inversion.
+    let inv = PackedBaseField::broadcast(BaseField::from(domain.size()).inverse());
+    values.iter_mut().for_each(|x| *x *= inv);
+}
+
+/// Evaluates the polynomial of log size `fft_log_size` with packed coefficients `coeffs` on
+/// `domain`, into the packed `values`. See [PolyOps::evaluate].
+///
+/// The polynomial must be of log size at least [MIN_FFT_LOG_SIZE].
+pub(crate) fn evaluate_packed(
+    coeffs: &[PackedBaseField],
+    fft_log_size: u32,
+    domain: CircleDomain,
+    twiddles: &TwiddleTree<SimdBackend>,
+    values: &mut [PackedBaseField],
+) {
+    let log_size = domain.log_size();
+    assert!(fft_log_size >= MIN_FFT_LOG_SIZE);
+    assert!(
+        log_size >= fft_log_size,
+        "Can only evaluate on larger domains"
+    );
+    assert_eq!(coeffs.len() << LOG_N_LANES, 1 << fft_log_size);
+    assert_eq!(values.len() << LOG_N_LANES, domain.size());
+    let twiddles = domain_line_twiddles_from_tree(domain, &twiddles.twiddles);
+
+    // Evaluate on a big domains by evaluating on several subdomains.
+    let log_subdomains = log_size - fft_log_size;
+    for i in 0..(1 << log_subdomains) {
+        // The subdomain twiddles are a slice of the large domain twiddles.
+        let subdomain_twiddles = (0..(fft_log_size - 1))
+            .map(|layer_i| {
+                &twiddles[layer_i as usize]
+                    [i << (fft_log_size - 2 - layer_i)..(i + 1) << (fft_log_size - 2 - layer_i)]
+            })
+            .collect::<Vec<_>>();
+
+        // FFT from the coefficients buffer to the values chunk.
+        unsafe {
+            rfft::fft(
+                transmute(coeffs.as_ptr()),
+                transmute(
+                    values[i << (fft_log_size - LOG_N_LANES)
+                        ..(i + 1) << (fft_log_size - LOG_N_LANES)]
+                        .as_mut_ptr(),
+                ),
+                &subdomain_twiddles,
+                fft_log_size as usize,
+            );
+        }
+    }
+}
+
+/// Evaluates polynomials, given by their log sizes and their packed coefficients, at `points`. See
+/// [PolyOps::eval_at_points].
+pub(crate) fn eval_packed_at_points(
+    polys: &[(u32, &[PackedBaseField])],
+    points: &[CirclePoint<SecureField>],
+) -> Vec<Vec<SecureField>> {
+    // Coefficients of polynomials larger than `CACHED_FFT_LOG_SIZE` are stored in a transposed
+    // order which depends on the log size (see `generate_evaluation_mappings`), so these need
+    // their own tables. Smaller polynomials share the tables of size `CACHED_FFT_LOG_SIZE`.
+    let table_log_size = |log_size: u32| log_size.max(CACHED_FFT_LOG_SIZE);
+    // The low table is packed, so it must span at least one packed word.
+    let log_low_size = |log_size: u32| log_size.div_ceil(2).max(LOG_N_LANES);
+    let mut tables = BTreeMap::new();
+    for &(log_size, _) in polys {
+        let log_size = table_log_size(log_size);
+        tables.entry(log_size).or_insert_with(|| {
+            points
+                .iter()
+                .map(|&point| {
+                    let mappings = SimdBackend::generate_evaluation_mappings(point, log_size);
+                    let PointTwiddles { low, high } =
+                        PointTwiddles::from_mappings(&mappings, log_low_size(log_size));
+                    let packed_low = low
+                        .array_chunks::<N_LANES>()
+                        .map(|&chunk| PackedSecureField::from_array(chunk))
+                        .collect_vec();
+                    (packed_low, high)
+                })
+                .collect_vec()
+        });
+    }
+
+    polys
+        .iter()
+        .map(|&(log_size, coeffs)| {
+            // Small polynomials don't fill a packed word, fallback to evaluate directly.
+            if log_size < LOG_N_LANES {
+                let coeffs = &cast_slice(coeffs)[..1 << log_size];
+                return points
+                    .iter()
+                    .map(|&point| slow_eval_at_point(coeffs, point))
+                    .collect();
+            }
+            let log_size = table_log_size(log_size);
+            let tables = &tables[&log_size];
+            let chunk_size = 1 << (log_low_size(log_size) - LOG_N_LANES);
+            // Traverse the coefficients once, accumulating the sums of all the points.
+            let mut sums = vec![PackedSecureField::zeroed(); points.len()];
+            for (i, chunk) in coeffs.chunks(chunk_size).enumerate() {
+                for ((packed_low, high), sum) in zip(tables, &mut sums) {
+                    let mut low_sum = PackedSecureField::zeroed();
+                    for (&packed_coeffs, &low) in zip(chunk, packed_low) {
+                        low_sum += low * packed_coeffs;
+                    }
+                    *sum += PackedSecureField::broadcast(high[i]) * low_sum;
+                }
+            }
+            sums.into_iter().map(|sum| sum.pointwise_sum()).collect()
+        })
+        .collect()
 }
 
 fn compute_small_coset_twiddles(coset: Coset) -> TwiddleTree<SimdBackend> {
@@ -380,20 +521,19 @@ fn compute_coset_twiddles(coset: Coset, twiddles: &mut Vec<PackedM31>) {
     }
 }
 
-fn slow_eval_at_point(
-    poly: &CirclePoly<SimdBackend>,
-    point: CirclePoint<SecureField>,
-) -> SecureField {
+/// Evaluates the polynomial with coefficients `coeffs`, in the order of [SimdBackend], at `point`.
+fn slow_eval_at_point(coeffs: &[BaseField], point: CirclePoint<SecureField>) -> SecureField {
+    let log_size = coeffs.len().ilog2();
     let mut mappings = vec![point.y, point.x];
     let mut x = point.x;
-    for _ in 2..poly.log_size() {
+    for _ in 2..log_size {
         x = CirclePoint::double_x(x);
         mappings.push(x);
     }
     mappings.reverse();
 
     // If the polynomial is large, the fft does a transpose in the middle.
-    if poly.log_size() > CACHED_FFT_LOG_SIZE {
+    if log_size > CACHED_FFT_LOG_SIZE {
         let n = mappings.len();
         let n0 = (n - LOG_N_LANES as usize) / 2;
         let n1 = (n - LOG_N_LANES as usize + 1) / 2;
@@ -402,20 +542,23 @@ fn slow_eval_at_point(
         // Swap content of a,c.
         a.swap_with_slice(&mut c[0..n0]);
     }
-    fold(poly.coeffs.as_slice(), &mappings)
+    fold(coeffs, &mappings)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::iter::zip;
+
     use itertools::Itertools;
     use rand::rngs::SmallRng;
     use rand::{Rng, SeedableRng};
 
     use crate::core::backend::simd::circle::slow_eval_at_point;
     use crate::core::backend::simd::fft::{CACHED_FFT_LOG_SIZE, MIN_FFT_LOG_SIZE};
+    use crate::core::backend::simd::m31::LOG_N_LANES;
     use crate::core::backend::simd::SimdBackend;
     use crate::core::backend::{Column, CpuBackend};
-    use crate::core::circle::CirclePoint;
+    use crate::core::circle::{CirclePoint, CirclePointIndex};
     use crate::core::fields::m31::BaseField;
     use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, CirclePoly, PolyOps};
     use crate::core::poly::{BitReversedOrder, NaturalOrder};
@@ -494,6 +637,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_circle_poly_truncate() {
+        for log_size in 1..LOG_N_LANES + 3 {
+            let poly =
+                CirclePoly::<SimdBackend>::new((1..=1 << log_size).map(BaseField::from).collect());
+            let extended = poly.extend(log_size + 2);
+
+            let truncated = SimdBackend::truncate(extended.clone(), log_size).unwrap();
+
+            assert_eq!(truncated.coeffs.to_cpu(), poly.coeffs.to_cpu());
+            assert!(SimdBackend::truncate(extended, log_size - 1).is_none());
+        }
+    }
+
     #[test]
     fn test_eval_securefield() {
         let mut rng = SmallRng::seed_from_u64(0);
@@ -510,10 +667,78 @@ mod tests {
 
             let eval = PolyOps::eval_at_point(&poly, p);
 
-            assert_eq!(eval, slow_eval_at_point(&poly, p), "log_size = {log_size}");
+            assert_eq!(
+                eval,
+                slow_eval_at_point(poly.coeffs.as_slice(), p),
+                "log_size = {log_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_evaluate_on_shifted_domain() {
+        let log_size = MIN_FFT_LOG_SIZE + 1;
+        let coeffs = (0..1 << log_size).map(BaseField::from).collect_vec();
+        let poly = CirclePoly::<SimdBackend>::new(coeffs.iter().copied().collect());
+        let cpu_poly = CirclePoly::<CpuBackend>::new(coeffs);
+        let domain = CanonicCoset::new(log_size + 1)
+            .circle_domain()
+            .shift(CirclePointIndex::generator());
+
+        let evaluation = poly.evaluate(domain);
+
+        assert_eq!(evaluation.values.to_cpu(), cpu_poly.evaluate(domain).values);
+        assert_eq!(
+            evaluation.interpolate().coeffs.to_cpu()[..1 << log_size],
+            cpu_poly.coeffs
+        );
+    }
+
+    #[test]
+    fn test_eval_at_points() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let polys = [
+            2,
+            MIN_FFT_LOG_SIZE,
+            CACHED_FFT_LOG_SIZE + 1,
+            CACHED_FFT_LOG_SIZE + 3,
+        ]
+        .map(|log_size| {
+            CirclePoly::<SimdBackend>::new((0..1 << log_size).map(BaseField::from).collect())
+        });
+        let poly_refs = polys.iter().collect_vec();
+        let points: [CirclePoint<_>; 3] = std::array::from_fn(|_| CirclePoint {
+            x: rng.gen(),
+            y: rng.gen(),
+        });
+
+        let evals = SimdBackend::eval_at_points(&poly_refs, &points);
+
+        for (poly, poly_evals) in zip(&polys, evals) {
+            let expected = points.map(|point| slow_eval_at_point(poly.coeffs.as_slice(), point));
+            assert_eq!(poly_evals, expected, "log_size={}", poly.log_size());
         }
     }
 
+    #[test]
+    fn test_circle_poly_arithmetic() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let log_size = MIN_FFT_LOG_SIZE + 1;
+        let a_coeffs: Vec<BaseField> = (0..1 << log_size).map(|_| rng.gen()).collect();
+        let b_coeffs: Vec<BaseField> = (0..1 << (log_size - 1)).map(|_| rng.gen()).collect();
+        let alpha: BaseField = rng.gen();
+        let a = CirclePoly::<SimdBackend>::new(a_coeffs.iter().copied().collect());
+        let b = CirclePoly::<SimdBackend>::new(b_coeffs.iter().copied().collect());
+        let cpu_a = CirclePoly::<CpuBackend>::new(a_coeffs);
+        let cpu_b = CirclePoly::<CpuBackend>::new(b_coeffs);
+
+        assert_eq!(
+            (a.clone() + b.clone() * alpha).coeffs.to_cpu(),
+            (cpu_a.clone() + cpu_b.clone() * alpha).coeffs
+        );
+        assert_eq!((&a * &b).coeffs.to_cpu(), (&cpu_a * &cpu_b).coeffs);
+    }
+
     #[test]
     fn test_optimized_precompute_twiddles() {
         let coset = CanonicCoset::new(10).half_coset();
@@ -1,12 +1,13 @@
 use std::array;
 use std::ops::{Add, Mul, MulAssign, Neg, Sub};
+use std::simd::Mask;
 
 use bytemuck::{Pod, Zeroable};
 use num_traits::{One, Zero};
 
 use super::m31::{PackedM31, N_LANES};
 use crate::core::fields::cm31::CM31;
-use crate::core::fields::FieldExpOps;
+use crate::core::fields::{FieldExpOps, InverseOrZero};
 
 /// SIMD implementation of [`CM31`].
 #[derive(Copy, Clone, Debug)]
@@ -59,6 +60,19 @@ impl PackedCM31 {
         (Self([a_evens, b_evens]), Self([a_odds, b_odds]))
     }
 
+    /// Returns a mask of the lanes holding zero.
+    pub fn zero_mask(&self) -> Mask<i32, N_LANES> {
+        self.a().zero_mask() & self.b().zero_mask()
+    }
+
+    /// Selects the lanes of `if_true` where `mask` is set, and the lanes of `if_false` elsewhere.
+    pub fn select(mask: Mask<i32, N_LANES>, if_true: Self, if_false: Self) -> Self {
+        Self([
+            PackedM31::select(mask, if_true.a(), if_false.a()),
+            PackedM31::select(mask, if_true.b(), if_false.b()),
+        ])
+    }
+
     /// Doubles each element in the vector.
     pub fn double(self) -> Self {
         let Self([a, b]) = self;
@@ -106,6 +120,13 @@ impl Zero for PackedCM31 {
     }
 }
 
+impl InverseOrZero for PackedCM31 {
+    fn select_nonzero(&self, if_nonzero: &Self, if_zero: &Self) -> Self {
+        let mask = self.zero_mask();
+        Self::select(mask, *if_zero, *if_nonzero)
+    }
+}
+
 unsafe impl Pod for PackedCM31 {}
 
 unsafe impl Zeroable for PackedCM31 {
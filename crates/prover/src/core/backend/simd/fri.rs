@@ -1,6 +1,7 @@
 use std::array;
 use std::simd::u32x8;
 
+use itertools::izip;
 use num_traits::Zero;
 
 use super::m31::{PackedBaseField, LOG_N_LANES, N_LANES};
@@ -8,7 +9,6 @@ use super::SimdBackend;
 use crate::core::backend::simd::fft::compute_first_twiddles;
 use crate::core::backend::simd::fft::ifft::simd_ibutterfly;
 use crate::core::backend::simd::qm31::PackedSecureField;
-use crate::core::backend::Column;
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::fields::secure_column::SecureColumnByCoords;
@@ -36,23 +36,12 @@ impl FriOps for SimdBackend {
         let itwiddles = domain_line_twiddles_from_tree(domain, &twiddles.itwiddles)[0];
 
         let mut folded_values = SecureColumnByCoords::<Self>::zeros(1 << (log_size - 1));
-
-        for vec_index in 0..(1 << (log_size - 1 - LOG_N_LANES)) {
-            let value = unsafe {
-                let twiddle_dbl: [u32; 16] =
-                    array::from_fn(|i| *itwiddles.get_unchecked(vec_index * 16 + i));
-                let val0 = eval.values.packed_at(vec_index * 2).into_packed_m31s();
-                let val1 = eval.values.packed_at(vec_index * 2 + 1).into_packed_m31s();
-                let pairs: [_; 4] = array::from_fn(|i| {
-                    let (a, b) = val0[i].deinterleave(val1[i]);
-                    simd_ibutterfly(a, b, std::mem::transmute(twiddle_dbl))
-                });
-                let val0 = PackedSecureField::from_packed_m31s(array::from_fn(|i| pairs[i].0));
-                let val1 = PackedSecureField::from_packed_m31s(array::from_fn(|i| pairs[i].1));
-                val0 + PackedSecureField::broadcast(alpha) * val1
-            };
-            unsafe { folded_values.set_packed(vec_index, value) };
-        }
+        fold_line_packed(
+            packed_coordinates(&eval.values),
+            itwiddles,
+            alpha,
+            packed_coordinates_mut(&mut folded_values),
+        );
 
         LineEvaluation::new(domain.double(), folded_values)
     }
@@ -75,84 +64,144 @@ impl FriOps for SimdBackend {
             return;
         }
 
-        let domain = src.domain;
-        let alpha_sq = alpha * alpha;
-        let itwiddles = domain_line_twiddles_from_tree(domain, &twiddles.itwiddles)[0];
-
-        for vec_index in 0..(1 << (log_size - 1 - LOG_N_LANES)) {
-            let value = unsafe {
-                // The 16 twiddles of the circle domain can be derived from the 8 twiddles of the
-                // next line domain. See `compute_first_twiddles()`.
-                let twiddle_dbl = u32x8::from_array(array::from_fn(|i| {
-                    *itwiddles.get_unchecked(vec_index * 8 + i)
-                }));
-                let (t0, _) = compute_first_twiddles(twiddle_dbl);
-                let val0 = src.values.packed_at(vec_index * 2).into_packed_m31s();
-                let val1 = src.values.packed_at(vec_index * 2 + 1).into_packed_m31s();
-                let pairs: [_; 4] = array::from_fn(|i| {
-                    let (a, b) = val0[i].deinterleave(val1[i]);
-                    simd_ibutterfly(a, b, t0)
-                });
-                let val0 = PackedSecureField::from_packed_m31s(array::from_fn(|i| pairs[i].0));
-                let val1 = PackedSecureField::from_packed_m31s(array::from_fn(|i| pairs[i].1));
-                val0 + PackedSecureField::broadcast(alpha) * val1
-            };
-            unsafe {
-                dst.values.set_packed(
-                    vec_index,
-                    dst.values.packed_at(vec_index) * PackedSecureField::broadcast(alpha_sq)
-                        + value,
-                )
-            };
-        }
+        let itwiddles = domain_line_twiddles_from_tree(src.domain, &twiddles.itwiddles)[0];
+        fold_circle_into_line_packed(
+            packed_coordinates_mut(&mut dst.values),
+            packed_coordinates(&src.values),
+            itwiddles,
+            alpha,
+        );
     }
 
     fn decompose(
         eval: &SecureEvaluation<Self, BitReversedOrder>,
     ) -> (SecureEvaluation<Self, BitReversedOrder>, SecureField) {
-        let lambda = decomposition_coefficient(eval);
-        let broadcasted_lambda = PackedSecureField::broadcast(lambda);
         let mut g_values = SecureColumnByCoords::<Self>::zeros(eval.len());
-
-        let range = eval.len().div_ceil(N_LANES);
-        let half_range = range / 2;
-        for i in 0..half_range {
-            let val = unsafe { eval.packed_at(i) } - broadcasted_lambda;
-            unsafe { g_values.set_packed(i, val) }
-        }
-        for i in half_range..range {
-            let val = unsafe { eval.packed_at(i) } + broadcasted_lambda;
-            unsafe { g_values.set_packed(i, val) }
-        }
+        let lambda = decompose_packed(
+            packed_coordinates(&eval.values),
+            eval.domain.log_size(),
+            packed_coordinates_mut(&mut g_values),
+        );
 
         let g = SecureEvaluation::new(eval.domain, g_values);
         (g, lambda)
     }
 }
 
+fn packed_coordinates(values: &SecureColumnByCoords<SimdBackend>) -> [&[PackedBaseField]; 4] {
+    values.columns.each_ref().map(|column| &column.data[..])
+}
+
+fn packed_coordinates_mut(
+    values: &mut SecureColumnByCoords<SimdBackend>,
+) -> [&mut [PackedBaseField]; 4] {
+    values.columns.each_mut().map(|column| &mut column.data[..])
+}
+
+/// Folds the line evaluation given by the [PackedBaseField]s of its coordinates into
+/// `folded_values`, given the twiddles of its domain. See [FriOps::fold_line].
+pub(crate) fn fold_line_packed(
+    values: [&[PackedBaseField]; 4],
+    itwiddles: &[u32],
+    alpha: SecureField,
+    folded_values: [&mut [PackedBaseField]; 4],
+) {
+    let [x, y, z, w] = folded_values;
+    for (vec_index, (x, y, z, w)) in izip!(x, y, z, w).enumerate() {
+        let value = unsafe {
+            let twiddle_dbl: [u32; 16] =
+                array::from_fn(|i| *itwiddles.get_unchecked(vec_index * 16 + i));
+            let val0 = values.map(|column| column[vec_index * 2]);
+            let val1 = values.map(|column| column[vec_index * 2 + 1]);
+            let pairs: [_; 4] = array::from_fn(|i| {
+                let (a, b) = val0[i].deinterleave(val1[i]);
+                simd_ibutterfly(a, b, std::mem::transmute(twiddle_dbl))
+            });
+            let val0 = PackedSecureField::from_packed_m31s(array::from_fn(|i| pairs[i].0));
+            let val1 = PackedSecureField::from_packed_m31s(array::from_fn(|i| pairs[i].1));
+            val0 + PackedSecureField::broadcast(alpha) * val1
+        };
+        [*x, *y, *z, *w] = value.into_packed_m31s();
+    }
+}
+
+/// Folds the circle evaluation given by the [PackedBaseField]s of its coordinates into the line
+/// evaluation `dst`, given the twiddles of its domain. See [FriOps::fold_circle_into_line].
+pub(crate) fn fold_circle_into_line_packed(
+    dst: [&mut [PackedBaseField]; 4],
+    src: [&[PackedBaseField]; 4],
+    itwiddles: &[u32],
+    alpha: SecureField,
+) {
+    let alpha_sq = alpha * alpha;
+    let [x, y, z, w] = dst;
+    for (vec_index, (x, y, z, w)) in izip!(x, y, z, w).enumerate() {
+        let value = unsafe {
+            // The 16 twiddles of the circle domain can be derived from the 8 twiddles of the
+            // next line domain. See `compute_first_twiddles()`.
+            let twiddle_dbl = u32x8::from_array(array::from_fn(|i| {
+                *itwiddles.get_unchecked(vec_index * 8 + i)
+            }));
+            let (t0, _) = compute_first_twiddles(twiddle_dbl);
+            let val0 = src.map(|column| column[vec_index * 2]);
+            let val1 = src.map(|column| column[vec_index * 2 + 1]);
+            let pairs: [_; 4] = array::from_fn(|i| {
+                let (a, b) = val0[i].deinterleave(val1[i]);
+                simd_ibutterfly(a, b, t0)
+            });
+            let val0 = PackedSecureField::from_packed_m31s(array::from_fn(|i| pairs[i].0));
+            let val1 = PackedSecureField::from_packed_m31s(array::from_fn(|i| pairs[i].1));
+            val0 + PackedSecureField::broadcast(alpha) * val1
+        };
+        let prev = PackedSecureField::from_packed_m31s([*x, *y, *z, *w]);
+        [*x, *y, *z, *w] =
+            (prev * PackedSecureField::broadcast(alpha_sq) + value).into_packed_m31s();
+    }
+}
+
+/// Decomposes the evaluation of log size `log_size` given by the [PackedBaseField]s of its
+/// coordinates into `g_values`, and returns `lambda`. See [FriOps::decompose].
+pub(crate) fn decompose_packed(
+    values: [&[PackedBaseField]; 4],
+    log_size: u32,
+    g_values: [&mut [PackedBaseField]; 4],
+) -> SecureField {
+    let lambda = decomposition_coefficient(values, log_size);
+    let lambda_coordinates = lambda.to_m31_array().map(PackedBaseField::broadcast);
+
+    for (values, g_values, lambda) in izip!(values, g_values, lambda_coordinates) {
+        let half_range = values.len() / 2;
+        for (i, (g, &value)) in g_values.iter_mut().zip(values).enumerate() {
+            *g = if i < half_range {
+                value - lambda
+            } else {
+                value + lambda
+            };
+        }
+    }
+    lambda
+}
+
 /// See [`decomposition_coefficient`].
 ///
 /// [`decomposition_coefficient`]: crate::core::backend::cpu::CpuBackend::decomposition_coefficient
-fn decomposition_coefficient(
-    eval: &SecureEvaluation<SimdBackend, BitReversedOrder>,
-) -> SecureField {
-    let cols = &eval.values.columns;
+fn decomposition_coefficient(cols: [&[PackedBaseField]; 4], log_size: u32) -> SecureField {
     let [mut x_sum, mut y_sum, mut z_sum, mut w_sum] = [PackedBaseField::zero(); 4];
 
-    let range = cols[0].len() / N_LANES;
+    let range = (1 << log_size) / N_LANES;
     let (half_a, half_b) = (range / 2, range);
 
     for i in 0..half_a {
-        x_sum += cols[0].data[i];
-        y_sum += cols[1].data[i];
-        z_sum += cols[2].data[i];
-        w_sum += cols[3].data[i];
+        x_sum += cols[0][i];
+        y_sum += cols[1][i];
+        z_sum += cols[2][i];
+        w_sum += cols[3][i];
     }
     for i in half_a..half_b {
-        x_sum -= cols[0].data[i];
-        y_sum -= cols[1].data[i];
-        z_sum -= cols[2].data[i];
-        w_sum -= cols[3].data[i];
+        x_sum -= cols[0][i];
+        y_sum -= cols[1][i];
+        z_sum -= cols[2][i];
+        w_sum -= cols[3][i];
     }
 
     let x = x_sum.pointwise_sum();
@@ -160,7 +209,7 @@ fn decomposition_coefficient(
     let z = z_sum.pointwise_sum();
     let w = w_sum.pointwise_sum();
 
-    SecureField::from_m31(x, y, z, w) / BaseField::from_u32_unchecked(1 << eval.domain.log_size())
+    SecureField::from_m31(x, y, z, w) / BaseField::from_u32_unchecked(1 << log_size)
 }
 
 #[cfg(test)]
@@ -257,7 +306,8 @@ mod tests {
                 values.values.clone(),
             ],
         };
-        let avx_eval = SecureEvaluation::new(domain, avx_column.clone());
+        let avx_eval =
+            SecureEvaluation::<SimdBackend, BitReversedOrder>::new(domain, avx_column.clone());
         let cpu_eval =
             SecureEvaluation::<CpuBackend, BitReversedOrder>::new(domain, avx_eval.values.to_cpu());
         let (cpu_g, cpu_lambda) = CpuBackend::decompose(&cpu_eval);
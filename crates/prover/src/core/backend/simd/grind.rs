@@ -1,3 +1,5 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::iter::zip;
 use std::simd::cmp::SimdPartialOrd;
 use std::simd::num::SimdUint;
 use std::simd::u32x16;
@@ -7,6 +9,8 @@ use bytemuck::cast_slice;
 use rayon::prelude::*;
 
 use super::blake2s::compress16;
+#[cfg(not(target_arch = "wasm32"))]
+use super::poseidon252::poseidon_hash_batch;
 use super::SimdBackend;
 use crate::core::backend::simd::m31::N_LANES;
 use crate::core::channel::Blake2sChannel;
@@ -17,6 +21,11 @@ use crate::core::proof_of_work::GrindOps;
 // Note: GRIND_LOW_BITS is a cap on how much extra time we need to wait for all threads to finish.
 const GRIND_LOW_BITS: u32 = 20;
 const GRIND_HI_BITS: u32 = 64 - GRIND_LOW_BITS;
+// Poseidon hashes are much slower than Blake2s ones, so the nonces are grinded in smaller blocks.
+#[cfg(not(target_arch = "wasm32"))]
+const POSEIDON_GRIND_LOW_BITS: u32 = 10;
+#[cfg(not(target_arch = "wasm32"))]
+const POSEIDON_GRIND_HI_BITS: u32 = 64 - POSEIDON_GRIND_LOW_BITS;
 
 impl GrindOps<Blake2sChannel> for SimdBackend {
     fn grind(channel: &Blake2sChannel, pow_bits: u32) -> u64 {
@@ -62,18 +71,57 @@ fn grind_blake(digest: &[u32], hi: u64, pow_bits: u32) -> Option<u64> {
     None
 }
 
-// TODO(shahars): This is a naive implementation. Optimize it.
 #[cfg(not(target_arch = "wasm32"))]
 impl GrindOps<Poseidon252Channel> for SimdBackend {
     fn grind(channel: &Poseidon252Channel, pow_bits: u32) -> u64 {
-        let mut nonce = 0;
-        loop {
+        #[cfg(not(feature = "parallel"))]
+        let res = (0..1 << POSEIDON_GRIND_HI_BITS)
+            .find_map(|hi| grind_poseidon(channel, hi, pow_bits))
+            .expect("Grind failed to find a solution.");
+
+        #[cfg(feature = "parallel")]
+        let res = (0..1 << POSEIDON_GRIND_HI_BITS)
+            .into_par_iter()
+            .find_map_any(|hi| grind_poseidon(channel, hi, pow_bits))
+            .expect("Grind failed to find a solution.");
+
+        res
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn grind_poseidon(channel: &Poseidon252Channel, hi: u64, pow_bits: u32) -> Option<u64> {
+    let digests = [channel.digest(); N_LANES];
+    for low in (0..1 << POSEIDON_GRIND_LOW_BITS).step_by(N_LANES) {
+        let nonces: [u64; N_LANES] =
+            std::array::from_fn(|i| (hi << POSEIDON_GRIND_LOW_BITS) + low + i as u64);
+        let hashes = poseidon_hash_batch(digests, nonces.map(Into::into));
+        for (nonce, hash) in zip(nonces, hashes) {
             let mut channel = channel.clone();
-            channel.mix_u64(nonce);
+            channel.update_digest(hash);
             if channel.trailing_zeros() >= pow_bits {
-                return nonce;
+                return Some(nonce);
             }
-            nonce += 1;
         }
     }
+    None
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::channel::{Channel, Poseidon252Channel};
+    use crate::core::proof_of_work::GrindOps;
+
+    #[test]
+    fn test_grind_poseidon() {
+        const POW_BITS: u32 = 10;
+        let mut channel = Poseidon252Channel::default();
+        channel.mix_u64(7);
+
+        let nonce = SimdBackend::grind(&channel, POW_BITS);
+
+        channel.mix_u64(nonce);
+        assert!(channel.trailing_zeros() >= POW_BITS);
+    }
 }
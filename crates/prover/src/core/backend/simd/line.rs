@@ -0,0 +1,221 @@
+use std::array;
+
+use super::bit_reverse::bit_reverse_packed;
+use super::m31::{PackedBaseField, LOG_N_LANES, N_LANES};
+use super::SimdBackend;
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::secure_column::SecureColumnByCoords;
+use crate::core::fields::FieldExpOps;
+use crate::core::poly::line::{line_fft, line_ifft, LineDomain, LineEvaluation, LineOps};
+use crate::core::poly::twiddles::TwiddleTree;
+use crate::core::poly::utils::domain_line_twiddles_from_tree;
+
+impl LineOps for SimdBackend {
+    fn interpolate_line(
+        eval: LineEvaluation<Self>,
+        twiddles: &TwiddleTree<Self>,
+    ) -> SecureColumnByCoords<Self> {
+        let domain = eval.domain();
+        assert!(twiddles.covers_coset(domain.coset()));
+        let itwiddles = domain_line_twiddles_from_tree(domain, &twiddles.itwiddles);
+        let len_inv = BaseField::from(domain.size()).inverse();
+        if domain.log_size() <= LOG_N_LANES {
+            let mut values = eval.values.to_vec();
+            line_ifft(&mut values, &undoubled(&itwiddles));
+            return values.into_iter().map(|v| v * len_inv).collect();
+        }
+
+        let mut values = eval.values;
+        for column in &mut values.columns {
+            interpolate_line_packed(&mut column.data, &itwiddles, len_inv);
+        }
+        values
+    }
+
+    fn evaluate_line(
+        coeffs: SecureColumnByCoords<Self>,
+        domain: LineDomain,
+        twiddles: &TwiddleTree<Self>,
+    ) -> LineEvaluation<Self> {
+        assert_eq!(coeffs.len(), domain.size());
+        assert!(twiddles.covers_coset(domain.coset()));
+        let twiddles = domain_line_twiddles_from_tree(domain, &twiddles.twiddles);
+        if domain.log_size() <= LOG_N_LANES {
+            let mut values = coeffs.to_vec();
+            line_fft(&mut values, &undoubled(&twiddles));
+            return LineEvaluation::new(domain, values.into_iter().collect());
+        }
+
+        let mut values = coeffs;
+        for column in &mut values.columns {
+            evaluate_line_packed(&mut column.data, &twiddles);
+        }
+        LineEvaluation::new(domain, values)
+    }
+}
+
+/// Interpolates the coordinate of a line evaluation given by its [PackedBaseField]s in place,
+/// given the twiddles of each layer and the inverse of the domain size. See
+/// [LineOps::interpolate_line].
+pub(crate) fn interpolate_line_packed(
+    column: &mut [PackedBaseField],
+    itwiddles: &[&[u32]],
+    len_inv: BaseField,
+) {
+    for layer_itwiddles in itwiddles {
+        apply_layer(column, layer_itwiddles, |v0, v1, itwid| {
+            (v0 + v1, (v0 - v1) * itwid)
+        });
+    }
+    bit_reverse_packed(column);
+    // Normalize the coefficients.
+    let len_inv = PackedBaseField::broadcast(len_inv);
+    column.iter_mut().for_each(|v| *v *= len_inv);
+}
+
+/// Evaluates the coordinate of a line polynomial given by its [PackedBaseField]s in place, given
+/// the twiddles of each layer. See [LineOps::evaluate_line].
+pub(crate) fn evaluate_line_packed(column: &mut [PackedBaseField], twiddles: &[&[u32]]) {
+    bit_reverse_packed(column);
+    for layer_twiddles in twiddles.iter().rev() {
+        apply_layer(column, layer_twiddles, |v0, v1, twid| {
+            let tmp = v1 * twid;
+            (v0 + tmp, v0 - tmp)
+        });
+    }
+}
+
+/// Returns the twiddle of a doubled twiddle, as stored in [TwiddleTree]s of [SimdBackend].
+fn undouble(twiddle_dbl: u32) -> BaseField {
+    BaseField::from_u32_unchecked(twiddle_dbl >> 1)
+}
+
+/// Returns the twiddles of each layer of doubled twiddles.
+fn undoubled(twiddles_dbl: &[&[u32]]) -> Vec<Vec<BaseField>> {
+    twiddles_dbl
+        .iter()
+        .map(|layer| layer.iter().copied().map(undouble).collect())
+        .collect()
+}
+
+/// Applies a butterfly to every pair of values that are `half_size` apart within chunks of
+/// `2 * half_size` values, using the `i`th (doubled) twiddle for all pairs of the `i`th chunk. The
+/// number of chunks is the number of twiddles.
+///
+/// Layers with chunks of at least [N_LANES] pairs are applied on packed values. Smaller layers
+/// are applied to each pair of packed values separately, so the column must hold at least two
+/// packed values.
+fn apply_layer(
+    column: &mut [PackedBaseField],
+    twiddles_dbl: &[u32],
+    butterfly: impl Fn(
+        PackedBaseField,
+        PackedBaseField,
+        PackedBaseField,
+    ) -> (PackedBaseField, PackedBaseField),
+) {
+    let half_size = column.len() * N_LANES / (2 * twiddles_dbl.len());
+    if half_size >= N_LANES {
+        let chunks = column.chunks_exact_mut(2 * half_size / N_LANES);
+        for (chunk, &twiddle_dbl) in chunks.zip(twiddles_dbl) {
+            let twid = PackedBaseField::broadcast(undouble(twiddle_dbl));
+            let (l, r) = chunk.split_at_mut(half_size / N_LANES);
+            for (v0, v1) in l.iter_mut().zip(r) {
+                (*v0, *v1) = butterfly(*v0, *v1, twid);
+            }
+        }
+        return;
+    }
+
+    // Each pair of packed values holds whole chunks. Gather the pairs of all chunks lane-wise so a
+    // single packed butterfly processes all of them, then scatter the results back.
+    let pair_index = |lane: usize| {
+        let (chunk, offset) = (lane / half_size, lane % half_size);
+        let index = chunk * 2 * half_size + offset;
+        (index / N_LANES, index % N_LANES)
+    };
+    let chunks_per_pair = N_LANES / half_size;
+    let pairs = column.chunks_exact_mut(2);
+    for (packed, twiddles_dbl) in pairs.zip(twiddles_dbl.chunks_exact(chunks_per_pair)) {
+        let values = [packed[0].to_array(), packed[1].to_array()];
+        let v0 = array::from_fn(|i| {
+            let (vec_index, j) = pair_index(i);
+            values[vec_index][j]
+        });
+        let v1 = array::from_fn(|i| {
+            let (vec_index, j) = pair_index(i);
+            values[vec_index][j + half_size]
+        });
+        let twid = array::from_fn(|i| undouble(twiddles_dbl[i / half_size]));
+        let (v0, v1) = butterfly(
+            PackedBaseField::from_array(v0),
+            PackedBaseField::from_array(v1),
+            PackedBaseField::from_array(twid),
+        );
+        let (v0, v1) = (v0.to_array(), v1.to_array());
+        let mut res = values;
+        for i in 0..N_LANES {
+            let (vec_index, j) = pair_index(i);
+            res[vec_index][j] = v0[i];
+            res[vec_index][j + half_size] = v1[i];
+        }
+        packed[0] = PackedBaseField::from_array(res[0]);
+        packed[1] = PackedBaseField::from_array(res[1]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::CpuBackend;
+    use crate::core::circle::Coset;
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::poly::circle::PolyOps;
+    use crate::core::poly::line::{LineDomain, LineEvaluation, LinePoly};
+
+    #[test]
+    fn interpolate_and_evaluate_match_cpu() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for log_size in 0..10 {
+            let domain = LineDomain::new(Coset::half_odds(log_size));
+            let values = (0..domain.size())
+                .map(|_| rng.gen::<SecureField>())
+                .collect::<Vec<_>>();
+            let cpu_eval =
+                LineEvaluation::<CpuBackend>::new(domain, values.iter().copied().collect());
+            let simd_eval =
+                LineEvaluation::<SimdBackend>::new(domain, values.into_iter().collect());
+
+            let extended_domain = LineDomain::new(Coset::half_odds(log_size + 1));
+            let twiddles = SimdBackend::precompute_twiddles(extended_domain.coset());
+
+            let cpu_poly = cpu_eval.clone().interpolate();
+            let simd_coeffs = simd_eval.interpolate_with_twiddles(&twiddles);
+
+            let simd_poly = LinePoly::new(simd_coeffs.to_vec());
+            assert_eq!(simd_poly, cpu_poly, "log_size={log_size}");
+            assert_eq!(
+                simd_poly
+                    .evaluate_with_twiddles(domain, &twiddles)
+                    .values
+                    .to_vec(),
+                cpu_eval.values.to_vec(),
+                "log_size={log_size}"
+            );
+            assert_eq!(
+                simd_poly
+                    .evaluate_with_twiddles(extended_domain, &twiddles)
+                    .values
+                    .to_vec(),
+                cpu_poly
+                    .evaluate::<CpuBackend>(extended_domain)
+                    .values
+                    .to_vec(),
+                "log_size={log_size}"
+            );
+        }
+    }
+}
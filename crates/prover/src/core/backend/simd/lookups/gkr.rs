@@ -11,7 +11,7 @@ use crate::core::backend::{Column, CpuBackend};
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::lookups::gkr_prover::{
-    correct_sum_as_poly_in_first_variable, EqEvals, GkrMultivariatePolyOracle, GkrOps, Layer,
+    correct_sum_as_poly_in_first_variable, GkrMultivariatePolyOracle, GkrOps, Layer,
 };
 use crate::core::lookups::mle::Mle;
 use crate::core::lookups::sumcheck::MultivariatePolyOracle;
@@ -88,35 +88,7 @@ impl GkrOps for SimdBackend {
             return h.to_cpu().sum_as_poly_in_first_variable(claim);
         }
 
-        let n_packed_terms = n_terms / N_LANES;
-        let packed_lambda = PackedSecureField::broadcast(h.lambda);
-
-        let (mut eval_at_0, mut eval_at_2) = match &h.input_layer {
-            Layer::GrandProduct(col) => eval_grand_product_sum(eq_evals, col, n_packed_terms),
-            Layer::LogUpGeneric {
-                numerators,
-                denominators,
-            } => eval_logup_generic_sum(
-                eq_evals,
-                numerators,
-                denominators,
-                n_packed_terms,
-                packed_lambda,
-            ),
-            Layer::LogUpMultiplicities {
-                numerators,
-                denominators,
-            } => eval_logup_multiplicities_sum(
-                eq_evals,
-                numerators,
-                denominators,
-                n_packed_terms,
-                packed_lambda,
-            ),
-            Layer::LogUpSingles { denominators } => {
-                eval_logup_singles_sum(eq_evals, denominators, n_packed_terms, packed_lambda)
-            }
-        };
+        let (mut eval_at_0, mut eval_at_2) = sum_at_0_and_2(eq_evals, &h.input_layer, h.lambda);
 
         eval_at_0 *= h.eq_fixed_var_correction;
         eval_at_2 *= h.eq_fixed_var_correction;
@@ -124,6 +96,48 @@ impl GkrOps for SimdBackend {
     }
 }
 
+/// Evaluates the sum over the terms of `input_layer`, weighted by `eq_evals`, at `t=0` and `t=2`.
+/// See [GkrOps::sum_as_poly_in_first_variable].
+///
+/// Assumption: `input_layer` has at least `N_LANES` terms.
+pub(crate) fn sum_at_0_and_2(
+    eq_evals: &SecureColumn,
+    input_layer: &Layer<SimdBackend>,
+    lambda: SecureField,
+) -> (SecureField, SecureField) {
+    let n_terms = 1 << (input_layer.n_variables() - 2);
+    assert!(n_terms >= N_LANES);
+    let n_packed_terms = n_terms / N_LANES;
+    let packed_lambda = PackedSecureField::broadcast(lambda);
+
+    match input_layer {
+        Layer::GrandProduct(col) => eval_grand_product_sum(eq_evals, col, n_packed_terms),
+        Layer::LogUpGeneric {
+            numerators,
+            denominators,
+        } => eval_logup_generic_sum(
+            eq_evals,
+            numerators,
+            denominators,
+            n_packed_terms,
+            packed_lambda,
+        ),
+        Layer::LogUpMultiplicities {
+            numerators,
+            denominators,
+        } => eval_logup_multiplicities_sum(
+            eq_evals,
+            numerators,
+            denominators,
+            n_packed_terms,
+            packed_lambda,
+        ),
+        Layer::LogUpSingles { denominators } => {
+            eval_logup_singles_sum(eq_evals, denominators, n_packed_terms, packed_lambda)
+        }
+    }
+}
+
 /// Generates the next GKR layer for Grand Product.
 ///
 /// Assumption: `len(layer) > N_LANES`.
@@ -281,7 +295,7 @@ fn next_logup_singles_layer(denominators: &Mle<SimdBackend, SecureField>) -> Lay
 ///
 /// Output of the form: `(eval_at_0, eval_at_2)`.
 fn eval_grand_product_sum(
-    eq_evals: &EqEvals<SimdBackend>,
+    eq_evals: &SecureColumn,
     col: &Mle<SimdBackend, SecureField>,
     n_packed_terms: usize,
 ) -> (SecureField, SecureField) {
@@ -316,7 +330,7 @@ fn eval_grand_product_sum(
 }
 
 fn eval_logup_generic_sum(
-    eq_evals: &EqEvals<SimdBackend>,
+    eq_evals: &SecureColumn,
     numerators: &Mle<SimdBackend, SecureField>,
     denominators: &Mle<SimdBackend, SecureField>,
     n_packed_terms: usize,
@@ -374,7 +388,7 @@ fn eval_logup_generic_sum(
 
 // TODO(andrew): Code duplication of `eval_logup_generic_sum`. Consider unifying these.
 fn eval_logup_multiplicities_sum(
-    eq_evals: &EqEvals<SimdBackend>,
+    eq_evals: &SecureColumn,
     numerators: &Mle<SimdBackend, BaseField>,
     denominators: &Mle<SimdBackend, SecureField>,
     n_packed_terms: usize,
@@ -435,7 +449,7 @@ fn eval_logup_multiplicities_sum(
 ///
 /// Output of the form: `(eval_at_0, eval_at_2)`.
 fn eval_logup_singles_sum(
-    eq_evals: &EqEvals<SimdBackend>,
+    eq_evals: &SecureColumn,
     denominators: &Mle<SimdBackend, SecureField>,
     n_packed_terms: usize,
     packed_lambda: PackedSecureField,
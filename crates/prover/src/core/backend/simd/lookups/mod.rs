@@ -1,2 +1,2 @@
-mod gkr;
+pub(crate) mod gkr;
 mod mle;
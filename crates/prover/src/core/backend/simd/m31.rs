@@ -2,8 +2,8 @@ use std::iter::Sum;
 use std::mem::transmute;
 use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use std::ptr;
-use std::simd::cmp::SimdOrd;
-use std::simd::{u32x16, Simd, Swizzle};
+use std::simd::cmp::{SimdOrd, SimdPartialEq};
+use std::simd::{u32x16, Mask, Simd, Swizzle};
 
 use bytemuck::{Pod, Zeroable};
 use num_traits::{One, Zero};
@@ -13,7 +13,7 @@ use super::qm31::PackedQM31;
 use crate::core::backend::simd::utils::{InterleaveEvens, InterleaveOdds};
 use crate::core::fields::m31::{pow2147483645, BaseField, M31, P};
 use crate::core::fields::qm31::QM31;
-use crate::core::fields::FieldExpOps;
+use crate::core::fields::{FieldExpOps, InverseOrZero};
 
 pub const LOG_N_LANES: u32 = 4;
 
@@ -50,6 +50,16 @@ impl PackedM31 {
         Self(Simd::simd_min(self.0, self.0 - MODULUS))
     }
 
+    /// Returns a mask of the lanes holding zero.
+    pub fn zero_mask(self) -> Mask<i32, N_LANES> {
+        self.reduce().0.simd_eq(Simd::splat(0))
+    }
+
+    /// Selects the lanes of `if_true` where `mask` is set, and the lanes of `if_false` elsewhere.
+    pub fn select(mask: Mask<i32, N_LANES>, if_true: Self, if_false: Self) -> Self {
+        Self(mask.select(if_true.0, if_false.0))
+    }
+
     /// Interleaves two vectors.
     pub fn interleave(self, other: Self) -> (Self, Self) {
         let (a, b) = self.0.interleave(other.0);
@@ -254,6 +264,12 @@ impl FieldExpOps for PackedM31 {
     }
 }
 
+impl InverseOrZero for PackedM31 {
+    fn select_nonzero(&self, if_nonzero: &Self, if_zero: &Self) -> Self {
+        Self::select(self.zero_mask(), *if_zero, *if_nonzero)
+    }
+}
+
 unsafe impl Pod for PackedM31 {}
 
 unsafe impl Zeroable for PackedM31 {
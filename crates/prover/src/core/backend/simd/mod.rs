@@ -15,6 +15,7 @@ pub mod domain;
 pub mod fft;
 pub mod fri;
 mod grind;
+pub mod line;
 pub mod lookups;
 pub mod m31;
 #[cfg(not(target_arch = "wasm32"))]
@@ -1,13 +1,67 @@
+//! Batched Poseidon hashing over the Stark252 field, for Merkle layers and grinding.
+//!
+//! [N_LANES] independent hashes of messages of the same length are computed in lockstep: each
+//! round of the permutation is applied to all the states before moving on to the next one, so the
+//! field multiplications of different hashes don't depend on each other and can be pipelined.
+//! The field elements are kept in the Montgomery form of [FieldElement252], with a multiplication
+//! specialized to the sparse Stark252 prime. The results are the same as [poseidon_hash_many].
+//!
+//! [poseidon_hash_many]: starknet_crypto::poseidon_hash_many
+
+use std::array;
+use std::iter::zip;
+use std::ops::{Add, Mul, Sub};
+
 use itertools::Itertools;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use starknet_ff::FieldElement as FieldElement252;
 
+use super::m31::{LOG_N_LANES, N_LANES};
 use super::SimdBackend;
 use crate::core::backend::{Col, Column, ColumnOps};
 use crate::core::fields::m31::BaseField;
-#[cfg(not(target_arch = "wasm32"))]
-use crate::core::vcs::ops::MerkleHasher;
-use crate::core::vcs::ops::MerkleOps;
-use crate::core::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
+#[cfg(feature = "parallel")]
+use crate::core::vcs::ops::par_read_rows;
+use crate::core::vcs::ops::{MerkleHasher, MerkleOps, NodeStates};
+use crate::core::vcs::poseidon252_merkle::{Poseidon252MerkleHasher, ELEMENTS_IN_BLOCK};
+use crate::parallel_iter;
+
+mod constants {
+    #![allow(dead_code)]
+
+    use starknet_ff::FieldElement;
+
+    starknet_crypto_codegen::poseidon_consts!();
+
+    pub(super) const N_FULL_ROUNDS: usize = FULL_ROUNDS;
+    pub(super) const N_PARTIAL_ROUNDS: usize = PARTIAL_ROUNDS;
+}
+
+use constants::{N_FULL_ROUNDS, N_PARTIAL_ROUNDS, POSEIDON_COMP_CONSTS};
+
+/// The round keys, compressed such that a partial round only has a key for the last element of
+/// the state (see [starknet_crypto::poseidon_permute_comp]).
+const ROUND_KEYS: [Felt252; POSEIDON_COMP_CONSTS.len()] = {
+    let mut keys = [Felt252::ZERO; POSEIDON_COMP_CONSTS.len()];
+    let mut i = 0;
+    while i < keys.len() {
+        keys[i] = Felt252(POSEIDON_COMP_CONSTS[i].into_mont());
+        i += 1;
+    }
+    keys
+};
+
+/// The Stark252 prime `2^251 + 17 * 2^192 + 1`, in little-endian limbs.
+const P: [u64; 4] = [1, 0, 0, 0x0800000000000011];
+
+/// `2^512 mod P`, the Montgomery form of `2^256`.
+const R2: Felt252 = Felt252([
+    0xfffffd737e000401,
+    0x00000001330fffff,
+    0xffffffffff6f8000,
+    0x07ffd4ab5e008810,
+]);
 
 impl ColumnOps<FieldElement252> for SimdBackend {
     type Column = Vec<FieldElement252>;
@@ -18,19 +72,462 @@ impl ColumnOps<FieldElement252> for SimdBackend {
 }
 
 impl MerkleOps<Poseidon252MerkleHasher> for SimdBackend {
-    // TODO(ShaharS): replace with SIMD implementation.
+    type LayerState = NodeStates<Poseidon252MerkleHasher>;
+
     fn commit_on_layer(
         log_size: u32,
         prev_layer: Option<&Vec<FieldElement252>>,
         columns: &[&Col<Self, BaseField>],
     ) -> Vec<FieldElement252> {
-        (0..(1 << log_size))
-            .map(|i| {
-                Poseidon252MerkleHasher::hash_node(
-                    prev_layer.map(|prev_layer| (prev_layer[2 * i], prev_layer[2 * i + 1])),
-                    &columns.iter().map(|column| column.at(i)).collect_vec(),
-                )
+        if log_size < LOG_N_LANES {
+            return parallel_iter!(0..1 << log_size)
+                .map(|i| {
+                    Poseidon252MerkleHasher::hash_node(
+                        prev_layer.map(|prev_layer| (prev_layer[2 * i], prev_layer[2 * i + 1])),
+                        &columns.iter().map(|column| column.at(i)).collect_vec(),
+                    )
+                })
+                .collect();
+        }
+
+        if let Some(prev_layer) = prev_layer {
+            assert_eq!(prev_layer.len(), 1 << (log_size + 1));
+        }
+
+        let mut res = vec![FieldElement252::default(); 1 << log_size];
+        #[cfg(not(feature = "parallel"))]
+        let iter = res.chunks_mut(N_LANES);
+
+        #[cfg(feature = "parallel")]
+        let iter = res.par_chunks_mut(N_LANES);
+
+        iter.enumerate().for_each(|(i, chunk)| {
+            // The children hashes of the nodes, if they exist.
+            let children_words = prev_layer.into_iter().flat_map(|prev_layer| {
+                let children = &prev_layer[2 * i * N_LANES..2 * (i + 1) * N_LANES];
+                [0, 1].map(|child| -> [Felt252; N_LANES] {
+                    array::from_fn(|j| children[2 * j + child].into())
+                })
+            });
+
+            // The column values of the nodes, in blocks of 31-bit words padded with zeros.
+            let column_words = columns.chunks(ELEMENTS_IN_BLOCK).map(|block| {
+                let mut block_values = [[BaseField::default(); N_LANES]; ELEMENTS_IN_BLOCK];
+                for (values, column) in zip(&mut block_values, block) {
+                    *values = column.data[i].to_array();
+                }
+                let words: [Felt252; N_LANES] =
+                    array::from_fn(|j| Felt252::from_words(block_values.map(|values| values[j].0)));
+                words
+            });
+
+            let hashes = hash_many_batch(children_words.chain(column_words));
+            for (res, hash) in zip(chunk, hashes) {
+                *res = hash.into();
+            }
+        });
+        res
+    }
+
+    fn start_layer(log_size: u32, prev_layer: Option<&Vec<FieldElement252>>) -> Self::LayerState {
+        NodeStates::start(log_size, prev_layer)
+    }
+
+    fn update_layer(state: &mut Self::LayerState, columns: &[&Col<Self, BaseField>]) {
+        state.update(columns);
+    }
+
+    fn finish_layer(state: Self::LayerState) -> Vec<FieldElement252> {
+        state.finish()
+    }
+
+    #[cfg(feature = "parallel")]
+    fn read_rows(columns: &[&Col<Self, BaseField>], rows: &[usize]) -> Vec<Vec<BaseField>> {
+        par_read_rows(columns, rows)
+    }
+}
+
+/// Computes [starknet_crypto::poseidon_hash] on [N_LANES] pairs of elements.
+pub fn poseidon_hash_batch(
+    x: [FieldElement252; N_LANES],
+    y: [FieldElement252; N_LANES],
+) -> [FieldElement252; N_LANES] {
+    let mut states = array::from_fn(|j| [x[j].into(), y[j].into(), Felt252::TWO]);
+    permute_batch(&mut states);
+    states.map(|[res, _, _]| res.into())
+}
+
+/// Computes [starknet_crypto::poseidon_hash_many] on [N_LANES] messages of the same length, given
+/// word by word.
+fn hash_many_batch(words: impl Iterator<Item = [Felt252; N_LANES]>) -> [Felt252; N_LANES] {
+    let mut states = [[Felt252::ZERO; 3]; N_LANES];
+    let mut words = words.fuse();
+    loop {
+        match (words.next(), words.next()) {
+            (Some(words0), Some(words1)) => {
+                for (state, word0, word1) in itertools::izip!(&mut states, words0, words1) {
+                    state[0] = state[0] + word0;
+                    state[1] = state[1] + word1;
+                }
+                permute_batch(&mut states);
+            }
+            // Padding.
+            (Some(words0), None) => {
+                for (state, word0) in zip(&mut states, words0) {
+                    state[0] = state[0] + word0;
+                    state[1] = state[1] + Felt252::ONE;
+                }
+                break;
+            }
+            (None, _) => {
+                for state in &mut states {
+                    state[0] = state[0] + Felt252::ONE;
+                }
+                break;
+            }
+        }
+    }
+    permute_batch(&mut states);
+    states.map(|[res, _, _]| res)
+}
+
+/// Applies [starknet_crypto::poseidon_permute_comp] on each state.
+fn permute_batch(states: &mut [[Felt252; 3]; N_LANES]) {
+    let (first_full_round_keys, round_keys) = ROUND_KEYS.split_at(3 * N_FULL_ROUNDS / 2);
+    let (partial_round_keys, last_full_round_keys) = round_keys.split_at(N_PARTIAL_ROUNDS);
+
+    for keys in first_full_round_keys.chunks_exact(3) {
+        full_round_batch(states, keys);
+    }
+    for &key in partial_round_keys {
+        for state in states.iter_mut() {
+            state[2] = (state[2] + key).cube();
+            mix(state);
+        }
+    }
+    for keys in last_full_round_keys.chunks_exact(3) {
+        full_round_batch(states, keys);
+    }
+}
+
+#[inline(always)]
+fn full_round_batch(states: &mut [[Felt252; 3]; N_LANES], keys: &[Felt252]) {
+    for state in states.iter_mut() {
+        for (element, &key) in zip(state.iter_mut(), keys) {
+            *element = (*element + key).cube();
+        }
+        mix(state);
+    }
+}
+
+/// Multiplies the state by the MDS matrix `((3, 1, 1), (1, -1, 1), (1, 1, -2))`.
+#[inline(always)]
+fn mix(state: &mut [Felt252; 3]) {
+    let t = state[0] + state[1] + state[2];
+    state[0] = t + state[0].double();
+    state[1] = t - state[1].double();
+    state[2] = t - (state[2].double() + state[2]);
+}
+
+/// An element of the Stark252 field, in the Montgomery form of [FieldElement252].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Felt252([u64; 4]);
+
+impl Felt252 {
+    const ZERO: Self = Self([0; 4]);
+    const ONE: Self = Self(FieldElement252::ONE.into_mont());
+    const TWO: Self = Self(FieldElement252::TWO.into_mont());
+
+    /// Returns the element `sum_j words[j] * 2^(31 * (7 - j))`, the packing of
+    /// [Poseidon252MerkleHasher::hash_node].
+    fn from_words(words: [u32; ELEMENTS_IN_BLOCK]) -> Self {
+        let mut limbs = [0; 4];
+        for (j, word) in words.into_iter().enumerate() {
+            let shift = 31 * (ELEMENTS_IN_BLOCK - 1 - j);
+            let (limb, offset) = (shift / 64, shift % 64);
+            limbs[limb] |= (word as u64) << offset;
+            if offset + 31 > 64 {
+                limbs[limb + 1] |= (word as u64) >> (64 - offset);
+            }
+        }
+        // The integer is smaller than P, and is mapped to its Montgomery form.
+        Self(limbs) * R2
+    }
+
+    fn double(self) -> Self {
+        self + self
+    }
+
+    fn cube(self) -> Self {
+        self * self * self
+    }
+}
+
+impl From<FieldElement252> for Felt252 {
+    fn from(value: FieldElement252) -> Self {
+        Self(value.into_mont())
+    }
+}
+
+impl From<Felt252> for FieldElement252 {
+    fn from(value: Felt252) -> Self {
+        FieldElement252::from_mont(value.0)
+    }
+}
+
+impl Add for Felt252 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self {
+        // Doesn't overflow, as P < 2^252.
+        Self(reduce(add_limbs(self.0, rhs.0)))
+    }
+}
+
+impl Sub for Felt252 {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self {
+        let (res, borrow) = sub_limbs(self.0, rhs.0);
+        // Adds P back if the subtraction wrapped around.
+        let mask = (borrow as u64).wrapping_neg();
+        Self(add_limbs(res, P.map(|limb| limb & mask)))
+    }
+}
+
+impl Mul for Felt252 {
+    type Output = Self;
+
+    /// Montgomery multiplication (CIOS). As `P = 1 mod 2^64`, the reduction factor of each step is
+    /// the negated lowest limb, and the middle limbs of `P` are zero.
+    #[inline(always)]
+    fn mul(self, rhs: Self) -> Self {
+        let [a0, a1, a2, a3] = self.0;
+        let [mut t0, mut t1, mut t2, mut t3, mut t4] = [0; 5];
+        for b in rhs.0 {
+            // t += a * b.
+            let (r0, carry) = mac(t0, a0, b, 0);
+            let (r1, carry) = mac(t1, a1, b, carry);
+            let (r2, carry) = mac(t2, a2, b, carry);
+            let (r3, carry) = mac(t3, a3, b, carry);
+            let (r4, r5) = adc(t4, carry, 0);
+
+            // t = (t + m * P) / 2^64, where m = -t0 makes the lowest limb zero.
+            let m = r0.wrapping_neg();
+            let (s1, carry) = adc(r1, 0, (r0 != 0) as u64);
+            let (s2, carry) = adc(r2, 0, carry);
+            let (s3, carry) = mac(r3, m, P[3], carry);
+            let (s4, carry) = adc(r4, 0, carry);
+            (t0, t1, t2, t3, t4) = (s1, s2, s3, s4, r5 + carry);
+        }
+        // The result is smaller than 2P < 2^256.
+        debug_assert_eq!(t4, 0);
+        Self(reduce([t0, t1, t2, t3]))
+    }
+}
+
+/// Returns `a + b * c + carry`, as its low and high limbs.
+#[inline(always)]
+fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let res = a as u128 + b as u128 * c as u128 + carry as u128;
+    (res as u64, (res >> 64) as u64)
+}
+
+/// Returns `a + b + carry`, as its low and high limbs.
+#[inline(always)]
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let res = a as u128 + b as u128 + carry as u128;
+    (res as u64, (res >> 64) as u64)
+}
+
+/// Reduces a value smaller than `2P` modulo P.
+#[inline(always)]
+fn reduce(value: [u64; 4]) -> [u64; 4] {
+    let (res, borrow) = sub_limbs(value, P);
+    // Branchless, as the branch is unpredictable.
+    let mask = (borrow as u64).wrapping_neg();
+    array::from_fn(|i| (value[i] & mask) | (res[i] & !mask))
+}
+
+#[inline(always)]
+fn sub_limbs(a: [u64; 4], b: [u64; 4]) -> ([u64; 4], bool) {
+    let mut res = [0; 4];
+    let mut borrow = false;
+    for (res, a, b) in itertools::izip!(&mut res, a, b) {
+        let (diff, borrow0) = a.overflowing_sub(b);
+        let (diff, borrow1) = diff.overflowing_sub(borrow as u64);
+        *res = diff;
+        borrow = borrow0 || borrow1;
+    }
+    (res, borrow)
+}
+
+/// Adds two values, ignoring the carry out.
+#[inline(always)]
+fn add_limbs(a: [u64; 4], b: [u64; 4]) -> [u64; 4] {
+    let mut res = [0; 4];
+    let mut carry = 0;
+    for (res, a, b) in itertools::izip!(&mut res, a, b) {
+        let sum = a as u128 + b as u128 + carry;
+        *res = sum as u64;
+        carry = sum >> 64;
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use std::array;
+
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+    use starknet_crypto::{poseidon_hash, poseidon_hash_many, poseidon_permute_comp};
+    use starknet_ff::FieldElement as FieldElement252;
+
+    use super::{hash_many_batch, permute_batch, poseidon_hash_batch, Felt252};
+    use crate::core::backend::simd::m31::N_LANES;
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::{Col, CpuBackend};
+    use crate::core::fields::m31::BaseField;
+    use crate::core::vcs::ops::MerkleOps;
+    use crate::core::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
+    use crate::core::vcs::test_utils::{
+        assert_layer_ops_match_commit_on_layer, assert_merkle_matches_cpu,
+    };
+
+    fn random_element(rng: &mut SmallRng) -> FieldElement252 {
+        let mut bytes: [u8; 32] = rng.gen();
+        bytes[0] &= 0x07;
+        FieldElement252::from_bytes_be(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_felt252_arithmetic_matches_starknet_ff() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let special = [
+            FieldElement252::ZERO,
+            FieldElement252::ONE,
+            FieldElement252::MAX,
+        ];
+        let elements = (0..100)
+            .map(|_| random_element(&mut rng))
+            .chain(special)
+            .collect_vec();
+
+        for (&a, &b) in elements.iter().cartesian_product(&elements) {
+            let (x, y) = (Felt252::from(a), Felt252::from(b));
+            assert_eq!(FieldElement252::from(x + y), a + b);
+            assert_eq!(FieldElement252::from(x - y), a - b);
+            assert_eq!(FieldElement252::from(x * y), a * b);
+        }
+    }
+
+    #[test]
+    fn test_from_words() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let words: [u32; 8] = array::from_fn(|_| rng.gen_range(0..1 << 31));
+            let expected = words.iter().fold(FieldElement252::ZERO, |acc, &word| {
+                acc * FieldElement252::from(1u64 << 31) + FieldElement252::from(word)
+            });
+
+            assert_eq!(FieldElement252::from(Felt252::from_words(words)), expected);
+        }
+    }
+
+    #[test]
+    fn test_permute_batch_matches_starknet_crypto() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let states: [[FieldElement252; 3]; N_LANES] =
+            array::from_fn(|_| array::from_fn(|_| random_element(&mut rng)));
+        let mut batch_states = states.map(|state| state.map(Felt252::from));
+
+        permute_batch(&mut batch_states);
+
+        for (mut state, batch_state) in states.into_iter().zip(batch_states) {
+            poseidon_permute_comp(&mut state);
+            assert_eq!(batch_state.map(FieldElement252::from), state);
+        }
+    }
+
+    #[test]
+    fn test_hash_many_batch_matches_starknet_crypto() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for n_words in 0..5 {
+            let messages: [Vec<FieldElement252>; N_LANES] =
+                array::from_fn(|_| (0..n_words).map(|_| random_element(&mut rng)).collect());
+            let words = (0..n_words)
+                .map(|i| -> [Felt252; N_LANES] { array::from_fn(|j| messages[j][i].into()) });
+
+            let hashes = hash_many_batch(words);
+
+            for (message, hash) in messages.iter().zip(hashes) {
+                assert_eq!(FieldElement252::from(hash), poseidon_hash_many(message));
+            }
+        }
+    }
+
+    #[test]
+    fn test_poseidon_hash_batch_matches_starknet_crypto() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let x = array::from_fn(|_| random_element(&mut rng));
+        let y = array::from_fn(|_| random_element(&mut rng));
+
+        let hashes = poseidon_hash_batch(x, y);
+
+        for i in 0..N_LANES {
+            assert_eq!(hashes[i], poseidon_hash(x[i], y[i]));
+        }
+    }
+
+    #[test]
+    fn test_commit_on_layer_matches_cpu() {
+        const LOG_SIZE: u32 = 6;
+        let mut rng = SmallRng::seed_from_u64(0);
+        let prev_layer = (0..2 << LOG_SIZE)
+            .map(|_| random_element(&mut rng))
+            .collect_vec();
+        // Two full blocks of columns, and a partial one.
+        let columns = (0..19)
+            .map(|_| (0..1 << LOG_SIZE).map(|_| rng.gen()).collect_vec())
+            .collect_vec();
+        let simd_columns = columns
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .copied()
+                    .collect::<Col<SimdBackend, BaseField>>()
             })
-            .collect()
+            .collect_vec();
+
+        for (prev_layer, n_columns) in [(None, 19), (Some(&prev_layer), 19), (Some(&prev_layer), 0)]
+        {
+            assert_eq!(
+                <SimdBackend as MerkleOps<Poseidon252MerkleHasher>>::commit_on_layer(
+                    LOG_SIZE,
+                    prev_layer,
+                    &simd_columns[..n_columns].iter().collect_vec(),
+                ),
+                <CpuBackend as MerkleOps<Poseidon252MerkleHasher>>::commit_on_layer(
+                    LOG_SIZE,
+                    prev_layer,
+                    &columns[..n_columns].iter().collect_vec(),
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_merkle_matches_cpu() {
+        assert_merkle_matches_cpu::<SimdBackend, Poseidon252MerkleHasher>();
+    }
+
+    #[test]
+    fn test_layer_ops_match_commit_on_layer() {
+        assert_layer_ops_match_commit_on_layer::<SimdBackend, Poseidon252MerkleHasher>();
     }
 }
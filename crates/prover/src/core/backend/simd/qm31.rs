@@ -9,7 +9,7 @@ use rand::distributions::{Distribution, Standard};
 use super::cm31::PackedCM31;
 use super::m31::{PackedM31, N_LANES};
 use crate::core::fields::qm31::QM31;
-use crate::core::fields::FieldExpOps;
+use crate::core::fields::{FieldExpOps, InverseOrZero};
 
 pub type PackedSecureField = PackedQM31;
 
@@ -239,6 +239,16 @@ impl SubAssign for PackedQM31 {
 
 unsafe impl Pod for PackedQM31 {}
 
+impl InverseOrZero for PackedQM31 {
+    fn select_nonzero(&self, if_nonzero: &Self, if_zero: &Self) -> Self {
+        let mask = self.a().zero_mask() & self.b().zero_mask();
+        Self([
+            PackedCM31::select(mask, if_zero.a(), if_nonzero.a()),
+            PackedCM31::select(mask, if_zero.b(), if_nonzero.b()),
+        ])
+    }
+}
+
 unsafe impl Zeroable for PackedQM31 {
     fn zeroed() -> Self {
         unsafe { core::mem::zeroed() }
@@ -300,10 +310,13 @@ impl From<QM31> for PackedQM31 {
 mod tests {
     use std::array;
 
+    use num_traits::Zero;
     use rand::rngs::SmallRng;
     use rand::{Rng, SeedableRng};
 
     use crate::core::backend::simd::qm31::PackedQM31;
+    use crate::core::fields::qm31::QM31;
+    use crate::core::fields::{FieldExpOps, InverseOrZero};
 
     #[test]
     fn addition_works() {
@@ -354,4 +367,21 @@ mod tests {
 
         assert_eq!(res.to_array(), values.map(|v| -v));
     }
+
+    #[test]
+    fn batch_inverse_or_zero_works() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut values: [[QM31; 16]; 8] = rng.gen();
+        values[0][3] = QM31::zero();
+        values[5] = [QM31::zero(); 16];
+        let packed_values = values.map(PackedQM31::from_array);
+        let mut packed_inverses = [PackedQM31::zero(); 8];
+
+        PackedQM31::batch_inverse_or_zero(&packed_values, &mut packed_inverses);
+
+        for (values, packed_inverses) in values.iter().zip(packed_inverses) {
+            let expected = values.map(|v| if v.is_zero() { v } else { v.inverse() });
+            assert_eq!(packed_inverses.to_array(), expected);
+        }
+    }
 }
@@ -10,12 +10,15 @@ use super::qm31::PackedSecureField;
 use super::SimdBackend;
 use crate::core::backend::cpu::quotients::{batch_random_coeffs, column_line_coeffs};
 use crate::core::backend::{Column, CpuBackend};
+use crate::core::circle::CirclePoint;
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::fields::secure_column::{SecureColumnByCoords, SECURE_EXTENSION_DEGREE};
-use crate::core::fields::FieldExpOps;
+use crate::core::fields::InverseOrZero;
 use crate::core::pcs::quotients::{ColumnSampleBatch, QuotientOps};
-use crate::core::poly::circle::{CircleDomain, CircleEvaluation, PolyOps, SecureEvaluation};
+use crate::core::poly::circle::{
+    CircleDomain, CircleEvaluation, CirclePoly, PolyOps, SecureEvaluation,
+};
 use crate::core::poly::BitReversedOrder;
 use crate::core::utils::bit_reverse;
 
@@ -95,6 +98,13 @@ impl QuotientOps for SimdBackend {
 
         SecureEvaluation::new(domain, extended_eval)
     }
+
+    fn eval_at_extension_points(
+        polys: &[&CirclePoly<Self>],
+        points: &[CirclePoint<SecureField>],
+    ) -> Vec<Vec<SecureField>> {
+        Self::eval_at_points(polys, points)
+    }
 }
 
 fn accumulate_quotients_on_subdomain(
@@ -234,7 +244,7 @@ fn denominator_inverses(
 
     let mut flat_denominator_inverses =
         unsafe { CM31Column::uninitialized(flat_denominators.len()) };
-    FieldExpOps::batch_inverse(
+    InverseOrZero::batch_inverse_or_zero(
         &flat_denominators.data,
         &mut flat_denominator_inverses.data[..],
     );
@@ -296,10 +306,10 @@ mod tests {
         let random_coeff = qm31!(1, 2, 3, 4);
         let a = polys[0].eval_at_point(SECURE_FIELD_CIRCLE_GEN);
         let b = polys[1].eval_at_point(SECURE_FIELD_CIRCLE_GEN);
-        let samples = vec![ColumnSampleBatch {
-            point: SECURE_FIELD_CIRCLE_GEN,
-            columns_and_values: vec![(0, a), (1, b)],
-        }];
+        let samples = vec![ColumnSampleBatch::new(
+            SECURE_FIELD_CIRCLE_GEN,
+            vec![(0, a), (1, b)],
+        )];
         let cpu_columns = columns
             .iter()
             .map(|c| CircleEvaluation::new(c.domain, c.values.to_cpu()))
@@ -1,10 +1,9 @@
 use std::iter;
 
 use super::{Channel, ChannelTime};
-use crate::core::fields::m31::{BaseField, N_BYTES_FELT, P};
+use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
-use crate::core::fields::IntoSlice;
 use crate::core::vcs::blake2_hash::{Blake2sHash, Blake2sHasher};
 use crate::core::vcs::blake2s_ref::compress;
 
@@ -26,30 +25,6 @@ impl Blake2sChannel {
         self.digest = new_digest;
         self.channel_time.inc_challenges();
     }
-    /// Generates a uniform random vector of BaseField elements.
-    fn draw_base_felts(&mut self) -> [BaseField; FELTS_PER_HASH] {
-        // Repeats hashing with an increasing counter until getting a good result.
-        // Retry probability for each round is ~ 2^(-28).
-        loop {
-            let u32s: [u32; FELTS_PER_HASH] = self
-                .draw_random_bytes()
-                .chunks_exact(N_BYTES_FELT) // 4 bytes per u32.
-                .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
-                .collect::<Vec<_>>()
-                .try_into()
-                .unwrap();
-
-            // Retry if not all the u32 are in the range [0, 2P).
-            if u32s.iter().all(|x| *x < 2 * P) {
-                return u32s
-                    .into_iter()
-                    .map(|x| BaseField::reduce(x as u64))
-                    .collect::<Vec<_>>()
-                    .try_into()
-                    .unwrap();
-            }
-        }
-    }
 }
 
 impl Channel for Blake2sChannel {
@@ -62,7 +37,9 @@ impl Channel for Blake2sChannel {
     fn mix_felts(&mut self, felts: &[SecureField]) {
         let mut hasher = Blake2sHasher::new();
         hasher.update(self.digest.as_ref());
-        hasher.update(IntoSlice::<u8>::into_slice(felts));
+        for felt in felts {
+            hasher.update(&felt.to_le_bytes());
+        }
 
         self.update_digest(hasher.finalize());
     }
@@ -96,6 +73,24 @@ impl Channel for Blake2sChannel {
         secure_felts.take(n_felts).collect()
     }
 
+    /// Generates a uniform random vector of BaseField elements.
+    fn draw_base_felts(&mut self) -> [BaseField; FELTS_PER_HASH] {
+        // Repeats hashing with an increasing counter until getting a good result.
+        // Retry probability for each round is ~ 2^(-28).
+        loop {
+            // Retry if not all the u32 are in the range [0, 2P).
+            let felts: Option<Vec<BaseField>> = self
+                .draw_u32s()
+                .into_iter()
+                .map(BaseField::from_uniform_u32)
+                .collect();
+
+            if let Some(felts) = felts {
+                return felts.try_into().unwrap();
+            }
+        }
+    }
+
     fn draw_random_bytes(&mut self) -> Vec<u8> {
         let mut hash_input = self.digest.as_ref().to_vec();
 
@@ -117,7 +112,9 @@ mod tests {
 
     use crate::core::channel::blake2s::Blake2sChannel;
     use crate::core::channel::Channel;
+    use crate::core::fields::om31::OM31;
     use crate::core::fields::qm31::SecureField;
+    use crate::core::fields::SecureExtension;
     use crate::m31;
 
     #[test]
@@ -182,4 +179,48 @@ mod tests {
 
         assert_ne!(initial_digest, channel.digest);
     }
+
+    #[test]
+    pub fn test_draw_extension_felts_matches_draw_felts() {
+        let mut channel = Blake2sChannel::default();
+        let mut extension_channel = Blake2sChannel::default();
+
+        let felts = channel.draw_felts(5);
+        let extension_felts = extension_channel.draw_extension_felts::<SecureField>(5);
+
+        assert_eq!(felts, extension_felts);
+        assert_eq!(channel.digest, extension_channel.digest);
+    }
+
+    #[test]
+    pub fn test_draw_octic_extension_felts() {
+        let mut channel = Blake2sChannel::default();
+
+        let random_felts = channel.draw_extension_felts::<OM31>(5);
+
+        assert_eq!(channel.channel_time.n_sent, 5);
+        assert_eq!(
+            random_felts.len(),
+            random_felts.iter().collect::<BTreeSet<_>>().len()
+        );
+    }
+
+    #[test]
+    pub fn test_mix_extension_felts() {
+        let mut channel = Blake2sChannel::default();
+        let mut extension_channel = Blake2sChannel::default();
+        let felts: Vec<OM31> = (0..2).map(|i| OM31::from(m31!(i + 1923782))).collect();
+        let secure_felts: Vec<SecureField> = felts
+            .iter()
+            .flat_map(|felt| felt.to_m31_vec())
+            .collect::<Vec<_>>()
+            .chunks(4)
+            .map(SecureField::from_m31_slice)
+            .collect();
+
+        channel.mix_felts(&secure_felts);
+        extension_channel.mix_extension_felts(&felts);
+
+        assert_eq!(channel.digest, extension_channel.digest);
+    }
 }
@@ -1,4 +1,11 @@
+use std::iter;
+
+use itertools::Itertools;
+
+use super::fields::m31::{BaseField, N_BYTES_FELT};
 use super::fields::qm31::SecureField;
+use super::fields::secure_column::SECURE_EXTENSION_DEGREE;
+use super::fields::SecureExtension;
 use super::vcs::ops::MerkleHasher;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -10,6 +17,7 @@ mod blake2s;
 pub use blake2s::Blake2sChannel;
 
 pub const EXTENSION_FELTS_PER_HASH: usize = 2;
+pub const BASE_FELTS_PER_HASH: usize = 8;
 
 #[derive(Clone, Default)]
 pub struct ChannelTime {
@@ -37,16 +45,73 @@ pub trait Channel: Default + Clone {
     fn mix_felts(&mut self, felts: &[SecureField]);
     fn mix_u64(&mut self, value: u64);
 
+    /// Mixes elements of an arbitrary [SecureExtension], as chunks of their [SecureField]
+    /// coordinates.
+    fn mix_extension_felts<E: SecureExtension>(&mut self, felts: &[E]) {
+        let secure_felts = felts
+            .iter()
+            .flat_map(|felt| felt.to_m31_vec())
+            .chunks(SECURE_EXTENSION_DEGREE)
+            .into_iter()
+            .map(|coords| SecureField::from_m31_slice(&coords.collect_vec()))
+            .collect_vec();
+        self.mix_felts(&secure_felts);
+    }
+
     // Draw functions.
     fn draw_felt(&mut self) -> SecureField;
     /// Generates a uniform random vector of SecureField elements.
     fn draw_felts(&mut self, n_felts: usize) -> Vec<SecureField>;
+    /// Generates a uniform random array of BaseField elements.
+    fn draw_base_felts(&mut self) -> [BaseField; BASE_FELTS_PER_HASH];
     /// Returns a vector of random bytes of length `BYTES_PER_HASH`.
     fn draw_random_bytes(&mut self) -> Vec<u8>;
+    /// Returns the random bytes of [Channel::draw_random_bytes], read as little-endian `u32` words.
+    fn draw_u32s(&mut self) -> Vec<u32> {
+        le_u32_words(&self.draw_random_bytes()).collect()
+    }
+
+    /// Generates a uniform random element of an arbitrary [SecureExtension].
+    ///
+    /// Equivalent to [Channel::draw_felt] when `E` is [SecureField].
+    fn draw_extension_felt<E: SecureExtension>(&mut self) -> E {
+        self.draw_extension_felts(1).pop().unwrap()
+    }
+
+    /// Generates a uniform random vector of elements of an arbitrary [SecureExtension].
+    ///
+    /// Equivalent to [Channel::draw_felts] when `E` is [SecureField].
+    fn draw_extension_felts<E: SecureExtension>(&mut self, n_felts: usize) -> Vec<E> {
+        let mut felts = iter::from_fn(|| Some(self.draw_base_felts())).flatten();
+        (0..n_felts)
+            .map(|_| {
+                let coords = felts.by_ref().take(E::EXTENSION_DEGREE).collect_vec();
+                E::from_m31_slice(&coords)
+            })
+            .collect()
+    }
+}
+
+/// Reads `bytes` as little-endian `u32` words, the encoding of [BaseField] elements. Trailing bytes
+/// that don't fill a word are ignored.
+///
+/// [BaseField]: crate::core::fields::m31::BaseField
+fn le_u32_words(bytes: &[u8]) -> impl Iterator<Item = u32> + '_ {
+    bytes
+        .chunks_exact(N_BYTES_FELT)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
 }
 
 pub trait MerkleChannel: Default {
     type C: Channel;
     type H: MerkleHasher;
     fn mix_root(channel: &mut Self::C, root: <Self::H as MerkleHasher>::Hash);
+
+    /// Mixes the nodes of a Merkle cap into the channel, in order. A cap of a single node is mixed
+    /// like the root it is.
+    fn mix_cap(channel: &mut Self::C, cap: &[<Self::H as MerkleHasher>::Hash]) {
+        for &node in cap {
+            Self::mix_root(channel, node);
+        }
+    }
 }
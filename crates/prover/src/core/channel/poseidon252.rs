@@ -4,7 +4,7 @@ use starknet_crypto::{poseidon_hash, poseidon_hash_many};
 use starknet_ff::FieldElement as FieldElement252;
 
 use super::{Channel, ChannelTime};
-use crate::core::fields::m31::BaseField;
+use crate::core::fields::m31::{BaseField, P};
 use crate::core::fields::qm31::SecureField;
 use crate::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
 
@@ -31,26 +31,6 @@ impl Poseidon252Channel {
         self.channel_time.inc_sent();
         res
     }
-
-    // TODO(shahars): Understand if we really need uniformity here.
-    /// Generates a close-to uniform random vector of BaseField elements.
-    fn draw_base_felts(&mut self) -> [BaseField; 8] {
-        let shift = (1u64 << 31).into();
-
-        let mut cur = self.draw_felt252();
-        let u32s: [u32; 8] = std::array::from_fn(|_| {
-            let next = cur.floor_div(shift);
-            let res = cur - next * shift;
-            cur = next;
-            res.try_into().unwrap()
-        });
-
-        u32s.into_iter()
-            .map(|x| BaseField::reduce(x as u64))
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap()
-    }
 }
 
 impl Channel for Poseidon252Channel {
@@ -102,6 +82,37 @@ impl Channel for Poseidon252Channel {
         secure_felts.take(n_felts).collect()
     }
 
+    /// Generates a close-to uniform random vector of BaseField elements.
+    ///
+    /// Each draw splits a felt252 into 31-bit chunks, and is repeated until all of them are
+    /// canonical encodings, i.e. smaller than P. Retry probability for each round is ~ 2^(-28).
+    ///
+    /// This is a protocol change: earlier versions reduced the chunks modulo P instead, which is
+    /// biased towards 0 and is what the Cairo verifier does. The two agree unless a chunk equals P,
+    /// in which case this channel draws again and their transcripts diverge, so proofs made with
+    /// this channel must be verified by a verifier that rejects non-canonical chunks too.
+    fn draw_base_felts(&mut self) -> [BaseField; FELTS_PER_HASH] {
+        let shift = (1u64 << 31).into();
+
+        loop {
+            let mut cur = self.draw_felt252();
+            let u32s: [u32; 8] = std::array::from_fn(|_| {
+                let next = cur.floor_div(shift);
+                let res = cur - next * shift;
+                cur = next;
+                res.try_into().unwrap()
+            });
+
+            let felts: Option<Vec<BaseField>> = u32s
+                .into_iter()
+                .map(|x| (x < P).then(|| BaseField::from_u32_unchecked(x)))
+                .collect();
+            if let Some(felts) = felts {
+                return felts.try_into().unwrap();
+            }
+        }
+    }
+
     fn draw_random_bytes(&mut self) -> Vec<u8> {
         let shift = (1u64 << 8).into();
         let mut cur = self.draw_felt252();
@@ -186,4 +197,16 @@ mod tests {
 
         assert_ne!(initial_digest, channel.digest);
     }
+
+    #[test]
+    pub fn test_draw_extension_felts_matches_draw_felts() {
+        let mut channel = Poseidon252Channel::default();
+        let mut extension_channel = Poseidon252Channel::default();
+
+        let felts = channel.draw_felts(5);
+        let extension_felts = extension_channel.draw_extension_felts::<SecureField>(5);
+
+        assert_eq!(felts, extension_felts);
+        assert_eq!(channel.digest, extension_channel.digest);
+    }
 }
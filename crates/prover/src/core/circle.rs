@@ -1,10 +1,10 @@
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
-use num_traits::{One, Zero};
+use num_traits::Zero;
 
 use super::fields::m31::{BaseField, M31};
 use super::fields::qm31::SecureField;
-use super::fields::{ComplexConjugate, Field, FieldExpOps};
+use super::fields::{ComplexConjugate, Field, FieldExpOps, FieldSqrt, SecureExtension};
 use crate::core::channel::Channel;
 use crate::core::fields::qm31::P4;
 use crate::math::utils::egcd;
@@ -120,6 +120,27 @@ impl<F: Zero + Add<Output = F> + FieldExpOps + Sub<Output = F> + Neg<Output = F>
     }
 }
 
+impl<F: FieldSqrt> CirclePoint<F> {
+    /// Returns a point on the circle with the given x-coordinate, or `None` if there is no such
+    /// point.
+    ///
+    /// The other point with the same x-coordinate is the [conjugate](Self::conjugate) of the
+    /// returned point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use stwo_prover::core::circle::{CirclePoint, M31_CIRCLE_GEN};
+    ///
+    /// let p = CirclePoint::from_x(M31_CIRCLE_GEN.x).unwrap();
+    /// assert!(p == M31_CIRCLE_GEN || p == M31_CIRCLE_GEN.conjugate());
+    /// ```
+    pub fn from_x(x: F) -> Option<Self> {
+        let y = (F::one() - x.square()).sqrt()?;
+        Some(Self { x, y })
+    }
+}
+
 impl<F: Zero + Add<Output = F> + FieldExpOps + Sub<Output = F> + Neg<Output = F>> Add
     for CirclePoint<F>
 {
@@ -166,16 +187,17 @@ impl CirclePoint<SecureField> {
         assert!(index < SECURE_FIELD_CIRCLE_ORDER);
         SECURE_FIELD_CIRCLE_GEN.mul(index)
     }
+}
 
+impl<E: SecureExtension> CirclePoint<E> {
+    /// Draws a random point on the circle over the [SecureExtension] `E`.
     pub fn get_random_point<C: Channel>(channel: &mut C) -> Self {
-        let t = channel.draw_felt();
+        let t = channel.draw_extension_felt::<E>();
         let t_square = t.square();
 
-        let one_plus_tsquared_inv = t_square.add(SecureField::one()).inverse();
+        let one_plus_tsquared_inv = t_square.add(E::one()).inverse();
 
-        let x = SecureField::one()
-            .add(t_square.neg())
-            .mul(one_plus_tsquared_inv);
+        let x = E::one().add(t_square.neg()).mul(one_plus_tsquared_inv);
         let y = t.double().mul(one_plus_tsquared_inv);
 
         Self { x, y }
@@ -385,8 +407,14 @@ impl Coset {
     }
 
     pub fn is_doubling_of(&self, other: Self) -> bool {
-        self.log_size <= other.log_size
-            && *self == other.repeated_double(other.log_size - self.log_size)
+        if self.log_size > other.log_size {
+            return false;
+        }
+        let doubling = other.repeated_double(other.log_size - self.log_size);
+        // The step size of a single point coset may be unreduced (see
+        // [CirclePointIndex::subgroup_gen]).
+        self.initial_index == doubling.initial_index
+            && self.step_size.reduce() == doubling.step_size.reduce()
     }
 
     pub fn initial(&self) -> CirclePoint<M31> {
@@ -510,11 +538,22 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_coset_is_doubling_of() {
+        let coset = Coset::half_odds(3);
+
+        assert!(coset.is_doubling_of(coset));
+        assert!(Coset::half_odds(1).is_doubling_of(coset));
+        assert!(Coset::half_odds(0).is_doubling_of(coset));
+        assert!(!coset.is_doubling_of(Coset::half_odds(1)));
+        assert!(!Coset::odds(1).is_doubling_of(coset));
+    }
+
     #[test]
     pub fn test_get_random_circle_point() {
         let mut channel = Blake2sChannel::default();
 
-        let first_random_circle_point = CirclePoint::get_random_point(&mut channel);
+        let first_random_circle_point = CirclePoint::<SecureField>::get_random_point(&mut channel);
 
         // Assert that the next random circle point is different.
         assert_ne!(
@@ -523,6 +562,17 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn test_from_x() {
+        let mut channel = Blake2sChannel::default();
+        let point = CirclePoint::<SecureField>::get_random_point(&mut channel);
+
+        let from_x = CirclePoint::from_x(point.x).unwrap();
+
+        assert!(from_x == point || from_x == point.conjugate());
+        assert_eq!(from_x.x.square() + from_x.y.square(), SecureField::one());
+    }
+
     #[test]
     pub fn test_secure_field_circle_gen() {
         let prime_factors = [
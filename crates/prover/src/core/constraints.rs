@@ -3,7 +3,7 @@ use num_traits::One;
 use super::circle::{CirclePoint, Coset};
 use super::fields::m31::BaseField;
 use super::fields::qm31::SecureField;
-use super::fields::ExtensionOf;
+use super::fields::{ExtensionOf, SecureExtension};
 use super::pcs::quotients::PointSample;
 use crate::core::fields::ComplexConjugate;
 
@@ -95,10 +95,10 @@ pub fn complex_conjugate_line(
 /// (conj(sample.y), conj(sample.value)).
 /// Relies on the fact that every polynomial F over the base
 /// field holds: F(p*) == F(p)* (* being the complex conjugate).
-pub fn complex_conjugate_line_coeffs(
-    sample: &PointSample,
-    alpha: SecureField,
-) -> (SecureField, SecureField, SecureField) {
+pub fn complex_conjugate_line_coeffs<E: SecureExtension>(
+    sample: &PointSample<E>,
+    alpha: E,
+) -> (E, E, E) {
     // TODO(AlonH): This assertion will fail at a probability of 1 to 2^62. Use a better solution.
     assert_ne!(
         sample.point.y,
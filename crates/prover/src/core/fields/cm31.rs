@@ -5,8 +5,8 @@ use std::ops::{
 
 use serde::{Deserialize, Serialize};
 
-use super::{ComplexConjugate, FieldExpOps};
-use crate::core::fields::m31::M31;
+use super::{ComplexConjugate, FieldDecodingError, FieldExpOps, FieldSqrt};
+use crate::core::fields::m31::{M31, N_BYTES_FELT};
 use crate::{impl_extension_field, impl_field};
 pub const P2: u64 = 4611686014132420609; // (2 ** 31 - 1) ** 2
 
@@ -27,6 +27,27 @@ impl CM31 {
     pub fn from_m31(a: M31, b: M31) -> CM31 {
         Self(a, b)
     }
+
+    /// Returns the canonical little-endian encoding of the coordinates.
+    pub fn to_le_bytes(self) -> [u8; 2 * N_BYTES_FELT] {
+        let mut bytes = [0; 2 * N_BYTES_FELT];
+        bytes[..N_BYTES_FELT].copy_from_slice(&self.0.to_le_bytes());
+        bytes[N_BYTES_FELT..].copy_from_slice(&self.1.to_le_bytes());
+        bytes
+    }
+
+    /// Decodes a canonical little-endian encoding of the coordinates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a coordinate is not in the range `[0, P)`.
+    pub fn try_from_le_bytes(bytes: [u8; 2 * N_BYTES_FELT]) -> Result<Self, FieldDecodingError> {
+        let (a, b) = bytes.split_at(N_BYTES_FELT);
+        Ok(Self(
+            M31::try_from_le_bytes(a.try_into().unwrap())?,
+            M31::try_from_le_bytes(b.try_into().unwrap())?,
+        ))
+    }
 }
 
 impl Display for CM31 {
@@ -72,6 +93,31 @@ impl FieldExpOps for CM31 {
     }
 }
 
+impl FieldSqrt for CM31 {
+    fn legendre_symbol(&self) -> i32 {
+        // An element of a quadratic extension is a square iff its norm is a square.
+        (self.0.square() + self.1.square()).legendre_symbol()
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        if self.1.is_zero() {
+            // sqrt(a) is either in M31, or of the form yi with y^2 = -a.
+            return match self.0.sqrt() {
+                Some(x) => Some(Self(x, M31::zero())),
+                None => (-self.0).sqrt().map(|y| Self(M31::zero(), y)),
+            };
+        }
+
+        // (x + yi)^2 = a + bi iff x^2 = (a +- sqrt(a^2 + b^2)) / 2 and y = b / 2x.
+        let norm_sqrt = (self.0.square() + self.1.square()).sqrt()?;
+        let two_inv = M31::from(2).inverse();
+        let x = ((self.0 + norm_sqrt) * two_inv)
+            .sqrt()
+            .or_else(|| ((self.0 - norm_sqrt) * two_inv).sqrt())?;
+        Some(Self(x, self.1 / x.double()))
+    }
+}
+
 #[cfg(test)]
 #[macro_export]
 macro_rules! cm31 {
@@ -87,7 +133,7 @@ mod tests {
 
     use super::CM31;
     use crate::core::fields::m31::P;
-    use crate::core::fields::{FieldExpOps, IntoSlice};
+    use crate::core::fields::{FieldExpOps, FieldSqrt, IntoSlice};
     use crate::m31;
 
     #[test]
@@ -134,4 +180,23 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    fn test_sqrt() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let x: CM31 = rng.gen();
+            let square = x * x;
+
+            let root = square.sqrt().unwrap();
+
+            assert_eq!(root * root, square);
+            assert!(square.is_square());
+        }
+        // The squares of base field non-squares.
+        assert_eq!(cm31!(P - 1, 0).sqrt(), Some(cm31!(0, 1)));
+        // 2 + i is the non-residue used to build QM31.
+        assert_eq!(cm31!(2, 1).legendre_symbol(), -1);
+        assert_eq!(cm31!(2, 1).sqrt(), None);
+    }
 }
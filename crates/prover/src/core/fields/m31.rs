@@ -7,7 +7,7 @@ use bytemuck::{Pod, Zeroable};
 use rand::distributions::{Distribution, Standard};
 use serde::{Deserialize, Serialize};
 
-use super::{ComplexConjugate, FieldExpOps};
+use super::{ComplexConjugate, FieldDecodingError, FieldExpOps, FieldSqrt};
 use crate::impl_field;
 pub const MODULUS_BITS: u32 = 31;
 pub const N_BYTES_FELT: usize = 4;
@@ -29,6 +29,7 @@ pub const P: u32 = 2147483647; // 2 ** 31 - 1
     Serialize,
     Deserialize,
 )]
+#[serde(try_from = "M31Encoding")]
 pub struct M31(pub u32);
 pub type BaseField = M31;
 
@@ -62,6 +63,52 @@ impl M31 {
     pub const fn from_u32_unchecked(arg: u32) -> Self {
         Self(arg)
     }
+
+    /// Maps a uniformly random `u32` to a uniformly random element.
+    ///
+    /// Values in the range `[0, 2P)` are reduced, while the remaining values (`2P` and `2P + 1`)
+    /// must be rejected, in which case `None` is returned.
+    pub fn from_uniform_u32(val: u32) -> Option<Self> {
+        (val < 2 * P).then(|| Self::partial_reduce(val))
+    }
+
+    /// Returns the canonical little-endian encoding.
+    pub fn to_le_bytes(self) -> [u8; N_BYTES_FELT] {
+        self.0.to_le_bytes()
+    }
+
+    /// Decodes a canonical little-endian encoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the encoded value is not in the range `[0, P)`.
+    ///
+    /// ```
+    /// use stwo_prover::core::fields::m31::{M31, P};
+    ///
+    /// assert_eq!(M31::try_from_le_bytes(7u32.to_le_bytes()), Ok(M31::from(7)));
+    /// assert!(M31::try_from_le_bytes(P.to_le_bytes()).is_err());
+    /// ```
+    pub fn try_from_le_bytes(bytes: [u8; N_BYTES_FELT]) -> Result<Self, FieldDecodingError> {
+        let value = u32::from_le_bytes(bytes);
+        if value >= P {
+            return Err(FieldDecodingError::NonCanonical { value });
+        }
+        Ok(Self(value))
+    }
+}
+
+/// Serialized representation of [M31], validated on deserialization.
+#[derive(Deserialize)]
+#[serde(rename = "M31")]
+struct M31Encoding(u32);
+
+impl TryFrom<M31Encoding> for M31 {
+    type Error = FieldDecodingError;
+
+    fn try_from(M31Encoding(value): M31Encoding) -> Result<Self, Self::Error> {
+        Self::try_from_le_bytes(value.to_le_bytes())
+    }
 }
 
 impl Display for M31 {
@@ -117,6 +164,23 @@ impl FieldExpOps for M31 {
     }
 }
 
+impl FieldSqrt for M31 {
+    fn legendre_symbol(&self) -> i32 {
+        // Euler's criterion: a^((p - 1) / 2).
+        match self.pow(((P - 1) / 2) as u128) {
+            v if v.is_zero() => 0,
+            v if v.is_one() => 1,
+            _ => -1,
+        }
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        // Since p = 3 (mod 4), a^((p + 1) / 4) is a square root of any square a.
+        let root = self.repeated_square(29);
+        (root.square() == *self).then_some(root)
+    }
+}
+
 impl ComplexConjugate for M31 {
     fn complex_conjugate(&self) -> Self {
         *self
@@ -209,7 +273,7 @@ mod tests {
     use rand::{Rng, SeedableRng};
 
     use super::{M31, P};
-    use crate::core::fields::IntoSlice;
+    use crate::core::fields::{FieldDecodingError, FieldSqrt, IntoSlice};
 
     fn mul_p(a: u32, b: u32) -> u32 {
         ((a as u64 * b as u64) % P as u64) as u32
@@ -255,4 +319,44 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_sqrt() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let x: M31 = rng.gen();
+            let square = x * x;
+
+            let root = square.sqrt().unwrap();
+
+            assert_eq!(root * root, square);
+            assert!(square.is_square());
+        }
+        // -1 is not a square since p = 3 (mod 4).
+        assert_eq!((-M31::from(1)).legendre_symbol(), -1);
+        assert_eq!((-M31::from(1)).sqrt(), None);
+        assert_eq!(M31::from(0).legendre_symbol(), 0);
+        assert_eq!(M31::from(0).sqrt(), Some(M31::from(0)));
+    }
+
+    #[test]
+    fn test_le_bytes_round_trip() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let x: M31 = rng.gen();
+
+            assert_eq!(M31::try_from_le_bytes(x.to_le_bytes()), Ok(x));
+        }
+        assert_eq!(
+            M31::try_from_le_bytes(P.to_le_bytes()),
+            Err(FieldDecodingError::NonCanonical { value: P })
+        );
+    }
+
+    #[test]
+    fn test_from_uniform_u32() {
+        assert_eq!(M31::from_uniform_u32(P + 5), Some(M31::from(5)));
+        assert_eq!(M31::from_uniform_u32(2 * P), None);
+        assert_eq!(M31::from_uniform_u32(u32::MAX), None);
+    }
 }
@@ -1,13 +1,18 @@
 use std::fmt::{Debug, Display};
+use std::hash::Hash;
 use std::iter::{Product, Sum};
 use std::ops::{Mul, MulAssign, Neg};
 
-use num_traits::{NumAssign, NumAssignOps, NumOps, One};
+use num_traits::{NumAssign, NumAssignOps, NumOps, One, Zero};
+use thiserror::Error;
 
+use self::m31::BaseField;
+use self::secure_column::ExtensionColumn;
 use super::backend::ColumnOps;
 
 pub mod cm31;
 pub mod m31;
+pub mod om31;
 pub mod qm31;
 pub mod secure_column;
 
@@ -37,49 +42,64 @@ pub trait FieldExpOps: Mul<Output = Self> + MulAssign + Sized + One + Clone {
 
     fn inverse(&self) -> Self;
 
+    /// Returns `self^(2^n)`.
+    fn repeated_square(&self, n: u32) -> Self {
+        let mut res = self.clone();
+        for _ in 0..n {
+            res = res.square();
+        }
+        res
+    }
+
     /// Inverts a batch of elements using Montgomery's trick.
     fn batch_inverse(column: &[Self], dst: &mut [Self]) {
-        const WIDTH: usize = 4;
-        let n = column.len();
-        debug_assert!(dst.len() >= n);
+        batch_inverse_with(column, dst, Self::clone);
+    }
+}
 
-        if n <= WIDTH || n % WIDTH != 0 {
-            batch_inverse_classic(column, dst);
-            return;
-        }
+/// Inverts a batch of elements using Montgomery's trick, where each element is first mapped by
+/// `map`.
+fn batch_inverse_with<T: FieldExpOps>(column: &[T], dst: &mut [T], map: impl Fn(&T) -> T) {
+    const WIDTH: usize = 4;
+    let n = column.len();
+    debug_assert!(dst.len() >= n);
 
-        // First pass. Compute 'WIDTH' cumulative products in an interleaving fashion, reducing
-        // instruction dependency and allowing better pipelining.
-        let mut cum_prod: [Self; WIDTH] = std::array::from_fn(|_| Self::one());
-        dst[..WIDTH].clone_from_slice(&cum_prod);
-        for i in 0..n {
-            cum_prod[i % WIDTH] *= column[i].clone();
-            dst[i] = cum_prod[i % WIDTH].clone();
-        }
+    if n <= WIDTH || n % WIDTH != 0 {
+        batch_inverse_classic(column, dst, map);
+        return;
+    }
 
-        // Inverse cumulative products.
-        // Use classic batch inversion.
-        let mut tail_inverses: [Self; WIDTH] = std::array::from_fn(|_| Self::one());
-        batch_inverse_classic(&dst[n - WIDTH..], &mut tail_inverses);
+    // First pass. Compute 'WIDTH' cumulative products in an interleaving fashion, reducing
+    // instruction dependency and allowing better pipelining.
+    let mut cum_prod: [T; WIDTH] = std::array::from_fn(|_| T::one());
+    dst[..WIDTH].clone_from_slice(&cum_prod);
+    for i in 0..n {
+        cum_prod[i % WIDTH] *= map(&column[i]);
+        dst[i] = cum_prod[i % WIDTH].clone();
+    }
 
-        // Second pass.
-        for i in (WIDTH..n).rev() {
-            dst[i] = dst[i - WIDTH].clone() * tail_inverses[i % WIDTH].clone();
-            tail_inverses[i % WIDTH] *= column[i].clone();
-        }
-        dst[0..WIDTH].clone_from_slice(&tail_inverses);
+    // Inverse cumulative products.
+    // Use classic batch inversion.
+    let mut tail_inverses: [T; WIDTH] = std::array::from_fn(|_| T::one());
+    batch_inverse_classic(&dst[n - WIDTH..], &mut tail_inverses, T::clone);
+
+    // Second pass.
+    for i in (WIDTH..n).rev() {
+        dst[i] = dst[i - WIDTH].clone() * tail_inverses[i % WIDTH].clone();
+        tail_inverses[i % WIDTH] *= map(&column[i]);
     }
+    dst[0..WIDTH].clone_from_slice(&tail_inverses);
 }
 
 /// Assumes dst is initialized and of the same length as column.
-fn batch_inverse_classic<T: FieldExpOps>(column: &[T], dst: &mut [T]) {
+fn batch_inverse_classic<T: FieldExpOps>(column: &[T], dst: &mut [T], map: impl Fn(&T) -> T) {
     let n = column.len();
     debug_assert!(dst.len() >= n);
 
-    dst[0] = column[0].clone();
+    dst[0] = map(&column[0]);
     // First pass.
     for i in 1..n {
-        dst[i] = dst[i - 1].clone() * column[i].clone();
+        dst[i] = dst[i - 1].clone() * map(&column[i]);
     }
 
     // Inverse cumulative product.
@@ -88,11 +108,69 @@ fn batch_inverse_classic<T: FieldExpOps>(column: &[T], dst: &mut [T]) {
     // Second pass.
     for i in (1..n).rev() {
         dst[i] = dst[i - 1].clone() * curr_inverse.clone();
-        curr_inverse *= column[i].clone();
+        curr_inverse *= map(&column[i]);
     }
     dst[0] = curr_inverse;
 }
 
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum FieldDecodingError {
+    #[error("non-canonical field element encoding: {value} is not less than the modulus")]
+    NonCanonical { value: u32 },
+}
+
+/// Square roots and quadratic residuosity.
+pub trait FieldSqrt: Field {
+    /// Returns the Legendre symbol of `self`: `0` if `self` is zero, `1` if it is a non-zero square
+    /// and `-1` otherwise.
+    fn legendre_symbol(&self) -> i32;
+
+    /// Returns whether `self` is a square (zero included).
+    fn is_square(&self) -> bool {
+        self.legendre_symbol() >= 0
+    }
+
+    /// Returns a square root of `self`, or `None` if `self` is not a square.
+    ///
+    /// The other square root is the negation of the returned one.
+    fn sqrt(&self) -> Option<Self>;
+}
+
+/// Inversion that maps zero to zero.
+///
+/// For packed types, zeros are handled lane-wise.
+pub trait InverseOrZero: FieldExpOps + Zero {
+    /// Selects `if_nonzero` in every element (lane) where `self` is non-zero, and `if_zero`
+    /// elsewhere.
+    fn select_nonzero(&self, if_nonzero: &Self, if_zero: &Self) -> Self;
+
+    /// Returns the inverse of `self`, or zero if `self` is zero.
+    fn inverse_or_zero(&self) -> Self {
+        let inverse = self.select_nonzero(self, &Self::one()).inverse();
+        self.select_nonzero(&inverse, &Self::zero())
+    }
+
+    /// Inverts a batch of elements using Montgomery's trick, mapping zeros to zero.
+    fn batch_inverse_or_zero(column: &[Self], dst: &mut [Self]) {
+        let one = Self::one();
+        batch_inverse_with(column, dst, |v| v.select_nonzero(v, &one));
+        let zero = Self::zero();
+        for (v, inverse) in column.iter().zip(dst.iter_mut()) {
+            *inverse = v.select_nonzero(inverse, &zero);
+        }
+    }
+}
+
+impl<F: Field> InverseOrZero for F {
+    fn select_nonzero(&self, if_nonzero: &Self, if_zero: &Self) -> Self {
+        if self.is_zero() {
+            *if_zero
+        } else {
+            *if_nonzero
+        }
+    }
+}
+
 pub trait Field:
     NumAssign
     + Neg<Output = Self>
@@ -160,6 +238,52 @@ impl<F: Field> ExtensionOf<F> for F {
     const EXTENSION_DEGREE: usize = 1;
 }
 
+/// An extension of [BaseField] large enough to be used as the secure field of the protocol.
+///
+/// Elements are represented by their coordinates over [BaseField]. The degree is always a multiple
+/// of the [QM31](qm31::QM31) degree, so elements can be mixed into a channel as [QM31](qm31::QM31)
+/// chunks.
+pub trait SecureExtension: ExtensionOf<BaseField> + Hash + Ord {
+    /// The column of coordinates that holds elements on a backend `B`.
+    type Column<B: FieldOps<BaseField>>: ExtensionColumn<B, Self>;
+
+    /// The subfield of index 2 fixed by [ComplexConjugate::complex_conjugate].
+    type Subfield: ExtensionOf<BaseField>;
+
+    /// Returns `(a, b)` such that the element is `a + b * i`, where `i` generates the extension
+    /// over [Self::Subfield] and is negated by [ComplexConjugate::complex_conjugate].
+    fn to_subfield_parts(&self) -> (Self::Subfield, Self::Subfield);
+
+    /// Multiplies the element by an element of [Self::Subfield].
+    fn mul_subfield(self, rhs: Self::Subfield) -> Self;
+
+    /// Returns the element with the given coordinates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of coordinates is not `EXTENSION_DEGREE`.
+    fn from_m31_slice(coords: &[BaseField]) -> Self;
+
+    /// Returns the coordinates of the element.
+    fn to_m31_vec(&self) -> Vec<BaseField>;
+
+    /// Returns the combined value, given the values of its composing base field polynomials at that
+    /// point.
+    fn from_partial_evals(evals: &[Self]) -> Self {
+        assert_eq!(evals.len(), Self::EXTENSION_DEGREE);
+        let mut unit_coords = vec![BaseField::zero(); Self::EXTENSION_DEGREE];
+        evals
+            .iter()
+            .enumerate()
+            .map(|(i, &eval)| {
+                unit_coords.fill(BaseField::zero());
+                unit_coords[i] = BaseField::one();
+                eval * Self::from_m31_slice(&unit_coords)
+            })
+            .sum()
+    }
+}
+
 #[macro_export]
 macro_rules! impl_field {
     ($field_name: ty, $field_size: ident) => {
@@ -463,7 +587,8 @@ mod tests {
     use rand::{Rng, SeedableRng};
 
     use crate::core::fields::m31::M31;
-    use crate::core::fields::FieldExpOps;
+    use crate::core::fields::qm31::QM31;
+    use crate::core::fields::{FieldExpOps, InverseOrZero};
 
     #[test]
     fn test_slice_batch_inverse() {
@@ -486,4 +611,30 @@ mod tests {
 
         M31::batch_inverse(&elements, &mut dst);
     }
+
+    #[test]
+    fn test_slice_batch_inverse_or_zero() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut elements: [QM31; 16] = rng.gen();
+        elements[3] = QM31::zero();
+        elements[12] = QM31::zero();
+        let expected = elements
+            .iter()
+            .map(|e| if e.is_zero() { *e } else { e.inverse() })
+            .collect::<Vec<_>>();
+        let mut dst = [QM31::zero(); 16];
+
+        QM31::batch_inverse_or_zero(&elements, &mut dst);
+
+        assert_eq!(expected, dst);
+        assert_eq!(QM31::zero().inverse_or_zero(), QM31::zero());
+    }
+
+    #[test]
+    fn test_repeated_square() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let element: QM31 = rng.gen();
+
+        assert_eq!(element.repeated_square(5), element.pow(1 << 5));
+    }
 }
@@ -0,0 +1,219 @@
+use std::fmt::{Debug, Display};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::qm31::QM31;
+use super::secure_column::ExtensionColumnByCoords;
+use super::{ComplexConjugate, FieldExpOps, FieldOps, SecureExtension};
+use crate::core::fields::m31::M31;
+use crate::{impl_extension_field, impl_field};
+
+pub const OM31_EXTENSION_DEGREE: usize = 8;
+
+/// The non-residue `u` of [QM31] used to build the octic extension.
+pub const U: QM31 = QM31::from_u32_unchecked(0, 0, 1, 0);
+
+/// Extension field of QM31.
+/// Equivalent to QM31\[x\] over (x^2 - u) as the irreducible polynomial, where u is the generator
+/// of QM31 over CM31.
+/// Represented as (a, b) of a + bv, where v^2 = u.
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub struct OM31(pub QM31, pub QM31);
+
+impl_field!(OM31, P8);
+impl_extension_field!(OM31, QM31);
+
+impl OM31 {
+    #[allow(clippy::too_many_arguments)]
+    pub const fn from_u32_unchecked(
+        a: u32,
+        b: u32,
+        c: u32,
+        d: u32,
+        e: u32,
+        f: u32,
+        g: u32,
+        h: u32,
+    ) -> Self {
+        Self(
+            QM31::from_u32_unchecked(a, b, c, d),
+            QM31::from_u32_unchecked(e, f, g, h),
+        )
+    }
+
+    pub fn from_qm31(a: QM31, b: QM31) -> Self {
+        Self(a, b)
+    }
+
+    pub fn from_m31_array(array: [M31; OM31_EXTENSION_DEGREE]) -> Self {
+        Self(
+            QM31::from_m31_array(array[..4].try_into().unwrap()),
+            QM31::from_m31_array(array[4..].try_into().unwrap()),
+        )
+    }
+
+    pub fn to_m31_array(self) -> [M31; OM31_EXTENSION_DEGREE] {
+        let [a, b, c, d] = self.0.to_m31_array();
+        let [e, f, g, h] = self.1.to_m31_array();
+        [a, b, c, d, e, f, g, h]
+    }
+
+    pub fn mul_qm31(self, rhs: QM31) -> Self {
+        Self(self.0 * rhs, self.1 * rhs)
+    }
+}
+
+/// Multiplies a [QM31] element by `u`.
+fn mul_by_u(x: QM31) -> QM31 {
+    // (a + bu) * u = rb + au.
+    QM31(x.1 * super::qm31::R, x.0)
+}
+
+impl Display for OM31 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}) + ({})v", self.0, self.1)
+    }
+}
+
+impl Debug for OM31 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}) + ({})v", self.0, self.1)
+    }
+}
+
+impl Mul for OM31 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        // (a + bv) * (c + dv) = (ac + ubd) + (ad + bc)v.
+        Self(
+            self.0 * rhs.0 + mul_by_u(self.1 * rhs.1),
+            self.0 * rhs.1 + self.1 * rhs.0,
+        )
+    }
+}
+
+impl From<QM31> for OM31 {
+    fn from(value: QM31) -> Self {
+        Self(value, QM31::zero())
+    }
+}
+
+impl From<u32> for OM31 {
+    fn from(value: u32) -> Self {
+        M31::from(value).into()
+    }
+}
+
+impl TryInto<QM31> for OM31 {
+    type Error = ();
+
+    fn try_into(self) -> Result<QM31, Self::Error> {
+        if self.1 != QM31::zero() {
+            return Err(());
+        }
+        Ok(self.0)
+    }
+}
+
+impl FieldExpOps for OM31 {
+    fn inverse(&self) -> Self {
+        assert!(!self.is_zero(), "0 has no inverse");
+        // (a + bv)^-1 = (a - bv) / (a^2 - ub^2).
+        let denom = self.0.square() - mul_by_u(self.1.square());
+        let denom_inverse = denom.inverse();
+        Self(self.0 * denom_inverse, -self.1 * denom_inverse)
+    }
+}
+
+impl SecureExtension for OM31 {
+    type Column<B: FieldOps<M31>> = ExtensionColumnByCoords<B, Self>;
+    type Subfield = QM31;
+
+    fn to_subfield_parts(&self) -> (QM31, QM31) {
+        (self.0, self.1)
+    }
+
+    fn mul_subfield(self, rhs: QM31) -> Self {
+        self.mul_qm31(rhs)
+    }
+
+    fn from_m31_slice(coords: &[M31]) -> Self {
+        Self::from_m31_array(coords.try_into().unwrap())
+    }
+
+    fn to_m31_vec(&self) -> Vec<M31> {
+        self.to_m31_array().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_traits::{One, Zero};
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::{OM31, U};
+    use crate::core::fields::m31::{M31, P};
+    use crate::core::fields::qm31::{P4, QM31};
+    use crate::core::fields::{FieldExpOps, SecureExtension};
+
+    #[test]
+    fn test_u_is_not_a_square() {
+        assert_eq!(U.pow((P4 - 1) / 2), -QM31::one());
+    }
+
+    #[test]
+    fn test_inverse() {
+        let om = OM31::from_u32_unchecked(1, 2, 3, 4, 5, 6, 7, 8);
+        let om_inv = om.inverse();
+        assert_eq!(om * om_inv, OM31::one());
+    }
+
+    #[test]
+    fn test_ops() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let om0: OM31 = rng.gen();
+        let om1: OM31 = rng.gen();
+        let om2: OM31 = rng.gen();
+        let m = M31::from(8);
+        let v = OM31(QM31::zero(), QM31::one());
+
+        assert_eq!(v * v, OM31::from(U));
+        assert_eq!(om0 * (om1 + om2), om0 * om1 + om0 * om2);
+        assert_eq!((om0 * om1) * om2, om0 * (om1 * om2));
+        assert_eq!(om1 * m, om1 * OM31::from(m));
+        assert_eq!(om0 * om1 / om1, om0);
+        assert_eq!(
+            -OM31::from_u32_unchecked(1, 2, 3, 4, 5, 6, 7, 8),
+            OM31::from_u32_unchecked(P - 1, P - 2, P - 3, P - 4, P - 5, P - 6, P - 7, P - 8)
+        );
+    }
+
+    #[test]
+    fn test_m31_coords_round_trip() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let om: OM31 = rng.gen();
+
+        let coords = om.to_m31_vec();
+
+        assert_eq!(coords.len(), 8);
+        assert_eq!(OM31::from_m31_slice(&coords), om);
+    }
+
+    #[test]
+    fn test_from_partial_evals() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let om: OM31 = rng.gen();
+        let partial_evals = om
+            .to_m31_vec()
+            .into_iter()
+            .map(OM31::from)
+            .collect::<Vec<_>>();
+
+        assert_eq!(OM31::from_partial_evals(&partial_evals), om);
+    }
+}
@@ -5,10 +5,12 @@ use std::ops::{
 
 use serde::{Deserialize, Serialize};
 
-use super::secure_column::SECURE_EXTENSION_DEGREE;
-use super::{ComplexConjugate, FieldExpOps};
+use super::secure_column::{SecureColumnByCoords, SECURE_EXTENSION_DEGREE};
+use super::{
+    ComplexConjugate, FieldDecodingError, FieldExpOps, FieldOps, FieldSqrt, SecureExtension,
+};
 use crate::core::fields::cm31::CM31;
-use crate::core::fields::m31::M31;
+use crate::core::fields::m31::{M31, N_BYTES_FELT};
 use crate::{impl_extension_field, impl_field};
 
 pub const P4: u128 = 21267647892944572736998860269687930881; // (2 ** 31 - 1) ** 4
@@ -44,6 +46,33 @@ impl QM31 {
         [self.0 .0, self.0 .1, self.1 .0, self.1 .1]
     }
 
+    /// Returns the canonical little-endian encoding of the coordinates.
+    pub fn to_le_bytes(self) -> [u8; SECURE_EXTENSION_DEGREE * N_BYTES_FELT] {
+        let mut bytes = [0; SECURE_EXTENSION_DEGREE * N_BYTES_FELT];
+        for (chunk, coord) in bytes
+            .chunks_exact_mut(N_BYTES_FELT)
+            .zip(self.to_m31_array())
+        {
+            chunk.copy_from_slice(&coord.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes a canonical little-endian encoding of the coordinates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a coordinate is not in the range `[0, P)`.
+    pub fn try_from_le_bytes(
+        bytes: [u8; SECURE_EXTENSION_DEGREE * N_BYTES_FELT],
+    ) -> Result<Self, FieldDecodingError> {
+        let mut coords = [M31::zero(); SECURE_EXTENSION_DEGREE];
+        for (coord, chunk) in coords.iter_mut().zip(bytes.chunks_exact(N_BYTES_FELT)) {
+            *coord = M31::try_from_le_bytes(chunk.try_into().unwrap())?;
+        }
+        Ok(Self::from_m31_array(coords))
+    }
+
     /// Returns the combined value, given the values of its composing base field polynomials at that
     /// point.
     pub fn from_partial_evals(evals: [Self; SECURE_EXTENSION_DEGREE]) -> Self {
@@ -126,6 +155,56 @@ impl FieldExpOps for QM31 {
     }
 }
 
+impl FieldSqrt for QM31 {
+    fn legendre_symbol(&self) -> i32 {
+        // An element of a quadratic extension is a square iff its norm is a square.
+        (self.0.square() - R * self.1.square()).legendre_symbol()
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        if self.1.is_zero() {
+            // sqrt(a) is either in CM31, or of the form yu with y^2 = a / r.
+            return match self.0.sqrt() {
+                Some(x) => Some(Self(x, CM31::zero())),
+                None => (self.0 / R).sqrt().map(|y| Self(CM31::zero(), y)),
+            };
+        }
+
+        // (x + yu)^2 = a + bu iff x^2 = (a +- sqrt(a^2 - rb^2)) / 2 and y = b / 2x.
+        let norm_sqrt = (self.0.square() - R * self.1.square()).sqrt()?;
+        let two_inv = M31::from(2).inverse();
+        let x = ((self.0 + norm_sqrt) * two_inv)
+            .sqrt()
+            .or_else(|| ((self.0 - norm_sqrt) * two_inv).sqrt())?;
+        Some(Self(x, self.1 / x.double()))
+    }
+}
+
+impl SecureExtension for QM31 {
+    type Column<B: FieldOps<M31>> = SecureColumnByCoords<B>;
+    type Subfield = CM31;
+
+    fn to_subfield_parts(&self) -> (CM31, CM31) {
+        (self.0, self.1)
+    }
+
+    fn mul_subfield(self, rhs: CM31) -> Self {
+        self.mul_cm31(rhs)
+    }
+
+    fn from_m31_slice(coords: &[M31]) -> Self {
+        Self::from_m31_array(coords.try_into().unwrap())
+    }
+
+    fn to_m31_vec(&self) -> Vec<M31> {
+        self.to_m31_array().to_vec()
+    }
+
+    fn from_partial_evals(evals: &[Self]) -> Self {
+        Self::from_partial_evals(evals.try_into().unwrap())
+    }
+}
+
 #[cfg(test)]
 #[macro_export]
 macro_rules! qm31 {
@@ -141,9 +220,10 @@ mod tests {
     use rand::rngs::SmallRng;
     use rand::{Rng, SeedableRng};
 
-    use super::QM31;
+    use super::{P4, QM31};
+    use crate::core::fields::cm31::CM31;
     use crate::core::fields::m31::P;
-    use crate::core::fields::{FieldExpOps, IntoSlice};
+    use crate::core::fields::{FieldExpOps, FieldSqrt, IntoSlice};
     use crate::m31;
 
     #[test]
@@ -192,4 +272,51 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    fn test_sqrt() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let x: QM31 = rng.gen();
+            let square = x * x;
+
+            let root = square.sqrt().unwrap();
+
+            assert_eq!(root * root, square);
+            assert!(square.is_square());
+        }
+        // Squares of elements of the form yu.
+        let yu = qm31!(0, 0, 3, 4);
+        assert_eq!(yu.square().sqrt().unwrap().square(), yu.square());
+    }
+
+    #[test]
+    fn test_legendre_symbol() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let x: QM31 = rng.gen();
+
+            let expected = x.pow((P4 - 1) / 2);
+
+            match x.legendre_symbol() {
+                1 => assert_eq!(expected, QM31::one()),
+                -1 => assert_eq!(expected, -QM31::one()),
+                _ => unreachable!(),
+            }
+            assert_eq!(x.sqrt().is_some(), x.is_square());
+        }
+    }
+
+    #[test]
+    fn test_le_bytes_round_trip() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let x: QM31 = rng.gen();
+        let mut non_canonical = x.to_le_bytes();
+        non_canonical[12..].copy_from_slice(&P.to_le_bytes());
+
+        assert_eq!(QM31::try_from_le_bytes(x.to_le_bytes()), Ok(x));
+        assert_eq!(CM31::try_from_le_bytes(x.1.to_le_bytes()), Ok(x.1));
+        assert_eq!(&x.to_le_bytes()[..8], &x.0.to_le_bytes());
+        assert!(QM31::try_from_le_bytes(non_canonical).is_err());
+    }
 }
@@ -1,9 +1,11 @@
 use std::array;
+use std::fmt::Debug;
 use std::iter::zip;
+use std::marker::PhantomData;
 
 use super::m31::BaseField;
 use super::qm31::SecureField;
-use super::{ExtensionOf, FieldOps};
+use super::{ExtensionOf, FieldOps, SecureExtension};
 use crate::core::backend::{Col, Column, CpuBackend};
 
 pub const SECURE_EXTENSION_DEGREE: usize =
@@ -11,10 +13,24 @@ pub const SECURE_EXTENSION_DEGREE: usize =
 
 /// A column major array of `SECURE_EXTENSION_DEGREE` base field columns, that represents a column
 /// of secure field element coordinates.
-#[derive(Clone, Debug)]
 pub struct SecureColumnByCoords<B: FieldOps<BaseField>> {
     pub columns: [Col<B, BaseField>; SECURE_EXTENSION_DEGREE],
 }
+// Implemented by hand, as deriving would require the backend itself to be `Clone` and `Debug`.
+impl<B: FieldOps<BaseField>> Clone for SecureColumnByCoords<B> {
+    fn clone(&self) -> Self {
+        Self {
+            columns: self.columns.clone(),
+        }
+    }
+}
+impl<B: FieldOps<BaseField>> Debug for SecureColumnByCoords<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecureColumnByCoords")
+            .field("columns", &self.columns)
+            .finish()
+    }
+}
 impl SecureColumnByCoords<CpuBackend> {
     // TODO(first): Remove.
     pub fn to_vec(&self) -> Vec<SecureField> {
@@ -109,3 +125,229 @@ impl From<SecureColumnByCoords<CpuBackend>> for Vec<SecureField> {
         column.into_iter().collect()
     }
 }
+
+/// A column of coordinates of elements of a [SecureExtension] `E`, on a backend `B`. See
+/// [SecureExtension::Column].
+pub trait ExtensionColumn<B: FieldOps<BaseField>, E>: Clone + Debug {
+    fn zeros(len: usize) -> Self;
+
+    /// Returns the column of `values`.
+    fn from_values(values: &[E]) -> Self;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn at(&self, index: usize) -> E;
+
+    fn set(&mut self, index: usize, value: E);
+
+    /// Returns the base field column of each coordinate.
+    fn coordinates(&self) -> &[Col<B, BaseField>];
+
+    fn coordinates_mut(&mut self) -> &mut [Col<B, BaseField>];
+
+    fn to_vec(&self) -> Vec<E> {
+        (0..self.len()).map(|i| self.at(i)).collect()
+    }
+}
+
+impl<B: FieldOps<BaseField>> ExtensionColumn<B, SecureField> for SecureColumnByCoords<B> {
+    fn zeros(len: usize) -> Self {
+        Self::zeros(len)
+    }
+
+    fn from_values(values: &[SecureField]) -> Self {
+        Self {
+            columns: array::from_fn(|i| values.iter().map(|v| v.to_m31_array()[i]).collect()),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn at(&self, index: usize) -> SecureField {
+        self.at(index)
+    }
+
+    fn set(&mut self, index: usize, value: SecureField) {
+        self.set(index, value)
+    }
+
+    fn coordinates(&self) -> &[Col<B, BaseField>] {
+        &self.columns
+    }
+
+    fn coordinates_mut(&mut self) -> &mut [Col<B, BaseField>] {
+        &mut self.columns
+    }
+}
+
+/// A column major vector of `E::EXTENSION_DEGREE` base field columns, that represents a column of
+/// coordinates of elements of an arbitrary [SecureExtension] `E`.
+///
+/// This is the extension-generic counterpart of [SecureColumnByCoords], which is specialized for
+/// [SecureField].
+pub struct ExtensionColumnByCoords<B: FieldOps<BaseField>, E: SecureExtension> {
+    pub columns: Vec<Col<B, BaseField>>,
+    _extension: PhantomData<E>,
+}
+impl<B: FieldOps<BaseField>, E: SecureExtension> Clone for ExtensionColumnByCoords<B, E> {
+    fn clone(&self) -> Self {
+        Self::new(self.columns.clone())
+    }
+}
+impl<B: FieldOps<BaseField>, E: SecureExtension> Debug for ExtensionColumnByCoords<B, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExtensionColumnByCoords")
+            .field("columns", &self.columns)
+            .finish()
+    }
+}
+impl<B: FieldOps<BaseField>, E: SecureExtension> ExtensionColumnByCoords<B, E> {
+    /// # Panics
+    ///
+    /// Panics if the number of columns is not `E::EXTENSION_DEGREE` or if the columns have
+    /// different lengths.
+    pub fn new(columns: Vec<Col<B, BaseField>>) -> Self {
+        assert_eq!(columns.len(), E::EXTENSION_DEGREE);
+        assert!(columns.iter().all(|c| c.len() == columns[0].len()));
+        Self {
+            columns,
+            _extension: PhantomData,
+        }
+    }
+
+    pub fn at(&self, index: usize) -> E {
+        E::from_m31_slice(&self.columns.iter().map(|c| c.at(index)).collect::<Vec<_>>())
+    }
+
+    pub fn zeros(len: usize) -> Self {
+        Self::new(
+            (0..E::EXTENSION_DEGREE)
+                .map(|_| Col::<B, BaseField>::zeros(len))
+                .collect(),
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.columns[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns[0].is_empty()
+    }
+
+    pub fn to_cpu(&self) -> ExtensionColumnByCoords<CpuBackend, E> {
+        ExtensionColumnByCoords::new(self.columns.iter().map(|c| c.to_cpu()).collect())
+    }
+
+    pub fn set(&mut self, index: usize, value: E) {
+        zip(&mut self.columns, value.to_m31_vec()).for_each(|(col, coord)| col.set(index, coord));
+    }
+}
+impl<B: FieldOps<BaseField>, E: SecureExtension> ExtensionColumn<B, E>
+    for ExtensionColumnByCoords<B, E>
+{
+    fn zeros(len: usize) -> Self {
+        Self::zeros(len)
+    }
+
+    fn from_values(values: &[E]) -> Self {
+        let coords = values.iter().map(|v| v.to_m31_vec()).collect::<Vec<_>>();
+        Self::new(
+            (0..E::EXTENSION_DEGREE)
+                .map(|i| coords.iter().map(|c| c[i]).collect())
+                .collect(),
+        )
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn at(&self, index: usize) -> E {
+        self.at(index)
+    }
+
+    fn set(&mut self, index: usize, value: E) {
+        self.set(index, value)
+    }
+
+    fn coordinates(&self) -> &[Col<B, BaseField>] {
+        &self.columns
+    }
+
+    fn coordinates_mut(&mut self) -> &mut [Col<B, BaseField>] {
+        &mut self.columns
+    }
+}
+impl<B: FieldOps<BaseField>> From<SecureColumnByCoords<B>>
+    for ExtensionColumnByCoords<B, SecureField>
+{
+    fn from(column: SecureColumnByCoords<B>) -> Self {
+        Self::new(column.columns.into())
+    }
+}
+impl<E: SecureExtension> FromIterator<E> for ExtensionColumnByCoords<CpuBackend, E> {
+    fn from_iter<I: IntoIterator<Item = E>>(iter: I) -> Self {
+        let values = iter.into_iter();
+        let (lower_bound, _) = values.size_hint();
+        let mut columns = (0..E::EXTENSION_DEGREE)
+            .map(|_| Vec::with_capacity(lower_bound))
+            .collect::<Vec<_>>();
+
+        for value in values {
+            zip(&mut columns, value.to_m31_vec()).for_each(|(col, coord)| col.push(coord));
+        }
+
+        Self::new(columns)
+    }
+}
+impl<E: SecureExtension> From<ExtensionColumnByCoords<CpuBackend, E>> for Vec<E> {
+    fn from(column: ExtensionColumnByCoords<CpuBackend, E>) -> Self {
+        (0..column.len()).map(|i| column.at(i)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::{ExtensionColumnByCoords, SecureColumnByCoords};
+    use crate::core::backend::CpuBackend;
+    use crate::core::fields::om31::OM31;
+    use crate::core::fields::qm31::SecureField;
+
+    #[test]
+    fn test_extension_column_round_trip() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let values = (0..16).map(|_| rng.gen()).collect::<Vec<OM31>>();
+
+        let column = values
+            .iter()
+            .copied()
+            .collect::<ExtensionColumnByCoords<CpuBackend, _>>();
+
+        assert_eq!(column.columns.len(), 8);
+        assert_eq!(Vec::from(column), values);
+    }
+
+    #[test]
+    fn test_extension_column_from_secure_column() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let values = (0..16).map(|_| rng.gen()).collect::<Vec<SecureField>>();
+        let secure_column = values
+            .iter()
+            .copied()
+            .collect::<SecureColumnByCoords<CpuBackend>>();
+
+        let column = ExtensionColumnByCoords::from(secure_column);
+
+        assert_eq!(Vec::from(column), values);
+    }
+}
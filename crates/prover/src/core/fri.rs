@@ -5,7 +5,6 @@ use std::iter::zip;
 use std::ops::RangeInclusive;
 
 use itertools::Itertools;
-use num_traits::Zero;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::{span, Level};
@@ -14,14 +13,14 @@ use super::backend::CpuBackend;
 use super::channel::{Channel, MerkleChannel};
 use super::fields::m31::BaseField;
 use super::fields::qm31::SecureField;
-use super::fields::secure_column::{SecureColumnByCoords, SECURE_EXTENSION_DEGREE};
-use super::fields::FieldOps;
+use super::fields::secure_column::ExtensionColumn;
+use super::fields::{FieldOps, SecureExtension};
 use super::poly::circle::{CircleEvaluation, PolyOps, SecureEvaluation};
-use super::poly::line::{LineEvaluation, LinePoly};
+use super::poly::line::{LineEvaluation, LineOps, LinePoly};
 use super::poly::twiddles::TwiddleTree;
 use super::poly::BitReversedOrder;
 // TODO(andrew): Create fri/ directory, move queries.rs there and split this file up.
-use super::queries::{Queries, SparseSubCircleDomain};
+use super::queries::{Queries, QuerySampling, SparseSubCircleDomain};
 use crate::core::circle::Coset;
 use crate::core::fft::ibutterfly;
 use crate::core::fields::FieldExpOps;
@@ -29,15 +28,20 @@ use crate::core::poly::line::LineDomain;
 use crate::core::utils::bit_reverse_index;
 use crate::core::vcs::ops::{MerkleHasher, MerkleOps};
 use crate::core::vcs::prover::{MerkleDecommitment, MerkleProver};
+use crate::core::vcs::utils::cap_log_size;
 use crate::core::vcs::verifier::{MerkleVerificationError, MerkleVerifier};
 
 /// FRI proof config
 // TODO(andrew): Support different step sizes.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FriConfig {
     pub log_blowup_factor: u32,
     pub log_last_layer_degree_bound: u32,
     pub n_queries: usize,
+    pub query_sampling: QuerySampling,
+    /// The log size of the Merkle caps the decommitment paths end at, for the FRI layers and the
+    /// committed trees. 0 for paths up to the roots.
+    pub log_merkle_cap_size: u32,
     // TODO(andrew): fold_steps.
 }
 
@@ -66,15 +70,63 @@ impl FriConfig {
             log_blowup_factor,
             log_last_layer_degree_bound,
             n_queries,
+            query_sampling: QuerySampling::default(),
+            log_merkle_cap_size: 0,
         }
     }
 
+    /// Returns the same configuration, sampling queries with the given strategy.
+    pub const fn with_query_sampling(self, query_sampling: QuerySampling) -> Self {
+        Self {
+            query_sampling,
+            ..self
+        }
+    }
+
+    /// Returns the same configuration, with decommitment paths up to Merkle caps of
+    /// `2^log_merkle_cap_size` nodes. Each path is `log_merkle_cap_size` hashes shorter, and the
+    /// caps are the commitments instead of the roots.
+    pub const fn with_log_merkle_cap_size(self, log_merkle_cap_size: u32) -> Self {
+        Self {
+            log_merkle_cap_size,
+            ..self
+        }
+    }
+
+    /// Returns the conjectured security bits provided by the query phase, assuming all queries are
+    /// distinct.
+    ///
+    /// With [`QuerySampling::WithReplacement`] duplicate positions may make the effective security
+    /// lower. Use [`FriConfig::effective_security_bits`] with the sampled queries to account for
+    /// that.
+    pub const fn security_bits(&self) -> u32 {
+        self.log_blowup_factor * self.n_queries as u32
+    }
+
+    /// Returns the conjectured security bits provided by the query phase for a concrete set of
+    /// sampled queries.
+    pub fn effective_security_bits(&self, queries: &Queries) -> u32 {
+        self.log_blowup_factor * queries.n_effective_queries() as u32
+    }
+
+    fn sample_queries(&self, channel: &mut impl Channel, log_domain_size: u32) -> Queries {
+        Queries::sample(
+            channel,
+            log_domain_size,
+            self.n_queries,
+            self.query_sampling,
+        )
+    }
+
     fn last_layer_domain_size(&self) -> usize {
         1 << (self.log_last_layer_degree_bound + self.log_blowup_factor)
     }
 }
 
-pub trait FriOps: FieldOps<BaseField> + PolyOps + Sized + FieldOps<SecureField> {
+/// FRI operations over a [SecureExtension] `E`, the [SecureField] by default.
+pub trait FriOps<E: SecureExtension = SecureField>:
+    FieldOps<BaseField> + PolyOps + LineOps<E> + Sized + FieldOps<SecureField>
+{
     /// Folds a degree `d` polynomial into a degree `d/2` polynomial.
     ///
     /// Let `eval` be a polynomial evaluated on a [LineDomain] `E`, `alpha` be a random field
@@ -86,10 +138,10 @@ pub trait FriOps: FieldOps<BaseField> + PolyOps + Sized + FieldOps<SecureField>
     ///
     /// Panics if there are less than two evaluations.
     fn fold_line(
-        eval: &LineEvaluation<Self>,
-        alpha: SecureField,
+        eval: &LineEvaluation<Self, E>,
+        alpha: E,
         twiddles: &TwiddleTree<Self>,
-    ) -> LineEvaluation<Self>;
+    ) -> LineEvaluation<Self, E>;
 
     /// Folds and accumulates a degree `d` circle polynomial into a degree `d/2` univariate
     /// polynomial.
@@ -108,9 +160,9 @@ pub trait FriOps: FieldOps<BaseField> + PolyOps + Sized + FieldOps<SecureField>
     // TODO(andrew): Make folding factor generic.
     // TODO(andrew): Fold directly into FRI layer to prevent allocation.
     fn fold_circle_into_line(
-        dst: &mut LineEvaluation<Self>,
-        src: &SecureEvaluation<Self, BitReversedOrder>,
-        alpha: SecureField,
+        dst: &mut LineEvaluation<Self, E>,
+        src: &SecureEvaluation<Self, BitReversedOrder, E>,
+        alpha: E,
         twiddles: &TwiddleTree<Self>,
     );
 
@@ -120,19 +172,25 @@ pub trait FriOps: FieldOps<BaseField> + PolyOps + Sized + FieldOps<SecureField>
     /// Based on lemma #12 from the CircleStark paper: f(P) = g(P)+ lambda * alternating(P),
     /// where lambda is the cosset diff of eval, and g is a polynomial in the fft-space.
     fn decompose(
-        eval: &SecureEvaluation<Self, BitReversedOrder>,
-    ) -> (SecureEvaluation<Self, BitReversedOrder>, SecureField);
+        eval: &SecureEvaluation<Self, BitReversedOrder, E>,
+    ) -> (SecureEvaluation<Self, BitReversedOrder, E>, E);
 }
 /// A FRI prover that applies the FRI protocol to prove a set of polynomials are of low degree.
-pub struct FriProver<B: FriOps + MerkleOps<MC::H>, MC: MerkleChannel> {
+///
+/// The polynomials are over a [SecureExtension] `E`, from which the folding coefficients are drawn.
+pub struct FriProver<
+    B: FriOps<E> + MerkleOps<MC::H>,
+    MC: MerkleChannel,
+    E: SecureExtension = SecureField,
+> {
     config: FriConfig,
-    inner_layers: Vec<FriLayerProver<B, MC::H>>,
-    last_layer_poly: LinePoly,
+    inner_layers: Vec<FriLayerProver<B, MC::H, E>>,
+    last_layer_poly: LinePoly<E>,
     /// Unique sizes of committed columns sorted in descending order.
     column_log_sizes: Vec<u32>,
 }
 
-impl<B: FriOps + MerkleOps<MC::H>, MC: MerkleChannel> FriProver<B, MC> {
+impl<B: FriOps<E> + MerkleOps<MC::H>, MC: MerkleChannel, E: SecureExtension> FriProver<B, MC, E> {
     /// Commits to multiple [CircleEvaluation]s.
     ///
     /// `columns` must be provided in descending order by size.
@@ -156,7 +214,7 @@ impl<B: FriOps + MerkleOps<MC::H>, MC: MerkleChannel> FriProver<B, MC> {
     pub fn commit(
         channel: &mut MC::C,
         config: FriConfig,
-        columns: &[SecureEvaluation<B, BitReversedOrder>],
+        columns: &[SecureEvaluation<B, BitReversedOrder, E>],
         twiddles: &TwiddleTree<B>,
     ) -> Self {
         let _span = span!(Level::INFO, "FRI commitment").entered();
@@ -165,7 +223,8 @@ impl<B: FriOps + MerkleOps<MC::H>, MC: MerkleChannel> FriProver<B, MC> {
         assert!(columns.iter().all(|e| e.domain.is_canonic()), "not canonic");
         let (inner_layers, last_layer_evaluation) =
             Self::commit_inner_layers(channel, config, columns, twiddles);
-        let last_layer_poly = Self::commit_last_layer(channel, config, last_layer_evaluation);
+        let last_layer_poly =
+            Self::commit_last_layer(channel, config, last_layer_evaluation, twiddles);
 
         let column_log_sizes = columns
             .iter()
@@ -185,15 +244,16 @@ impl<B: FriOps + MerkleOps<MC::H>, MC: MerkleChannel> FriProver<B, MC> {
     /// All `columns` must be provided in descending order by size.
     ///
     /// Returns all inner layers and the evaluation of the last layer.
+    #[allow(clippy::type_complexity)]
     fn commit_inner_layers(
         channel: &mut MC::C,
         config: FriConfig,
-        columns: &[SecureEvaluation<B, BitReversedOrder>],
+        columns: &[SecureEvaluation<B, BitReversedOrder, E>],
         twiddles: &TwiddleTree<B>,
-    ) -> (Vec<FriLayerProver<B, MC::H>>, LineEvaluation<B>) {
+    ) -> (Vec<FriLayerProver<B, MC::H, E>>, LineEvaluation<B, E>) {
         // Returns the length of the [LineEvaluation] a [CircleEvaluation] gets folded into.
         let folded_len =
-            |e: &SecureEvaluation<B, BitReversedOrder>| e.len() >> CIRCLE_TO_LINE_FOLD_STEP;
+            |e: &SecureEvaluation<B, BitReversedOrder, E>| e.len() >> CIRCLE_TO_LINE_FOLD_STEP;
 
         let first_layer_size = folded_len(&columns[0]);
         let first_layer_domain = LineDomain::new(Coset::half_odds(first_layer_size.ilog2()));
@@ -204,7 +264,7 @@ impl<B: FriOps + MerkleOps<MC::H>, MC: MerkleChannel> FriProver<B, MC> {
         let mut layers = Vec::new();
 
         // Circle polynomials can all be folded with the same alpha.
-        let circle_poly_alpha = channel.draw_felt();
+        let circle_poly_alpha = channel.draw_extension_felt();
 
         while layer_evaluation.len() > config.last_layer_domain_size() {
             // Check for any columns (circle poly evaluations) that should be combined.
@@ -218,8 +278,8 @@ impl<B: FriOps + MerkleOps<MC::H>, MC: MerkleChannel> FriProver<B, MC> {
             }
 
             let layer = FriLayerProver::new(layer_evaluation);
-            MC::mix_root(channel, layer.merkle_tree.root());
-            let folding_alpha = channel.draw_felt();
+            MC::mix_cap(channel, &layer.cap(config.log_merkle_cap_size));
+            let folding_alpha = channel.draw_extension_felt();
             let folded_layer_evaluation = B::fold_line(&layer.evaluation, folding_alpha, twiddles);
 
             layer_evaluation = folded_layer_evaluation;
@@ -245,19 +305,21 @@ impl<B: FriOps + MerkleOps<MC::H>, MC: MerkleChannel> FriProver<B, MC> {
     fn commit_last_layer(
         channel: &mut MC::C,
         config: FriConfig,
-        evaluation: LineEvaluation<B>,
-    ) -> LinePoly {
+        evaluation: LineEvaluation<B, E>,
+        twiddles: &TwiddleTree<B>,
+    ) -> LinePoly<E> {
         assert_eq!(evaluation.len(), config.last_layer_domain_size());
 
-        let evaluation = evaluation.to_cpu();
-        let mut coeffs = evaluation.interpolate().into_ordered_coefficients();
+        // The coefficients are sent to the verifier, so they are copied from the backend.
+        let coeffs = evaluation.interpolate_with_twiddles(twiddles).to_vec();
+        let mut coeffs = LinePoly::new(coeffs).into_ordered_coefficients();
 
         let last_layer_degree_bound = 1 << config.log_last_layer_degree_bound;
         let zeros = coeffs.split_off(last_layer_degree_bound);
-        assert!(zeros.iter().all(SecureField::is_zero), "invalid degree");
+        assert!(zeros.iter().all(E::is_zero), "invalid degree");
 
         let last_layer_poly = LinePoly::from_ordered_coefficients(coeffs);
-        channel.mix_felts(&last_layer_poly);
+        channel.mix_extension_felts(&last_layer_poly);
 
         last_layer_poly
     }
@@ -268,9 +330,9 @@ impl<B: FriOps + MerkleOps<MC::H>, MC: MerkleChannel> FriProver<B, MC> {
     pub fn decommit(
         self,
         channel: &mut MC::C,
-    ) -> (FriProof<MC::H>, BTreeMap<u32, SparseSubCircleDomain>) {
+    ) -> (FriProof<MC::H, E>, BTreeMap<u32, SparseSubCircleDomain>) {
         let max_column_log_size = self.column_log_sizes[0];
-        let queries = Queries::generate(channel, max_column_log_size, self.config.n_queries);
+        let queries = self.config.sample_queries(channel, max_column_log_size);
         let positions = get_opening_positions(&queries, &self.column_log_sizes);
         let proof = self.decommit_on_queries(&queries);
         (proof, positions)
@@ -279,7 +341,7 @@ impl<B: FriOps + MerkleOps<MC::H>, MC: MerkleChannel> FriProver<B, MC> {
     /// # Panics
     ///
     /// Panics if the queries were sampled on the wrong domain size.
-    fn decommit_on_queries(self, queries: &Queries) -> FriProof<MC::H> {
+    fn decommit_on_queries(self, queries: &Queries) -> FriProof<MC::H, E> {
         let max_column_log_size = self.column_log_sizes[0];
         assert_eq!(queries.log_domain_size, max_column_log_size);
         let first_layer_queries = queries.fold(CIRCLE_TO_LINE_FOLD_STEP);
@@ -287,7 +349,7 @@ impl<B: FriOps + MerkleOps<MC::H>, MC: MerkleChannel> FriProver<B, MC> {
             .inner_layers
             .into_iter()
             .scan(first_layer_queries, |layer_queries, layer| {
-                let layer_proof = layer.decommit(layer_queries);
+                let layer_proof = layer.decommit(layer_queries, self.config.log_merkle_cap_size);
                 *layer_queries = layer_queries.fold(FOLD_STEP);
                 Some(layer_proof)
             })
@@ -302,23 +364,24 @@ impl<B: FriOps + MerkleOps<MC::H>, MC: MerkleChannel> FriProver<B, MC> {
     }
 }
 
-pub struct FriVerifier<MC: MerkleChannel> {
+/// A FRI verifier of polynomials over a [SecureExtension] `E`. See [FriProver].
+pub struct FriVerifier<MC: MerkleChannel, E: SecureExtension = SecureField> {
     config: FriConfig,
     /// Alpha used to fold all circle polynomials to univariate polynomials.
-    circle_poly_alpha: SecureField,
+    circle_poly_alpha: E,
     /// Domain size queries should be sampled from.
     expected_query_log_domain_size: u32,
     /// The list of degree bounds of all committed circle polynomials.
     column_bounds: Vec<CirclePolyDegreeBound>,
-    inner_layers: Vec<FriLayerVerifier<MC::H>>,
+    inner_layers: Vec<FriLayerVerifier<MC::H, E>>,
     last_layer_domain: LineDomain,
-    last_layer_poly: LinePoly,
+    last_layer_poly: LinePoly<E>,
     /// The queries used for decommitment. Initialized when calling
     /// [`FriVerifier::column_opening_positions`].
     queries: Option<Queries>,
 }
 
-impl<MC: MerkleChannel> FriVerifier<MC> {
+impl<MC: MerkleChannel, E: SecureExtension> FriVerifier<MC, E> {
     /// Verifies the commitment stage of FRI.
     ///
     /// `column_bounds` should be the committed circle polynomial degree bounds in descending order.
@@ -338,7 +401,7 @@ impl<MC: MerkleChannel> FriVerifier<MC> {
     pub fn commit(
         channel: &mut MC::C,
         config: FriConfig,
-        proof: FriProof<MC::H>,
+        proof: FriProof<MC::H, E>,
         column_bounds: Vec<CirclePolyDegreeBound>,
     ) -> Result<Self, FriVerificationError> {
         assert!(column_bounds.is_sorted_by_key(|b| Reverse(*b)));
@@ -348,7 +411,7 @@ impl<MC: MerkleChannel> FriVerifier<MC> {
             max_column_bound.log_degree_bound + config.log_blowup_factor;
 
         // Circle polynomials can all be folded with the same alpha.
-        let circle_poly_alpha = channel.draw_felt();
+        let circle_poly_alpha = channel.draw_extension_felt();
 
         let mut inner_layers = Vec::new();
         let mut layer_bound = max_column_bound.fold_to_line();
@@ -357,15 +420,16 @@ impl<MC: MerkleChannel> FriVerifier<MC> {
         ));
 
         for (layer_index, proof) in proof.inner_layers.into_iter().enumerate() {
-            MC::mix_root(channel, proof.commitment);
+            MC::mix_cap(channel, &proof.commitment);
 
-            let folding_alpha = channel.draw_felt();
+            let folding_alpha = channel.draw_extension_felt();
 
             inner_layers.push(FriLayerVerifier {
                 degree_bound: layer_bound,
                 domain: layer_domain,
                 folding_alpha,
                 layer_index,
+                log_merkle_cap_size: config.log_merkle_cap_size,
                 proof,
             });
 
@@ -386,7 +450,7 @@ impl<MC: MerkleChannel> FriVerifier<MC> {
             return Err(FriVerificationError::LastLayerDegreeInvalid);
         }
 
-        channel.mix_felts(&last_layer_poly);
+        channel.mix_extension_felts(&last_layer_poly);
 
         Ok(Self {
             config,
@@ -400,6 +464,11 @@ impl<MC: MerkleChannel> FriVerifier<MC> {
         })
     }
 
+    /// Returns the sampled queries, or `None` if the queries were not yet sampled.
+    pub fn queries(&self) -> Option<&Queries> {
+        self.queries.as_ref()
+    }
+
     /// Verifies the decommitment stage of FRI.
     ///
     /// The decommitment values need to be provided in the same order as their commitment.
@@ -413,7 +482,7 @@ impl<MC: MerkleChannel> FriVerifier<MC> {
     // TODO(andrew): Finish docs.
     pub fn decommit(
         mut self,
-        decommitted_values: Vec<SparseCircleEvaluation>,
+        decommitted_values: Vec<SparseCircleEvaluation<E>>,
     ) -> Result<(), FriVerificationError> {
         let queries = self.queries.take().expect("queries not sampled");
         self.decommit_on_queries(&queries, decommitted_values)
@@ -422,7 +491,7 @@ impl<MC: MerkleChannel> FriVerifier<MC> {
     fn decommit_on_queries(
         self,
         queries: &Queries,
-        decommitted_values: Vec<SparseCircleEvaluation>,
+        decommitted_values: Vec<SparseCircleEvaluation<E>>,
     ) -> Result<(), FriVerificationError> {
         assert_eq!(queries.log_domain_size, self.expected_query_log_domain_size);
         assert_eq!(decommitted_values.len(), self.column_bounds.len());
@@ -439,15 +508,15 @@ impl<MC: MerkleChannel> FriVerifier<MC> {
     fn decommit_inner_layers(
         &self,
         queries: &Queries,
-        decommitted_values: Vec<SparseCircleEvaluation>,
-    ) -> Result<(Queries, Vec<SecureField>), FriVerificationError> {
+        decommitted_values: Vec<SparseCircleEvaluation<E>>,
+    ) -> Result<(Queries, Vec<E>), FriVerificationError> {
         let circle_poly_alpha = self.circle_poly_alpha;
         let circle_poly_alpha_sq = circle_poly_alpha * circle_poly_alpha;
 
         let mut decommitted_values = decommitted_values.into_iter();
         let mut column_bounds = self.column_bounds.iter().copied().peekable();
         let mut layer_queries = queries.fold(CIRCLE_TO_LINE_FOLD_STEP);
-        let mut layer_query_evals = vec![SecureField::zero(); layer_queries.len()];
+        let mut layer_query_evals = vec![E::zero(); layer_queries.len()];
 
         for layer in self.inner_layers.iter() {
             // Check for column evals that need to folded into this layer.
@@ -479,7 +548,7 @@ impl<MC: MerkleChannel> FriVerifier<MC> {
     fn decommit_last_layer(
         self,
         queries: Queries,
-        query_evals: Vec<SecureField>,
+        query_evals: Vec<E>,
     ) -> Result<(), FriVerificationError> {
         let Self {
             last_layer_domain: domain,
@@ -511,7 +580,7 @@ impl<MC: MerkleChannel> FriVerifier<MC> {
             .dedup()
             .map(|b| b.log_degree_bound + self.config.log_blowup_factor)
             .collect_vec();
-        let queries = Queries::generate(channel, column_log_sizes[0], self.config.n_queries);
+        let queries = self.config.sample_queries(channel, column_log_sizes[0]);
         let positions = get_opening_positions(&queries, &column_log_sizes);
         self.queries = Some(queries);
         positions
@@ -608,9 +677,9 @@ impl LinePolyDegreeBound {
 
 /// A FRI proof.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct FriProof<H: MerkleHasher> {
-    pub inner_layers: Vec<FriLayerProof<H>>,
-    pub last_layer_poly: LinePoly,
+pub struct FriProof<H: MerkleHasher, E: SecureExtension = SecureField> {
+    pub inner_layers: Vec<FriLayerProof<H, E>>,
+    pub last_layer_poly: LinePoly<E>,
 }
 
 /// Number of folds for univariate polynomials.
@@ -624,23 +693,25 @@ pub const CIRCLE_TO_LINE_FOLD_STEP: u32 = 1;
 ///
 /// The subset corresponds to the set of evaluations needed by a FRI verifier.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct FriLayerProof<H: MerkleHasher> {
+pub struct FriLayerProof<H: MerkleHasher, E: SecureExtension = SecureField> {
     /// The subset stored corresponds to the set of evaluations the verifier doesn't have but needs
     /// to fold and verify the merkle decommitment.
-    pub evals_subset: Vec<SecureField>,
+    pub evals_subset: Vec<E>,
     pub decommitment: MerkleDecommitment<H>,
-    pub commitment: H::Hash,
+    /// The Merkle cap of the layer, up to which the decommitment paths go.
+    pub commitment: Vec<H::Hash>,
 }
 
-struct FriLayerVerifier<H: MerkleHasher> {
+struct FriLayerVerifier<H: MerkleHasher, E: SecureExtension> {
     degree_bound: LinePolyDegreeBound,
     domain: LineDomain,
-    folding_alpha: SecureField,
+    folding_alpha: E,
     layer_index: usize,
-    proof: FriLayerProof<H>,
+    log_merkle_cap_size: u32,
+    proof: FriLayerProof<H, E>,
 }
 
-impl<H: MerkleHasher> FriLayerVerifier<H> {
+impl<H: MerkleHasher, E: SecureExtension> FriLayerVerifier<H, E> {
     /// Verifies the layer's merkle decommitment and returns the the folded queries and query evals.
     ///
     /// # Errors
@@ -655,20 +726,22 @@ impl<H: MerkleHasher> FriLayerVerifier<H> {
     fn verify_and_fold(
         &self,
         queries: Queries,
-        evals_at_queries: Vec<SecureField>,
-    ) -> Result<(Queries, Vec<SecureField>), FriVerificationError> {
+        evals_at_queries: Vec<E>,
+    ) -> Result<(Queries, Vec<E>), FriVerificationError> {
         let decommitment = self.proof.decommitment.clone();
-        let commitment = self.proof.commitment;
+        let commitment = self.proof.commitment.clone();
 
         // Extract the evals needed for decommitment and folding.
         let sparse_evaluation = self.extract_evaluation(&queries, &evals_at_queries)?;
 
         // TODO: When leaf values are removed from the decommitment, also remove this block.
-        let actual_decommitment_evals: SecureColumnByCoords<CpuBackend> = sparse_evaluation
-            .subline_evals
-            .iter()
-            .flat_map(|e| e.values.into_iter())
-            .collect();
+        let actual_decommitment_evals = E::Column::<CpuBackend>::from_values(
+            &sparse_evaluation
+                .subline_evals
+                .iter()
+                .flat_map(|e| e.values.to_vec())
+                .collect_vec(),
+        );
 
         let folded_queries = queries.fold(FOLD_STEP);
 
@@ -682,16 +755,17 @@ impl<H: MerkleHasher> FriLayerVerifier<H> {
             })
             .collect::<Vec<usize>>();
 
-        let merkle_verifier = MerkleVerifier::new(
+        let merkle_verifier = MerkleVerifier::with_cap(
             commitment,
-            vec![self.domain.log_size(); SECURE_EXTENSION_DEGREE],
+            vec![self.domain.log_size(); E::EXTENSION_DEGREE],
+            self.log_merkle_cap_size,
         );
         merkle_verifier
             .verify(
                 [(self.domain.log_size(), decommitment_positions)]
                     .into_iter()
                     .collect(),
-                actual_decommitment_evals.columns.to_vec(),
+                actual_decommitment_evals.coordinates().to_vec(),
                 decommitment,
             )
             .map_err(|e| FriVerificationError::InnerLayerCommitmentInvalid {
@@ -716,8 +790,8 @@ impl<H: MerkleHasher> FriLayerVerifier<H> {
     fn extract_evaluation(
         &self,
         queries: &Queries,
-        evals_at_queries: &[SecureField],
-    ) -> Result<SparseLineEvaluation, FriVerificationError> {
+        evals_at_queries: &[E],
+    ) -> Result<SparseLineEvaluation<E>, FriVerificationError> {
         // Evals provided by the verifier.
         let mut evals_at_queries = evals_at_queries.iter().copied();
 
@@ -756,7 +830,7 @@ impl<H: MerkleHasher> FriLayerVerifier<H> {
 
             all_subline_evals.push(LineEvaluation::new(
                 subline_domain,
-                subline_evals.into_iter().collect(),
+                E::Column::<CpuBackend>::from_values(&subline_evals),
             ));
         }
 
@@ -776,14 +850,15 @@ impl<H: MerkleHasher> FriLayerVerifier<H> {
 /// The polynomial evaluations are viewed as evaluation of a polynomial on multiple distinct cosets
 /// of size two. Each leaf of the merkle tree commits to a single coset evaluation.
 // TODO(andrew): Support different step sizes.
-struct FriLayerProver<B: FriOps + MerkleOps<H>, H: MerkleHasher> {
-    evaluation: LineEvaluation<B>,
+struct FriLayerProver<B: FriOps<E> + MerkleOps<H>, H: MerkleHasher, E: SecureExtension> {
+    evaluation: LineEvaluation<B, E>,
     merkle_tree: MerkleProver<B, H>,
 }
 
-impl<B: FriOps + MerkleOps<H>, H: MerkleHasher> FriLayerProver<B, H> {
-    fn new(evaluation: LineEvaluation<B>) -> Self {
-        let merkle_tree = MerkleProver::commit(evaluation.values.columns.iter().collect_vec());
+impl<B: FriOps<E> + MerkleOps<H>, H: MerkleHasher, E: SecureExtension> FriLayerProver<B, H, E> {
+    fn new(evaluation: LineEvaluation<B, E>) -> Self {
+        let merkle_tree =
+            MerkleProver::commit(evaluation.values.coordinates().iter().collect_vec());
         #[allow(unreachable_code)]
         FriLayerProver {
             evaluation,
@@ -791,8 +866,17 @@ impl<B: FriOps + MerkleOps<H>, H: MerkleHasher> FriLayerProver<B, H> {
         }
     }
 
-    /// Generates a decommitment of the subline evaluations at the specified positions.
-    fn decommit(self, queries: &Queries) -> FriLayerProof<H> {
+    /// Returns the Merkle cap of `2^log_merkle_cap_size` nodes of the layer, lowered to the size of
+    /// the layer if needed.
+    fn cap(&self, log_merkle_cap_size: u32) -> Vec<H::Hash> {
+        let log_size = self.evaluation.len().ilog2();
+        self.merkle_tree
+            .cap(cap_log_size(log_merkle_cap_size, [log_size]))
+    }
+
+    /// Generates a decommitment of the subline evaluations at the specified positions, with paths
+    /// up to a cap of `2^log_merkle_cap_size` nodes.
+    fn decommit(self, queries: &Queries, log_merkle_cap_size: u32) -> FriLayerProof<H, E> {
         let mut decommit_positions = Vec::new();
         let mut evals_subset = Vec::new();
 
@@ -818,13 +902,14 @@ impl<B: FriOps + MerkleOps<H>, H: MerkleHasher> FriLayerProver<B, H> {
             }
         }
 
-        let commitment = self.merkle_tree.root();
+        let commitment = self.cap(log_merkle_cap_size);
         // TODO(andrew): Use _evals.
-        let (_evals, decommitment) = self.merkle_tree.decommit(
+        let (_evals, decommitment) = self.merkle_tree.decommit_to_cap(
             [(self.evaluation.len().ilog2(), decommit_positions)]
                 .into_iter()
                 .collect(),
-            self.evaluation.values.columns.iter().collect_vec(),
+            self.evaluation.values.coordinates().iter().collect_vec(),
+            log_merkle_cap_size,
         );
 
         FriLayerProof {
@@ -837,23 +922,21 @@ impl<B: FriOps + MerkleOps<H>, H: MerkleHasher> FriLayerProver<B, H> {
 
 /// Holds a foldable subset of circle polynomial evaluations.
 #[derive(Debug, Clone)]
-pub struct SparseCircleEvaluation {
-    subcircle_evals: Vec<CircleEvaluation<CpuBackend, SecureField, BitReversedOrder>>,
+pub struct SparseCircleEvaluation<E: SecureExtension = SecureField> {
+    subcircle_evals: Vec<CircleEvaluation<CpuBackend, E, BitReversedOrder>>,
 }
 
-impl SparseCircleEvaluation {
+impl<E: SecureExtension> SparseCircleEvaluation<E> {
     /// # Panics
     ///
     /// Panics if the evaluation domain sizes don't equal the folding factor.
-    pub fn new(
-        subcircle_evals: Vec<CircleEvaluation<CpuBackend, SecureField, BitReversedOrder>>,
-    ) -> Self {
+    pub fn new(subcircle_evals: Vec<CircleEvaluation<CpuBackend, E, BitReversedOrder>>) -> Self {
         let folding_factor = 1 << CIRCLE_TO_LINE_FOLD_STEP;
         assert!(subcircle_evals.iter().all(|e| e.len() == folding_factor));
         Self { subcircle_evals }
     }
 
-    fn fold(self, alpha: SecureField) -> Vec<SecureField> {
+    fn fold(self, alpha: E) -> Vec<E> {
         self.subcircle_evals
             .into_iter()
             .map(|e| {
@@ -861,7 +944,7 @@ impl SparseCircleEvaluation {
                 let mut buffer = LineEvaluation::new_zero(buffer_domain);
                 fold_circle_into_line(
                     &mut buffer,
-                    &SecureEvaluation::new(e.domain, e.values.into_iter().collect()),
+                    &SecureEvaluation::new(e.domain, E::Column::<CpuBackend>::from_values(&e)),
                     alpha,
                 );
                 buffer.values.at(0)
@@ -870,34 +953,33 @@ impl SparseCircleEvaluation {
     }
 }
 
-impl<'a> IntoIterator for &'a mut SparseCircleEvaluation {
-    type Item = &'a mut CircleEvaluation<CpuBackend, SecureField, BitReversedOrder>;
-    type IntoIter =
-        std::slice::IterMut<'a, CircleEvaluation<CpuBackend, SecureField, BitReversedOrder>>;
+impl<'a, E: SecureExtension> IntoIterator for &'a mut SparseCircleEvaluation<E> {
+    type Item = &'a mut CircleEvaluation<CpuBackend, E, BitReversedOrder>;
+    type IntoIter = std::slice::IterMut<'a, CircleEvaluation<CpuBackend, E, BitReversedOrder>>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.subcircle_evals.iter_mut()
     }
 }
 
-/// Holds a small foldable subset of univariate SecureField polynomial evaluations.
+/// Holds a small foldable subset of univariate [SecureExtension] polynomial evaluations.
 /// Evaluation is held at the CPU backend.
 #[derive(Debug, Clone)]
-struct SparseLineEvaluation {
-    subline_evals: Vec<LineEvaluation<CpuBackend>>,
+struct SparseLineEvaluation<E: SecureExtension> {
+    subline_evals: Vec<LineEvaluation<CpuBackend, E>>,
 }
 
-impl SparseLineEvaluation {
+impl<E: SecureExtension> SparseLineEvaluation<E> {
     /// # Panics
     ///
     /// Panics if the evaluation domain sizes don't equal the folding factor.
-    fn new(subline_evals: Vec<LineEvaluation<CpuBackend>>) -> Self {
+    fn new(subline_evals: Vec<LineEvaluation<CpuBackend, E>>) -> Self {
         let folding_factor = 1 << FOLD_STEP;
         assert!(subline_evals.iter().all(|e| e.len() == folding_factor));
         Self { subline_evals }
     }
 
-    fn fold(self, alpha: SecureField) -> Vec<SecureField> {
+    fn fold(self, alpha: E) -> Vec<E> {
         self.subline_evals
             .into_iter()
             .map(|e| fold_line(&e, alpha).values.at(0))
@@ -907,17 +989,18 @@ impl SparseLineEvaluation {
 
 /// Folds a degree `d` polynomial into a degree `d/2` polynomial.
 /// See [`FriOps::fold_line`].
-pub fn fold_line(
-    eval: &LineEvaluation<CpuBackend>,
-    alpha: SecureField,
-) -> LineEvaluation<CpuBackend> {
+pub fn fold_line<E: SecureExtension>(
+    eval: &LineEvaluation<CpuBackend, E>,
+    alpha: E,
+) -> LineEvaluation<CpuBackend, E> {
     let n = eval.len();
     assert!(n >= 2, "Evaluation too small");
 
     let domain = eval.domain();
 
-    let folded_values = eval
+    let folded_values: Vec<E> = eval
         .values
+        .to_vec()
         .into_iter()
         .array_chunks()
         .enumerate()
@@ -931,23 +1014,27 @@ pub fn fold_line(
         })
         .collect();
 
-    LineEvaluation::new(domain.double(), folded_values)
+    LineEvaluation::new(
+        domain.double(),
+        E::Column::<CpuBackend>::from_values(&folded_values),
+    )
 }
 
 /// Folds and accumulates a degree `d` circle polynomial into a degree `d/2` univariate
 /// polynomial.
 /// See [`FriOps::fold_circle_into_line`].
-pub fn fold_circle_into_line(
-    dst: &mut LineEvaluation<CpuBackend>,
-    src: &SecureEvaluation<CpuBackend, BitReversedOrder>,
-    alpha: SecureField,
+pub fn fold_circle_into_line<E: SecureExtension>(
+    dst: &mut LineEvaluation<CpuBackend, E>,
+    src: &SecureEvaluation<CpuBackend, BitReversedOrder, E>,
+    alpha: E,
 ) {
     assert_eq!(src.len() >> CIRCLE_TO_LINE_FOLD_STEP, dst.len());
 
     let domain = src.domain;
     let alpha_sq = alpha * alpha;
 
-    src.into_iter()
+    src.to_vec()
+        .into_iter()
         .array_chunks()
         .enumerate()
         .for_each(|(i, [f_p, f_neg_p])| {
@@ -987,7 +1074,7 @@ mod tests {
     use crate::core::poly::circle::{CircleDomain, PolyOps, SecureEvaluation};
     use crate::core::poly::line::{LineDomain, LineEvaluation, LinePoly};
     use crate::core::poly::{BitReversedOrder, NaturalOrder};
-    use crate::core::queries::{Queries, SparseSubCircleDomain};
+    use crate::core::queries::{Queries, QuerySampling, SparseSubCircleDomain};
     use crate::core::test_utils::test_channel;
     use crate::core::utils::bit_reverse_index;
     use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
@@ -1019,7 +1106,7 @@ mod tests {
             .map(|p| poly.eval_at_point(p.into()))
             .collect();
         CpuBackend::bit_reverse_column(&mut values);
-        let evals = LineEvaluation::new(domain, values.into_iter().collect());
+        let evals = LineEvaluation::<CpuBackend>::new(domain, values.into_iter().collect());
 
         let drp_evals = fold_line(&evals, alpha);
         let mut drp_evals = drp_evals.values.into_iter().collect_vec();
@@ -1070,7 +1157,7 @@ mod tests {
     fn committing_evaluation_from_invalid_domain_fails() {
         let invalid_domain = CircleDomain::new(Coset::new(CirclePointIndex::generator(), 3));
         assert!(!invalid_domain.is_canonic(), "must be an invalid domain");
-        let evaluation = SecureEvaluation::new(
+        let evaluation = SecureEvaluation::<CpuBackend, BitReversedOrder>::new(
             invalid_domain,
             vec![SecureField::one(); 1 << 4].into_iter().collect(),
         );
@@ -1173,6 +1260,37 @@ mod tests {
         verifier.decommit(decommitment_values)
     }
 
+    #[test]
+    fn valid_proof_with_distinct_queries_passes_verification() -> Result<(), FriVerificationError> {
+        const LOG_DEGREE: u32 = 4;
+        const N_QUERIES: usize = 20;
+        let evaluation = polynomial_evaluation(LOG_DEGREE, LOG_BLOWUP_FACTOR);
+        let config = FriConfig::new(1, LOG_BLOWUP_FACTOR, N_QUERIES)
+            .with_query_sampling(QuerySampling::Distinct);
+        let prover = FriProver::commit(
+            &mut test_channel(),
+            config,
+            &[evaluation.clone()],
+            &CpuBackend::precompute_twiddles(evaluation.domain.half_coset),
+        );
+        let (proof, prover_opening_positions) = prover.decommit(&mut test_channel());
+        let positions = &prover_opening_positions[&evaluation.domain.log_size()];
+        let decommitment_value = open_polynomial(&evaluation, positions);
+        let bounds = vec![CirclePolyDegreeBound::new(LOG_DEGREE)];
+
+        let mut verifier = FriVerifier::commit(&mut test_channel(), config, proof, bounds).unwrap();
+        let verifier_opening_positions = verifier.column_query_positions(&mut test_channel());
+        let queries = verifier.queries().unwrap();
+
+        assert_eq!(prover_opening_positions, verifier_opening_positions);
+        assert_eq!(queries.n_effective_queries(), N_QUERIES);
+        assert_eq!(
+            config.effective_security_bits(queries),
+            config.security_bits()
+        );
+        verifier.decommit(vec![decommitment_value])
+    }
+
     #[test]
     fn proof_with_removed_layer_fails_verification() {
         const LOG_DEGREE: u32 = 6;
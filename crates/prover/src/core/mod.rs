@@ -70,3 +70,17 @@ macro_rules! parallel_iter {
         iter
     }};
 }
+
+/// [Sync] when the `parallel` feature is enabled, so that values can be shared with the threads of
+/// [parallel_iter], and implemented by every type otherwise.
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Sync + ?Sized> MaybeSync for T {}
+
+/// [Sync] when the `parallel` feature is enabled, so that values can be shared with the threads of
+/// [parallel_iter], and implemented by every type otherwise.
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> MaybeSync for T {}
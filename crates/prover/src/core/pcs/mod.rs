@@ -4,15 +4,23 @@
 //! Note: This implementation is not really a polynomial commitment scheme, because we are not in
 //! the unique decoding regime. This is enough for a STARK proof though, where we only want to imply
 //! the existence of such polynomials, and are ok with having a small decoding list.
-//! Note: Opened points cannot come from the commitment domain.
+//! Note: Points opened with [CommitmentSchemeProver::prove_values] cannot come from the commitment
+//! domain. [CommitmentSchemeProver::open] handles arbitrary points.
 
+mod opening;
+mod preprocessed;
 mod prover;
 pub mod quotients;
 mod utils;
 mod verifier;
 
+use serde::{Deserialize, Serialize};
+
+pub use self::opening::{DomainDecommitment, OpeningProof};
+pub use self::preprocessed::{PreprocessedColumns, PreprocessedTrace};
 pub use self::prover::{
-    CommitmentSchemeProof, CommitmentSchemeProver, CommitmentTreeProver, TreeBuilder,
+    CommitmentSchemeProof, CommitmentSchemeProver, CommitmentTreeProver, EvaluationStorage,
+    StreamingTreeBuilder, TreeBuilder, RECOMPUTED_COLUMNS_CHUNK_SIZE,
 };
 pub use self::utils::TreeVec;
 pub use self::verifier::CommitmentSchemeVerifier;
@@ -25,11 +33,20 @@ pub struct TreeSubspan {
     pub col_end: usize,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PcsConfig {
     pub pow_bits: u32,
     pub fri_config: FriConfig,
 }
+
+impl PcsConfig {
+    /// Returns the conjectured security bits, combining the proof of work and the FRI query
+    /// phase. See [`FriConfig::security_bits`].
+    pub const fn security_bits(&self) -> u32 {
+        self.pow_bits + self.fri_config.security_bits()
+    }
+}
+
 impl Default for PcsConfig {
     fn default() -> Self {
         Self {
@@ -0,0 +1,699 @@
+//! Opening committed polynomials at arbitrary points, independently of a STARK proof.
+//!
+//! Every column is additionally sampled at a random point drawn after the commitments. Requested
+//! points are then handled according to their [OpeningKind]:
+//! * Points that differ from their complex conjugate go through the FRI quotients, like in
+//!   [CommitmentSchemeProver::prove_values].
+//! * Points on the commitment domain of a column are decommitted from its tree directly.
+//! * Any other point is paired with the random point in a separate quotient (see
+//!   [accumulate_paired_quotients]).
+
+use std::collections::BTreeMap;
+use std::iter::zip;
+
+use itertools::Itertools;
+use num_traits::One;
+use serde::{Deserialize, Serialize};
+use tracing::{span, Level};
+
+use super::quotients::{accumulate_paired_quotients, PairedSample, PointSample};
+use super::utils::TreeVec;
+use super::{CommitmentSchemeProof, CommitmentSchemeProver, CommitmentSchemeVerifier};
+use crate::core::backend::{BackendForChannel, Col, Column};
+use crate::core::channel::{Channel, MerkleChannel};
+use crate::core::circle::{CirclePoint, CirclePointIndex};
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::qm31::SecureField;
+use crate::core::fields::ComplexConjugate;
+use crate::core::fri::SparseCircleEvaluation;
+use crate::core::poly::circle::CanonicCoset;
+use crate::core::prover::VerificationError;
+use crate::core::queries::SparseSubCircleDomain;
+use crate::core::utils::{bit_reverse_index, coset_index_to_bit_reversed_index};
+use crate::core::vcs::ops::MerkleHasher;
+use crate::core::vcs::prover::MerkleDecommitment;
+use crate::core::ColumnVec;
+
+/// A proof of the values of committed polynomials at arbitrary points.
+///
+/// See [CommitmentSchemeProver::open].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpeningProof<H: MerkleHasher> {
+    /// The proof of the sampled values. The sampled values of each column are its values at the
+    /// requested points, followed by its value at the random point.
+    pub pcs_proof: CommitmentSchemeProof<H>,
+    /// For each tree, the decommitment of the requested points on its commitment domains, if
+    /// there are any.
+    pub domain_decommitments: TreeVec<Option<DomainDecommitment<H>>>,
+}
+
+impl<H: MerkleHasher> OpeningProof<H> {
+    /// Returns the values at the requested points.
+    pub fn values(&self) -> TreeVec<ColumnVec<Vec<SecureField>>> {
+        self.pcs_proof
+            .sampled_values
+            .as_cols_ref()
+            .map_cols(|values| values[..values.len() - 1].to_vec())
+    }
+}
+
+/// The decommitment of the values of a tree at the positions of requested points.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DomainDecommitment<H: MerkleHasher> {
+    pub queried_values: ColumnVec<Vec<BaseField>>,
+    pub decommitment: MerkleDecommitment<H>,
+}
+
+impl<B: BackendForChannel<MC>, MC: MerkleChannel> CommitmentSchemeProver<B, MC> {
+    /// Opens the committed polynomials at `points`, given for each tree and column.
+    ///
+    /// Unlike [Self::prove_values], the points may be anywhere on the circle, including on the
+    /// commitment domains.
+    pub fn open(
+        &self,
+        points: TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
+        channel: &mut MC::C,
+    ) -> OpeningProof<MC::H> {
+        let random_point = CirclePoint::get_random_point(channel);
+        let column_log_sizes = self.trees.as_ref().map(|tree| {
+            (0..tree.polynomials.len())
+                .map(|column| tree.column_log_size(column))
+                .collect_vec()
+        });
+        let kinds = opening_kinds(&column_log_sizes, &points);
+
+        let domain_decommitments = self
+            .trees
+            .as_ref()
+            .zip_eq(domain_queries(&column_log_sizes, &kinds))
+            .map(|(tree, queries)| {
+                (!queries.is_empty()).then(|| {
+                    let twiddles = self.twiddles_for_log_size(tree.max_log_size());
+                    let (queried_values, decommitment) = tree.decommit(
+                        queries,
+                        &twiddles,
+                        self.config.fri_config.log_merkle_cap_size,
+                    );
+                    DomainDecommitment {
+                        queried_values,
+                        decommitment,
+                    }
+                })
+            });
+
+        let samples = self.sample_points(with_random_point(points, random_point));
+        let sampled_values = samples
+            .as_cols_ref()
+            .map_cols(|x| x.iter().map(|o| o.value).collect());
+        channel.mix_felts(&sampled_values.clone().flatten_cols());
+        let random_coeff = channel.draw_felt();
+        let paired_coeff = channel.draw_felt();
+
+        let columns = self.columns();
+        let (samples, paired_samples) = split_samples(samples.flatten(), kinds.flatten());
+        let mut quotients = self.compute_quotients(&samples, random_coeff);
+
+        let span = span!(Level::INFO, "Paired quotients").entered();
+        for quotient in &mut quotients {
+            let domain = quotient.domain;
+            let log_size = domain.log_size();
+            let paired_samples = paired_samples
+                .iter()
+                .filter(|paired| {
+                    let (tree, column) = columns[paired.column_index];
+                    tree.column_log_size(column) == log_size
+                })
+                .copied()
+                .collect_vec();
+            if paired_samples.is_empty() {
+                continue;
+            }
+            let twiddles = self.twiddles_for_log_size(log_size);
+            let mut cpu_columns = vec![vec![]; columns.len()];
+            for paired in &paired_samples {
+                let (tree, column) = columns[paired.column_index];
+                cpu_columns[paired.column_index] =
+                    tree.evaluation(column, &twiddles).values.to_cpu();
+            }
+            let domain_points = (0..domain.size())
+                .map(|row| domain.at(bit_reverse_index(row, log_size)))
+                .collect_vec();
+            let values = accumulate_paired_quotients(
+                &domain_points,
+                &cpu_columns.iter().map(|c| c.as_slice()).collect_vec(),
+                &paired_samples,
+                random_coeff,
+            );
+            for (i, column) in quotient.values.columns.iter_mut().enumerate() {
+                let coordinates: Col<B, BaseField> = values
+                    .iter()
+                    .map(|&v| (v * paired_coeff).to_m31_array()[i])
+                    .collect();
+                B::add_scaled_column(column, &coordinates, BaseField::one());
+            }
+        }
+        span.exit();
+
+        let pcs_proof = self.prove_quotients(sampled_values, quotients, channel);
+        OpeningProof {
+            pcs_proof,
+            domain_decommitments,
+        }
+    }
+}
+
+impl<MC: MerkleChannel> CommitmentSchemeVerifier<MC> {
+    /// Verifies the values of the committed polynomials at `points`, given for each tree and
+    /// column. Returns the verified values.
+    ///
+    /// See [CommitmentSchemeProver::open].
+    pub fn verify_opening(
+        &self,
+        points: TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
+        proof: OpeningProof<MC::H>,
+        channel: &mut MC::C,
+    ) -> Result<TreeVec<ColumnVec<Vec<SecureField>>>, VerificationError> {
+        let random_point = CirclePoint::get_random_point(channel);
+        let column_log_sizes = self.column_log_sizes();
+        let sampled_points = with_random_point(points.clone(), random_point);
+        check_sampled_values_structure(&sampled_points, &proof.pcs_proof.sampled_values)?;
+        let kinds = opening_kinds(&column_log_sizes, &points);
+        let values = proof.values();
+
+        // Verify the values at points on the commitment domains.
+        let domain_queries = domain_queries(&column_log_sizes, &kinds);
+        if proof.domain_decommitments.len() != self.trees.len() {
+            return Err(VerificationError::InvalidStructure(
+                "Wrong number of domain decommitments".to_string(),
+            ));
+        }
+        for (tree_index, domain_decommitment) in
+            proof.domain_decommitments.0.into_iter().enumerate()
+        {
+            let queries = &domain_queries[tree_index];
+            let Some(DomainDecommitment {
+                queried_values,
+                decommitment,
+            }) = domain_decommitment
+            else {
+                if queries.is_empty() {
+                    continue;
+                }
+                return Err(VerificationError::InvalidStructure(
+                    "Missing domain decommitment".to_string(),
+                ));
+            };
+            self.trees[tree_index].verify(queries.clone(), queried_values.clone(), decommitment)?;
+            for (column_index, column_kinds) in kinds[tree_index].iter().enumerate() {
+                let log_size = column_log_sizes[tree_index][column_index];
+                for (sample_index, kind) in column_kinds.iter().enumerate() {
+                    let OpeningKind::Domain(position) = *kind else {
+                        continue;
+                    };
+                    let query_index = queries[&log_size].binary_search(&position).unwrap();
+                    let queried_value = queried_values[column_index][query_index];
+                    if values[tree_index][column_index][sample_index] != queried_value.into() {
+                        return Err(VerificationError::OpenedValuesNotMatching);
+                    }
+                }
+            }
+        }
+
+        channel.mix_felts(&proof.pcs_proof.sampled_values.clone().flatten_cols());
+        let random_coeff = channel.draw_felt();
+        let paired_coeff = channel.draw_felt();
+
+        let samples = sampled_points
+            .clone()
+            .zip_cols(proof.pcs_proof.sampled_values.clone())
+            .map_cols(|(points, values)| {
+                zip(points, values)
+                    .map(|(point, value)| PointSample { point, value })
+                    .collect_vec()
+            });
+        let (samples, paired_samples) = split_samples(samples.flatten(), kinds.flatten());
+        let bounds = self.degree_bounds(&sampled_points);
+        let column_log_sizes = column_log_sizes.flatten();
+
+        self.verify_quotients(
+            bounds,
+            proof.pcs_proof,
+            channel,
+            |query_domains, queried_values| {
+                let paired_answers = paired_answers(
+                    &column_log_sizes,
+                    &paired_samples,
+                    random_coeff,
+                    &query_domains,
+                    queried_values,
+                )?;
+                let mut answers = fri_answers_with_log_sizes(
+                    &column_log_sizes,
+                    &samples,
+                    random_coeff,
+                    query_domains,
+                    queried_values,
+                )?;
+                for (log_size, answer) in &mut answers {
+                    let Some(paired_answer) = paired_answers.get(log_size) else {
+                        continue;
+                    };
+                    for (eval, paired_values) in zip(answer, paired_answer) {
+                        for (value, paired_value) in zip(&mut eval.values, paired_values) {
+                            *value += paired_coeff * *paired_value;
+                        }
+                    }
+                }
+                Ok(answers.into_iter().map(|(_, answer)| answer).collect())
+            },
+        )?;
+
+        Ok(values)
+    }
+}
+
+/// How a requested point is opened.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OpeningKind {
+    /// A point that differs from its complex conjugate.
+    Generic,
+    /// A point on the commitment domain, at the given position of the bit-reversed domain.
+    Domain(usize),
+    /// Any other point, which is paired with the random point.
+    Paired,
+}
+
+impl OpeningKind {
+    /// Returns how `point` is opened on a column committed on a domain of log size `log_size`.
+    fn new(point: CirclePoint<SecureField>, log_size: u32) -> Self {
+        if point.y != point.y.complex_conjugate() {
+            return Self::Generic;
+        }
+        match to_base_point(point).and_then(|point| domain_position(point, log_size)) {
+            Some(position) => Self::Domain(position),
+            None => Self::Paired,
+        }
+    }
+}
+
+fn opening_kinds(
+    column_log_sizes: &TreeVec<Vec<u32>>,
+    points: &TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
+) -> TreeVec<ColumnVec<Vec<OpeningKind>>> {
+    TreeVec(
+        zip(column_log_sizes.iter(), points.iter())
+            .map(|(log_sizes, points)| {
+                zip(log_sizes, points)
+                    .map(|(&log_size, points)| {
+                        points
+                            .iter()
+                            .map(|&point| OpeningKind::new(point, log_size))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
+/// Returns, for each tree, the sorted positions to decommit for each column log size.
+fn domain_queries(
+    column_log_sizes: &TreeVec<Vec<u32>>,
+    kinds: &TreeVec<ColumnVec<Vec<OpeningKind>>>,
+) -> TreeVec<BTreeMap<u32, Vec<usize>>> {
+    TreeVec(
+        zip(column_log_sizes.iter(), kinds.iter())
+            .map(|(log_sizes, kinds)| {
+                let mut queries = BTreeMap::<u32, Vec<usize>>::new();
+                for (&log_size, kinds) in zip(log_sizes, kinds) {
+                    for kind in kinds {
+                        if let OpeningKind::Domain(position) = kind {
+                            queries.entry(log_size).or_default().push(*position);
+                        }
+                    }
+                }
+                queries.values_mut().for_each(|positions| {
+                    positions.sort_unstable();
+                    positions.dedup();
+                });
+                queries
+            })
+            .collect(),
+    )
+}
+
+fn with_random_point(
+    points: TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
+    random_point: CirclePoint<SecureField>,
+) -> TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>> {
+    points.map_cols(|mut points| {
+        points.push(random_point);
+        points
+    })
+}
+
+fn check_sampled_values_structure(
+    sampled_points: &TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
+    sampled_values: &TreeVec<ColumnVec<Vec<SecureField>>>,
+) -> Result<(), VerificationError> {
+    let matches = sampled_points.len() == sampled_values.len()
+        && zip(sampled_points.iter(), sampled_values.iter()).all(|(points, values)| {
+            points.len() == values.len()
+                && zip(points, values).all(|(points, values)| points.len() == values.len())
+        });
+    if !matches {
+        return Err(VerificationError::InvalidStructure(
+            "Sampled values don't match the points".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Splits the samples of each column, whose last sample is at the random point, into the samples
+/// answered by the FRI quotients and the paired samples. Samples on the commitment domain are
+/// dropped.
+fn split_samples(
+    samples: ColumnVec<Vec<PointSample>>,
+    kinds: ColumnVec<Vec<OpeningKind>>,
+) -> (ColumnVec<Vec<PointSample>>, Vec<PairedSample>) {
+    let mut paired_samples = Vec::new();
+    let samples = zip(samples, kinds)
+        .enumerate()
+        .map(|(column_index, (samples, kinds))| {
+            let partner = *samples.last().unwrap();
+            let mut column_samples = Vec::new();
+            for (&sample, kind) in zip(&samples, kinds) {
+                match kind {
+                    OpeningKind::Generic => column_samples.push(sample),
+                    OpeningKind::Domain(_) => {}
+                    OpeningKind::Paired => paired_samples.push(PairedSample {
+                        column_index,
+                        sample,
+                        partner,
+                    }),
+                }
+            }
+            column_samples.push(partner);
+            column_samples
+        })
+        .collect();
+    (samples, paired_samples)
+}
+
+/// Like [super::quotients::fri_answers], but also returns the log size of each answer.
+fn fri_answers_with_log_sizes(
+    column_log_sizes: &[u32],
+    samples: &[Vec<PointSample>],
+    random_coeff: SecureField,
+    query_domains: BTreeMap<u32, SparseSubCircleDomain>,
+    queried_values: &[Vec<BaseField>],
+) -> Result<Vec<(u32, SparseCircleEvaluation)>, VerificationError> {
+    let log_sizes = column_log_sizes.iter().copied().sorted().rev().dedup();
+    let answers = super::quotients::fri_answers(
+        column_log_sizes.to_vec(),
+        samples,
+        random_coeff,
+        query_domains,
+        queried_values,
+    )?;
+    Ok(zip(log_sizes, answers).collect())
+}
+
+/// Computes the paired quotients on the FRI query domains, for each log size with paired samples.
+fn paired_answers(
+    column_log_sizes: &[u32],
+    paired_samples: &[PairedSample],
+    random_coeff: SecureField,
+    query_domains: &BTreeMap<u32, SparseSubCircleDomain>,
+    queried_values: &[Vec<BaseField>],
+) -> Result<BTreeMap<u32, Vec<Vec<SecureField>>>, VerificationError> {
+    let mut answers = BTreeMap::new();
+    let log_sizes = paired_samples
+        .iter()
+        .map(|paired| column_log_sizes[paired.column_index])
+        .sorted()
+        .dedup();
+    for log_size in log_sizes {
+        let paired_samples = paired_samples
+            .iter()
+            .filter(|paired| column_log_sizes[paired.column_index] == log_size)
+            .copied()
+            .collect_vec();
+        let query_domain = &query_domains[&log_size];
+        let n_queried_values = query_domain.flatten().len();
+        if paired_samples
+            .iter()
+            .any(|paired| queried_values[paired.column_index].len() != n_queried_values)
+        {
+            return Err(VerificationError::InvalidStructure(
+                "Wrong number of queried values".to_string(),
+            ));
+        }
+
+        let commitment_domain = CanonicCoset::new(log_size).circle_domain();
+        let mut offset = 0;
+        let mut subdomain_answers = Vec::new();
+        for subdomain in query_domain.iter() {
+            let domain = subdomain.to_circle_domain(&commitment_domain);
+            let domain_points = (0..domain.size())
+                .map(|row| domain.at(bit_reverse_index(row, log_size)))
+                .collect_vec();
+            let mut columns = vec![&[][..]; queried_values.len()];
+            for paired in &paired_samples {
+                columns[paired.column_index] =
+                    &queried_values[paired.column_index][offset..offset + domain.size()];
+            }
+            subdomain_answers.push(accumulate_paired_quotients(
+                &domain_points,
+                &columns,
+                &paired_samples,
+                random_coeff,
+            ));
+            offset += domain.size();
+        }
+        answers.insert(log_size, subdomain_answers);
+    }
+    Ok(answers)
+}
+
+/// Returns the point if its coordinates are in the base field.
+fn to_base_point(point: CirclePoint<SecureField>) -> Option<CirclePoint<BaseField>> {
+    let [x, x1, x2, x3] = point.x.to_m31_array();
+    let [y, y1, y2, y3] = point.y.to_m31_array();
+    let is_base = [x1, x2, x3, y1, y2, y3]
+        .iter()
+        .all(|c| *c == BaseField::from(0));
+    is_base.then_some(CirclePoint { x, y })
+}
+
+/// Returns the position of `point` in the bit-reversed canonic circle domain of log size
+/// `log_size`, if the point is in the domain.
+fn domain_position(point: CirclePoint<BaseField>, log_size: u32) -> Option<usize> {
+    // The domain consists of the points of order exactly `2^(log_size + 1)`, which are the odd
+    // multiples of a generator of that order.
+    if point.log_order() != log_size + 1 {
+        return None;
+    }
+    let generator = CirclePointIndex::subgroup_gen(log_size + 1).to_point();
+    // Recover the multiple bit by bit, from the least significant bit.
+    let mut multiple = 0;
+    for bit in 0..=log_size {
+        let rest = point - generator.mul(multiple as u128);
+        if rest.repeated_double(log_size - bit) != CirclePoint::zero() {
+            multiple |= 1 << bit;
+        }
+    }
+    Some(coset_index_to_bit_reversed_index(
+        (multiple - 1) / 2,
+        log_size,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::{domain_position, OpeningKind};
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::{BackendForChannel, CpuBackend};
+    use crate::core::channel::Blake2sChannel;
+    use crate::core::circle::{CirclePoint, SECURE_FIELD_CIRCLE_GEN};
+    use crate::core::fields::m31::BaseField;
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::pcs::{
+        CommitmentSchemeProver, CommitmentSchemeVerifier, EvaluationStorage, PcsConfig, TreeVec,
+    };
+    use crate::core::poly::circle::{CanonicCoset, CirclePoly};
+    use crate::core::poly::twiddles::TwiddleCache;
+    use crate::core::prover::VerificationError;
+    use crate::core::utils::bit_reverse_index;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
+
+    #[test]
+    fn test_domain_position() {
+        let log_size = 6;
+        let domain = CanonicCoset::new(log_size).circle_domain();
+
+        for position in 0..domain.size() {
+            let point = domain.at(bit_reverse_index(position, log_size));
+            assert_eq!(domain_position(point, log_size), Some(position));
+        }
+        let point = CanonicCoset::new(log_size - 1).at(3);
+        assert_eq!(domain_position(point, log_size), None);
+    }
+
+    fn open_and_verify<B: BackendForChannel<Blake2sMerkleChannel>>(
+        points: impl Fn(u32) -> Vec<CirclePoint<SecureField>>,
+        tamper: bool,
+    ) -> Result<TreeVec<Vec<Vec<SecureField>>>, VerificationError> {
+        open_and_verify_with_storage::<B>(points, tamper, EvaluationStorage::Keep)
+    }
+
+    fn open_and_verify_with_storage<B: BackendForChannel<Blake2sMerkleChannel>>(
+        points: impl Fn(u32) -> Vec<CirclePoint<SecureField>>,
+        tamper: bool,
+        evaluation_storage: EvaluationStorage,
+    ) -> Result<TreeVec<Vec<Vec<SecureField>>>, VerificationError> {
+        let config = PcsConfig::default();
+        let trees = [vec![5, 4], vec![6]];
+
+        let mut prover =
+            CommitmentSchemeProver::<B, Blake2sMerkleChannel>::new(config, TwiddleCache::default())
+                .with_evaluation_storage(evaluation_storage);
+        let mut prover_channel = Blake2sChannel::default();
+        for log_sizes in &trees {
+            let polys = log_sizes
+                .iter()
+                .map(|&log_size| {
+                    CirclePoly::<B>::new(
+                        (0..1 << log_size)
+                            .map(|i| BaseField::from(i * 7 + log_size as usize))
+                            .collect(),
+                    )
+                })
+                .collect_vec();
+            let mut tree_builder = prover.tree_builder();
+            tree_builder.extend_polys(polys);
+            tree_builder.commit(&mut prover_channel);
+        }
+        let sampled_points = TreeVec(
+            trees
+                .iter()
+                .map(|log_sizes| log_sizes.iter().map(|&log_size| points(log_size)).collect())
+                .collect_vec(),
+        );
+        let mut proof = prover.open(sampled_points.clone(), &mut prover_channel);
+        if tamper {
+            proof.pcs_proof.sampled_values[0][0][0] += SecureField::from(1);
+        }
+
+        let mut verifier = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+        let mut verifier_channel = Blake2sChannel::default();
+        for (tree_index, log_sizes) in trees.iter().enumerate() {
+            verifier.commit(
+                prover.commitments()[tree_index].clone(),
+                log_sizes,
+                &mut verifier_channel,
+            );
+        }
+        let values =
+            verifier.verify_opening(sampled_points.clone(), proof, &mut verifier_channel)?;
+
+        let polys = prover.polynomials();
+        for (tree_index, log_sizes) in trees.iter().enumerate() {
+            for column_index in 0..log_sizes.len() {
+                let poly = polys[tree_index][column_index];
+                for (point, value) in sampled_points[tree_index][column_index]
+                    .iter()
+                    .zip(&values[tree_index][column_index])
+                {
+                    assert_eq!(poly.eval_at_point(*point), *value);
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    /// Points of every [OpeningKind] for a column of log size `log_size`.
+    fn points(log_size: u32) -> Vec<CirclePoint<SecureField>> {
+        let log_blowup_factor = PcsConfig::default().fri_config.log_blowup_factor;
+        let commitment_domain = CanonicCoset::new(log_size + log_blowup_factor).circle_domain();
+        vec![
+            SECURE_FIELD_CIRCLE_GEN,
+            // A trace domain point.
+            CanonicCoset::new(log_size).at(1).into_ef(),
+            // A commitment domain point.
+            commitment_domain.at(5).into_ef(),
+            SECURE_FIELD_CIRCLE_GEN.mul(3),
+        ]
+    }
+
+    #[test]
+    fn test_point_kinds() {
+        let kinds = points(5)
+            .into_iter()
+            .map(|point| OpeningKind::new(point, 6))
+            .collect_vec();
+
+        assert!(matches!(
+            kinds[..],
+            [
+                OpeningKind::Generic,
+                OpeningKind::Paired,
+                OpeningKind::Domain(_),
+                OpeningKind::Generic
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_open_at_arbitrary_points() {
+        open_and_verify::<CpuBackend>(points, false).unwrap();
+        open_and_verify::<SimdBackend>(points, false).unwrap();
+    }
+
+    #[test]
+    fn test_open_with_recomputed_evaluations() {
+        let keep =
+            open_and_verify_with_storage::<SimdBackend>(points, false, EvaluationStorage::Keep);
+        let recompute = open_and_verify_with_storage::<SimdBackend>(
+            points,
+            false,
+            EvaluationStorage::Recompute,
+        );
+
+        assert_eq!(recompute.unwrap(), keep.unwrap());
+    }
+
+    #[test]
+    fn test_open_without_points() {
+        let values = open_and_verify::<SimdBackend>(|_| vec![], false).unwrap();
+
+        assert!(values.iter().flatten().all(Vec::is_empty));
+    }
+
+    #[test]
+    fn test_open_with_wrong_value_fails() {
+        let generic = open_and_verify::<CpuBackend>(|_| vec![SECURE_FIELD_CIRCLE_GEN], true);
+        let paired = open_and_verify::<CpuBackend>(
+            |log_size| vec![CanonicCoset::new(log_size).at(1).into_ef()],
+            true,
+        );
+        let domain = open_and_verify::<CpuBackend>(
+            |log_size| {
+                vec![CanonicCoset::new(log_size + 1)
+                    .circle_domain()
+                    .at(2)
+                    .into_ef()]
+            },
+            true,
+        );
+
+        assert!(generic.is_err());
+        assert!(paired.is_err());
+        assert!(matches!(
+            domain,
+            Err(VerificationError::OpenedValuesNotMatching)
+        ));
+    }
+}
@@ -0,0 +1,267 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+use tracing::{span, Level};
+
+use super::{CommitmentTreeProver, PcsConfig};
+use crate::core::backend::BackendForChannel;
+use crate::core::channel::MerkleChannel;
+use crate::core::fields::m31::BaseField;
+use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, PolyOps};
+use crate::core::poly::twiddles::TwiddleCache;
+use crate::core::poly::BitReversedOrder;
+use crate::core::vcs::ops::MerkleHasher;
+use crate::core::ColumnVec;
+
+/// A registry of named constant columns, to be committed once as a [PreprocessedTrace].
+pub struct PreprocessedColumns<B: PolyOps> {
+    names: Vec<String>,
+    evals: ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>,
+}
+
+impl<B: PolyOps> PreprocessedColumns<B> {
+    pub fn new() -> Self {
+        Self {
+            names: Vec::new(),
+            evals: Vec::new(),
+        }
+    }
+
+    /// Registers a column under `name` and returns its index in the preprocessed tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a column with the same name was already registered, or if the evaluation is not
+    /// over a canonic domain.
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        eval: CircleEvaluation<B, BaseField, BitReversedOrder>,
+    ) -> usize {
+        let name = name.into();
+        assert!(
+            self.index_of(&name).is_none(),
+            "duplicate preprocessed column {name}"
+        );
+        assert!(eval.domain.is_canonic(), "domain is not canonic");
+        self.names.push(name);
+        self.evals.push(eval);
+        self.evals.len() - 1
+    }
+
+    /// Returns the index of the column registered under `name`.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl<B: PolyOps> Default for PreprocessedColumns<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A committed tree of constant columns.
+///
+/// The tree is built once, independently of any proof, and its Merkle cap is known to the verifier
+/// in advance. Each proof loads it into its [super::CommitmentSchemeProver] with
+/// [super::CommitmentSchemeProver::commit_preprocessed], which shares the extended evaluations and
+/// Merkle layers instead of recomputing them. Cloning is cheap.
+pub struct PreprocessedTrace<B: BackendForChannel<MC>, MC: MerkleChannel> {
+    names: Vec<String>,
+    log_blowup_factor: u32,
+    pub(super) tree: Arc<CommitmentTreeProver<B, MC>>,
+}
+
+impl<B: BackendForChannel<MC>, MC: MerkleChannel> PreprocessedTrace<B, MC> {
+    /// Interpolates and commits to `columns`, with the blowup factor of `config`.
+    ///
+    /// Nothing is mixed into a channel; the cap is mixed when the tree is loaded into a proof.
+    pub fn commit(
+        columns: PreprocessedColumns<B>,
+        config: PcsConfig,
+        twiddles: &TwiddleCache<B>,
+    ) -> Self {
+        Self::commit_with_log_blowup_factor(columns, config.fri_config.log_blowup_factor, twiddles)
+    }
+
+    /// Interpolates and commits to `columns`, like [Self::commit], with a blowup factor of
+    /// `log_blowup_factor`, which must be at least the blowup factor of the proofs that load the
+    /// tree.
+    pub fn commit_with_log_blowup_factor(
+        columns: PreprocessedColumns<B>,
+        log_blowup_factor: u32,
+        twiddles: &TwiddleCache<B>,
+    ) -> Self {
+        let _span = span!(Level::INFO, "Preprocessed commitment").entered();
+        let polys = columns
+            .evals
+            .into_iter()
+            .map(|eval| {
+                let twiddles = twiddles.get_for_domain(eval.domain);
+                eval.interpolate_with_twiddles(&twiddles)
+            })
+            .collect_vec();
+        let max_log_size = polys.iter().map(|p| p.log_size()).max().unwrap_or(0);
+        let twiddles = twiddles
+            .get_for_domain(CanonicCoset::new(max_log_size + log_blowup_factor).circle_domain());
+        let tree = CommitmentTreeProver::from_polys(polys, log_blowup_factor, &twiddles);
+        Self {
+            names: columns.names,
+            log_blowup_factor,
+            tree: Arc::new(tree),
+        }
+    }
+
+    /// Returns the Merkle cap of `2^log_cap_size` nodes of the tree, which the verifier commits to
+    /// instead of a commitment read from the proof. It is the root alone if `log_cap_size` is 0.
+    pub fn cap(&self, log_cap_size: u32) -> Vec<<MC::H as MerkleHasher>::Hash> {
+        self.tree.cap(log_cap_size)
+    }
+
+    /// Returns the log sizes of the columns, before the blowup.
+    pub fn log_sizes(&self) -> ColumnVec<u32> {
+        self.tree.polynomials.iter().map(|p| p.log_size()).collect()
+    }
+
+    pub fn log_blowup_factor(&self) -> u32 {
+        self.log_blowup_factor
+    }
+
+    /// Returns the log blowup factors of the columns, which the verifier commits with.
+    pub fn log_blowup_factors(&self) -> ColumnVec<u32> {
+        vec![self.log_blowup_factor; self.names.len()]
+    }
+
+    /// Returns the index of the column registered under `name`.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+}
+
+impl<B: BackendForChannel<MC>, MC: MerkleChannel> Clone for PreprocessedTrace<B, MC> {
+    fn clone(&self) -> Self {
+        Self {
+            names: self.names.clone(),
+            log_blowup_factor: self.log_blowup_factor,
+            tree: Arc::clone(&self.tree),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::{PreprocessedColumns, PreprocessedTrace};
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::channel::Blake2sChannel;
+    use crate::core::circle::SECURE_FIELD_CIRCLE_GEN;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig, TreeVec};
+    use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
+    use crate::core::poly::twiddles::TwiddleCache;
+    use crate::core::poly::BitReversedOrder;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
+
+    fn column(
+        log_size: u32,
+        seed: usize,
+    ) -> CircleEvaluation<SimdBackend, BaseField, BitReversedOrder> {
+        CircleEvaluation::new(
+            CanonicCoset::new(log_size).circle_domain(),
+            (0..1 << log_size)
+                .map(|i| BaseField::from(i * seed))
+                .collect(),
+        )
+    }
+
+    fn preprocessed(
+        config: PcsConfig,
+        twiddles: &TwiddleCache<SimdBackend>,
+    ) -> PreprocessedTrace<SimdBackend, Blake2sMerkleChannel> {
+        let mut columns = PreprocessedColumns::new();
+        columns.insert("a", column(5, 3));
+        columns.insert("b", column(6, 7));
+        PreprocessedTrace::commit(columns, config, twiddles)
+    }
+
+    #[test]
+    fn test_preprocessed_root_is_deterministic() {
+        let config = PcsConfig::default();
+
+        let trace = preprocessed(config, &TwiddleCache::default());
+
+        assert_eq!(
+            trace.cap(0),
+            preprocessed(config, &TwiddleCache::default()).cap(0)
+        );
+        assert_eq!(trace.log_sizes(), [5, 6]);
+        assert_eq!(trace.column_index("b"), Some(1));
+        assert_eq!(trace.column_index("c"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate preprocessed column a")]
+    fn test_duplicate_preprocessed_column_fails() {
+        let mut columns = PreprocessedColumns::<SimdBackend>::new();
+        columns.insert("a", column(4, 1));
+        columns.insert("a", column(4, 2));
+    }
+
+    #[test]
+    fn test_preprocessed_trace_reused_across_proofs() {
+        let config = PcsConfig::default();
+        let twiddles = TwiddleCache::default();
+        let trace = preprocessed(config, &twiddles);
+
+        for seed in [1, 2] {
+            // Prove.
+            let prover_channel = &mut Blake2sChannel::default();
+            let mut prover = CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+                config,
+                twiddles.clone(),
+            );
+            let span = prover.commit_preprocessed(&trace, prover_channel);
+            assert_eq!((span.tree_index, span.col_start, span.col_end), (0, 0, 2));
+            let mut tree_builder = prover.tree_builder();
+            tree_builder.extend_evals([column(5, seed)]);
+            tree_builder.commit(prover_channel);
+            let sampled_points = TreeVec::new(vec![
+                vec![vec![SECURE_FIELD_CIRCLE_GEN]; 2],
+                vec![vec![SECURE_FIELD_CIRCLE_GEN]],
+            ]);
+            let proof = prover.prove_values(sampled_points.clone(), prover_channel);
+
+            // Verify, with the cap of the preprocessed tree known in advance.
+            let verifier_channel = &mut Blake2sChannel::default();
+            let mut verifier = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+            let log_cap_size = config.fri_config.log_merkle_cap_size;
+            verifier.commit(
+                trace.cap(log_cap_size),
+                &trace.log_sizes(),
+                verifier_channel,
+            );
+            verifier.commit(prover.commitments()[1].clone(), &[5], verifier_channel);
+            verifier
+                .verify_values(sampled_points, proof, verifier_channel)
+                .unwrap();
+        }
+        assert_eq!(
+            trace.names().iter().map(String::as_str).collect_vec(),
+            ["a", "b"]
+        );
+    }
+}
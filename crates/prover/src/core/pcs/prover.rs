@@ -1,64 +1,191 @@
+use std::borrow::Cow;
+use std::cmp::Reverse;
 use std::collections::BTreeMap;
+use std::iter::zip;
+use std::sync::Arc;
 
 use itertools::Itertools;
+use num_traits::One;
 use serde::{Deserialize, Serialize};
 use tracing::{span, Level};
 
 use super::super::circle::CirclePoint;
 use super::super::fields::m31::BaseField;
 use super::super::fields::qm31::SecureField;
-use super::super::fri::{FriProof, FriProver};
+use super::super::fields::secure_column::ExtensionColumn;
+use super::super::fields::SecureExtension;
+use super::super::fri::{FriOps, FriProof, FriProver};
 use super::super::poly::circle::CanonicCoset;
 use super::super::poly::BitReversedOrder;
 use super::super::ColumnVec;
-use super::quotients::{compute_fri_quotients, PointSample};
+use super::quotients::{ColumnSampleBatch, PointSample, QuotientOps};
 use super::utils::TreeVec;
-use super::{PcsConfig, TreeSubspan};
+use super::{PcsConfig, PreprocessedTrace, TreeSubspan};
 use crate::core::air::Trace;
-use crate::core::backend::BackendForChannel;
+use crate::core::backend::{BackendForChannel, Column};
 use crate::core::channel::{Channel, MerkleChannel};
-use crate::core::poly::circle::{CircleEvaluation, CirclePoly};
-use crate::core::poly::twiddles::TwiddleTree;
+use crate::core::poly::circle::{CircleEvaluation, CirclePoly, PolyOps, SecureEvaluation};
+use crate::core::poly::twiddles::{TwiddleCache, TwiddleTree};
 use crate::core::vcs::ops::MerkleHasher;
-use crate::core::vcs::prover::{MerkleDecommitment, MerkleProver};
+use crate::core::vcs::prover::{MerkleDecommitment, MerkleProver, MerkleProverBuilder};
+use crate::core::vcs::utils::cap_log_size;
+
+/// The number of columns whose dropped evaluations are recomputed at once (see
+/// [EvaluationStorage::Recompute]).
+pub const RECOMPUTED_COLUMNS_CHUNK_SIZE: usize = 16;
+
+/// What a [CommitmentSchemeProver] does with the extended evaluations of a tree once it is
+/// committed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EvaluationStorage {
+    /// Keeps the evaluations for the whole proof.
+    #[default]
+    Keep,
+    /// Drops the evaluations after the Merkle commitment, and recomputes them from the
+    /// polynomials when they are needed: per component for constraint evaluation, and in chunks of
+    /// [RECOMPUTED_COLUMNS_CHUNK_SIZE] columns for the FRI quotients and for decommitment. This
+    /// trades CPU time for a lower peak memory. Preprocessed trees always keep their evaluations.
+    Recompute,
+}
 
 /// The prover side of a FRI polynomial commitment scheme. See [super].
-pub struct CommitmentSchemeProver<'a, B: BackendForChannel<MC>, MC: MerkleChannel> {
-    pub trees: TreeVec<CommitmentTreeProver<B, MC>>,
+pub struct CommitmentSchemeProver<B: BackendForChannel<MC>, MC: MerkleChannel> {
+    pub trees: TreeVec<Arc<CommitmentTreeProver<B, MC>>>,
     pub config: PcsConfig,
-    twiddles: &'a TwiddleTree<B>,
+    twiddles: TwiddleCache<B>,
+    evaluation_storage: EvaluationStorage,
 }
 
-impl<'a, B: BackendForChannel<MC>, MC: MerkleChannel> CommitmentSchemeProver<'a, B, MC> {
-    pub fn new(config: PcsConfig, twiddles: &'a TwiddleTree<B>) -> Self {
+impl<B: BackendForChannel<MC>, MC: MerkleChannel> CommitmentSchemeProver<B, MC> {
+    /// Creates a commitment scheme prover, which takes the twiddles it needs from `twiddles`.
+    ///
+    /// The cache may be shared with other provers (see [TwiddleCache]).
+    pub fn new(config: PcsConfig, twiddles: TwiddleCache<B>) -> Self {
         CommitmentSchemeProver {
             trees: TreeVec::default(),
             config,
             twiddles,
+            evaluation_storage: EvaluationStorage::default(),
+        }
+    }
+
+    /// Returns the same prover, with the given strategy for the evaluations of the trees it commits
+    /// from now on.
+    pub fn with_evaluation_storage(self, evaluation_storage: EvaluationStorage) -> Self {
+        Self {
+            evaluation_storage,
+            ..self
         }
     }
 
-    fn commit(&mut self, polynomials: ColumnVec<CirclePoly<B>>, channel: &mut MC::C) {
+    /// Returns the twiddle cache of the prover.
+    pub fn twiddles(&self) -> &TwiddleCache<B> {
+        &self.twiddles
+    }
+
+    /// Returns twiddles for the canonic evaluation domains of log size up to `log_size`.
+    pub(super) fn twiddles_for_log_size(&self, log_size: u32) -> Arc<TwiddleTree<B>> {
+        self.twiddles
+            .get_for_domain(CanonicCoset::new(log_size).circle_domain())
+    }
+
+    fn commit(
+        &mut self,
+        polynomials: ColumnVec<CirclePoly<B>>,
+        log_blowup_factors: ColumnVec<u32>,
+        channel: &mut MC::C,
+    ) {
         let _span = span!(Level::INFO, "Commitment").entered();
-        let tree = CommitmentTreeProver::new(
+        let max_log_size = zip(&polynomials, &log_blowup_factors)
+            .map(|(p, log_blowup_factor)| p.log_size() + log_blowup_factor)
+            .max()
+            .unwrap_or(0);
+        let twiddles = self.twiddles_for_log_size(max_log_size);
+        let mut tree = CommitmentTreeProver::from_polys_with_log_blowup_factors(
             polynomials,
-            self.config.fri_config.log_blowup_factor,
+            log_blowup_factors,
+            &twiddles,
+        );
+        MC::mix_cap(
             channel,
-            self.twiddles,
+            &tree.cap(self.config.fri_config.log_merkle_cap_size),
         );
-        self.trees.push(tree);
+        if self.evaluation_storage == EvaluationStorage::Recompute {
+            tree.drop_evaluations();
+        }
+        self.trees.push(Arc::new(tree));
     }
 
-    pub fn tree_builder(&mut self) -> TreeBuilder<'_, 'a, B, MC> {
+    /// Panics if columns can't be committed with a blowup factor of `log_blowup_factor`, which must
+    /// be at least the blowup factor of FRI.
+    fn check_log_blowup_factor(&self, log_blowup_factor: u32) {
+        assert!(
+            log_blowup_factor >= self.config.fri_config.log_blowup_factor,
+            "blowup factor {log_blowup_factor} is smaller than the blowup factor of FRI"
+        );
+    }
+
+    /// Loads a committed [PreprocessedTrace] as the next tree and mixes its Merkle cap into the
+    /// channel.
+    ///
+    /// The tree is shared with `preprocessed`, so nothing is extended or hashed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the trace was committed with a blowup factor smaller than the one of FRI.
+    pub fn commit_preprocessed(
+        &mut self,
+        preprocessed: &PreprocessedTrace<B, MC>,
+        channel: &mut MC::C,
+    ) -> TreeSubspan {
+        self.check_log_blowup_factor(preprocessed.log_blowup_factor());
+        MC::mix_cap(
+            channel,
+            &preprocessed.cap(self.config.fri_config.log_merkle_cap_size),
+        );
+        let tree_index = self.trees.len();
+        self.trees.push(Arc::clone(&preprocessed.tree));
+        TreeSubspan {
+            tree_index,
+            col_start: 0,
+            col_end: preprocessed.names().len(),
+        }
+    }
+
+    pub fn tree_builder(&mut self) -> TreeBuilder<'_, B, MC> {
         TreeBuilder {
             tree_index: self.trees.len(),
+            log_blowup_factor: self.config.fri_config.log_blowup_factor,
             commitment_scheme: self,
             polys: Vec::default(),
+            log_blowup_factors: Vec::default(),
+            column_log_blowup_factors: None,
         }
     }
 
-    pub fn roots(&self) -> TreeVec<<MC::H as MerkleHasher>::Hash> {
-        self.trees.as_ref().map(|tree| tree.commitment.root())
+    /// Returns a builder for the next tree that commits to columns as they are generated. See
+    /// [StreamingTreeBuilder].
+    pub fn streaming_tree_builder(&mut self) -> StreamingTreeBuilder<'_, B, MC> {
+        let evaluations = match self.evaluation_storage {
+            EvaluationStorage::Keep => Some(Vec::new()),
+            EvaluationStorage::Recompute => None,
+        };
+        StreamingTreeBuilder {
+            tree_index: self.trees.len(),
+            log_blowup_factor: self.config.fri_config.log_blowup_factor,
+            commitment_scheme: self,
+            polys: Vec::default(),
+            log_blowup_factors: Vec::default(),
+            evaluations,
+            merkle: MerkleProverBuilder::new(),
+        }
+    }
+
+    /// Returns the commitments of the trees: their Merkle caps, of the size set in the FRI
+    /// configuration.
+    pub fn commitments(&self) -> TreeVec<Vec<<MC::H as MerkleHasher>::Hash>> {
+        let log_cap_size = self.config.fri_config.log_merkle_cap_size;
+        self.trees.as_ref().map(|tree| tree.cap(log_cap_size))
     }
 
     pub fn polynomials(&self) -> TreeVec<ColumnVec<&CirclePoly<B>>> {
@@ -67,57 +194,197 @@ impl<'a, B: BackendForChannel<MC>, MC: MerkleChannel> CommitmentSchemeProver<'a,
             .map(|tree| tree.polynomials.iter().collect())
     }
 
+    /// Returns the evaluations of the committed polynomials on their commitment domains.
+    ///
+    /// Evaluations that were dropped after commitment (see [EvaluationStorage]) are recomputed, all
+    /// at once.
     pub fn evaluations(
         &self,
-    ) -> TreeVec<ColumnVec<&CircleEvaluation<B, BaseField, BitReversedOrder>>> {
-        self.trees
-            .as_ref()
-            .map(|tree| tree.evaluations.iter().collect())
+    ) -> TreeVec<ColumnVec<Cow<'_, CircleEvaluation<B, BaseField, BitReversedOrder>>>> {
+        self.trees.as_ref().map(|tree| {
+            let twiddles = self.twiddles_for_log_size(tree.max_log_size());
+            tree.evaluations(&twiddles)
+        })
     }
 
+    /// Returns the committed trace. Evaluations that were dropped after commitment are missing.
     pub fn trace(&self) -> Trace<'_, B> {
         let polys = self.polynomials();
-        let evals = self.evaluations();
+        let evals = self.trees.as_ref().map(|tree| match &tree.evaluations {
+            Some(evaluations) => evaluations.iter().map(Some).collect(),
+            None => vec![None; tree.polynomials.len()],
+        });
         Trace { polys, evals }
     }
 
-    pub fn prove_values(
+    /// Proves the values of the committed polynomials at `sampled_points`.
+    ///
+    /// The points, and the randomness of the quotients and FRI, are in the [SecureExtension] `E`,
+    /// which is the [SecureField] in [super::super::prover::prove].
+    pub fn prove_values<E: SecureExtension>(
         &self,
-        sampled_points: TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
+        sampled_points: TreeVec<ColumnVec<Vec<CirclePoint<E>>>>,
         channel: &mut MC::C,
-    ) -> CommitmentSchemeProof<MC::H> {
-        // Evaluate polynomials on open points.
-        let span = span!(Level::INFO, "Evaluate columns out of domain").entered();
-        let samples = self
-            .polynomials()
-            .zip_cols(&sampled_points)
-            .map_cols(|(poly, points)| {
-                points
-                    .iter()
-                    .map(|&point| PointSample {
-                        point,
-                        value: poly.eval_at_point(point),
-                    })
-                    .collect_vec()
-            });
-        span.exit();
+    ) -> CommitmentSchemeProof<MC::H, E>
+    where
+        B: FriOps<E> + QuotientOps<E>,
+    {
+        let samples = self.sample_points(sampled_points);
         let sampled_values = samples
             .as_cols_ref()
             .map_cols(|x| x.iter().map(|o| o.value).collect());
-        channel.mix_felts(&sampled_values.clone().flatten_cols());
+        channel.mix_extension_felts(&sampled_values.clone().flatten_cols());
 
         // Compute oods quotients for boundary constraints on the sampled points.
-        let columns = self.evaluations().flatten();
-        let quotients = compute_fri_quotients(
-            &columns,
-            &samples.flatten(),
-            channel.draw_felt(),
-            self.config.fri_config.log_blowup_factor,
-        );
+        let quotients = self.compute_quotients(&samples.flatten(), channel.draw_extension_felt());
+
+        self.prove_quotients(sampled_values, quotients, channel)
+    }
 
+    /// Returns the tree and index in the tree of each committed column, in flattened order.
+    pub(super) fn columns(&self) -> Vec<(&CommitmentTreeProver<B, MC>, usize)> {
+        self.trees
+            .iter()
+            .flat_map(|tree| (0..tree.polynomials.len()).map(move |column| (&**tree, column)))
+            .collect()
+    }
+
+    /// Computes the FRI quotients of the committed columns on `samples`. See
+    /// [super::quotients::compute_fri_quotients].
+    ///
+    /// Evaluations that were dropped after commitment are recomputed
+    /// [RECOMPUTED_COLUMNS_CHUNK_SIZE] columns at a time, and the quotients of the chunks of a
+    /// column log size are summed.
+    ///
+    /// The columns of a log size may have different blowup factors. Their quotient is of the
+    /// degree of the largest of them, which is at most the log size minus the blowup factor of
+    /// FRI.
+    pub(super) fn compute_quotients<E: SecureExtension>(
+        &self,
+        samples: &[Vec<PointSample<E>>],
+        random_coeff: E,
+    ) -> Vec<SecureEvaluation<B, BitReversedOrder, E>>
+    where
+        B: QuotientOps<E>,
+    {
+        let _span = span!(Level::INFO, "Compute FRI quotients").entered();
+        let columns = self.columns();
+        let log_sizes = columns
+            .iter()
+            .map(|(tree, i)| tree.column_log_size(*i))
+            .collect_vec();
+        log_sizes
+            .iter()
+            .copied()
+            .sorted_by_key(|&log_size| Reverse(log_size))
+            .dedup()
+            .map(|log_size| {
+                let twiddles = self.twiddles_for_log_size(log_size);
+                let (group, samples): (Vec<_>, Vec<_>) = zip(&columns, samples)
+                    .zip(&log_sizes)
+                    .filter(|(_, &column_log_size)| column_log_size == log_size)
+                    .map(|(column, _)| column)
+                    .unzip();
+                let log_blowup_factor = group
+                    .iter()
+                    .map(|&&(tree, i)| tree.log_blowup_factor(i))
+                    .min()
+                    .unwrap();
+                let domain = CanonicCoset::new(log_size).circle_domain();
+                let sample_batches = ColumnSampleBatch::new_vec(&samples);
+                let chunk_size = match group.iter().all(|(tree, _)| tree.evaluations.is_some()) {
+                    true => group.len(),
+                    false => RECOMPUTED_COLUMNS_CHUNK_SIZE,
+                };
+                (0..group.len())
+                    .step_by(chunk_size)
+                    .map(|start| {
+                        let chunk = start..group.len().min(start + chunk_size);
+                        let evaluations = group[chunk.clone()]
+                            .iter()
+                            .map(|&&(tree, i)| tree.evaluation(i, &twiddles))
+                            .collect_vec();
+                        let chunk_batches = sample_batches
+                            .iter()
+                            .map(|batch| batch.column_range(chunk.clone()))
+                            .collect_vec();
+                        B::accumulate_quotients(
+                            domain,
+                            &evaluations.iter().map(|eval| eval.as_ref()).collect_vec(),
+                            random_coeff,
+                            &chunk_batches,
+                            log_blowup_factor,
+                        )
+                    })
+                    .reduce(|mut quotients, chunk_quotients| {
+                        let coordinates = quotients.values.coordinates_mut();
+                        for (dst, src) in zip(coordinates, chunk_quotients.values.coordinates()) {
+                            B::add_scaled_column(dst, src, BaseField::one());
+                        }
+                        quotients
+                    })
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    /// Evaluates the committed polynomials on their sampled points.
+    pub(super) fn sample_points<E: SecureExtension>(
+        &self,
+        sampled_points: TreeVec<ColumnVec<Vec<CirclePoint<E>>>>,
+    ) -> TreeVec<ColumnVec<Vec<PointSample<E>>>>
+    where
+        B: QuotientOps<E>,
+    {
+        let _span = span!(Level::INFO, "Evaluate columns out of domain").entered();
+        let polys = self.polynomials().flatten();
+        let mut values = sampled_points
+            .as_cols_ref()
+            .flatten()
+            .into_iter()
+            .map(|points| vec![E::zero(); points.len()])
+            .collect_vec();
+        // Group the samples by point, to share the point-dependent work between columns.
+        let mut samples_by_point = BTreeMap::<_, Vec<_>>::new();
+        for (column_index, points) in sampled_points.as_cols_ref().flatten().iter().enumerate() {
+            for (sample_index, &point) in points.iter().enumerate() {
+                samples_by_point
+                    .entry(point)
+                    .or_default()
+                    .push((column_index, sample_index));
+            }
+        }
+        for (point, samples) in samples_by_point {
+            let point_polys = samples.iter().map(|&(i, _)| polys[i]).collect_vec();
+            let point_values = B::eval_at_extension_points(&point_polys, &[point]);
+            for ((column_index, sample_index), value) in zip(samples, point_values) {
+                values[column_index][sample_index] = value[0];
+            }
+        }
+        let mut values = values.into_iter();
+        sampled_points.map_cols(|points| {
+            zip(points, values.next().unwrap())
+                .map(|(point, value)| PointSample { point, value })
+                .collect_vec()
+        })
+    }
+
+    /// Proves that `quotients` are of low degree and decommits the committed trees on the FRI
+    /// queries.
+    pub(super) fn prove_quotients<E: SecureExtension>(
+        &self,
+        sampled_values: TreeVec<ColumnVec<Vec<E>>>,
+        quotients: Vec<SecureEvaluation<B, BitReversedOrder, E>>,
+        channel: &mut MC::C,
+    ) -> CommitmentSchemeProof<MC::H, E>
+    where
+        B: FriOps<E>,
+    {
         // Run FRI commitment phase on the oods quotients.
+        let max_log_size = quotients.iter().map(|q| q.domain.log_size()).max().unwrap();
+        let twiddles = self.twiddles_for_log_size(max_log_size);
         let fri_prover =
-            FriProver::<B, MC>::commit(channel, self.config.fri_config, &quotients, self.twiddles);
+            FriProver::<B, MC, E>::commit(channel, self.config.fri_config, &quotients, &twiddles);
 
         // Proof of work.
         let span1 = span!(Level::INFO, "Grind").entered();
@@ -134,7 +401,12 @@ impl<'a, B: BackendForChannel<MC>, MC: MerkleChannel> CommitmentSchemeProver<'a,
                 .iter()
                 .map(|(&log_size, domain)| (log_size, domain.flatten()))
                 .collect();
-            tree.decommit(queries)
+            let twiddles = self.twiddles_for_log_size(tree.max_log_size());
+            tree.decommit(
+                queries,
+                &twiddles,
+                self.config.fri_config.log_merkle_cap_size,
+            )
         });
 
         let queried_values = decommitment_results.as_ref().map(|(v, _)| v.clone());
@@ -151,20 +423,58 @@ impl<'a, B: BackendForChannel<MC>, MC: MerkleChannel> CommitmentSchemeProver<'a,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct CommitmentSchemeProof<H: MerkleHasher> {
-    pub sampled_values: TreeVec<ColumnVec<Vec<SecureField>>>,
+pub struct CommitmentSchemeProof<H: MerkleHasher, E: SecureExtension = SecureField> {
+    pub sampled_values: TreeVec<ColumnVec<Vec<E>>>,
     pub decommitments: TreeVec<MerkleDecommitment<H>>,
     pub queried_values: TreeVec<ColumnVec<Vec<BaseField>>>,
     pub proof_of_work: u64,
-    pub fri_proof: FriProof<H>,
+    pub fri_proof: FriProof<H, E>,
 }
 
-pub struct TreeBuilder<'a, 'b, B: BackendForChannel<MC>, MC: MerkleChannel> {
+pub struct TreeBuilder<'a, B: BackendForChannel<MC>, MC: MerkleChannel> {
     tree_index: usize,
-    commitment_scheme: &'a mut CommitmentSchemeProver<'b, B, MC>,
+    /// The blowup factor of the columns extended from now on.
+    log_blowup_factor: u32,
+    commitment_scheme: &'a mut CommitmentSchemeProver<B, MC>,
     polys: ColumnVec<CirclePoly<B>>,
+    log_blowup_factors: ColumnVec<u32>,
+    /// The blowup factors of all the columns of the tree, if they were set up front.
+    column_log_blowup_factors: Option<ColumnVec<u32>>,
 }
-impl<'a, 'b, B: BackendForChannel<MC>, MC: MerkleChannel> TreeBuilder<'a, 'b, B, MC> {
+impl<'a, B: BackendForChannel<MC>, MC: MerkleChannel> TreeBuilder<'a, B, MC> {
+    /// Sets the blowup factor of the columns extended from now on, which defaults to the blowup
+    /// factor of FRI.
+    ///
+    /// A larger blowup factor makes the commitment domains of the columns larger, e.g. so that
+    /// they can be reused as constraint evaluation domains.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `log_blowup_factor` is smaller than the blowup factor of FRI, which bounds the
+    /// degree of all the committed columns.
+    pub fn set_log_blowup_factor(&mut self, log_blowup_factor: u32) {
+        self.commitment_scheme
+            .check_log_blowup_factor(log_blowup_factor);
+        self.log_blowup_factor = log_blowup_factor;
+    }
+
+    /// Sets the blowup factors of all the columns of the tree, by column index, in place of the
+    /// one of [Self::set_log_blowup_factor]. The tree must then be committed with exactly that
+    /// many columns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a blowup factor is smaller than the blowup factor of FRI, or if columns were
+    /// already extended.
+    pub fn set_column_log_blowup_factors(&mut self, log_blowup_factors: ColumnVec<u32>) {
+        assert!(self.polys.is_empty(), "columns were already extended");
+        for &log_blowup_factor in &log_blowup_factors {
+            self.commitment_scheme
+                .check_log_blowup_factor(log_blowup_factor);
+        }
+        self.column_log_blowup_factors = Some(log_blowup_factors);
+    }
+
     pub fn extend_evals(
         &mut self,
         columns: impl IntoIterator<Item = CircleEvaluation<B, BaseField, BitReversedOrder>>,
@@ -172,12 +482,19 @@ impl<'a, 'b, B: BackendForChannel<MC>, MC: MerkleChannel> TreeBuilder<'a, 'b, B,
         let span = span!(Level::INFO, "Interpolation for commitment").entered();
         let polys = columns
             .into_iter()
-            .map(|eval| eval.interpolate_with_twiddles(self.commitment_scheme.twiddles))
+            .map(|eval| {
+                let twiddles = self.commitment_scheme.twiddles.get_for_domain(eval.domain);
+                eval.interpolate_with_twiddles(&twiddles)
+            })
             .collect_vec();
         span.exit();
         self.extend_polys(polys)
     }
 
+    /// # Panics
+    ///
+    /// Panics if the tree has more columns than the blowup factors set with
+    /// [Self::set_column_log_blowup_factors].
     pub fn extend_polys(
         &mut self,
         columns: impl IntoIterator<Item = CirclePoly<B>>,
@@ -185,6 +502,19 @@ impl<'a, 'b, B: BackendForChannel<MC>, MC: MerkleChannel> TreeBuilder<'a, 'b, B,
         let col_start = self.polys.len();
         self.polys.extend(columns);
         let col_end = self.polys.len();
+        match &self.column_log_blowup_factors {
+            Some(log_blowup_factors) => {
+                assert!(
+                    col_end <= log_blowup_factors.len(),
+                    "tree {} has more columns than blowup factors",
+                    self.tree_index
+                );
+                self.log_blowup_factors = log_blowup_factors[..col_end].to_vec();
+            }
+            None => self
+                .log_blowup_factors
+                .resize(col_end, self.log_blowup_factor),
+        }
         TreeSubspan {
             tree_index: self.tree_index,
             col_start,
@@ -192,9 +522,140 @@ impl<'a, 'b, B: BackendForChannel<MC>, MC: MerkleChannel> TreeBuilder<'a, 'b, B,
         }
     }
 
+    /// # Panics
+    ///
+    /// Panics if the tree has fewer columns than the blowup factors set with
+    /// [Self::set_column_log_blowup_factors].
+    pub fn commit(self, channel: &mut MC::C) {
+        let _span = span!(Level::INFO, "Commitment").entered();
+        if let Some(log_blowup_factors) = &self.column_log_blowup_factors {
+            assert_eq!(
+                self.polys.len(),
+                log_blowup_factors.len(),
+                "tree {} has fewer columns than blowup factors",
+                self.tree_index
+            );
+        }
+        self.commitment_scheme
+            .commit(self.polys, self.log_blowup_factors, channel);
+    }
+}
+
+/// Builds a tree from columns that are generated in batches, hashing and releasing the extended
+/// evaluations of each batch as soon as it is extended.
+///
+/// Columns must be extended in non-increasing commitment domain log size (the log size plus the
+/// blowup factor), so that the Merkle layers can be hashed from the largest one down (see
+/// [MerkleProverBuilder]). Only the extended evaluations of a single batch are held at once, with
+/// a partial hash of each node of the current Merkle layer, on top of the polynomials and the
+/// evaluations kept by the [EvaluationStorage] of the commitment scheme. The root is the same as
+/// with a [TreeBuilder] over the same columns.
+pub struct StreamingTreeBuilder<'a, B: BackendForChannel<MC>, MC: MerkleChannel> {
+    tree_index: usize,
+    /// The blowup factor of the columns extended from now on.
+    log_blowup_factor: u32,
+    commitment_scheme: &'a mut CommitmentSchemeProver<B, MC>,
+    polys: ColumnVec<CirclePoly<B>>,
+    log_blowup_factors: ColumnVec<u32>,
+    /// The evaluations of the hashed columns, if they are kept.
+    evaluations: Option<ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>>,
+    merkle: MerkleProverBuilder<B, MC::H>,
+}
+impl<'a, B: BackendForChannel<MC>, MC: MerkleChannel> StreamingTreeBuilder<'a, B, MC> {
+    /// Sets the blowup factor of the columns extended from now on. See
+    /// [TreeBuilder::set_log_blowup_factor].
+    pub fn set_log_blowup_factor(&mut self, log_blowup_factor: u32) {
+        self.commitment_scheme
+            .check_log_blowup_factor(log_blowup_factor);
+        self.log_blowup_factor = log_blowup_factor;
+    }
+
+    pub fn extend_evals(
+        &mut self,
+        columns: impl IntoIterator<Item = CircleEvaluation<B, BaseField, BitReversedOrder>>,
+    ) -> TreeSubspan {
+        let span = span!(Level::INFO, "Interpolation for commitment").entered();
+        let polys = columns
+            .into_iter()
+            .map(|eval| {
+                let twiddles = self.commitment_scheme.twiddles.get_for_domain(eval.domain);
+                eval.interpolate_with_twiddles(&twiddles)
+            })
+            .collect_vec();
+        span.exit();
+        self.extend_polys(polys)
+    }
+
+    /// Extends the columns and adds them to the Merkle layers of their log sizes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a column has a larger commitment domain than a column extended before it.
+    pub fn extend_polys(
+        &mut self,
+        columns: impl IntoIterator<Item = CirclePoly<B>>,
+    ) -> TreeSubspan {
+        let log_blowup_factor = self.log_blowup_factor;
+        let col_start = self.polys.len();
+        // The extended columns of the batch that are of the same log size.
+        let mut extended = Vec::new();
+        for poly in columns {
+            let log_size = poly.log_size() + log_blowup_factor;
+            if let Some(last) = self.polys.len().checked_sub(1) {
+                let last_log_size = self.polys[last].log_size() + self.log_blowup_factors[last];
+                assert!(
+                    log_size <= last_log_size,
+                    "columns must be streamed in non-increasing log size"
+                );
+                if log_size < last_log_size {
+                    self.hash(std::mem::take(&mut extended));
+                }
+            }
+            let _span = span!(Level::INFO, "Extension").entered();
+            let twiddles = self.commitment_scheme.twiddles_for_log_size(log_size);
+            extended.push(extend(&poly, log_blowup_factor, &twiddles));
+            self.polys.push(poly);
+            self.log_blowup_factors.push(log_blowup_factor);
+        }
+        self.hash(extended);
+        TreeSubspan {
+            tree_index: self.tree_index,
+            col_start,
+            col_end: self.polys.len(),
+        }
+    }
+
+    /// Adds extended columns of the same log size to their Merkle layer, and drops them unless they
+    /// are kept.
+    fn hash(&mut self, extended: ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>) {
+        let Some(first) = extended.first() else {
+            return;
+        };
+        let _span = span!(Level::INFO, "Merkle").entered();
+        let log_size = first.domain.log_size();
+        let columns = extended.iter().map(|eval| &eval.values).collect_vec();
+        self.merkle.add_columns(log_size, &columns);
+        if let Some(evaluations) = &mut self.evaluations {
+            evaluations.extend(extended);
+        }
+    }
+
+    /// Hashes the remaining columns and commits the tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no column was extended.
     pub fn commit(self, channel: &mut MC::C) {
         let _span = span!(Level::INFO, "Commitment").entered();
-        self.commitment_scheme.commit(self.polys, channel);
+        let tree = CommitmentTreeProver {
+            polynomials: self.polys,
+            evaluations: self.evaluations,
+            commitment: self.merkle.finish(),
+            log_blowup_factors: self.log_blowup_factors,
+        };
+        let log_cap_size = self.commitment_scheme.config.fri_config.log_merkle_cap_size;
+        MC::mix_cap(channel, &tree.cap(log_cap_size));
+        self.commitment_scheme.trees.push(Arc::new(tree));
     }
 }
 
@@ -202,8 +663,12 @@ impl<'a, 'b, B: BackendForChannel<MC>, MC: MerkleChannel> TreeBuilder<'a, 'b, B,
 /// commit on a set of polynomials at a time. This corresponds to such a set.
 pub struct CommitmentTreeProver<B: BackendForChannel<MC>, MC: MerkleChannel> {
     pub polynomials: ColumnVec<CirclePoly<B>>,
-    pub evaluations: ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>,
+    /// The evaluations of the polynomials on their commitment domains, or `None` if they were
+    /// dropped after commitment (see [Self::drop_evaluations]).
+    pub evaluations: Option<ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>>,
     pub commitment: MerkleProver<B, MC::H>,
+    /// The blowup factor of each column.
+    log_blowup_factors: ColumnVec<u32>,
 }
 
 impl<B: BackendForChannel<MC>, MC: MerkleChannel> CommitmentTreeProver<B, MC> {
@@ -213,43 +678,533 @@ impl<B: BackendForChannel<MC>, MC: MerkleChannel> CommitmentTreeProver<B, MC> {
         channel: &mut MC::C,
         twiddles: &TwiddleTree<B>,
     ) -> Self {
+        let tree = Self::from_polys(polynomials, log_blowup_factor, twiddles);
+        MC::mix_root(channel, tree.commitment.root());
+        tree
+    }
+
+    /// Extends and commits to `polynomials`, like [Self::new], without mixing the root into a
+    /// channel.
+    pub fn from_polys(
+        polynomials: ColumnVec<CirclePoly<B>>,
+        log_blowup_factor: u32,
+        twiddles: &TwiddleTree<B>,
+    ) -> Self {
+        let log_blowup_factors = vec![log_blowup_factor; polynomials.len()];
+        Self::from_polys_with_log_blowup_factors(polynomials, log_blowup_factors, twiddles)
+    }
+
+    /// Extends each polynomial by its own blowup factor and commits to them, like
+    /// [Self::from_polys].
+    pub fn from_polys_with_log_blowup_factors(
+        polynomials: ColumnVec<CirclePoly<B>>,
+        log_blowup_factors: ColumnVec<u32>,
+        twiddles: &TwiddleTree<B>,
+    ) -> Self {
+        assert_eq!(polynomials.len(), log_blowup_factors.len());
         let span = span!(Level::INFO, "Extension").entered();
-        let evaluations = polynomials
-            .iter()
-            .map(|poly| {
-                poly.evaluate_with_twiddles(
-                    CanonicCoset::new(poly.log_size() + log_blowup_factor).circle_domain(),
-                    twiddles,
-                )
-            })
+        let evaluations = zip(&polynomials, &log_blowup_factors)
+            .map(|(poly, &log_blowup_factor)| extend(poly, log_blowup_factor, twiddles))
             .collect_vec();
 
         span.exit();
 
         let _span = span!(Level::INFO, "Merkle").entered();
         let tree = MerkleProver::commit(evaluations.iter().map(|eval| &eval.values).collect());
-        MC::mix_root(channel, tree.root());
 
         CommitmentTreeProver {
             polynomials,
-            evaluations,
+            evaluations: Some(evaluations),
             commitment: tree,
+            log_blowup_factors,
         }
     }
 
-    /// Decommits the merkle tree on the given query positions.
+    /// Drops the evaluations, which are recomputed from the polynomials when needed.
+    pub fn drop_evaluations(&mut self) {
+        self.evaluations = None;
+    }
+
+    /// Returns the blowup factor of column `column`.
+    pub fn log_blowup_factor(&self, column: usize) -> u32 {
+        self.log_blowup_factors[column]
+    }
+
+    /// Returns the log size of the commitment domain of column `column`.
+    pub fn column_log_size(&self, column: usize) -> u32 {
+        self.polynomials[column].log_size() + self.log_blowup_factors[column]
+    }
+
+    /// Returns the log size of the largest commitment domain in the tree.
+    pub fn max_log_size(&self) -> u32 {
+        (0..self.polynomials.len())
+            .map(|column| self.column_log_size(column))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the Merkle cap of `2^log_cap_size` nodes of the tree, lowered to the layer of the
+    /// smallest column if needed.
+    pub fn cap(&self, log_cap_size: u32) -> Vec<<MC::H as MerkleHasher>::Hash> {
+        let column_log_sizes =
+            (0..self.polynomials.len()).map(|column| self.column_log_size(column));
+        self.commitment
+            .cap(cap_log_size(log_cap_size, column_log_sizes))
+    }
+
+    /// Returns the evaluation of column `column` on its commitment domain, recomputing it with
+    /// `twiddles` if it was dropped.
+    pub fn evaluation(
+        &self,
+        column: usize,
+        twiddles: &TwiddleTree<B>,
+    ) -> Cow<'_, CircleEvaluation<B, BaseField, BitReversedOrder>> {
+        match &self.evaluations {
+            Some(evaluations) => Cow::Borrowed(&evaluations[column]),
+            None => Cow::Owned(extend(
+                &self.polynomials[column],
+                self.log_blowup_factors[column],
+                twiddles,
+            )),
+        }
+    }
+
+    /// Returns the evaluations of all columns on their commitment domains, recomputing them with
+    /// `twiddles` if they were dropped.
+    pub fn evaluations(
+        &self,
+        twiddles: &TwiddleTree<B>,
+    ) -> ColumnVec<Cow<'_, CircleEvaluation<B, BaseField, BitReversedOrder>>> {
+        (0..self.polynomials.len())
+            .map(|column| self.evaluation(column, twiddles))
+            .collect()
+    }
+
+    /// Decommits the merkle tree on the given query positions, with paths up to a cap of
+    /// `2^log_cap_size` nodes.
     /// Returns the values at the queried positions and the decommitment.
     /// The queries are given as a mapping from the log size of the layer size to the queried
     /// positions on each column of that size.
-    fn decommit(
+    ///
+    /// Evaluations that were dropped after commitment are recomputed
+    /// [RECOMPUTED_COLUMNS_CHUNK_SIZE] columns at a time, keeping only the rows that are read.
+    pub(super) fn decommit(
         &self,
         queries: BTreeMap<u32, Vec<usize>>,
+        twiddles: &TwiddleTree<B>,
+        log_cap_size: u32,
     ) -> (ColumnVec<Vec<BaseField>>, MerkleDecommitment<MC::H>) {
-        let eval_vec = self
-            .evaluations
+        if let Some(evaluations) = &self.evaluations {
+            let eval_vec = evaluations.iter().map(|eval| &eval.values).collect_vec();
+            return self
+                .commitment
+                .decommit_to_cap(queries, eval_vec, log_cap_size);
+        }
+
+        let column_log_sizes = (0..self.polynomials.len())
+            .map(|column| self.column_log_size(column))
+            .collect_vec();
+        let positions =
+            self.commitment
+                .decommitment_positions(&queries, &column_log_sizes, log_cap_size);
+        let mut values = Vec::with_capacity(self.polynomials.len());
+        for chunk in &(0..self.polynomials.len()).chunks(RECOMPUTED_COLUMNS_CHUNK_SIZE) {
+            let evaluations = chunk
+                .map(|column| self.evaluation(column, twiddles))
+                .collect_vec();
+            values.extend(evaluations.iter().map(|eval| {
+                let log_size = eval.domain.log_size();
+                positions.get(&log_size).map_or(vec![], |positions| {
+                    positions
+                        .iter()
+                        .map(|&row| eval.values.at(row))
+                        .collect_vec()
+                })
+            }));
+        }
+        self.commitment.decommit_values_to_cap(
+            queries,
+            &column_log_sizes,
+            |column, row| {
+                let rows = &positions[&column_log_sizes[column]];
+                values[column][rows.binary_search(&row).unwrap()]
+            },
+            log_cap_size,
+        )
+    }
+}
+
+/// Evaluates `poly` on the canonic circle domain `2^log_blowup_factor` times its size.
+fn extend<B: PolyOps>(
+    poly: &CirclePoly<B>,
+    log_blowup_factor: u32,
+    twiddles: &TwiddleTree<B>,
+) -> CircleEvaluation<B, BaseField, BitReversedOrder> {
+    poly.evaluate_with_twiddles(
+        CanonicCoset::new(poly.log_size() + log_blowup_factor).circle_domain(),
+        twiddles,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::zip;
+
+    use itertools::Itertools;
+    use num_traits::One;
+
+    use super::{CommitmentSchemeProver, EvaluationStorage, RECOMPUTED_COLUMNS_CHUNK_SIZE};
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::CpuBackend;
+    use crate::core::channel::Blake2sChannel;
+    use crate::core::circle::{CirclePoint, SECURE_FIELD_CIRCLE_GEN};
+    use crate::core::fields::m31::BaseField;
+    use crate::core::fields::om31::OM31;
+    use crate::core::fri::FriConfig;
+    use crate::core::pcs::{CommitmentSchemeVerifier, PcsConfig, TreeVec};
+    use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
+    use crate::core::poly::twiddles::TwiddleCache;
+    use crate::core::poly::BitReversedOrder;
+    use crate::core::prover::VerificationError;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
+
+    const LOG_SIZES: [u32; 5] = [6, 6, 6, 5, 3];
+
+    fn column(
+        log_size: u32,
+        seed: usize,
+    ) -> CircleEvaluation<SimdBackend, BaseField, BitReversedOrder> {
+        CircleEvaluation::new(
+            CanonicCoset::new(log_size).circle_domain(),
+            (0..1 << log_size)
+                .map(|i| BaseField::from(i * seed + 1))
+                .collect(),
+        )
+    }
+
+    fn columns() -> Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+        LOG_SIZES
             .iter()
-            .map(|eval| &eval.values)
+            .enumerate()
+            .map(|(seed, &log_size)| column(log_size, seed))
+            .collect()
+    }
+
+    #[test]
+    fn test_streaming_commitment_matches_tree_builder() {
+        let config = PcsConfig::default();
+        let mut prover = CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+            config,
+            TwiddleCache::default(),
+        );
+        let channel = &mut Blake2sChannel::default();
+        let mut tree_builder = prover.tree_builder();
+        tree_builder.extend_evals(columns());
+        tree_builder.commit(channel);
+
+        for evaluation_storage in [EvaluationStorage::Keep, EvaluationStorage::Recompute] {
+            let mut streaming_prover =
+                CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+                    config,
+                    TwiddleCache::default(),
+                )
+                .with_evaluation_storage(evaluation_storage);
+            let streaming_channel = &mut Blake2sChannel::default();
+            let mut tree_builder = streaming_prover.streaming_tree_builder();
+            let mut columns = columns().into_iter();
+            // Batches that split a log size, and one that spans two.
+            for batch_size in [2, 2, 1] {
+                let span = tree_builder.extend_evals(columns.by_ref().take(batch_size));
+                assert_eq!(span.col_end - span.col_start, batch_size);
+            }
+            tree_builder.commit(streaming_channel);
+
+            assert_eq!(streaming_prover.commitments(), prover.commitments());
+            assert_eq!(streaming_channel.digest(), channel.digest());
+            let tree = &streaming_prover.trees[0];
+            match evaluation_storage {
+                EvaluationStorage::Keep => {
+                    assert_eq!(
+                        tree.evaluations
+                            .as_ref()
+                            .unwrap()
+                            .iter()
+                            .map(|e| e.domain.log_size())
+                            .collect_vec(),
+                        LOG_SIZES.map(|log_size| log_size + config.fri_config.log_blowup_factor)
+                    )
+                }
+                EvaluationStorage::Recompute => assert!(tree.evaluations.is_none()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_streaming_commitment_prove_and_verify() {
+        let config = PcsConfig::default();
+        let mut prover = CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+            config,
+            TwiddleCache::default(),
+        )
+        .with_evaluation_storage(EvaluationStorage::Recompute);
+        let prover_channel = &mut Blake2sChannel::default();
+        let mut tree_builder = prover.streaming_tree_builder();
+        for column in columns() {
+            tree_builder.extend_evals([column]);
+        }
+        tree_builder.commit(prover_channel);
+        let sampled_points =
+            TreeVec::new(vec![vec![vec![SECURE_FIELD_CIRCLE_GEN]; LOG_SIZES.len()]]);
+        let proof = prover.prove_values(sampled_points.clone(), prover_channel);
+
+        let verifier_channel = &mut Blake2sChannel::default();
+        let mut verifier = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+        verifier.commit(
+            prover.commitments()[0].clone(),
+            &LOG_SIZES,
+            verifier_channel,
+        );
+        verifier
+            .verify_values(sampled_points, proof, verifier_channel)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_octic_extension_prove_and_verify() {
+        let config = PcsConfig::default();
+        let mut prover = CommitmentSchemeProver::<CpuBackend, Blake2sMerkleChannel>::new(
+            config,
+            TwiddleCache::default(),
+        );
+        let prover_channel = &mut Blake2sChannel::default();
+        let mut tree_builder = prover.tree_builder();
+        tree_builder.extend_evals(columns().iter().map(|column| column.to_cpu()));
+        tree_builder.commit(prover_channel);
+        let point_channel = &mut Blake2sChannel::default();
+        let points: [CirclePoint<OM31>; 2] =
+            std::array::from_fn(|_| CirclePoint::get_random_point(point_channel));
+        let sampled_points = TreeVec::new(vec![(0..LOG_SIZES.len())
+            .map(|i| points[..1 + i % 2].to_vec())
+            .collect_vec()]);
+        let proof = prover.prove_values(sampled_points.clone(), &mut prover_channel.clone());
+        let mut invalid_proof = prover.prove_values(sampled_points.clone(), prover_channel);
+        invalid_proof.sampled_values[0][0][0] += OM31::one();
+
+        let verifier_channel = &mut Blake2sChannel::default();
+        let mut verifier = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+        verifier.commit(
+            prover.commitments()[0].clone(),
+            &LOG_SIZES,
+            verifier_channel,
+        );
+        assert!(verifier
+            .verify_values(
+                sampled_points.clone(),
+                invalid_proof,
+                &mut verifier_channel.clone()
+            )
+            .is_err());
+        verifier
+            .verify_values(sampled_points, proof, verifier_channel)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_recomputed_evaluations_in_chunks_match_kept() {
+        let config = PcsConfig::default();
+        let log_sizes = (0..2 * RECOMPUTED_COLUMNS_CHUNK_SIZE + 3)
+            .map(|i| if i % 5 == 0 { 4 } else { 5 })
             .collect_vec();
-        self.commitment.decommit(queries, eval_vec)
+        // Columns sampled at different sets of points, to split the sample batches unevenly.
+        let sampled_points = TreeVec::new(vec![(0..log_sizes.len())
+            .map(|i| match i % 3 {
+                0 => vec![SECURE_FIELD_CIRCLE_GEN],
+                _ => vec![SECURE_FIELD_CIRCLE_GEN, SECURE_FIELD_CIRCLE_GEN.double()],
+            })
+            .collect_vec()]);
+        let proofs =
+            [EvaluationStorage::Keep, EvaluationStorage::Recompute].map(|evaluation_storage| {
+                let mut prover = CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+                    config,
+                    TwiddleCache::default(),
+                )
+                .with_evaluation_storage(evaluation_storage);
+                let channel = &mut Blake2sChannel::default();
+                let mut tree_builder = prover.tree_builder();
+                tree_builder.extend_evals(
+                    log_sizes
+                        .iter()
+                        .enumerate()
+                        .map(|(seed, &log_size)| column(log_size, seed)),
+                );
+                tree_builder.commit(channel);
+                let proof = prover.prove_values(sampled_points.clone(), channel);
+                format!("{proof:?}")
+            });
+
+        assert_eq!(proofs[0], proofs[1]);
+    }
+
+    #[test]
+    fn test_merkle_cap_prove_and_verify() {
+        let config = PcsConfig {
+            fri_config: FriConfig::new(0, 1, 3).with_log_merkle_cap_size(2),
+            ..Default::default()
+        };
+        let mut prover = CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+            config,
+            TwiddleCache::default(),
+        );
+        let prover_channel = &mut Blake2sChannel::default();
+        let mut tree_builder = prover.tree_builder();
+        tree_builder.extend_evals(columns());
+        tree_builder.commit(prover_channel);
+        let sampled_points =
+            TreeVec::new(vec![vec![vec![SECURE_FIELD_CIRCLE_GEN]; LOG_SIZES.len()]]);
+        let proof = prover.prove_values(sampled_points.clone(), prover_channel);
+        // The caps are the commitments, of the trees and of the FRI layers.
+        assert_eq!(prover.commitments()[0].len(), 1 << 2);
+        assert_eq!(proof.fri_proof.inner_layers[0].commitment.len(), 1 << 2);
+
+        let verifier_channel = &mut Blake2sChannel::default();
+        let mut verifier = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+        verifier.commit(
+            prover.commitments()[0].clone(),
+            &LOG_SIZES,
+            verifier_channel,
+        );
+        verifier
+            .verify_values(sampled_points, proof, verifier_channel)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_mixed_blowup_factors_prove_and_verify() {
+        let config = PcsConfig::default();
+        let fri_log_blowup_factor = config.fri_config.log_blowup_factor;
+        let log_blowup_factors = [fri_log_blowup_factor + 2, fri_log_blowup_factor, 3];
+        let mut prover = CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+            config,
+            TwiddleCache::default(),
+        );
+        let prover_channel = &mut Blake2sChannel::default();
+        // A tree with the blowup factor of FRI.
+        let mut tree_builder = prover.tree_builder();
+        tree_builder.extend_evals(columns());
+        tree_builder.commit(prover_channel);
+        // A tree with a blowup factor per column group, where the first two groups have the
+        // same commitment domain log size.
+        let mut tree_builder = prover.tree_builder();
+        for (log_size, log_blowup_factor) in zip([4, 6, 5], log_blowup_factors) {
+            tree_builder.set_log_blowup_factor(log_blowup_factor);
+            tree_builder.extend_evals([column(log_size, 2), column(log_size, 3)]);
+        }
+        tree_builder.commit(prover_channel);
+        assert_eq!(
+            prover.trees[1].column_log_size(0),
+            4 + log_blowup_factors[0]
+        );
+        let sampled_points = TreeVec::new(vec![
+            vec![vec![SECURE_FIELD_CIRCLE_GEN]; LOG_SIZES.len()],
+            vec![vec![SECURE_FIELD_CIRCLE_GEN]; 6],
+        ]);
+        let proof = prover.prove_values(sampled_points.clone(), prover_channel);
+
+        let verifier_channel = &mut Blake2sChannel::default();
+        let mut verifier = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+        verifier.commit(
+            prover.commitments()[0].clone(),
+            &LOG_SIZES,
+            verifier_channel,
+        );
+        verifier
+            .commit_with_log_blowup_factors(
+                prover.commitments()[1].clone(),
+                &[4, 4, 6, 6, 5, 5],
+                &log_blowup_factors
+                    .map(|log_blowup_factor| [log_blowup_factor; 2])
+                    .concat(),
+                verifier_channel,
+            )
+            .unwrap();
+        verifier
+            .verify_values(sampled_points, proof, verifier_channel)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_streaming_commitment_with_mixed_blowup_factors_matches_tree_builder() {
+        // Non-increasing in commitment domain log size, but not in log size.
+        let log_sizes_and_blowup_factors = [(4, 3), (6, 1), (5, 2), (5, 1)];
+        let config = PcsConfig::default();
+        let mut prover = CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+            config,
+            TwiddleCache::default(),
+        );
+        let mut streaming_prover = CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+            config,
+            TwiddleCache::default(),
+        );
+        let channel = &mut Blake2sChannel::default();
+        let streaming_channel = &mut Blake2sChannel::default();
+
+        let mut tree_builder = prover.tree_builder();
+        let mut streaming_tree_builder = streaming_prover.streaming_tree_builder();
+        for (log_size, log_blowup_factor) in log_sizes_and_blowup_factors {
+            tree_builder.set_log_blowup_factor(log_blowup_factor);
+            tree_builder.extend_evals([column(log_size, 1)]);
+            streaming_tree_builder.set_log_blowup_factor(log_blowup_factor);
+            streaming_tree_builder.extend_evals([column(log_size, 1)]);
+        }
+        tree_builder.commit(channel);
+        streaming_tree_builder.commit(streaming_channel);
+
+        assert_eq!(streaming_prover.commitments(), prover.commitments());
+        assert_eq!(streaming_prover.trees[0].max_log_size(), 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "blowup factor 1 is smaller than the blowup factor of FRI")]
+    fn test_blowup_factor_smaller_than_fri_fails() {
+        let config = PcsConfig {
+            fri_config: FriConfig::new(0, 2, 3),
+            ..Default::default()
+        };
+        let mut prover = CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+            config,
+            TwiddleCache::default(),
+        );
+        prover.tree_builder().set_log_blowup_factor(1);
+    }
+
+    #[test]
+    fn test_verifier_blowup_factor_smaller_than_fri_fails() {
+        let config = PcsConfig {
+            fri_config: FriConfig::new(0, 2, 3),
+            ..Default::default()
+        };
+        let mut verifier = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+        let channel = &mut Blake2sChannel::default();
+
+        let result =
+            verifier.commit_with_log_blowup_factors(Default::default(), &[4, 4], &[2, 1], channel);
+
+        assert!(matches!(
+            result,
+            Err(VerificationError::InvalidStructure(_))
+        ));
+        assert!(verifier.trees.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "columns must be streamed in non-increasing log size")]
+    fn test_streaming_commitment_with_increasing_log_size_fails() {
+        let mut prover = CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+            PcsConfig::default(),
+            TwiddleCache::default(),
+        );
+        let mut tree_builder = prover.streaming_tree_builder();
+        tree_builder.extend_evals([column(4, 1)]);
+        tree_builder.extend_evals([column(5, 1)]);
     }
 }
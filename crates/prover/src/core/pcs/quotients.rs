@@ -1,24 +1,29 @@
 use std::cmp::Reverse;
 use std::collections::BTreeMap;
 use std::iter::zip;
+use std::ops::Range;
 
 use itertools::{izip, multiunzip, Itertools};
+use num_traits::Zero;
 use tracing::{span, Level};
 
 use crate::core::backend::cpu::quotients::{accumulate_row_quotients, quotient_constants};
 use crate::core::circle::CirclePoint;
+use crate::core::constraints::pair_vanishing;
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
+use crate::core::fields::{FieldExpOps, SecureExtension};
 use crate::core::fri::SparseCircleEvaluation;
 use crate::core::poly::circle::{
-    CanonicCoset, CircleDomain, CircleEvaluation, PolyOps, SecureEvaluation,
+    CanonicCoset, CircleDomain, CircleEvaluation, CirclePoly, PolyOps, SecureEvaluation,
 };
 use crate::core::poly::BitReversedOrder;
 use crate::core::prover::VerificationError;
 use crate::core::queries::SparseSubCircleDomain;
 use crate::core::utils::bit_reverse_index;
 
-pub trait QuotientOps: PolyOps {
+/// Quotient operations with samples over a [SecureExtension] `E`, the [SecureField] by default.
+pub trait QuotientOps<E: SecureExtension = SecureField>: PolyOps {
     /// Accumulates the quotients of the columns at the given domain.
     /// For a column f(x), and a point sample (p,v), the quotient is
     ///   (f(x) - V0(x))/V1(x)
@@ -28,25 +33,72 @@ pub trait QuotientOps: PolyOps {
     fn accumulate_quotients(
         domain: CircleDomain,
         columns: &[&CircleEvaluation<Self, BaseField, BitReversedOrder>],
-        random_coeff: SecureField,
-        sample_batches: &[ColumnSampleBatch],
+        random_coeff: E,
+        sample_batches: &[ColumnSampleBatch<E>],
         log_blowup_factor: u32,
-    ) -> SecureEvaluation<Self, BitReversedOrder>;
+    ) -> SecureEvaluation<Self, BitReversedOrder, E>;
+
+    /// Evaluates each polynomial at each of the points, as in [PolyOps::eval_at_points].
+    fn eval_at_extension_points(
+        polys: &[&CirclePoly<Self>],
+        points: &[CirclePoint<E>],
+    ) -> Vec<Vec<E>>;
 }
 
 /// A batch of column samplings at a point.
-pub struct ColumnSampleBatch {
+///
+/// The batch may be a part of a larger batch at the same point, whose columns are accumulated in
+/// separate parts (see [Self::column_range]). The quotients of the parts add up to the quotient of
+/// the whole batch.
+pub struct ColumnSampleBatch<E = SecureField> {
     /// The point at which the columns are sampled.
-    pub point: CirclePoint<SecureField>,
+    pub point: CirclePoint<E>,
     /// The sampled column indices and their values at the point.
-    pub columns_and_values: Vec<(usize, SecureField)>,
+    pub columns_and_values: Vec<(usize, E)>,
+    /// The number of columns of the whole batch that precede the ones of this part.
+    pub n_preceding_columns: usize,
+    /// The number of columns of the whole batch.
+    pub n_batch_columns: usize,
 }
 
-impl ColumnSampleBatch {
+impl<E: SecureExtension> ColumnSampleBatch<E> {
+    /// Creates a whole batch.
+    pub fn new(point: CirclePoint<E>, columns_and_values: Vec<(usize, E)>) -> Self {
+        let n_batch_columns = columns_and_values.len();
+        Self {
+            point,
+            columns_and_values,
+            n_preceding_columns: 0,
+            n_batch_columns,
+        }
+    }
+
+    /// Returns the part of the batch on the columns in `columns`, with column indices relative to
+    /// its start.
+    ///
+    /// The column indices of the batch must be increasing, as in [Self::new_vec].
+    pub fn column_range(&self, columns: Range<usize>) -> Self {
+        let start = self
+            .columns_and_values
+            .partition_point(|&(column, _)| column < columns.start);
+        let end = self
+            .columns_and_values
+            .partition_point(|&(column, _)| column < columns.end);
+        Self {
+            point: self.point,
+            columns_and_values: self.columns_and_values[start..end]
+                .iter()
+                .map(|&(column, value)| (column - columns.start, value))
+                .collect(),
+            n_preceding_columns: self.n_preceding_columns + start,
+            n_batch_columns: self.n_batch_columns,
+        }
+    }
+
     /// Groups column samples by sampled point.
     /// # Arguments
     /// samples: For each column, a vector of samples.
-    pub fn new_vec(samples: &[&Vec<PointSample>]) -> Vec<Self> {
+    pub fn new_vec(samples: &[&Vec<PointSample<E>>]) -> Vec<Self> {
         // Group samples by point, and create a ColumnSampleBatch for each point.
         // This should keep a stable ordering.
         let mut grouped_samples = BTreeMap::new();
@@ -60,25 +112,94 @@ impl ColumnSampleBatch {
         }
         grouped_samples
             .into_iter()
-            .map(|(point, columns_and_values)| ColumnSampleBatch {
-                point,
-                columns_and_values,
-            })
+            .map(|(point, columns_and_values)| ColumnSampleBatch::new(point, columns_and_values))
             .collect()
     }
 }
 
-pub struct PointSample {
-    pub point: CirclePoint<SecureField>,
-    pub value: SecureField,
+#[derive(Clone, Copy, Debug)]
+pub struct PointSample<E = SecureField> {
+    pub point: CirclePoint<E>,
+    pub value: E,
 }
 
-pub fn compute_fri_quotients<B: QuotientOps>(
-    columns: &[&CircleEvaluation<B, BaseField, BitReversedOrder>],
-    samples: &[Vec<PointSample>],
+/// A sample of a column at a point that equals its complex conjugate, at which the quotients of
+/// [QuotientOps::accumulate_quotients] are undefined. Instead of its conjugate, the point is
+/// paired with a sample of the same column at another point.
+#[derive(Clone, Copy, Debug)]
+pub struct PairedSample {
+    /// The index of the sampled column.
+    pub column_index: usize,
+    pub sample: PointSample,
+    /// A sample of the column at a point with a different y-coordinate.
+    pub partner: PointSample,
+}
+
+/// Accumulates the quotients of paired samples at the given domain points.
+///
+/// `columns` are the values of the columns at `domain_points`. For a column f(x), and a paired
+/// sample (p,v), (q,w), the quotient is
+///   (f(x) - L(x))/V(x)
+/// where L is the line in x.y through (p.y, v), (q.y, w), and V is the line through p and q.
+/// This ensures that if f(p)=v and f(q)=w, then the quotient is a polynomial.
+/// The result is a linear combination of the quotients using powers of random_coeff.
+///
+/// # Panics
+///
+/// Panics if a sampled point and its partner have the same y-coordinate, or if the domain contains
+/// a sampled point.
+pub fn accumulate_paired_quotients(
+    domain_points: &[CirclePoint<BaseField>],
+    columns: &[&[BaseField]],
+    samples: &[PairedSample],
     random_coeff: SecureField,
+) -> Vec<SecureField> {
+    let denominators = samples
+        .iter()
+        .flat_map(|paired| {
+            domain_points.iter().map(|domain_point| {
+                pair_vanishing(
+                    paired.sample.point,
+                    paired.partner.point,
+                    domain_point.into_ef(),
+                )
+            })
+        })
+        .collect_vec();
+    assert!(
+        !denominators.iter().any(SecureField::is_zero),
+        "sampled point in the domain"
+    );
+    let mut denominator_inverses = vec![SecureField::zero(); denominators.len()];
+    SecureField::batch_inverse(&denominators, &mut denominator_inverses);
+
+    let mut values = vec![SecureField::zero(); domain_points.len()];
+    for (paired, denominator_inverses) in
+        zip(samples, denominator_inverses.chunks(domain_points.len()))
+    {
+        let PairedSample {
+            column_index,
+            sample,
+            partner,
+        } = paired;
+        let dy = partner.point.y - sample.point.y;
+        assert!(!dy.is_zero(), "paired points have the same y-coordinate");
+        let slope = (partner.value - sample.value) / dy;
+        for (row, (value, domain_point)) in zip(&mut values, domain_points).enumerate() {
+            let line = sample.value + slope * (-sample.point.y + domain_point.y);
+            let numerator = columns[*column_index][row] - line;
+            *value = *value * random_coeff + numerator * denominator_inverses[row];
+        }
+    }
+    values
+}
+
+pub fn compute_fri_quotients<B: QuotientOps<E>, E: SecureExtension>(
+    columns: &[&CircleEvaluation<B, BaseField, BitReversedOrder>],
+    samples: &[Vec<PointSample<E>>],
+    random_coeff: E,
     log_blowup_factor: u32,
-) -> Vec<SecureEvaluation<B, BitReversedOrder>> {
+) -> Vec<SecureEvaluation<B, BitReversedOrder, E>> {
     let _span = span!(Level::INFO, "Compute FRI quotients").entered();
     zip(columns, samples)
         .sorted_by_key(|(c, _)| Reverse(c.domain.log_size()))
@@ -100,13 +221,13 @@ pub fn compute_fri_quotients<B: QuotientOps>(
         .collect()
 }
 
-pub fn fri_answers(
+pub fn fri_answers<E: SecureExtension>(
     column_log_sizes: Vec<u32>,
-    samples: &[Vec<PointSample>],
-    random_coeff: SecureField,
+    samples: &[Vec<PointSample<E>>],
+    random_coeff: E,
     query_domain_per_log_size: BTreeMap<u32, SparseSubCircleDomain>,
     queried_values_per_column: &[Vec<BaseField>],
-) -> Result<Vec<SparseCircleEvaluation>, VerificationError> {
+) -> Result<Vec<SparseCircleEvaluation<E>>, VerificationError> {
     izip!(column_log_sizes, samples, queried_values_per_column)
         .sorted_by_key(|(log_size, ..)| Reverse(*log_size))
         .group_by(|(log_size, ..)| *log_size)
@@ -125,13 +246,13 @@ pub fn fri_answers(
         .collect()
 }
 
-pub fn fri_answers_for_log_size(
+pub fn fri_answers_for_log_size<E: SecureExtension>(
     log_size: u32,
-    samples: &[&Vec<PointSample>],
-    random_coeff: SecureField,
+    samples: &[&Vec<PointSample<E>>],
+    random_coeff: E,
     query_domain: &SparseSubCircleDomain,
     queried_values_per_column: &[&Vec<BaseField>],
-) -> Result<SparseCircleEvaluation, VerificationError> {
+) -> Result<SparseCircleEvaluation<E>, VerificationError> {
     let commitment_domain = CanonicCoset::new(log_size).circle_domain();
     let sample_batches = ColumnSampleBatch::new_vec(samples);
     for queried_values in queried_values_per_column {
@@ -8,7 +8,7 @@ use super::TreeSubspan;
 use crate::core::ColumnVec;
 
 /// A container that holds an element for each commitment tree.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TreeVec<T>(pub Vec<T>);
 
 impl<T> TreeVec<T> {
@@ -1,15 +1,18 @@
+use std::collections::BTreeMap;
 use std::iter::zip;
 
 use itertools::Itertools;
 
 use super::super::circle::CirclePoint;
-use super::super::fields::qm31::SecureField;
-use super::super::fri::{CirclePolyDegreeBound, FriVerifier};
+use super::super::fields::m31::BaseField;
+use super::super::fields::SecureExtension;
+use super::super::fri::{CirclePolyDegreeBound, FriVerifier, SparseCircleEvaluation};
 use super::quotients::{fri_answers, PointSample};
 use super::utils::TreeVec;
 use super::{CommitmentSchemeProof, PcsConfig};
 use crate::core::channel::{Channel, MerkleChannel};
 use crate::core::prover::VerificationError;
+use crate::core::queries::SparseSubCircleDomain;
 use crate::core::vcs::ops::MerkleHasher;
 use crate::core::vcs::verifier::MerkleVerifier;
 use crate::core::ColumnVec;
@@ -30,40 +33,129 @@ impl<MC: MerkleChannel> CommitmentSchemeVerifier<MC> {
     }
 
     /// A [TreeVec<ColumnVec>] of the log sizes of each column in each commitment tree.
-    fn column_log_sizes(&self) -> TreeVec<ColumnVec<u32>> {
+    pub(super) fn column_log_sizes(&self) -> TreeVec<ColumnVec<u32>> {
         self.trees
             .as_ref()
             .map(|tree| tree.column_log_sizes.clone())
     }
 
-    /// Reads a commitment from the prover.
+    /// Reads a commitment from the prover, of columns committed with the blowup factor of FRI.
+    ///
+    /// The commitment is the Merkle cap of the tree, of the size set in the FRI configuration.
     pub fn commit(
         &mut self,
-        commitment: <MC::H as MerkleHasher>::Hash,
+        commitment: Vec<<MC::H as MerkleHasher>::Hash>,
         log_sizes: &[u32],
         channel: &mut MC::C,
     ) {
-        MC::mix_root(channel, commitment);
+        let log_blowup_factor = self.config.fri_config.log_blowup_factor;
         let extended_log_sizes = log_sizes
             .iter()
-            .map(|&log_size| log_size + self.config.fri_config.log_blowup_factor)
+            .map(|&log_size| log_size + log_blowup_factor)
             .collect();
-        let verifier = MerkleVerifier::new(commitment, extended_log_sizes);
+        self.push_tree(commitment, extended_log_sizes, channel);
+    }
+
+    /// Reads a commitment from the prover, of columns committed with the given blowup factors.
+    ///
+    /// Fails if `log_sizes` and `log_blowup_factors` have different lengths, or if a blowup factor
+    /// is smaller than the blowup factor of FRI.
+    pub fn commit_with_log_blowup_factors(
+        &mut self,
+        commitment: Vec<<MC::H as MerkleHasher>::Hash>,
+        log_sizes: &[u32],
+        log_blowup_factors: &[u32],
+        channel: &mut MC::C,
+    ) -> Result<(), VerificationError> {
+        if log_sizes.len() != log_blowup_factors.len() {
+            return Err(VerificationError::InvalidStructure(format!(
+                "{} log sizes but {} blowup factors",
+                log_sizes.len(),
+                log_blowup_factors.len()
+            )));
+        }
+        let fri_log_blowup_factor = self.config.fri_config.log_blowup_factor;
+        if let Some(&log_blowup_factor) = log_blowup_factors
+            .iter()
+            .find(|&&log_blowup_factor| log_blowup_factor < fri_log_blowup_factor)
+        {
+            return Err(VerificationError::InvalidStructure(format!(
+                "Blowup factor {log_blowup_factor} is smaller than the blowup factor of FRI"
+            )));
+        }
+        let extended_log_sizes = zip(log_sizes, log_blowup_factors)
+            .map(|(&log_size, &log_blowup_factor)| log_size + log_blowup_factor)
+            .collect();
+        self.push_tree(commitment, extended_log_sizes, channel);
+        Ok(())
+    }
+
+    /// Mixes `commitment` into the channel and adds its tree, of columns with the log sizes
+    /// `extended_log_sizes` after the blowup.
+    fn push_tree(
+        &mut self,
+        commitment: Vec<<MC::H as MerkleHasher>::Hash>,
+        extended_log_sizes: Vec<u32>,
+        channel: &mut MC::C,
+    ) {
+        MC::mix_cap(channel, &commitment);
+        let verifier = MerkleVerifier::with_cap(
+            commitment,
+            extended_log_sizes,
+            self.config.fri_config.log_merkle_cap_size,
+        );
         self.trees.push(verifier);
     }
 
-    pub fn verify_values(
+    /// Verifies the values of the committed polynomials at `sampled_points`, in the
+    /// [SecureExtension] `E`. See [super::CommitmentSchemeProver::prove_values].
+    pub fn verify_values<E: SecureExtension>(
         &self,
-        sampled_points: TreeVec<ColumnVec<Vec<CirclePoint<SecureField>>>>,
-        proof: CommitmentSchemeProof<MC::H>,
+        sampled_points: TreeVec<ColumnVec<Vec<CirclePoint<E>>>>,
+        proof: CommitmentSchemeProof<MC::H, E>,
         channel: &mut MC::C,
     ) -> Result<(), VerificationError> {
-        channel.mix_felts(&proof.sampled_values.clone().flatten_cols());
-        let random_coeff = channel.draw_felt();
+        channel.mix_extension_felts(&proof.sampled_values.clone().flatten_cols());
+        let random_coeff = channel.draw_extension_felt();
 
-        let bounds = self
-            .column_log_sizes()
-            .zip_cols(&sampled_points)
+        let bounds = self.degree_bounds(&sampled_points);
+        let samples = sampled_points
+            .zip_cols(proof.sampled_values.clone())
+            .map_cols(|(sampled_points, sampled_values)| {
+                zip(sampled_points, sampled_values)
+                    .map(|(point, value)| PointSample { point, value })
+                    .collect_vec()
+            })
+            .flatten();
+        let column_log_sizes = self.column_log_sizes().flatten();
+
+        self.verify_quotients(
+            bounds,
+            proof,
+            channel,
+            |fri_query_domains, queried_values| {
+                fri_answers(
+                    column_log_sizes,
+                    &samples,
+                    random_coeff,
+                    fri_query_domains,
+                    queried_values,
+                )
+            },
+        )
+    }
+
+    /// Returns the distinct degree bounds of the columns that have sampled points, in descending
+    /// order.
+    ///
+    /// The bound of a column is the log size of its commitment domain minus the blowup factor of
+    /// FRI, which is looser than its degree if it was committed with a larger blowup factor.
+    pub(super) fn degree_bounds<T>(
+        &self,
+        sampled_points: &TreeVec<ColumnVec<Vec<T>>>,
+    ) -> Vec<CirclePolyDegreeBound> {
+        self.column_log_sizes()
+            .zip_cols(sampled_points)
             .map_cols(|(log_size, sampled_points)| {
                 vec![
                     CirclePolyDegreeBound::new(log_size - self.config.fri_config.log_blowup_factor);
@@ -75,11 +167,25 @@ impl<MC: MerkleChannel> CommitmentSchemeVerifier<MC> {
             .sorted()
             .rev()
             .dedup()
-            .collect_vec();
+            .collect_vec()
+    }
 
+    /// Verifies that the quotients are of low degree and the decommitments of the committed trees
+    /// on the FRI queries. `answers` computes the quotients on the FRI query domains from the
+    /// queried values of all columns.
+    pub(super) fn verify_quotients<E: SecureExtension>(
+        &self,
+        bounds: Vec<CirclePolyDegreeBound>,
+        proof: CommitmentSchemeProof<MC::H, E>,
+        channel: &mut MC::C,
+        answers: impl FnOnce(
+            BTreeMap<u32, SparseSubCircleDomain>,
+            &[Vec<BaseField>],
+        ) -> Result<Vec<SparseCircleEvaluation<E>>, VerificationError>,
+    ) -> Result<(), VerificationError> {
         // FRI commitment phase on OODS quotients.
         let mut fri_verifier =
-            FriVerifier::<MC>::commit(channel, self.config.fri_config, proof.fri_proof, bounds)?;
+            FriVerifier::<MC, E>::commit(channel, self.config.fri_config, proof.fri_proof, bounds)?;
 
         // Verify proof of work.
         channel.mix_u64(proof.proof_of_work);
@@ -107,22 +213,7 @@ impl<MC: MerkleChannel> CommitmentSchemeVerifier<MC> {
             .collect::<Result<_, _>>()?;
 
         // Answer FRI queries.
-        let samples = sampled_points
-            .zip_cols(proof.sampled_values)
-            .map_cols(|(sampled_points, sampled_values)| {
-                zip(sampled_points, sampled_values)
-                    .map(|(point, value)| PointSample { point, value })
-                    .collect_vec()
-            })
-            .flatten();
-
-        let fri_answers = fri_answers(
-            self.column_log_sizes().flatten().into_iter().collect(),
-            &samples,
-            random_coeff,
-            fri_query_domains,
-            &proof.queried_values.flatten(),
-        )?;
+        let fri_answers = answers(fri_query_domains, &proof.queried_values.flatten())?;
 
         fri_verifier.decommit(fri_answers)?;
         Ok(())
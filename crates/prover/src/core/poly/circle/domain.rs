@@ -96,6 +96,43 @@ impl CircleDomain {
     pub fn shift(&self, shift: CirclePointIndex) -> CircleDomain {
         CircleDomain::new(self.half_coset.shift(shift))
     }
+
+    /// Returns the circle domain with the same points as `coset` (in another order), if there is
+    /// one.
+    ///
+    /// A coset `c + <G>` is a circle domain iff it is closed under conjugation without containing
+    /// self-conjugate points, i.e. iff `2c` is an odd multiple of `G`. For example, canonic cosets
+    /// are circle domains.
+    pub fn from_coset(coset: Coset) -> Option<Self> {
+        let double_initial = coset.initial_index * 2;
+        if coset.log_size == 0
+            || double_initial.0 == 0
+            || double_initial.0.trailing_zeros() != coset.step_size.0.trailing_zeros()
+        {
+            return None;
+        }
+        Some(Self::new(Coset {
+            log_size: coset.log_size - 1,
+            step_size: coset.step_size * 2,
+            step: coset.step.double(),
+            ..coset
+        }))
+    }
+
+    /// Returns the smallest circle domain containing the points of `coset`, if there is one.
+    ///
+    /// This is either the coset itself (see [CircleDomain::from_coset]), or the union of the coset
+    /// and its conjugate if they are disjoint. Cosets which intersect their conjugate without being
+    /// circle domains (i.e. subgroups) are not contained in any circle domain of twice their size.
+    pub fn containing_coset(coset: Coset) -> Option<Self> {
+        if let Some(domain) = Self::from_coset(coset) {
+            return Some(domain);
+        }
+        let double_initial = coset.initial_index * 2;
+        (double_initial.0 != 0
+            && double_initial.0.trailing_zeros() < coset.step_size.0.trailing_zeros())
+        .then_some(Self::new(coset))
+    }
 }
 
 impl IntoIterator for CircleDomain {
@@ -165,6 +202,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_circle_domain_from_coset() {
+        let canonic_coset = CanonicCoset::new(5);
+        let disjoint_coset = Coset::new(CirclePointIndex(3), 4);
+
+        assert_eq!(
+            CircleDomain::from_coset(canonic_coset.coset()),
+            Some(canonic_coset.circle_domain())
+        );
+        assert_eq!(CircleDomain::from_coset(disjoint_coset), None);
+        assert_eq!(CircleDomain::from_coset(Coset::subgroup(4)), None);
+        assert_eq!(
+            CircleDomain::containing_coset(disjoint_coset),
+            Some(CircleDomain::new(disjoint_coset))
+        );
+        assert_eq!(CircleDomain::containing_coset(Coset::subgroup(4)), None);
+    }
+
     #[test]
     fn test_domain_split() {
         let domain = CanonicCoset::new(5).circle_domain();
@@ -90,7 +90,15 @@ impl<B: PolyOps> CircleEvaluation<B, BaseField, BitReversedOrder> {
 
     /// Computes a minimal [CirclePoly] that evaluates to the same values as this evaluation, using
     /// precomputed twiddles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the twiddles don't cover the domain (see [TwiddleTree::covers]).
     pub fn interpolate_with_twiddles(self, twiddles: &TwiddleTree<B>) -> CirclePoly<B> {
+        assert!(
+            twiddles.covers(self.domain),
+            "twiddles don't cover the domain"
+        );
         B::interpolate(self, twiddles)
     }
 }
@@ -0,0 +1,259 @@
+use itertools::Itertools;
+use num_traits::{One, Zero};
+
+use super::{CirclePoly, PolyOps, SecureCirclePoly};
+use crate::core::circle::{CirclePoint, M31_CIRCLE_GEN};
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::qm31::SecureField;
+use crate::core::fields::{Field, FieldExpOps};
+
+impl<B: PolyOps> CirclePoly<B> {
+    /// Computes a polynomial that evaluates to `value` at `point` for each pair in `samples`.
+    ///
+    /// Unlike [super::CircleEvaluation::interpolate], the points can be any distinct points of the
+    /// circle. The result has the smallest log size `n` with `2^n > samples.len()`, except that a
+    /// single sample gives a constant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two samples have the same point.
+    pub fn interpolate_from_points(samples: &[(CirclePoint<BaseField>, BaseField)]) -> Self {
+        let coeffs =
+            interpolate_coefficients(samples.iter().map(|&(p, v)| (p.into_ef(), v.into())));
+        // The interpolant is unique and the samples are in the base field, so are its coefficients.
+        Self::new(coeffs.iter().map(|c| c.to_m31_array()[0]).collect())
+    }
+}
+
+impl<B: PolyOps> SecureCirclePoly<B> {
+    /// Computes a polynomial that evaluates to `value` at `point` for each pair in `samples`.
+    ///
+    /// See [CirclePoly::interpolate_from_points].
+    ///
+    /// # Panics
+    ///
+    /// Panics if two samples have the same point.
+    pub fn interpolate_from_points(samples: &[(CirclePoint<SecureField>, SecureField)]) -> Self {
+        let coeffs = interpolate_coefficients(samples.iter().copied());
+        let coords = coeffs.iter().map(|c| c.to_m31_array()).collect_vec();
+        Self(std::array::from_fn(|i| {
+            CirclePoly::new(coords.iter().map(|c| c[i]).collect())
+        }))
+    }
+}
+
+/// The square root of `-1` in [SecureField].
+const I: SecureField = SecureField::from_u32_unchecked(0, 1, 0, 0);
+
+/// Returns the FFT basis coefficients of a polynomial through `samples`.
+///
+/// A point `(x, y)` is mapped to `z = x + i * y`, which turns polynomials of total degree at most
+/// `k` into Laurent polynomials in `z` with degrees in `-k..=k`. These are interpolated through
+/// `2k + 1` points in the Lagrange form, padding an even number of samples with a zero at an
+/// unused point, and then converted to the FFT basis.
+fn interpolate_coefficients(
+    samples: impl IntoIterator<Item = (CirclePoint<SecureField>, SecureField)>,
+) -> Vec<SecureField> {
+    let mut samples = samples.into_iter().map(|(p, v)| (to_z(p), v)).collect_vec();
+    let mut sorted_zs = samples.iter().map(|&(z, _)| z).collect_vec();
+    sorted_zs.sort_unstable();
+    assert!(
+        sorted_zs.iter().tuple_windows().all(|(a, b)| a != b),
+        "duplicate interpolation point"
+    );
+    if samples.len() <= 1 {
+        let value = samples.first().map_or(SecureField::zero(), |&(_, v)| v);
+        return vec![value];
+    }
+    if samples.len() % 2 == 0 {
+        let unused = (1..)
+            .map(|i| to_z(M31_CIRCLE_GEN.mul(i).into_ef()))
+            .find(|z| sorted_zs.binary_search(z).is_err())
+            .unwrap();
+        samples.push((unused, SecureField::zero()));
+    }
+
+    // `f(z) = z^-k * g(z)`, where `g` has degree at most `2k` and `g(z_j) = z_j^k * v_j`.
+    let k = (samples.len() - 1) / 2;
+    let samples = samples
+        .into_iter()
+        .map(|(z, v)| (z, z.pow(k as u128) * v))
+        .collect_vec();
+    let laurent_coeffs = lagrange_interpolate(&samples);
+
+    // `z^j = T_j(x) + i * y * U_(j-1)(x)` and `z^-j = T_j(x) - i * y * U_(j-1)(x)`, where `T` and
+    // `U` are the Chebyshev polynomials of the first and second kind.
+    let log_size = (k + 1).next_power_of_two().ilog2() + 1;
+    let half_size = 1 << (log_size - 1);
+    let mut x_part = vec![SecureField::zero(); half_size];
+    let mut u_part = vec![SecureField::zero(); half_size];
+    x_part[0] = laurent_coeffs[k];
+    for j in 1..=k {
+        let (pos, neg) = (laurent_coeffs[k + j], laurent_coeffs[k - j]);
+        x_part[j] = pos + neg;
+        u_part[j - 1] = I * (pos - neg);
+    }
+    let mut y_part = chebyshev_u_to_t(&u_part);
+    chebyshev_to_fft_basis(&mut x_part);
+    chebyshev_to_fft_basis(&mut y_part);
+
+    // The lowest bit of a coefficient index selects `y`.
+    x_part.into_iter().interleave(y_part).collect()
+}
+
+/// Maps a point `(x, y)` of the circle to `x + i * y`, which has norm 1.
+fn to_z(point: CirclePoint<SecureField>) -> SecureField {
+    point.x + I * point.y
+}
+
+/// Returns the coefficients of the polynomial through `samples` in the monomial basis.
+///
+/// Computes the vanishing polynomial of all points once and divides it by each linear factor,
+/// which takes `O(n^2)` operations.
+fn lagrange_interpolate(samples: &[(SecureField, SecureField)]) -> Vec<SecureField> {
+    let mut vanishing = vec![SecureField::one()];
+    for &(z, _) in samples {
+        vanishing.push(SecureField::zero());
+        for i in (1..vanishing.len()).rev() {
+            let prev = vanishing[i - 1];
+            vanishing[i] -= z * prev;
+        }
+    }
+    // `vanishing` holds the coefficients from the highest degree down. The barycentric weights are
+    // the inverses of its derivative at each point.
+    let degree = samples.len();
+    let derivatives = samples
+        .iter()
+        .map(|&(z, _)| {
+            vanishing[..degree]
+                .iter()
+                .enumerate()
+                .fold(SecureField::zero(), |acc, (i, &c)| {
+                    acc * z + c * BaseField::from(degree - i)
+                })
+        })
+        .collect_vec();
+    let mut weights = vec![SecureField::zero(); degree];
+    SecureField::batch_inverse(&derivatives, &mut weights);
+
+    let mut coeffs = vec![SecureField::zero(); degree];
+    for (&(z, value), weight) in samples.iter().zip(weights) {
+        // Synthetic division of the vanishing polynomial by `X - z`.
+        let scale = value * weight;
+        let mut quotient = SecureField::zero();
+        for (coeff, &c) in coeffs.iter_mut().rev().zip(&vanishing) {
+            quotient = quotient * z + c;
+            *coeff += scale * quotient;
+        }
+    }
+    coeffs
+}
+
+/// Converts the coefficients of a series of Chebyshev polynomials of the second kind `U_j` to
+/// one of the first kind `T_j`.
+///
+/// Uses `U_j = 2 * (T_j + T_(j-2) + ...)`, where a trailing `T_0` only counts once.
+fn chebyshev_u_to_t(u_coeffs: &[SecureField]) -> Vec<SecureField> {
+    let mut t_coeffs = vec![SecureField::zero(); u_coeffs.len()];
+    // Suffix sums of the coefficients of the same parity.
+    let mut sums = [SecureField::zero(); 2];
+    for j in (0..u_coeffs.len()).rev() {
+        sums[j % 2] += u_coeffs[j];
+        t_coeffs[j] = sums[j % 2].double();
+    }
+    if let Some(t0) = t_coeffs.first_mut() {
+        *t0 = sums[0];
+    }
+    t_coeffs
+}
+
+/// Converts the coefficients of a series of Chebyshev polynomials `T_j`, in place, to the FFT
+/// basis of products of `T_1 = x, T_2 = pi(x), T_4 = pi^2(x), ...` selected by the index bits.
+///
+/// Splits off `T_(n/2) * h(x)` using `T_(n/2 + r) = 2 * T_(n/2) * T_r - T_(n/2 - r)`, and recurses
+/// on both halves.
+fn chebyshev_to_fft_basis(coeffs: &mut [SecureField]) {
+    let half = coeffs.len() / 2;
+    if half == 0 {
+        return;
+    }
+    let (low, high) = coeffs.split_at_mut(half);
+    for r in 1..half {
+        low[half - r] -= high[r];
+        high[r] = high[r].double();
+    }
+    chebyshev_to_fft_basis(low);
+    chebyshev_to_fft_basis(high);
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::core::backend::cpu::{CpuBackend, CpuCirclePoly};
+    use crate::core::circle::{CirclePoint, SECURE_FIELD_CIRCLE_GEN};
+    use crate::core::fields::m31::BaseField;
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::poly::circle::{CanonicCoset, SecureCirclePoly};
+
+    #[test]
+    fn test_interpolate_from_points() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let coset = CanonicCoset::new(8);
+        for n_points in [1, 2, 3, 5, 8, 13, 64] {
+            let samples = (0..n_points)
+                .map(|i| (coset.at(7 * i + 3), rng.gen::<BaseField>()))
+                .collect_vec();
+
+            let poly = CpuCirclePoly::interpolate_from_points(&samples);
+
+            assert!(poly.log_size() <= n_points.next_power_of_two().ilog2() + 1);
+            for (point, value) in samples {
+                assert_eq!(poly.eval_at_point(point.into_ef()), value.into());
+            }
+        }
+    }
+
+    #[test]
+    fn test_interpolate_from_points_with_same_y() {
+        // The points share `y`, so they can't be interpolated by `a + b * y`.
+        let point = CanonicCoset::new(4).at(1);
+        let mirrored = CirclePoint {
+            x: -point.x,
+            y: point.y,
+        };
+        let samples = [(point, BaseField::from(1)), (mirrored, BaseField::from(2))];
+
+        let poly = CpuCirclePoly::interpolate_from_points(&samples);
+
+        assert_eq!(poly.log_size(), 2);
+        for (point, value) in samples {
+            assert_eq!(poly.eval_at_point(point.into_ef()), value.into());
+        }
+    }
+
+    #[test]
+    fn test_secure_interpolate_from_points() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let samples = (1..6)
+            .map(|i| (SECURE_FIELD_CIRCLE_GEN.mul(i), rng.gen::<SecureField>()))
+            .collect_vec();
+
+        let poly = SecureCirclePoly::<CpuBackend>::interpolate_from_points(&samples);
+
+        for (point, value) in samples {
+            assert_eq!(poly.eval_at_point(point), value);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate interpolation point")]
+    fn test_interpolate_from_duplicate_points_fails() {
+        let point = CanonicCoset::new(4).at(1);
+        let samples = [(point, BaseField::from(1)), (point, BaseField::from(1))];
+
+        CpuCirclePoly::interpolate_from_points(&samples);
+    }
+}
@@ -1,6 +1,8 @@
 mod canonic;
 mod domain;
 mod evaluation;
+mod interpolation;
+mod natural_order;
 mod ops;
 mod poly;
 mod secure_poly;
@@ -8,8 +10,9 @@ mod secure_poly;
 pub use canonic::CanonicCoset;
 pub use domain::{CircleDomain, MAX_CIRCLE_DOMAIN_LOG_SIZE};
 pub use evaluation::{CircleEvaluation, CosetSubEvaluation};
+pub use natural_order::{NaturalOrderTrace, NaturalOrderView};
 pub use ops::PolyOps;
-pub use poly::CirclePoly;
+pub use poly::{product_log_size, CirclePoly};
 pub use secure_poly::{SecureCirclePoly, SecureEvaluation};
 
 #[cfg(test)]
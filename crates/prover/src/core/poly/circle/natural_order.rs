@@ -0,0 +1,231 @@
+use super::{CanonicCoset, CircleEvaluation};
+use crate::core::backend::{Col, Column};
+use crate::core::fields::m31::BaseField;
+use crate::core::fields::{ExtensionOf, FieldOps};
+use crate::core::poly::BitReversedOrder;
+use crate::core::utils::coset_index_to_bit_reversed_index;
+use crate::core::ColumnVec;
+
+/// A read-only view of a [BitReversedOrder] evaluation over a canonic coset, indexed in natural
+/// (coset) order. Row `i` is the value at the `i`th point of the [CanonicCoset].
+pub struct NaturalOrderView<'a, B: FieldOps<F>, F: ExtensionOf<BaseField>> {
+    values: &'a Col<B, F>,
+    log_size: u32,
+}
+
+impl<'a, B: FieldOps<F>, F: ExtensionOf<BaseField>> NaturalOrderView<'a, B, F> {
+    /// Creates a view of `values`, which are in bit-reversed circle domain order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values is not a power of two.
+    pub fn new(values: &'a Col<B, F>) -> Self {
+        assert!(values.len().is_power_of_two());
+        let log_size = values.len().ilog2();
+        Self { values, log_size }
+    }
+
+    /// Returns the value at `row`.
+    pub fn at(&self, row: usize) -> F {
+        self.values
+            .at(coset_index_to_bit_reversed_index(row, self.log_size))
+    }
+
+    /// Returns the number of rows.
+    pub fn len(&self) -> usize {
+        1 << self.log_size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns an iterator over the values in natural order.
+    pub fn iter(&self) -> impl Iterator<Item = F> + '_ {
+        (0..self.len()).map(|row| self.at(row))
+    }
+
+    /// Copies the values in natural order into a vector.
+    pub fn to_vec(&self) -> Vec<F> {
+        self.iter().collect()
+    }
+}
+
+impl<B: FieldOps<F>, F: ExtensionOf<BaseField>> CircleEvaluation<B, F, BitReversedOrder> {
+    /// Returns a view of the evaluation indexed by rows in natural (coset) order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the evaluation domain is not canonic.
+    pub fn natural_order(&self) -> NaturalOrderView<'_, B, F> {
+        assert!(self.domain.is_canonic(), "domain is not canonic");
+        NaturalOrderView::new(&self.values)
+    }
+}
+
+/// A trace over a [CanonicCoset] whose rows are written and read in natural (coset) order.
+///
+/// The columns are stored in [BitReversedOrder] circle domain order, which is the order expected
+/// by the commitment scheme, so rows are mapped on access and no reordering copies are needed
+/// when the trace is turned into evaluations.
+#[derive(Clone, Debug)]
+pub struct NaturalOrderTrace<B: FieldOps<BaseField>> {
+    columns: Vec<Col<B, BaseField>>,
+    log_size: u32,
+}
+
+impl<B: FieldOps<BaseField>> NaturalOrderTrace<B> {
+    /// Creates a trace of `n_columns` zero columns with `2^log_size` rows.
+    pub fn new(log_size: u32, n_columns: usize) -> Self {
+        let columns = (0..n_columns)
+            .map(|_| Col::<B, BaseField>::zeros(1 << log_size))
+            .collect();
+        Self { columns, log_size }
+    }
+
+    /// Wraps existing evaluations over the canonic circle domain of log size `log_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any evaluation is not over that domain.
+    pub fn from_evaluations(
+        log_size: u32,
+        evals: ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>>,
+    ) -> Self {
+        let domain = CanonicCoset::new(log_size).circle_domain();
+        let columns = evals
+            .into_iter()
+            .map(|eval| {
+                assert_eq!(eval.domain, domain, "evaluation not over the trace domain");
+                eval.values
+            })
+            .collect();
+        Self { columns, log_size }
+    }
+
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    pub fn n_rows(&self) -> usize {
+        1 << self.log_size
+    }
+
+    pub fn n_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Returns the value of `column` at `row`.
+    pub fn at(&self, column: usize, row: usize) -> BaseField {
+        self.columns[column].at(self.storage_index(row))
+    }
+
+    /// Sets the value of `column` at `row`.
+    pub fn set(&mut self, column: usize, row: usize, value: BaseField) {
+        let index = self.storage_index(row);
+        self.columns[column].set(index, value);
+    }
+
+    /// Returns the values of all columns at `row`.
+    pub fn row(&self, row: usize) -> Vec<BaseField> {
+        let index = self.storage_index(row);
+        self.columns.iter().map(|column| column.at(index)).collect()
+    }
+
+    /// Sets the values of all columns at `row`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values doesn't match the number of columns.
+    pub fn set_row(&mut self, row: usize, values: &[BaseField]) {
+        assert_eq!(values.len(), self.columns.len());
+        let index = self.storage_index(row);
+        for (column, &value) in self.columns.iter_mut().zip(values) {
+            column.set(index, value);
+        }
+    }
+
+    /// Returns a natural order view of `column`.
+    pub fn column(&self, column: usize) -> NaturalOrderView<'_, B, BaseField> {
+        NaturalOrderView::new(&self.columns[column])
+    }
+
+    /// Converts the trace into evaluations over the canonic circle domain, without copying.
+    pub fn into_evaluations(self) -> ColumnVec<CircleEvaluation<B, BaseField, BitReversedOrder>> {
+        let domain = CanonicCoset::new(self.log_size).circle_domain();
+        self.columns
+            .into_iter()
+            .map(|column| CircleEvaluation::new(domain, column))
+            .collect()
+    }
+
+    fn storage_index(&self, row: usize) -> usize {
+        coset_index_to_bit_reversed_index(row, self.log_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::NaturalOrderTrace;
+    use crate::core::backend::cpu::CpuBackend;
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::circle::CirclePoint;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::poly::circle::CanonicCoset;
+
+    #[test]
+    fn natural_order_trace_rows_match_coset_points() {
+        const LOG_SIZE: u32 = 6;
+        let coset = CanonicCoset::new(LOG_SIZE);
+        let mut trace = NaturalOrderTrace::<SimdBackend>::new(LOG_SIZE, 2);
+        for row in 0..trace.n_rows() {
+            trace.set_row(row, &[BaseField::from(row), BaseField::from(2 * row)]);
+        }
+        // Interpolate the first column, which is `i` at the `i`th point of the coset.
+        let polys = trace
+            .clone()
+            .into_evaluations()
+            .into_iter()
+            .map(|eval| eval.to_cpu().interpolate())
+            .collect_vec();
+
+        for row in 0..trace.n_rows() {
+            let point = coset.at(row).into_ef::<SecureField>();
+            assert_eq!(polys[0].eval_at_point(point), BaseField::from(row).into());
+            assert_eq!(trace.row(row), [row, 2 * row].map(BaseField::from));
+        }
+        assert_eq!(
+            trace.column(1).to_vec(),
+            (0..trace.n_rows())
+                .map(|row| BaseField::from(2 * row))
+                .collect_vec()
+        );
+    }
+
+    #[test]
+    fn natural_order_view_of_evaluation() {
+        const LOG_SIZE: u32 = 4;
+        let coset = CanonicCoset::new(LOG_SIZE);
+        let mut trace = NaturalOrderTrace::<CpuBackend>::new(LOG_SIZE, 1);
+        for row in 0..trace.n_rows() {
+            let CirclePoint { x, .. } = coset.at(row);
+            trace.set(0, row, x);
+        }
+
+        let eval = trace.into_evaluations().pop().unwrap();
+
+        let domain = coset.circle_domain();
+        assert!(eval
+            .natural_order()
+            .iter()
+            .enumerate()
+            .all(|(row, x)| x == coset.at(row).x));
+        assert_eq!(
+            eval.clone().bit_reverse().values.into_iter().collect_vec(),
+            domain.iter().map(|p| p.x).collect_vec()
+        );
+    }
+}
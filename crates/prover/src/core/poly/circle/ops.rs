@@ -31,10 +31,25 @@ pub trait PolyOps: FieldOps<BaseField> + Sized {
     /// Used by the [`CirclePoly::eval_at_point()`] function.
     fn eval_at_point(poly: &CirclePoly<Self>, point: CirclePoint<SecureField>) -> SecureField;
 
+    /// Evaluates each polynomial at each of the points.
+    ///
+    /// Returns a vector `res` with `res[i][j] = polys[i].eval_at_point(points[j])`. The
+    /// point-dependent part of the evaluation is computed once per point and shared by all the
+    /// polynomials.
+    fn eval_at_points(
+        polys: &[&CirclePoly<Self>],
+        points: &[CirclePoint<SecureField>],
+    ) -> Vec<Vec<SecureField>>;
+
     /// Extends the polynomial to a larger degree bound.
     /// Used by the [`CirclePoly::extend()`] function.
     fn extend(poly: &CirclePoly<Self>, log_size: u32) -> CirclePoly<Self>;
 
+    /// Keeps the first `2^log_size` coefficients of the polynomial, which must be at least as
+    /// large. Returns `None` if any of the dropped coefficients is nonzero.
+    /// Used by the [`CirclePoly::div_coset_vanishing()`] function.
+    fn truncate(poly: CirclePoly<Self>, log_size: u32) -> Option<CirclePoly<Self>>;
+
     /// Evaluates the polynomial at all points in the domain.
     /// Used by the [`CirclePoly::evaluate()`] function.
     fn evaluate(
@@ -45,4 +60,16 @@ pub trait PolyOps: FieldOps<BaseField> + Sized {
 
     /// Precomputes twiddles for a given coset.
     fn precompute_twiddles(coset: Coset) -> TwiddleTree<Self>;
+
+    /// Computes `dst[i] += alpha * src[i]` for all `i`.
+    /// Used by the [CirclePoly] arithmetic operations.
+    fn add_scaled_column(
+        dst: &mut Col<Self, BaseField>,
+        src: &Col<Self, BaseField>,
+        alpha: BaseField,
+    );
+
+    /// Computes `dst[i] *= src[i]` for all `i`.
+    /// Used by the [CirclePoly] arithmetic operations.
+    fn mul_column(dst: &mut Col<Self, BaseField>, src: &Col<Self, BaseField>);
 }
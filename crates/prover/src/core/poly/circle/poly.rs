@@ -1,11 +1,17 @@
-use super::{CircleDomain, CircleEvaluation, PolyOps};
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num_traits::{One, Zero};
+
+use super::{CanonicCoset, CircleDomain, CircleEvaluation, PolyOps};
 use crate::core::backend::{Col, Column};
-use crate::core::circle::CirclePoint;
+use crate::core::circle::{CirclePoint, Coset};
+use crate::core::constraints::{coset_vanishing, point_vanishing};
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
-use crate::core::fields::FieldOps;
+use crate::core::fields::{FieldExpOps, FieldOps};
 use crate::core::poly::twiddles::TwiddleTree;
 use crate::core::poly::BitReversedOrder;
+use crate::core::utils::bit_reverse_index;
 
 /// A polynomial defined on a [CircleDomain].
 #[derive(Clone, Debug)]
@@ -57,20 +63,280 @@ impl<B: PolyOps> CirclePoly<B> {
     }
 
     /// Evaluates the polynomial at all points in the domain, using precomputed twiddles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the twiddles don't cover the domain (see [TwiddleTree::covers]).
     pub fn evaluate_with_twiddles(
         &self,
         domain: CircleDomain,
         twiddles: &TwiddleTree<B>,
     ) -> CircleEvaluation<B, BaseField, BitReversedOrder> {
+        assert!(twiddles.covers(domain), "twiddles don't cover the domain");
         B::evaluate(self, domain, twiddles)
     }
+
+    /// Evaluates the polynomial at all points of an arbitrary coset.
+    ///
+    /// Returns the values in bit-reversed coset order, i.e. the `i`-th value is the evaluation at
+    /// `coset.at(bit_reverse_index(i, coset.log_size()))`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the coset is not contained in a circle domain (see
+    /// [CircleDomain::containing_coset]), or if that domain is smaller than the polynomial.
+    pub fn evaluate_on_coset(&self, coset: Coset) -> Col<B, BaseField> {
+        let domain = CircleDomain::containing_coset(coset)
+            .unwrap_or_else(|| panic!("{coset:?} is not contained in a circle domain"));
+        let domain_values = self.evaluate(domain).values;
+        coset_to_domain_positions(coset, domain)
+            .map(|position| domain_values.at(position))
+            .collect()
+    }
+
+    /// Computes the minimal polynomial that evaluates to `values` on `coset`, where `values` are in
+    /// bit-reversed coset order (see [CirclePoly::evaluate_on_coset]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the coset is not a circle domain (see [CircleDomain::from_coset]).
+    pub fn interpolate_from_coset(coset: Coset, values: &Col<B, BaseField>) -> Self {
+        assert_eq!(values.len(), coset.size());
+        let domain = CircleDomain::from_coset(coset)
+            .unwrap_or_else(|| panic!("{coset:?} is not a circle domain"));
+        let mut domain_values = Col::<B, BaseField>::zeros(domain.size());
+        for (i, position) in coset_to_domain_positions(coset, domain).enumerate() {
+            domain_values.set(position, values.at(i));
+        }
+        CircleEvaluation::<B, BaseField, BitReversedOrder>::new(domain, domain_values).interpolate()
+    }
+
+    /// Returns the polynomial extended to `log_size`, or `self` if it is already of that size.
+    fn extended_to(self, log_size: u32) -> Self {
+        match self.log_size.cmp(&log_size) {
+            std::cmp::Ordering::Less => self.extend(log_size),
+            _ => self,
+        }
+    }
+
+    /// Returns the first `2^log_size` coefficients of the polynomial.
+    ///
+    /// Since the coefficients are stored in bit-reversed order, this is the inverse of
+    /// [`CirclePoly::extend`] for polynomials that fit in the smaller size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the dropped coefficients is nonzero, i.e. if the polynomial doesn't fit in
+    /// `2^log_size` coefficients.
+    fn truncated_to(self, log_size: u32) -> Self {
+        if self.log_size == log_size {
+            return self;
+        }
+        B::truncate(self, log_size)
+            .unwrap_or_else(|| panic!("polynomial doesn't fit in log size {log_size}"))
+    }
+
+    /// Divides the polynomial by the vanishing polynomial of `coset`.
+    ///
+    /// The polynomial must vanish on `coset`, in which case the (exact) quotient has the same log
+    /// size as `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the polynomial doesn't vanish on `coset`, or if the coset intersects the
+    /// evaluation domain used for the division, which can only happen for cosets that are not
+    /// subsets of a canonic coset of size at most `2^log_size`.
+    pub fn div_coset_vanishing(&self, coset: Coset) -> Self {
+        self.div_coset_vanishing_with_twiddles(coset, &self.precompute_division_twiddles())
+    }
+
+    /// Divides the polynomial by the vanishing polynomial of `coset`, using precomputed twiddles
+    /// (see [CirclePoly::div_coset_vanishing]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the twiddles don't cover the canonic circle domain of log size `log_size + 1`,
+    /// in addition to the cases of [CirclePoly::div_coset_vanishing].
+    pub fn div_coset_vanishing_with_twiddles(
+        &self,
+        coset: Coset,
+        twiddles: &TwiddleTree<B>,
+    ) -> Self {
+        let quotient = self.div_by(|p| coset_vanishing(coset, p), twiddles);
+        quotient.truncated_to(self.log_size)
+    }
+
+    /// Divides the polynomial by the vanishing polynomial of `point` (see [point_vanishing]).
+    ///
+    /// The polynomial must vanish at `point`. The quotient may have degree up to that of `self`,
+    /// so its log size is one more than that of `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `point` or its antipode lie on the evaluation domain used for the division, i.e.
+    /// the canonic circle domain of size `2^(log_size + 1)`.
+    pub fn div_point_vanishing(&self, point: CirclePoint<BaseField>) -> Self {
+        self.div_point_vanishing_with_twiddles(point, &self.precompute_division_twiddles())
+    }
+
+    /// Divides the polynomial by the vanishing polynomial of `point`, using precomputed twiddles
+    /// (see [CirclePoly::div_point_vanishing]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the twiddles don't cover the canonic circle domain of log size `log_size + 1`,
+    /// in addition to the cases of [CirclePoly::div_point_vanishing].
+    pub fn div_point_vanishing_with_twiddles(
+        &self,
+        point: CirclePoint<BaseField>,
+        twiddles: &TwiddleTree<B>,
+    ) -> Self {
+        self.div_by(|p| point_vanishing(point, p), twiddles)
+    }
+
+    /// Computes `self * rhs`, using precomputed twiddles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the twiddles don't cover the canonic circle domain of log size
+    /// [`product_log_size`] of the log sizes of the polynomials.
+    pub fn mul_with_twiddles(&self, rhs: &Self, twiddles: &TwiddleTree<B>) -> Self {
+        let domain =
+            CanonicCoset::new(product_log_size(self.log_size, rhs.log_size)).circle_domain();
+        assert!(
+            twiddles.covers(domain),
+            "twiddles don't cover the product domain"
+        );
+        let mut values = B::evaluate(self, domain, twiddles).values;
+        B::mul_column(&mut values, &B::evaluate(rhs, domain, twiddles).values);
+        B::interpolate(CircleEvaluation::new(domain, values), twiddles)
+    }
+
+    /// Precomputes twiddles that can be used for the divisions of this polynomial, and of every
+    /// polynomial of at most the same log size.
+    pub fn precompute_division_twiddles(&self) -> TwiddleTree<B> {
+        B::precompute_twiddles(division_domain(self.log_size).half_coset)
+    }
+
+    /// Divides the polynomial by a function, by evaluating on the canonic domain of log size
+    /// `log_size + 1` and interpolating. The function must not vanish on that domain.
+    fn div_by(
+        &self,
+        denominator: impl Fn(CirclePoint<BaseField>) -> BaseField,
+        twiddles: &TwiddleTree<B>,
+    ) -> Self {
+        let domain = division_domain(self.log_size);
+        assert!(
+            twiddles.covers(domain),
+            "twiddles don't cover the division domain"
+        );
+        let denominators = (0..domain.size())
+            .map(|i| denominator(domain.at(bit_reverse_index(i, domain.log_size()))))
+            .collect::<Vec<_>>();
+        assert!(
+            !denominators.iter().any(BaseField::is_zero),
+            "denominator vanishes on the evaluation domain"
+        );
+        let mut denominator_inverses = vec![BaseField::zero(); domain.size()];
+        BaseField::batch_inverse(&denominators, &mut denominator_inverses);
+
+        let mut values = B::evaluate(self, domain, twiddles).values;
+        B::mul_column(&mut values, &denominator_inverses.into_iter().collect());
+        B::interpolate(CircleEvaluation::new(domain, values), twiddles)
+    }
+}
+
+/// Returns the domain on which a polynomial of log size `log_size` is evaluated to be divided.
+fn division_domain(log_size: u32) -> CircleDomain {
+    CanonicCoset::new(log_size + 1).circle_domain()
+}
+
+/// Returns, for each point of `coset` in bit-reversed coset order, its position in the bit-reversed
+/// order of `domain`, which must contain the coset.
+fn coset_to_domain_positions(coset: Coset, domain: CircleDomain) -> impl Iterator<Item = usize> {
+    (0..coset.size()).map(move |i| {
+        let index = coset.index_at(bit_reverse_index(i, coset.log_size()));
+        let position = domain.find(index).expect("point not in domain");
+        bit_reverse_index(position, domain.log_size())
+    })
+}
+
+/// Returns the log size of a polynomial that can hold the product of polynomials of the given log
+/// sizes.
+///
+/// A polynomial with `2^n` coefficients has the form `p(x) + y * q(x)`, where `p` and `q` have
+/// degree less than `2^(n-1)`. Since `y^2 = 1 - x^2`, a product of such polynomials of sizes `n0`
+/// and `n1` has an `x` component of degree up to `2^(n0-1) + 2^(n1-1)`, so the product needs more
+/// than `2^n0 + 2^n1` coefficients.
+pub fn product_log_size(log_size0: u32, log_size1: u32) -> u32 {
+    ((1usize << log_size0) + (1 << log_size1) + 1)
+        .next_power_of_two()
+        .ilog2()
+}
+
+impl<B: PolyOps> Add for CirclePoly<B> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let log_size = self.log_size.max(rhs.log_size);
+        let mut res = self.extended_to(log_size);
+        B::add_scaled_column(
+            &mut res.coeffs,
+            &rhs.extended_to(log_size).coeffs,
+            BaseField::one(),
+        );
+        res
+    }
+}
+
+impl<B: PolyOps> Sub for CirclePoly<B> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let log_size = self.log_size.max(rhs.log_size);
+        let mut res = self.extended_to(log_size);
+        B::add_scaled_column(
+            &mut res.coeffs,
+            &rhs.extended_to(log_size).coeffs,
+            -BaseField::one(),
+        );
+        res
+    }
+}
+
+impl<B: PolyOps> Neg for CirclePoly<B> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self * -BaseField::one()
+    }
+}
+
+impl<B: PolyOps> Mul<BaseField> for CirclePoly<B> {
+    type Output = Self;
+
+    fn mul(self, rhs: BaseField) -> Self::Output {
+        let mut coeffs = Col::<B, BaseField>::zeros(self.coeffs.len());
+        B::add_scaled_column(&mut coeffs, &self.coeffs, rhs);
+        Self::new(coeffs)
+    }
+}
+
+impl<B: PolyOps> Mul for &CirclePoly<B> {
+    type Output = CirclePoly<B>;
+
+    /// Multiplies two polynomials by evaluating them on a large enough canonic domain (see
+    /// [product_log_size]) and interpolating the pointwise product.
+    fn mul(self, rhs: Self) -> Self::Output {
+        let domain =
+            CanonicCoset::new(product_log_size(self.log_size, rhs.log_size)).circle_domain();
+        self.mul_with_twiddles(rhs, &B::precompute_twiddles(domain.half_coset))
+    }
 }
 
 #[cfg(test)]
 impl crate::core::backend::cpu::CpuCirclePoly {
     pub fn is_in_fft_space(&self, log_fft_size: u32) -> bool {
-        use num_traits::Zero;
-
         let mut coeffs = self.coeffs.clone();
         while coeffs.last() == Some(&BaseField::zero()) {
             coeffs.pop();
@@ -85,8 +351,6 @@ impl crate::core::backend::cpu::CpuCirclePoly {
     /// Fri space is the space of polynomials of total degree n/2.
     /// Highest degree monomials are x^{n/2} and x^{(n/2)-1}y.
     pub fn is_in_fri_space(&self, log_fft_size: u32) -> bool {
-        use num_traits::Zero;
-
         let mut coeffs = self.coeffs.clone();
         while coeffs.last() == Some(&BaseField::zero()) {
             coeffs.pop();
@@ -100,9 +364,28 @@ impl crate::core::backend::cpu::CpuCirclePoly {
 
 #[cfg(test)]
 mod tests {
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
     use crate::core::backend::cpu::CpuCirclePoly;
-    use crate::core::circle::CirclePoint;
+    use crate::core::backend::CpuBackend;
+    use crate::core::circle::{
+        CirclePoint, CirclePointIndex, Coset, M31_CIRCLE_GEN, SECURE_FIELD_CIRCLE_GEN,
+    };
+    use crate::core::constraints::{coset_vanishing, point_vanishing};
     use crate::core::fields::m31::BaseField;
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, PolyOps};
+    use crate::core::poly::BitReversedOrder;
+    use crate::core::utils::bit_reverse_index;
+
+    fn random_poly(rng: &mut SmallRng, log_size: u32) -> CpuCirclePoly {
+        CpuCirclePoly::new((0..1 << log_size).map(|_| rng.gen()).collect())
+    }
+
+    fn random_point() -> CirclePoint<SecureField> {
+        SECURE_FIELD_CIRCLE_GEN.mul(21903)
+    }
 
     #[test]
     fn test_circle_poly_extend() {
@@ -115,4 +398,156 @@ mod tests {
             extended.eval_at_point(random_point)
         );
     }
+
+    #[test]
+    fn test_evaluate_on_shifted_domain() {
+        let rng = &mut SmallRng::seed_from_u64(0);
+        let poly = random_poly(rng, 5);
+        let domain = CanonicCoset::new(6)
+            .circle_domain()
+            .shift(CirclePointIndex::generator());
+        let twiddles = CpuBackend::precompute_twiddles(domain.half_coset);
+
+        let evaluation = poly.evaluate_with_twiddles(domain, &twiddles);
+
+        for (i, point) in domain.iter().enumerate() {
+            assert_eq!(
+                evaluation.values[bit_reverse_index(i, domain.log_size())],
+                poly.eval_at_point(point.into_ef()).0 .0
+            );
+        }
+        let interpolated = evaluation.interpolate_with_twiddles(&twiddles);
+        assert_eq!(interpolated.coeffs[..poly.coeffs.len()], poly.coeffs);
+    }
+
+    #[test]
+    #[should_panic(expected = "twiddles don't cover the domain")]
+    fn test_evaluate_with_uncovering_twiddles_fails() {
+        let poly = CpuCirclePoly::new(vec![BaseField::from(1); 4]);
+        let domain = CanonicCoset::new(3)
+            .circle_domain()
+            .shift(CirclePointIndex::generator());
+        let twiddles = CpuBackend::precompute_twiddles(CanonicCoset::new(8).half_coset());
+
+        poly.evaluate_with_twiddles(domain, &twiddles);
+    }
+
+    #[test]
+    fn test_evaluate_on_coset() {
+        let rng = &mut SmallRng::seed_from_u64(0);
+        let poly = random_poly(rng, 5);
+        let coset = Coset::new(CirclePointIndex(3), 4);
+
+        let values = poly.evaluate_on_coset(coset);
+
+        for (i, value) in values.into_iter().enumerate() {
+            let point = coset.at(bit_reverse_index(i, coset.log_size()));
+            assert_eq!(value, poly.eval_at_point(point.into_ef()).0 .0);
+        }
+    }
+
+    #[test]
+    fn test_interpolate_from_canonic_coset() {
+        let rng = &mut SmallRng::seed_from_u64(0);
+        let poly = random_poly(rng, 5);
+        let coset = CanonicCoset::new(5).coset();
+
+        let values = poly.evaluate_on_coset(coset);
+        let interpolated = CpuCirclePoly::interpolate_from_coset(coset, &values);
+
+        assert_eq!(interpolated.coeffs, poly.coeffs);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not contained in a circle domain")]
+    fn test_evaluate_on_subgroup_fails() {
+        let poly = CpuCirclePoly::new(vec![BaseField::from(1); 4]);
+
+        poly.evaluate_on_coset(Coset::subgroup(2));
+    }
+
+    #[test]
+    fn test_circle_poly_linear_ops() {
+        let rng = &mut SmallRng::seed_from_u64(0);
+        let a = random_poly(rng, 5);
+        let b = random_poly(rng, 3);
+        let alpha: BaseField = rng.gen();
+        let p = random_point();
+        let (a_p, b_p) = (a.eval_at_point(p), b.eval_at_point(p));
+
+        assert_eq!((a.clone() + b.clone()).eval_at_point(p), a_p + b_p);
+        assert_eq!((b.clone() - a.clone()).eval_at_point(p), b_p - a_p);
+        assert_eq!((-a.clone()).eval_at_point(p), -a_p);
+        assert_eq!((a * alpha).eval_at_point(p), a_p * alpha);
+    }
+
+    #[test]
+    fn test_circle_poly_mul() {
+        let rng = &mut SmallRng::seed_from_u64(0);
+        let a = random_poly(rng, 5);
+        let b = random_poly(rng, 4);
+        let p = random_point();
+
+        let product = &a * &b;
+
+        assert_eq!(product.log_size(), 6);
+        assert_eq!(
+            product.eval_at_point(p),
+            a.eval_at_point(p) * b.eval_at_point(p)
+        );
+    }
+
+    #[test]
+    fn test_circle_poly_div_coset_vanishing() {
+        let rng = &mut SmallRng::seed_from_u64(0);
+        let coset = CanonicCoset::new(3).coset();
+        let vanishing_domain = CanonicCoset::new(4).circle_domain();
+        let vanishing = CircleEvaluation::<CpuBackend, _, BitReversedOrder>::new(
+            vanishing_domain,
+            (0..vanishing_domain.size())
+                .map(|i| {
+                    let point = vanishing_domain.at(bit_reverse_index(i, 4));
+                    coset_vanishing(coset, point)
+                })
+                .collect(),
+        )
+        .interpolate();
+        let quotient = random_poly(rng, 4);
+        let p = random_point();
+        let poly = &vanishing * &quotient;
+
+        let res = poly.div_coset_vanishing(coset);
+
+        assert_eq!(res.eval_at_point(p), quotient.eval_at_point(p));
+        assert_eq!(
+            res.eval_at_point(p) * coset_vanishing(coset, p),
+            poly.eval_at_point(p)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "polynomial doesn't fit in log size 4")]
+    fn test_circle_poly_div_coset_vanishing_inexact_fails() {
+        let rng = &mut SmallRng::seed_from_u64(0);
+        let poly = random_poly(rng, 4);
+
+        poly.div_coset_vanishing(CanonicCoset::new(3).coset());
+    }
+
+    #[test]
+    fn test_circle_poly_div_point_vanishing() {
+        let rng = &mut SmallRng::seed_from_u64(0);
+        let poly = random_poly(rng, 4);
+        let vanish_point = M31_CIRCLE_GEN.mul(98765);
+        let value = poly.eval_at_point(vanish_point.into_ef()).0 .0;
+        let poly = poly - CpuCirclePoly::new(vec![value]);
+        let p = random_point();
+
+        let res = poly.div_point_vanishing(vanish_point);
+
+        assert_eq!(
+            res.eval_at_point(p) * point_vanishing(vanish_point, p),
+            poly.eval_at_point(p)
+        );
+    }
 }
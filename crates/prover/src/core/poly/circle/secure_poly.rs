@@ -1,16 +1,22 @@
+use std::iter::zip;
 use std::marker::PhantomData;
-use std::ops::{Deref, DerefMut};
+use std::ops::{Add, Deref, DerefMut, Mul, Neg, Sub};
 
-use super::{CircleDomain, CircleEvaluation, CirclePoly, PolyOps};
-use crate::core::backend::CpuBackend;
-use crate::core::circle::CirclePoint;
+use num_traits::{One, Zero};
+
+use super::{product_log_size, CanonicCoset, CircleDomain, CircleEvaluation, CirclePoly, PolyOps};
+use crate::core::backend::{Col, Column, CpuBackend};
+use crate::core::circle::{CirclePoint, Coset};
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
-use crate::core::fields::secure_column::{SecureColumnByCoords, SECURE_EXTENSION_DEGREE};
-use crate::core::fields::FieldOps;
+use crate::core::fields::secure_column::{
+    ExtensionColumn, SecureColumnByCoords, SECURE_EXTENSION_DEGREE,
+};
+use crate::core::fields::{FieldOps, SecureExtension};
 use crate::core::poly::twiddles::TwiddleTree;
 use crate::core::poly::BitReversedOrder;
 
+#[derive(Clone, Debug)]
 pub struct SecureCirclePoly<B: FieldOps<BaseField>>(pub [CirclePoly<B>; SECURE_EXTENSION_DEGREE]);
 
 impl<B: PolyOps> SecureCirclePoly<B> {
@@ -47,6 +53,179 @@ impl<B: PolyOps> SecureCirclePoly<B> {
     pub fn into_coordinate_polys(self) -> [CirclePoly<B>; SECURE_EXTENSION_DEGREE] {
         self.0
     }
+
+    /// Returns the largest log size among the coordinate polynomials.
+    fn max_log_size(&self) -> u32 {
+        self.0.iter().map(|p| p.log_size()).max().unwrap()
+    }
+
+    /// Divides each coordinate polynomial by the vanishing polynomial of `coset`.
+    ///
+    /// See [`CirclePoly::div_coset_vanishing`].
+    pub fn div_coset_vanishing(&self, coset: Coset) -> Self {
+        let twiddles = self.precompute_division_twiddles();
+        Self(
+            self.0
+                .each_ref()
+                .map(|p| p.div_coset_vanishing_with_twiddles(coset, &twiddles)),
+        )
+    }
+
+    /// Divides each coordinate polynomial by the vanishing polynomial of `point`.
+    ///
+    /// See [`CirclePoly::div_point_vanishing`].
+    pub fn div_point_vanishing(&self, point: CirclePoint<BaseField>) -> Self {
+        let twiddles = self.precompute_division_twiddles();
+        Self(
+            self.0
+                .each_ref()
+                .map(|p| p.div_point_vanishing_with_twiddles(point, &twiddles)),
+        )
+    }
+
+    /// Precomputes twiddles that can be used for the divisions of all the coordinate polynomials.
+    fn precompute_division_twiddles(&self) -> TwiddleTree<B> {
+        let largest = self.0.iter().max_by_key(|p| p.log_size()).unwrap();
+        largest.precompute_division_twiddles()
+    }
+}
+
+/// Returns the coordinates of `coeff * e_i` for each basis element `e_i` of [SecureField] over
+/// [BaseField], i.e. the matrix of multiplication by `coeff` (indexed `[i][j]`).
+fn mul_matrix(
+    coeff: SecureField,
+) -> [[BaseField; SECURE_EXTENSION_DEGREE]; SECURE_EXTENSION_DEGREE] {
+    std::array::from_fn(|i| (coeff * basis_element(i)).to_m31_array())
+}
+
+fn basis_element(i: usize) -> SecureField {
+    let mut coords = [BaseField::zero(); SECURE_EXTENSION_DEGREE];
+    coords[i] = BaseField::one();
+    SecureField::from_m31_array(coords)
+}
+
+/// Returns `sum_i scales[i] * columns[i]`.
+fn linear_combination<B: PolyOps>(
+    columns: &[&Col<B, BaseField>],
+    scales: impl IntoIterator<Item = BaseField>,
+) -> Col<B, BaseField> {
+    let mut res = Col::<B, BaseField>::zeros(columns[0].len());
+    for (column, scale) in zip(columns, scales) {
+        if !scale.is_zero() {
+            B::add_scaled_column(&mut res, column, scale);
+        }
+    }
+    res
+}
+
+impl<B: PolyOps> Add for SecureCirclePoly<B> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut rhs = rhs.0.into_iter();
+        Self(self.0.map(|p| p + rhs.next().unwrap()))
+    }
+}
+
+impl<B: PolyOps> Sub for SecureCirclePoly<B> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut rhs = rhs.0.into_iter();
+        Self(self.0.map(|p| p - rhs.next().unwrap()))
+    }
+}
+
+impl<B: PolyOps> Neg for SecureCirclePoly<B> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(self.0.map(|p| -p))
+    }
+}
+
+impl<B: PolyOps> Mul<BaseField> for SecureCirclePoly<B> {
+    type Output = Self;
+
+    fn mul(self, rhs: BaseField) -> Self::Output {
+        Self(self.0.map(|p| p * rhs))
+    }
+}
+
+impl<B: PolyOps> Mul<SecureField> for SecureCirclePoly<B> {
+    type Output = Self;
+
+    fn mul(self, rhs: SecureField) -> Self::Output {
+        let log_size = self.max_log_size();
+        let polys = self.0.map(|p| {
+            if p.log_size() < log_size {
+                p.extend(log_size)
+            } else {
+                p
+            }
+        });
+        let columns = polys.each_ref().map(|p| &p.coeffs);
+        let matrix = mul_matrix(rhs);
+        Self(std::array::from_fn(|j| {
+            CirclePoly::new(linear_combination::<B>(
+                &columns,
+                matrix.iter().map(|row| row[j]),
+            ))
+        }))
+    }
+}
+
+impl<B: PolyOps> Mul<&CirclePoly<B>> for &SecureCirclePoly<B> {
+    type Output = SecureCirclePoly<B>;
+
+    fn mul(self, rhs: &CirclePoly<B>) -> Self::Output {
+        let log_size = product_log_size(self.max_log_size(), rhs.log_size());
+        let twiddles = B::precompute_twiddles(CanonicCoset::new(log_size).half_coset());
+        SecureCirclePoly(
+            self.0
+                .each_ref()
+                .map(|p| p.mul_with_twiddles(rhs, &twiddles)),
+        )
+    }
+}
+
+impl<B: PolyOps> Mul for &SecureCirclePoly<B> {
+    type Output = SecureCirclePoly<B>;
+
+    /// Multiplies two polynomials by evaluating their coordinates on a large enough canonic
+    /// domain, combining the pointwise products of coordinates with the multiplication table of
+    /// [SecureField], and interpolating.
+    fn mul(self, rhs: Self) -> Self::Output {
+        let log_size = product_log_size(self.max_log_size(), rhs.max_log_size());
+        let domain = CanonicCoset::new(log_size).circle_domain();
+        let twiddles = B::precompute_twiddles(domain.half_coset);
+        let lhs_evals = self
+            .evaluate_with_twiddles(domain, &twiddles)
+            .values
+            .columns;
+        let rhs_evals = rhs.evaluate_with_twiddles(domain, &twiddles).values.columns;
+
+        // `products[i * 4 + k]` holds the evaluations of `lhs_i * rhs_k`.
+        let products = (0..SECURE_EXTENSION_DEGREE)
+            .flat_map(|i| (0..SECURE_EXTENSION_DEGREE).map(move |k| (i, k)))
+            .map(|(i, k)| {
+                let mut product = lhs_evals[i].clone();
+                B::mul_column(&mut product, &rhs_evals[k]);
+                product
+            })
+            .collect::<Vec<_>>();
+        let product_refs = products.iter().collect::<Vec<_>>();
+        // `table[i * 4 + k]` holds the coordinates of `e_i * e_k`.
+        let table = (0..SECURE_EXTENSION_DEGREE)
+            .flat_map(|i| mul_matrix(basis_element(i)))
+            .collect::<Vec<_>>();
+
+        let columns = std::array::from_fn(|j| {
+            linear_combination::<B>(&product_refs, table.iter().map(|coords| coords[j]))
+        });
+        SecureEvaluation::<B, BitReversedOrder>::new(domain, SecureColumnByCoords { columns })
+            .interpolate_with_twiddles(&twiddles)
+    }
 }
 
 impl<B: FieldOps<BaseField>> Deref for SecureCirclePoly<B> {
@@ -57,19 +236,20 @@ impl<B: FieldOps<BaseField>> Deref for SecureCirclePoly<B> {
     }
 }
 
-/// A [`SecureField`] evaluation defined on a [CircleDomain].
+/// A [`SecureField`] evaluation defined on a [CircleDomain], or an evaluation over another
+/// [SecureExtension] `E`.
 ///
-/// The evaluation is stored as a column major array of [`SECURE_EXTENSION_DEGREE`] many base field
-/// evaluations. The evaluations are ordered according to the [CircleDomain] ordering.
-#[derive(Clone)]
-pub struct SecureEvaluation<B: FieldOps<BaseField>, EvalOrder> {
+/// The evaluation is stored as a column major array of [`SECURE_EXTENSION_DEGREE`] (or
+/// `E::EXTENSION_DEGREE`) many base field evaluations. The evaluations are ordered according to the
+/// [CircleDomain] ordering.
+pub struct SecureEvaluation<B: FieldOps<BaseField>, EvalOrder, E: SecureExtension = SecureField> {
     pub domain: CircleDomain,
-    pub values: SecureColumnByCoords<B>,
+    pub values: E::Column<B>,
     _eval_order: PhantomData<EvalOrder>,
 }
 
-impl<B: FieldOps<BaseField>, EvalOrder> SecureEvaluation<B, EvalOrder> {
-    pub fn new(domain: CircleDomain, values: SecureColumnByCoords<B>) -> Self {
+impl<B: FieldOps<BaseField>, EvalOrder, E: SecureExtension> SecureEvaluation<B, EvalOrder, E> {
+    pub fn new(domain: CircleDomain, values: E::Column<B>) -> Self {
         assert_eq!(domain.size(), values.len());
         Self {
             domain,
@@ -77,7 +257,18 @@ impl<B: FieldOps<BaseField>, EvalOrder> SecureEvaluation<B, EvalOrder> {
             _eval_order: PhantomData,
         }
     }
+}
+
+// Implemented by hand, as deriving would require the backend itself to be `Clone`.
+impl<B: FieldOps<BaseField>, EvalOrder, E: SecureExtension> Clone
+    for SecureEvaluation<B, EvalOrder, E>
+{
+    fn clone(&self) -> Self {
+        Self::new(self.domain, self.values.clone())
+    }
+}
 
+impl<B: FieldOps<BaseField>, EvalOrder> SecureEvaluation<B, EvalOrder> {
     pub fn into_coordinate_evals(
         self,
     ) -> [CircleEvaluation<B, BaseField, EvalOrder>; SECURE_EXTENSION_DEGREE] {
@@ -94,15 +285,19 @@ impl<B: FieldOps<BaseField>, EvalOrder> SecureEvaluation<B, EvalOrder> {
     }
 }
 
-impl<B: FieldOps<BaseField>, EvalOrder> Deref for SecureEvaluation<B, EvalOrder> {
-    type Target = SecureColumnByCoords<B>;
+impl<B: FieldOps<BaseField>, EvalOrder, E: SecureExtension> Deref
+    for SecureEvaluation<B, EvalOrder, E>
+{
+    type Target = E::Column<B>;
 
     fn deref(&self) -> &Self::Target {
         &self.values
     }
 }
 
-impl<B: FieldOps<BaseField>, EvalOrder> DerefMut for SecureEvaluation<B, EvalOrder> {
+impl<B: FieldOps<BaseField>, EvalOrder, E: SecureExtension> DerefMut
+    for SecureEvaluation<B, EvalOrder, E>
+{
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.values
     }
@@ -128,3 +323,61 @@ impl<EvalOrder> From<CircleEvaluation<CpuBackend, SecureField, EvalOrder>>
         Self::new(evaluation.domain, evaluation.values.into_iter().collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::SecureCirclePoly;
+    use crate::core::backend::cpu::CpuCirclePoly;
+    use crate::core::backend::CpuBackend;
+    use crate::core::circle::{CirclePoint, SECURE_FIELD_CIRCLE_GEN};
+    use crate::core::fields::m31::BaseField;
+    use crate::core::fields::qm31::SecureField;
+
+    fn random_poly(rng: &mut SmallRng, log_size: u32) -> SecureCirclePoly<CpuBackend> {
+        SecureCirclePoly(std::array::from_fn(|_| {
+            CpuCirclePoly::new((0..1 << log_size).map(|_| rng.gen()).collect())
+        }))
+    }
+
+    fn random_point() -> CirclePoint<SecureField> {
+        SECURE_FIELD_CIRCLE_GEN.mul(21903)
+    }
+
+    #[test]
+    fn test_secure_circle_poly_linear_ops() {
+        let rng = &mut SmallRng::seed_from_u64(0);
+        let a = random_poly(rng, 5);
+        let b = random_poly(rng, 3);
+        let alpha: BaseField = rng.gen();
+        let beta: SecureField = rng.gen();
+        let p = random_point();
+        let (a_p, b_p) = (a.eval_at_point(p), b.eval_at_point(p));
+
+        assert_eq!((a.clone() + b.clone()).eval_at_point(p), a_p + b_p);
+        assert_eq!((b.clone() - a.clone()).eval_at_point(p), b_p - a_p);
+        assert_eq!((-a.clone()).eval_at_point(p), -a_p);
+        assert_eq!((a.clone() * alpha).eval_at_point(p), a_p * alpha);
+        assert_eq!((a * beta).eval_at_point(p), a_p * beta);
+    }
+
+    #[test]
+    fn test_secure_circle_poly_mul() {
+        let rng = &mut SmallRng::seed_from_u64(0);
+        let a = random_poly(rng, 5);
+        let b = random_poly(rng, 4);
+        let c = CpuCirclePoly::new((0..1 << 3).map(|_| rng.gen()).collect());
+        let p = random_point();
+
+        assert_eq!(
+            (&a * &b).eval_at_point(p),
+            a.eval_at_point(p) * b.eval_at_point(p)
+        );
+        assert_eq!(
+            (&a * &c).eval_at_point(p),
+            a.eval_at_point(p) * c.eval_at_point(p)
+        );
+    }
+}
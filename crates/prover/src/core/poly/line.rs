@@ -1,21 +1,21 @@
 use std::cmp::Ordering;
 use std::fmt::Debug;
-use std::iter::Map;
-use std::ops::{Deref, DerefMut};
+use std::iter::{zip, Map};
+use std::ops::{Add, Deref, DerefMut, Mul, Neg, Sub};
 
-use itertools::Itertools;
 use num_traits::Zero;
 use serde::{Deserialize, Serialize};
 
-use super::circle::CircleDomain;
+use super::circle::{CircleDomain, PolyOps};
+use super::twiddles::TwiddleTree;
 use super::utils::fold;
-use crate::core::backend::{ColumnOps, CpuBackend};
+use crate::core::backend::CpuBackend;
 use crate::core::circle::{CirclePoint, Coset, CosetIterator};
-use crate::core::fft::ibutterfly;
+use crate::core::fft::{butterfly, ibutterfly};
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
-use crate::core::fields::secure_column::SecureColumnByCoords;
-use crate::core::fields::{ExtensionOf, FieldExpOps, FieldOps};
+use crate::core::fields::secure_column::ExtensionColumn;
+use crate::core::fields::{ExtensionOf, FieldOps, SecureExtension};
 use crate::core::utils::bit_reverse;
 
 /// Domain comprising of the x-coordinates of points in a [Coset].
@@ -107,31 +107,31 @@ impl From<CircleDomain> for LineDomain {
 type LineDomainIterator =
     Map<CosetIterator<CirclePoint<BaseField>>, fn(CirclePoint<BaseField>) -> BaseField>;
 
-/// A univariate polynomial defined on a [LineDomain].
+/// A univariate polynomial defined on a [LineDomain], with coefficients in a [SecureExtension].
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
-pub struct LinePoly {
+pub struct LinePoly<E: SecureExtension = SecureField> {
     /// Coefficients of the polynomial in [line_ifft] algorithm's basis.
     ///
     /// The coefficients are stored in bit-reversed order.
-    coeffs: Vec<SecureField>,
+    coeffs: Vec<E>,
     /// The number of coefficients stored as `log2(len(coeffs))`.
     log_size: u32,
 }
 
-impl LinePoly {
+impl<E: SecureExtension> LinePoly<E> {
     /// Creates a new line polynomial from bit reversed coefficients.
     ///
     /// # Panics
     ///
     /// Panics if the number of coefficients is not a power of two.
-    pub fn new(coeffs: Vec<SecureField>) -> Self {
+    pub fn new(coeffs: Vec<E>) -> Self {
         assert!(coeffs.len().is_power_of_two());
         let log_size = coeffs.len().ilog2();
         Self { coeffs, log_size }
     }
 
     /// Evaluates the polynomial at a single point.
-    pub fn eval_at_point(&self, mut x: SecureField) -> SecureField {
+    pub fn eval_at_point(&self, mut x: E) -> E {
         let mut doublings = Vec::new();
         for _ in 0..self.log_size {
             doublings.push(x);
@@ -150,7 +150,7 @@ impl LinePoly {
     }
 
     /// Returns the polynomial's coefficients in their natural order.
-    pub fn into_ordered_coefficients(mut self) -> Vec<SecureField> {
+    pub fn into_ordered_coefficients(mut self) -> Vec<E> {
         bit_reverse(&mut self.coeffs);
         self.coeffs
     }
@@ -160,22 +160,135 @@ impl LinePoly {
     /// # Panics
     ///
     /// Panics if the number of coefficients is not a power of two.
-    pub fn from_ordered_coefficients(mut coeffs: Vec<SecureField>) -> Self {
+    pub fn from_ordered_coefficients(mut coeffs: Vec<E>) -> Self {
         bit_reverse(&mut coeffs);
         Self::new(coeffs)
     }
+
+    /// Returns the log of the number of coefficients.
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    /// Extends the polynomial to a larger number of coefficients by padding with zeros.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `log_size` is smaller than the polynomial's log size.
+    pub fn extend(&self, log_size: u32) -> Self {
+        assert!(log_size >= self.log_size);
+        let mut coeffs = self.clone().into_ordered_coefficients();
+        coeffs.resize(1 << log_size, E::zero());
+        Self::from_ordered_coefficients(coeffs)
+    }
+
+    /// Evaluates the polynomial over `domain` on backend `B`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the domain is smaller than the number of coefficients.
+    pub fn evaluate<B: LineOps<E>>(&self, domain: LineDomain) -> LineEvaluation<B, E> {
+        self.evaluate_with_twiddles(domain, &B::precompute_twiddles(domain.coset()))
+    }
+
+    /// Evaluates the polynomial over `domain` on backend `B`, using precomputed twiddles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the domain is smaller than the number of coefficients, or if the twiddles don't
+    /// cover the domain (see [TwiddleTree::covers_coset]).
+    pub fn evaluate_with_twiddles<B: LineOps<E>>(
+        &self,
+        domain: LineDomain,
+        twiddles: &TwiddleTree<B>,
+    ) -> LineEvaluation<B, E> {
+        let poly = self.extend(domain.log_size());
+        B::evaluate_line(E::Column::<B>::from_values(&poly), domain, twiddles)
+    }
+}
+
+impl Add for LinePoly {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let log_size = self.log_size.max(rhs.log_size);
+        let mut res = self.extend(log_size);
+        zip(&mut res.coeffs, rhs.extend(log_size).coeffs).for_each(|(a, b)| *a += b);
+        res
+    }
 }
 
-impl Deref for LinePoly {
-    type Target = [SecureField];
+impl Sub for LinePoly {
+    type Output = Self;
 
-    fn deref(&self) -> &[SecureField] {
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Neg for LinePoly {
+    type Output = Self;
+
+    fn neg(mut self) -> Self::Output {
+        self.coeffs.iter_mut().for_each(|c| *c = -*c);
+        self
+    }
+}
+
+impl Mul<SecureField> for LinePoly {
+    type Output = Self;
+
+    fn mul(mut self, rhs: SecureField) -> Self::Output {
+        self.coeffs.iter_mut().for_each(|c| *c *= rhs);
+        self
+    }
+}
+
+impl Mul<BaseField> for LinePoly {
+    type Output = Self;
+
+    fn mul(mut self, rhs: BaseField) -> Self::Output {
+        self.coeffs.iter_mut().for_each(|c| *c *= rhs);
+        self
+    }
+}
+
+impl Mul for &LinePoly {
+    type Output = LinePoly;
+
+    /// Multiplies two polynomials by evaluating them on a large enough [LineDomain] and
+    /// interpolating the pointwise product.
+    fn mul(self, rhs: Self) -> Self::Output {
+        // A polynomial with `n` coefficients has degree less than `n` in `x`.
+        let log_size = (self.len() + rhs.len() - 1).next_power_of_two().ilog2();
+        let domain = LineDomain::new(Coset::half_odds(log_size));
+        let twiddles = CpuBackend::precompute_twiddles(domain.coset());
+        let lhs_values = self
+            .evaluate_with_twiddles(domain, &twiddles)
+            .values
+            .to_vec();
+        let rhs_values = rhs
+            .evaluate_with_twiddles(domain, &twiddles)
+            .values
+            .to_vec();
+        let values = zip(lhs_values, rhs_values).map(|(a, b)| a * b).collect();
+        let coeffs = LineEvaluation::<CpuBackend>::new(domain, values)
+            .interpolate_with_twiddles(&twiddles)
+            .to_vec();
+        LinePoly::new(coeffs)
+    }
+}
+
+impl<E: SecureExtension> Deref for LinePoly<E> {
+    type Target = [E];
+
+    fn deref(&self) -> &[E] {
         &self.coeffs
     }
 }
 
-impl DerefMut for LinePoly {
-    fn deref_mut(&mut self) -> &mut [SecureField] {
+impl<E: SecureExtension> DerefMut for LinePoly<E> {
+    fn deref_mut(&mut self) -> &mut [E] {
         &mut self.coeffs
     }
 }
@@ -184,26 +297,25 @@ impl DerefMut for LinePoly {
 // TODO(andrew): Remove EvalOrder. Bit-reversed evals are only necessary since LineEvaluation is
 // only used by FRI where evaluations are in bit-reversed order.
 // TODO(andrew): Remove pub.
-#[derive(Clone, Debug)]
-pub struct LineEvaluation<B: FieldOps<BaseField>> {
+pub struct LineEvaluation<B: FieldOps<BaseField>, E: SecureExtension = SecureField> {
     /// Evaluations of a univariate polynomial on `domain`.
-    pub values: SecureColumnByCoords<B>,
+    pub values: E::Column<B>,
     domain: LineDomain,
 }
 
-impl<B: FieldOps<BaseField>> LineEvaluation<B> {
+impl<B: FieldOps<BaseField>, E: SecureExtension> LineEvaluation<B, E> {
     /// Creates new [LineEvaluation] from a set of polynomial evaluations over a [LineDomain].
     ///
     /// # Panics
     ///
     /// Panics if the number of evaluations does not match the size of the domain.
-    pub fn new(domain: LineDomain, values: SecureColumnByCoords<B>) -> Self {
+    pub fn new(domain: LineDomain, values: E::Column<B>) -> Self {
         assert_eq!(values.len(), domain.size());
         Self { values, domain }
     }
 
     pub fn new_zero(domain: LineDomain) -> Self {
-        Self::new(domain, SecureColumnByCoords::zeros(domain.size()))
+        Self::new(domain, E::Column::<B>::zeros(domain.size()))
     }
 
     /// Returns the number of evaluations.
@@ -215,33 +327,83 @@ impl<B: FieldOps<BaseField>> LineEvaluation<B> {
     pub fn domain(&self) -> LineDomain {
         self.domain
     }
+}
+
+// Implemented by hand, as deriving would require the backend itself to be `Clone` and `Debug`.
+impl<B: FieldOps<BaseField>, E: SecureExtension> Clone for LineEvaluation<B, E> {
+    fn clone(&self) -> Self {
+        Self::new(self.domain, self.values.clone())
+    }
+}
+
+impl<B: FieldOps<BaseField>, E: SecureExtension> Debug for LineEvaluation<B, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LineEvaluation")
+            .field("values", &self.values)
+            .field("domain", &self.domain)
+            .finish()
+    }
+}
 
+impl<B: FieldOps<BaseField>> LineEvaluation<B> {
     /// Clones the values into a new line evaluation in the CPU.
     pub fn to_cpu(&self) -> LineEvaluation<CpuBackend> {
         LineEvaluation::new(self.domain, self.values.to_cpu())
     }
 }
 
-impl LineEvaluation<CpuBackend> {
+impl<B: LineOps<E>, E: SecureExtension> LineEvaluation<B, E> {
+    /// Interpolates the polynomial as evaluations on `domain`, using precomputed twiddles.
+    ///
+    /// Returns the coefficients laid out as in [LinePoly], on the evaluation's backend.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the twiddles don't cover the domain (see [TwiddleTree::covers_coset]).
+    pub fn interpolate_with_twiddles(self, twiddles: &TwiddleTree<B>) -> E::Column<B> {
+        B::interpolate_line(self, twiddles)
+    }
+}
+
+impl<E: SecureExtension> LineEvaluation<CpuBackend, E> {
     /// Interpolates the polynomial as evaluations on `domain`.
-    pub fn interpolate(self) -> LinePoly {
-        let mut values = self.values.into_iter().collect_vec();
-        CpuBackend::bit_reverse_column(&mut values);
-        line_ifft(&mut values, self.domain);
-        // Normalize the coefficients.
-        let len_inv = BaseField::from(values.len()).inverse();
-        values.iter_mut().for_each(|v| *v *= len_inv);
-        LinePoly::new(values)
+    pub fn interpolate(self) -> LinePoly<E> {
+        let twiddles = CpuBackend::precompute_twiddles(self.domain.coset());
+        LinePoly::new(self.interpolate_with_twiddles(&twiddles).to_vec())
     }
 }
 
+/// Operations on univariate polynomials over a [LineDomain].
+///
+/// The twiddles of a [LineDomain] are those of its coset (see [TwiddleTree::covers_coset]).
+pub trait LineOps<E: SecureExtension = SecureField>: PolyOps {
+    /// Interpolates a polynomial from its evaluations over a [LineDomain].
+    ///
+    /// Returns the normalized coefficients in the line IFFT algorithm's basis, stored in
+    /// bit-reversed order (the same layout as [LinePoly]).
+    fn interpolate_line(
+        eval: LineEvaluation<Self, E>,
+        twiddles: &TwiddleTree<Self>,
+    ) -> E::Column<Self>;
+
+    /// Evaluates a polynomial over `domain`.
+    ///
+    /// `coeffs` are laid out as in [LinePoly] and their number must match the size of the domain.
+    fn evaluate_line(
+        coeffs: E::Column<Self>,
+        domain: LineDomain,
+        twiddles: &TwiddleTree<Self>,
+    ) -> LineEvaluation<Self, E>;
+}
+
 /// Performs a univariate IFFT on a polynomial's evaluation over a [LineDomain].
 ///
 /// This is not the standard univariate IFFT, because [LineDomain] is not a cyclic group.
 ///
-/// The transform happens in-place. `values` should be the evaluations of a polynomial over `domain`
-/// in their natural order. After the transformation `values` becomes the coefficients of the
-/// polynomial stored in bit-reversed order.
+/// The transform happens in-place. `values` should be the evaluations of a polynomial over the
+/// domain in bit-reversed order, and `itwiddles` the inverse twiddles of each layer of the domain,
+/// as returned by [domain_line_twiddles_from_tree]. After the transformation `values` becomes the
+/// coefficients of the polynomial stored in bit-reversed order.
 ///
 /// For performance reasons and flexibility the normalization of the coefficients is omitted. The
 /// normalized coefficients can be obtained by scaling all coefficients by `1 / len(values)`.
@@ -257,17 +419,45 @@ impl LineEvaluation<CpuBackend> {
 ///
 /// # Panics
 ///
-/// Panics if the number of values doesn't match the size of the domain.
-fn line_ifft<F: ExtensionOf<BaseField> + Copy>(values: &mut [F], mut domain: LineDomain) {
-    assert_eq!(values.len(), domain.size());
-    while domain.size() > 1 {
-        for chunk in values.chunks_exact_mut(domain.size()) {
-            let (l, r) = chunk.split_at_mut(domain.size() / 2);
-            for (i, x) in domain.iter().take(domain.size() / 2).enumerate() {
-                ibutterfly(&mut l[i], &mut r[i], x.inverse());
-            }
+/// Panics if the number of values doesn't match the number of twiddles.
+///
+/// [domain_line_twiddles_from_tree]: super::utils::domain_line_twiddles_from_tree
+pub(crate) fn line_ifft<F: ExtensionOf<BaseField> + Copy>(
+    values: &mut [F],
+    itwiddles: &[impl AsRef<[BaseField]>],
+) {
+    assert_eq!(values.len(), 1 << itwiddles.len());
+    for layer_itwiddles in itwiddles.iter().map(AsRef::as_ref) {
+        let half_size = values.len() / (2 * layer_itwiddles.len());
+        for (chunk, &itwid) in zip(values.chunks_exact_mut(2 * half_size), layer_itwiddles) {
+            let (l, r) = chunk.split_at_mut(half_size);
+            zip(l, r).for_each(|(v0, v1)| ibutterfly(v0, v1, itwid));
+        }
+    }
+    bit_reverse(values);
+}
+
+/// Performs a univariate FFT on a polynomial's coefficients over a [LineDomain].
+///
+/// This is the inverse of [line_ifft], up to the omitted normalization: `values` should be the
+/// coefficients of a polynomial stored in bit-reversed order, and after the transformation `values`
+/// becomes the evaluations of the polynomial over the domain in bit-reversed order.
+///
+/// # Panics
+///
+/// Panics if the number of values doesn't match the number of twiddles.
+pub(crate) fn line_fft<F: ExtensionOf<BaseField> + Copy>(
+    values: &mut [F],
+    twiddles: &[impl AsRef<[BaseField]>],
+) {
+    assert_eq!(values.len(), 1 << twiddles.len());
+    bit_reverse(values);
+    for layer_twiddles in twiddles.iter().rev().map(AsRef::as_ref) {
+        let half_size = values.len() / (2 * layer_twiddles.len());
+        for (chunk, &twid) in zip(values.chunks_exact_mut(2 * half_size), layer_twiddles) {
+            let (l, r) = chunk.split_at_mut(half_size);
+            zip(l, r).for_each(|(v0, v1)| butterfly(v0, v1, twid));
         }
-        domain = domain.double();
     }
 }
 
@@ -276,11 +466,14 @@ mod tests {
     type B = CpuBackend;
 
     use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
 
     use super::LineDomain;
     use crate::core::backend::{ColumnOps, CpuBackend};
     use crate::core::circle::{CirclePoint, Coset};
     use crate::core::fields::m31::BaseField;
+    use crate::core::fields::qm31::SecureField;
     use crate::core::poly::line::{LineEvaluation, LinePoly};
     use crate::core::utils::bit_reverse_index;
 
@@ -405,4 +598,40 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn line_polynomial_evaluate_matches_eval_at_point() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let poly = LinePoly::<SecureField>::new((0..8).map(|_| rng.gen()).collect());
+        let domain = LineDomain::new(Coset::half_odds(5));
+
+        let evals = poly.evaluate::<B>(domain);
+
+        for (i, x) in domain.iter().enumerate() {
+            assert_eq!(
+                evals.values.at(bit_reverse_index(i, domain.log_size())),
+                poly.eval_at_point(x.into()),
+                "mismatch at {i}"
+            );
+        }
+        assert_eq!(evals.interpolate(), poly.extend(5));
+    }
+
+    #[test]
+    fn line_polynomial_arithmetic() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let a = LinePoly::new((0..8).map(|_| rng.gen()).collect());
+        let b = LinePoly::new((0..4).map(|_| rng.gen()).collect());
+        let alpha: SecureField = rng.gen();
+        let x: SecureField = rng.gen();
+        let (a_x, b_x) = (a.eval_at_point(x), b.eval_at_point(x));
+
+        assert_eq!((a.clone() + b.clone()).eval_at_point(x), a_x + b_x);
+        assert_eq!((a.clone() - b.clone()).eval_at_point(x), a_x - b_x);
+        assert_eq!((-a.clone()).eval_at_point(x), -a_x);
+        assert_eq!((a.clone() * alpha).eval_at_point(x), a_x * alpha);
+        let product = &a * &b;
+        assert_eq!(product.log_size(), 4);
+        assert_eq!(product.eval_at_point(x), a_x * b_x);
+    }
 }
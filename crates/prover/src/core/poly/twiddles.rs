@@ -1,4 +1,6 @@
-use super::circle::PolyOps;
+use std::sync::{Arc, RwLock};
+
+use super::circle::{CircleDomain, PolyOps};
 use crate::core::circle::Coset;
 
 /// Precomputed twiddles for a specific coset tower.
@@ -11,3 +13,148 @@ pub struct TwiddleTree<B: PolyOps> {
     pub twiddles: B::Twiddles,
     pub itwiddles: B::Twiddles,
 }
+
+impl<B: PolyOps> TwiddleTree<B> {
+    /// Returns true if the twiddles can be used to evaluate and interpolate on `domain`.
+    pub fn covers(&self, domain: CircleDomain) -> bool {
+        self.covers_coset(domain.half_coset)
+    }
+
+    /// Returns true if `coset` is in the coset tower of the twiddles, e.g. the coset of a
+    /// [LineDomain] that can be ffted using these twiddles.
+    ///
+    /// [LineDomain]: super::line::LineDomain
+    pub fn covers_coset(&self, coset: Coset) -> bool {
+        coset.is_doubling_of(self.root_coset)
+    }
+}
+
+/// A thread-safe cache of [TwiddleTree]s, which computes twiddles on demand.
+///
+/// The cache holds at most one tree per coset tower. When twiddles are requested for a coset that
+/// is not covered by any cached tree, the twiddles of that coset are computed, and replace the
+/// cached trees they cover. Requesting twiddles for the largest domain first therefore avoids
+/// recomputations.
+///
+/// Clones of the cache share the same trees, so a single cache can serve many proofs.
+pub struct TwiddleCache<B: PolyOps> {
+    trees: Arc<RwLock<Vec<Arc<TwiddleTree<B>>>>>,
+}
+
+impl<B: PolyOps> TwiddleCache<B> {
+    pub fn new() -> Self {
+        Self {
+            trees: Arc::default(),
+        }
+    }
+
+    /// Returns twiddles for a coset tower containing `coset`, computing them if needed.
+    pub fn get(&self, coset: Coset) -> Arc<TwiddleTree<B>> {
+        if let Some(tree) = self.find(coset) {
+            return tree;
+        }
+        let tree = Arc::new(B::precompute_twiddles(coset));
+        let mut trees = self.trees.write().unwrap();
+        // Another thread might have computed covering twiddles in the meantime.
+        if let Some(tree) = trees
+            .iter()
+            .find(|tree| coset.is_doubling_of(tree.root_coset))
+        {
+            return tree.clone();
+        }
+        trees.retain(|cached| !cached.root_coset.is_doubling_of(coset));
+        trees.push(tree.clone());
+        tree
+    }
+
+    /// Returns twiddles that can be used to evaluate and interpolate on `domain`, computing them
+    /// if needed.
+    pub fn get_for_domain(&self, domain: CircleDomain) -> Arc<TwiddleTree<B>> {
+        self.get(domain.half_coset)
+    }
+
+    /// Returns the cached twiddles for a coset tower containing `coset`, if there are any.
+    pub fn find(&self, coset: Coset) -> Option<Arc<TwiddleTree<B>>> {
+        let trees = self.trees.read().unwrap();
+        trees
+            .iter()
+            .find(|tree| coset.is_doubling_of(tree.root_coset))
+            .cloned()
+    }
+
+    /// Returns the number of cached trees.
+    pub fn len(&self) -> usize {
+        self.trees.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<B: PolyOps> Clone for TwiddleCache<B> {
+    fn clone(&self) -> Self {
+        Self {
+            trees: self.trees.clone(),
+        }
+    }
+}
+
+impl<B: PolyOps> Default for TwiddleCache<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: PolyOps> From<Arc<TwiddleTree<B>>> for TwiddleCache<B> {
+    fn from(tree: Arc<TwiddleTree<B>>) -> Self {
+        Self {
+            trees: Arc::new(RwLock::new(vec![tree])),
+        }
+    }
+}
+
+impl<B: PolyOps> From<TwiddleTree<B>> for TwiddleCache<B> {
+    fn from(tree: TwiddleTree<B>) -> Self {
+        Arc::new(tree).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::TwiddleCache;
+    use crate::core::backend::CpuBackend;
+    use crate::core::circle::CirclePointIndex;
+    use crate::core::poly::circle::CanonicCoset;
+
+    #[test]
+    fn test_twiddle_cache_grows_on_demand() {
+        let cache = TwiddleCache::<CpuBackend>::new();
+        let small = CanonicCoset::new(4).circle_domain();
+        let large = CanonicCoset::new(6).circle_domain();
+
+        let small_tree = cache.get_for_domain(small);
+        let large_tree = cache.get_for_domain(large);
+
+        assert!(small_tree.covers(small));
+        assert!(large_tree.covers(large));
+        assert_eq!(cache.len(), 1);
+        assert!(Arc::ptr_eq(&cache.get_for_domain(small), &large_tree));
+    }
+
+    #[test]
+    fn test_twiddle_cache_keeps_distinct_towers() {
+        let cache = TwiddleCache::<CpuBackend>::new();
+        let domain = CanonicCoset::new(4).circle_domain();
+        let shifted = domain.shift(CirclePointIndex::generator());
+
+        let tree = cache.get_for_domain(domain);
+        let shifted_tree = cache.clone().get_for_domain(shifted);
+
+        assert!(shifted_tree.covers(shifted));
+        assert_eq!(cache.len(), 2);
+        assert!(Arc::ptr_eq(&cache.get_for_domain(domain), &tree));
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::mem;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::{StarkProof, StarkProofSizeBreakdown, VerificationError};
+use crate::core::vcs::ops::MerkleHasher;
+
+/// A hash removed from a hash witness because it repeats an earlier hash of the proof:
+/// its position in the witness, and the index of the hash it repeats among the distinct hashes
+/// sent so far.
+type RepeatedHash = (u32, u32);
+
+/// A column of queried values removed because it repeats an earlier column of the proof: its index
+/// among the queried columns of all the trees, and the index of the column it repeats.
+type RepeatedColumn = (u32, u32);
+
+/// A [StarkProof] where the hashes of the Merkle decommitments and the queried values of identical
+/// columns are sent once.
+///
+/// Only exact repeats are removed. Hashes repeat whenever the same subtree is committed twice, e.g.
+/// on columns of constant or padded values, within a tree, across trees and across FRI layers.
+/// Queried values repeat whenever the same column is committed twice, in the same tree or in
+/// different trees. Trees with the same column sizes are queried at the same positions, but their
+/// paths are only shared where their hashes are equal. The hashes the verifier can derive, those on
+/// the query paths and above the Merkle caps, are already left out of the decommitments.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompressedStarkProof<H: MerkleHasher> {
+    /// The proof, without the repeated hashes.
+    pub proof: StarkProof<H>,
+    /// The hashes removed from the hash witness of each decommitment of the proof, in
+    /// order: the commitment scheme trees, then the FRI layers.
+    pub repeated_hashes: Vec<Vec<RepeatedHash>>,
+    /// The queried columns removed from the proof, in increasing order. They are left empty.
+    pub repeated_columns: Vec<RepeatedColumn>,
+}
+
+impl<H: MerkleHasher> StarkProof<H> {
+    /// Compresses the proof, by removing the hashes of its decommitments and the queried columns
+    /// that repeat an earlier one.
+    pub fn compress(mut self) -> CompressedStarkProof<H> {
+        // The index of each distinct hash, in the order they are sent.
+        let mut distinct_hashes = HashMap::new();
+        let repeated_hashes = self
+            .hash_witnesses_mut()
+            .map(|hashes| {
+                let mut repeated_hashes = vec![];
+                *hashes = (mem::take(hashes).into_iter().enumerate())
+                    .filter_map(|(position, hash)| {
+                        let n_distinct_hashes = distinct_hashes.len() as u32;
+                        match distinct_hashes.entry(hash) {
+                            Entry::Occupied(index) => {
+                                repeated_hashes.push((position as u32, *index.get()));
+                                None
+                            }
+                            Entry::Vacant(index) => {
+                                index.insert(n_distinct_hashes);
+                                Some(hash)
+                            }
+                        }
+                    })
+                    .collect();
+                repeated_hashes
+            })
+            .collect();
+
+        // The index of the first column with each queried values.
+        let queried_values = &mut self.commitment_scheme_proof.queried_values;
+        let mut first_columns = HashMap::new();
+        let repeated_columns = (queried_values.iter().flatten().enumerate())
+            .filter(|(_, column)| !column.is_empty())
+            .filter_map(|(index, column)| {
+                let first = *first_columns.entry(column).or_insert(index as u32);
+                (first != index as u32).then_some((index as u32, first))
+            })
+            .collect_vec();
+        let mut columns = queried_values.iter_mut().flatten().collect_vec();
+        for &(index, _) in &repeated_columns {
+            columns[index as usize].clear();
+        }
+
+        CompressedStarkProof {
+            proof: self,
+            repeated_hashes,
+            repeated_columns,
+        }
+    }
+
+    /// Returns the hash witnesses of the decommitments, in the order of
+    /// [CompressedStarkProof::repeated_hashes].
+    fn hash_witnesses_mut(&mut self) -> impl Iterator<Item = &mut Vec<H::Hash>> {
+        let commitment_scheme_proof = &mut self.commitment_scheme_proof;
+        commitment_scheme_proof
+            .decommitments
+            .iter_mut()
+            .chain(
+                commitment_scheme_proof
+                    .fri_proof
+                    .inner_layers
+                    .iter_mut()
+                    .map(|layer| &mut layer.decommitment),
+            )
+            .map(|decommitment| &mut decommitment.hash_witness)
+    }
+}
+
+impl<H: MerkleHasher> CompressedStarkProof<H> {
+    /// Restores the removed hashes of the proof.
+    ///
+    /// # Errors
+    ///
+    /// Returns [VerificationError::InvalidStructure] if the repeated hashes don't match the
+    /// decommitments of the proof, or the repeated columns don't match its queried values.
+    pub fn decompress(self) -> Result<StarkProof<H>, VerificationError> {
+        let Self {
+            mut proof,
+            repeated_hashes,
+            repeated_columns,
+        } = self;
+        let invalid_structure =
+            || VerificationError::InvalidStructure("Invalid repeated hashes".to_string());
+
+        let mut repeated_hashes = repeated_hashes.into_iter();
+        let mut distinct_hashes = vec![];
+        for hashes in proof.hash_witnesses_mut() {
+            let mut repeated_hashes = repeated_hashes
+                .next()
+                .ok_or_else(invalid_structure)?
+                .into_iter()
+                .peekable();
+            let mut sent_hashes = mem::take(hashes).into_iter();
+            for position in 0..sent_hashes.len() + repeated_hashes.len() {
+                let hash = match repeated_hashes.next_if(|&(p, _)| p as usize == position) {
+                    Some((_, index)) => *distinct_hashes
+                        .get(index as usize)
+                        .ok_or_else(invalid_structure)?,
+                    None => {
+                        let hash = sent_hashes.next().ok_or_else(invalid_structure)?;
+                        distinct_hashes.push(hash);
+                        hash
+                    }
+                };
+                hashes.push(hash);
+            }
+            if repeated_hashes.next().is_some() {
+                return Err(invalid_structure());
+            }
+        }
+        if repeated_hashes.next().is_some() {
+            return Err(invalid_structure());
+        }
+
+        let mut columns = (proof.commitment_scheme_proof.queried_values.iter_mut())
+            .flatten()
+            .collect_vec();
+        for (index, first) in repeated_columns {
+            let (index, first) = (index as usize, first as usize);
+            // Columns repeat an earlier column, and are restored in order.
+            if first >= index || index >= columns.len() || !columns[index].is_empty() {
+                return Err(VerificationError::InvalidStructure(
+                    "Invalid repeated columns".to_string(),
+                ));
+            }
+            *columns[index] = columns[first].clone();
+        }
+
+        Ok(proof)
+    }
+
+    /// Returns the estimate size (in bytes) of the proof.
+    pub fn size_estimate(&self) -> usize {
+        self.proof.size_estimate()
+            + repeated_hashes_size_estimate(&self.repeated_hashes)
+            + repeated_columns_size_estimate(&self.repeated_columns)
+    }
+
+    /// Returns size estimates (in bytes) for different parts of the proof.
+    pub fn size_breakdown_estimate(&self) -> StarkProofSizeBreakdown {
+        let n_trace_witnesses = self.proof.commitment_scheme_proof.decommitments.len();
+        let (trace_repeated_hashes, fri_repeated_hashes) =
+            (self.repeated_hashes).split_at(n_trace_witnesses.min(self.repeated_hashes.len()));
+        let mut breakdown = self.proof.size_breakdown_estimate();
+        breakdown.trace_decommitments += repeated_hashes_size_estimate(trace_repeated_hashes);
+        breakdown.fri_decommitments += repeated_hashes_size_estimate(fri_repeated_hashes);
+        breakdown.queries_values += repeated_columns_size_estimate(&self.repeated_columns);
+        breakdown
+    }
+}
+
+fn repeated_hashes_size_estimate(repeated_hashes: &[Vec<RepeatedHash>]) -> usize {
+    repeated_hashes.iter().map(Vec::len).sum::<usize>() * mem::size_of::<RepeatedHash>()
+}
+
+fn repeated_columns_size_estimate(repeated_columns: &[RepeatedColumn]) -> usize {
+    mem::size_of_val(repeated_columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::channel::Blake2sChannel;
+    use crate::core::circle::SECURE_FIELD_CIRCLE_GEN;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig, TreeVec};
+    use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
+    use crate::core::poly::twiddles::TwiddleCache;
+    use crate::core::prover::{StarkProof, VerificationError};
+    use crate::core::vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher};
+
+    const LOG_SIZE: u32 = 6;
+
+    /// Proves the values of a tree of constant columns and of a tree of a non-constant column.
+    fn prove_values() -> StarkProof<Blake2sMerkleHasher> {
+        let domain = CanonicCoset::new(LOG_SIZE).circle_domain();
+        let mut prover = CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+            PcsConfig::default(),
+            TwiddleCache::default(),
+        );
+        let channel = &mut Blake2sChannel::default();
+        let mut tree_builder = prover.tree_builder();
+        tree_builder.extend_evals((0..2).map(|_| {
+            CircleEvaluation::new(
+                domain,
+                [BaseField::from(5); 1 << LOG_SIZE].into_iter().collect(),
+            )
+        }));
+        tree_builder.commit(channel);
+        let mut tree_builder = prover.tree_builder();
+        tree_builder.extend_evals([CircleEvaluation::new(
+            domain,
+            (0..1 << LOG_SIZE).map(BaseField::from).collect(),
+        )]);
+        tree_builder.commit(channel);
+        let sampled_points = TreeVec::new(vec![
+            vec![vec![SECURE_FIELD_CIRCLE_GEN]; 2],
+            vec![vec![SECURE_FIELD_CIRCLE_GEN]],
+        ]);
+        StarkProof {
+            commitments: prover.commitments(),
+            commitment_scheme_proof: prover.prove_values(sampled_points, channel),
+        }
+    }
+
+    #[test]
+    fn test_compress_and_decompress() {
+        let mut proof = prove_values();
+        let hash_witnesses = proof
+            .hash_witnesses_mut()
+            .map(|hashes| hashes.clone())
+            .collect_vec();
+
+        let compressed_proof = prove_values().compress();
+        // The subtrees of the constant columns are all the same, and so are their queried values.
+        assert!(compressed_proof
+            .repeated_hashes
+            .iter()
+            .any(|hashes| !hashes.is_empty()));
+        assert_eq!(compressed_proof.repeated_columns, [(1, 0)]);
+        let mut decompressed_proof = compressed_proof.decompress().unwrap();
+
+        assert_eq!(
+            decompressed_proof
+                .hash_witnesses_mut()
+                .map(|hashes| hashes.clone())
+                .collect_vec(),
+            hash_witnesses
+        );
+        assert_eq!(
+            decompressed_proof.commitment_scheme_proof.queried_values,
+            proof.commitment_scheme_proof.queried_values
+        );
+        let channel = &mut Blake2sChannel::default();
+        let mut verifier =
+            CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(PcsConfig::default());
+        verifier.commit(
+            decompressed_proof.commitments[0].clone(),
+            &[LOG_SIZE; 2],
+            channel,
+        );
+        verifier.commit(
+            decompressed_proof.commitments[1].clone(),
+            &[LOG_SIZE],
+            channel,
+        );
+        let sampled_points = TreeVec::new(vec![
+            vec![vec![SECURE_FIELD_CIRCLE_GEN]; 2],
+            vec![vec![SECURE_FIELD_CIRCLE_GEN]],
+        ]);
+        verifier
+            .verify_values(
+                sampled_points,
+                decompressed_proof.commitment_scheme_proof,
+                channel,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_decompress_invalid_repeated_hash() {
+        let mut compressed_proof = prove_values().compress();
+        let repeated_hashes = compressed_proof
+            .repeated_hashes
+            .iter_mut()
+            .find(|repeated_hashes| !repeated_hashes.is_empty())
+            .unwrap();
+        repeated_hashes[0].1 = u32::MAX;
+
+        assert!(matches!(
+            compressed_proof.decompress().unwrap_err(),
+            VerificationError::InvalidStructure(_)
+        ));
+    }
+
+    #[test]
+    fn test_decompress_invalid_repeated_column() {
+        let mut compressed_proof = prove_values().compress();
+        compressed_proof.repeated_columns[0].1 = compressed_proof.repeated_columns[0].0;
+
+        assert!(matches!(
+            compressed_proof.decompress().unwrap_err(),
+            VerificationError::InvalidStructure(_)
+        ));
+    }
+
+    #[test]
+    fn test_decompress_missing_repeated_hashes() {
+        let mut compressed_proof = prove_values().compress();
+        compressed_proof.repeated_hashes.pop();
+
+        assert!(matches!(
+            compressed_proof.decompress().unwrap_err(),
+            VerificationError::InvalidStructure(_)
+        ));
+    }
+}
@@ -0,0 +1,432 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{StarkProof, VerificationError};
+use crate::core::air::{Air, Component};
+use crate::core::backend::BackendForChannel;
+use crate::core::channel::MerkleChannel;
+use crate::core::pcs::{
+    CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig, PreprocessedTrace, TreeBuilder,
+    TreeSubspan, TreeVec,
+};
+use crate::core::poly::twiddles::TwiddleCache;
+use crate::core::vcs::ops::MerkleHasher;
+use crate::core::ColumnVec;
+
+/// The shape of a [Component], which is all the verifier needs to know about it ahead of a proof.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentDescription {
+    pub n_constraints: usize,
+    pub max_constraint_log_degree_bound: u32,
+    pub trace_log_degree_bounds: TreeVec<ColumnVec<u32>>,
+}
+
+impl ComponentDescription {
+    pub fn new(component: &dyn Component) -> Self {
+        Self {
+            n_constraints: component.n_constraints(),
+            max_constraint_log_degree_bound: component.max_constraint_log_degree_bound(),
+            trace_log_degree_bounds: component.trace_log_degree_bounds(),
+        }
+    }
+}
+
+/// The prover's output of [setup]: the committed preprocessed trees, the blowup factors of the
+/// columns and the PCS configuration.
+pub struct ProvingKey<B: BackendForChannel<MC>, MC: MerkleChannel> {
+    /// The preprocessed trees, by tree index.
+    pub preprocessed: BTreeMap<usize, PreprocessedTrace<B, MC>>,
+    /// The log blowup factors of the columns of each tree, excluding the composition tree.
+    pub column_log_blowup_factors: TreeVec<ColumnVec<u32>>,
+    pub config: PcsConfig,
+}
+
+impl<B: BackendForChannel<MC>, MC: MerkleChannel> ProvingKey<B, MC> {
+    /// Creates a commitment scheme prover with the configuration of the key.
+    pub fn commitment_scheme(&self, twiddles: TwiddleCache<B>) -> CommitmentSchemeProver<B, MC> {
+        CommitmentSchemeProver::new(self.config, twiddles)
+    }
+
+    /// Returns a builder for the next tree of `commitment_scheme`, which extends the columns with
+    /// the blowup factors of the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key has no columns for the next tree, or if it is preprocessed.
+    pub fn tree_builder<'a>(
+        &self,
+        commitment_scheme: &'a mut CommitmentSchemeProver<B, MC>,
+    ) -> TreeBuilder<'a, B, MC> {
+        let tree_index = commitment_scheme.trees.len();
+        assert!(
+            !self.preprocessed.contains_key(&tree_index),
+            "tree {tree_index} is preprocessed"
+        );
+        let log_blowup_factors = self
+            .column_log_blowup_factors
+            .get(tree_index)
+            .unwrap_or_else(|| panic!("tree {tree_index} is not in the key"))
+            .clone();
+        let mut tree_builder = commitment_scheme.tree_builder();
+        tree_builder.set_column_log_blowup_factors(log_blowup_factors);
+        tree_builder
+    }
+
+    /// Loads the preprocessed tree of the next tree index into `commitment_scheme`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the next tree is not preprocessed.
+    pub fn commit_preprocessed(
+        &self,
+        commitment_scheme: &mut CommitmentSchemeProver<B, MC>,
+        channel: &mut MC::C,
+    ) -> TreeSubspan {
+        let tree_index = commitment_scheme.trees.len();
+        let preprocessed = self
+            .preprocessed
+            .get(&tree_index)
+            .unwrap_or_else(|| panic!("tree {tree_index} is not preprocessed"));
+        commitment_scheme.commit_preprocessed(preprocessed, channel)
+    }
+}
+
+/// Everything the verifier needs to know about a statement ahead of its proofs: the components,
+/// the log sizes and blowup factors of the committed columns, the caps of the preprocessed trees
+/// and the PCS configuration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct VerifyingKey<H: MerkleHasher> {
+    pub components: Vec<ComponentDescription>,
+    /// The log sizes of the columns of each tree, excluding the composition tree.
+    pub column_log_sizes: TreeVec<ColumnVec<u32>>,
+    /// The log blowup factors of the columns of each tree, excluding the composition tree.
+    pub column_log_blowup_factors: TreeVec<ColumnVec<u32>>,
+    /// The Merkle caps of the preprocessed trees, by tree index, of the size set in the
+    /// configuration.
+    pub preprocessed_caps: BTreeMap<usize, Vec<H::Hash>>,
+    pub config: PcsConfig,
+}
+
+impl<H: MerkleHasher> VerifyingKey<H> {
+    /// Creates a commitment scheme verifier with the configuration of the key.
+    pub fn commitment_scheme<MC: MerkleChannel<H = H>>(&self) -> CommitmentSchemeVerifier<MC> {
+        CommitmentSchemeVerifier::new(self.config)
+    }
+
+    /// Checks that `components` have the shapes recorded in the key.
+    pub fn check_components(&self, components: &[&dyn Component]) -> Result<(), VerificationError> {
+        let descriptions = components
+            .iter()
+            .map(|&component| ComponentDescription::new(component));
+        if !descriptions.eq(self.components.iter().cloned()) {
+            return Err(VerificationError::InvalidStructure(
+                "Components do not match the verifying key".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reads the commitment of the next tree from `proof`, with the column log sizes and blowup
+    /// factors of the key.
+    ///
+    /// The cap of a preprocessed tree is taken from the key, and the proof must agree with it.
+    pub fn commit<MC: MerkleChannel<H = H>>(
+        &self,
+        commitment_scheme: &mut CommitmentSchemeVerifier<MC>,
+        proof: &StarkProof<H>,
+        channel: &mut MC::C,
+    ) -> Result<(), VerificationError> {
+        let tree_index = commitment_scheme.trees.len();
+        let (Some(log_sizes), Some(log_blowup_factors), Some(commitment)) = (
+            self.column_log_sizes.get(tree_index),
+            self.column_log_blowup_factors.get(tree_index),
+            proof.commitments.get(tree_index),
+        ) else {
+            return Err(VerificationError::InvalidStructure(format!(
+                "Unexpected commitment of tree {tree_index}"
+            )));
+        };
+        if let Some(cap) = self.preprocessed_caps.get(&tree_index) {
+            if cap != commitment {
+                return Err(VerificationError::PreprocessedRootMismatch(tree_index));
+            }
+        }
+        commitment_scheme.commit_with_log_blowup_factors(
+            commitment.clone(),
+            log_sizes,
+            log_blowup_factors,
+            channel,
+        )
+    }
+}
+
+/// Produces the proving and verifying keys of `air`, with the preprocessed trees given by tree
+/// index.
+///
+/// Only the shape of the components of `air` is used, so it may be built with dummy interaction
+/// elements and claimed sums. The columns of a preprocessed tree keep the blowup factors they were
+/// committed with, and the other columns are committed with the blowup factor of FRI.
+///
+/// # Panics
+///
+/// Panics if a preprocessed tree doesn't have the column log sizes the components expect for its
+/// tree index, or was committed with a blowup factor smaller than the one of FRI.
+pub fn setup<B: BackendForChannel<MC>, MC: MerkleChannel>(
+    air: &dyn Air,
+    config: PcsConfig,
+    preprocessed: impl IntoIterator<Item = (usize, PreprocessedTrace<B, MC>)>,
+) -> (ProvingKey<B, MC>, VerifyingKey<MC::H>) {
+    let components = air.components();
+    let column_log_sizes = TreeVec::concat_cols(
+        components
+            .iter()
+            .map(|component| component.trace_log_degree_bounds()),
+    );
+    let preprocessed: BTreeMap<_, _> = preprocessed.into_iter().collect();
+    for (&tree_index, trace) in &preprocessed {
+        assert_eq!(
+            column_log_sizes.get(tree_index),
+            Some(&trace.log_sizes()),
+            "preprocessed tree {tree_index} doesn't match the components"
+        );
+        assert!(
+            trace.log_blowup_factor() >= config.fri_config.log_blowup_factor,
+            "preprocessed tree {tree_index} committed with a blowup factor smaller than FRI's"
+        );
+    }
+    let column_log_blowup_factors = TreeVec::new(
+        column_log_sizes
+            .iter()
+            .enumerate()
+            .map(
+                |(tree_index, log_sizes)| match preprocessed.get(&tree_index) {
+                    Some(trace) => trace.log_blowup_factors(),
+                    None => vec![config.fri_config.log_blowup_factor; log_sizes.len()],
+                },
+            )
+            .collect(),
+    );
+
+    let verifying_key = VerifyingKey {
+        components: components
+            .iter()
+            .map(|&component| ComponentDescription::new(component))
+            .collect(),
+        column_log_sizes,
+        column_log_blowup_factors: column_log_blowup_factors.clone(),
+        preprocessed_caps: preprocessed
+            .iter()
+            .map(|(&tree_index, trace)| {
+                let log_cap_size = config.fri_config.log_merkle_cap_size;
+                (tree_index, trace.cap(log_cap_size))
+            })
+            .collect(),
+        config,
+    };
+    let proving_key = ProvingKey {
+        preprocessed,
+        column_log_blowup_factors,
+        config,
+    };
+    (proving_key, verifying_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{setup, VerifyingKey};
+    use crate::constraint_framework::{
+        EvalAtRow, FrameworkComponent, FrameworkEval, TraceLocationAllocator,
+    };
+    use crate::core::air::{Air, Component};
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::channel::Blake2sChannel;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::pcs::{PcsConfig, PreprocessedColumns, PreprocessedTrace};
+    use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
+    use crate::core::poly::twiddles::TwiddleCache;
+    use crate::core::poly::BitReversedOrder;
+    use crate::core::prover::{prove, verify, StarkProof, VerificationError};
+    use crate::core::vcs::blake2_hash::Blake2sHash;
+    use crate::core::vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher};
+
+    const LOG_N_ROWS: u32 = 6;
+
+    /// Enforces that the trace column equals the preprocessed column.
+    struct CopyEval;
+    impl FrameworkEval for CopyEval {
+        fn log_size(&self) -> u32 {
+            LOG_N_ROWS
+        }
+        fn max_constraint_log_degree_bound(&self) -> u32 {
+            LOG_N_ROWS + 1
+        }
+        fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+            let value = eval.next_trace_mask();
+            let [constant] = eval.next_interaction_mask(1, [0]);
+            eval.add_constraint(value - constant);
+            eval
+        }
+    }
+
+    struct CopyAir(FrameworkComponent<CopyEval>);
+    impl CopyAir {
+        fn new() -> Self {
+            Self(FrameworkComponent::new(
+                &mut TraceLocationAllocator::default(),
+                CopyEval,
+            ))
+        }
+    }
+    impl Air for CopyAir {
+        fn components(&self) -> Vec<&dyn Component> {
+            vec![&self.0]
+        }
+    }
+
+    fn column() -> CircleEvaluation<SimdBackend, BaseField, BitReversedOrder> {
+        let domain = CanonicCoset::new(LOG_N_ROWS).circle_domain();
+        CircleEvaluation::new(domain, (0..1 << LOG_N_ROWS).map(BaseField::from).collect())
+    }
+
+    fn preprocessed(
+        n_columns: usize,
+        log_blowup_factor: u32,
+    ) -> PreprocessedTrace<SimdBackend, Blake2sMerkleChannel> {
+        let mut columns = PreprocessedColumns::new();
+        for i in 0..n_columns {
+            columns.insert(format!("constant{i}"), column());
+        }
+        PreprocessedTrace::commit_with_log_blowup_factor(
+            columns,
+            log_blowup_factor,
+            &TwiddleCache::default(),
+        )
+    }
+
+    fn prove_copy(
+        preprocessed_log_blowup_factor: u32,
+    ) -> (
+        CopyAir,
+        VerifyingKey<Blake2sMerkleHasher>,
+        StarkProof<Blake2sMerkleHasher>,
+    ) {
+        let config = PcsConfig::default();
+        let air = CopyAir::new();
+        let (proving_key, verifying_key) = setup(
+            &air,
+            config,
+            [(1, preprocessed(1, preprocessed_log_blowup_factor))],
+        );
+
+        let channel = &mut Blake2sChannel::default();
+        let commitment_scheme = &mut proving_key.commitment_scheme(TwiddleCache::default());
+        let mut tree_builder = proving_key.tree_builder(commitment_scheme);
+        tree_builder.extend_evals([column()]);
+        tree_builder.commit(channel);
+        proving_key.commit_preprocessed(commitment_scheme, channel);
+        let proof = prove::<SimdBackend, _>(&[&air.0], channel, commitment_scheme).unwrap();
+        (air, verifying_key, proof)
+    }
+
+    fn verify_copy(
+        air: &CopyAir,
+        verifying_key: &VerifyingKey<Blake2sMerkleHasher>,
+        proof: StarkProof<Blake2sMerkleHasher>,
+    ) -> Result<(), VerificationError> {
+        let channel = &mut Blake2sChannel::default();
+        let commitment_scheme = &mut verifying_key.commitment_scheme::<Blake2sMerkleChannel>();
+        verifying_key.check_components(&air.components())?;
+        verifying_key.commit(commitment_scheme, &proof, channel)?;
+        verifying_key.commit(commitment_scheme, &proof, channel)?;
+        verify(&air.components(), channel, commitment_scheme, proof)
+    }
+
+    #[test]
+    fn test_prove_and_verify_with_keys() {
+        let log_blowup_factor = PcsConfig::default().fri_config.log_blowup_factor;
+        let (air, verifying_key, proof) = prove_copy(log_blowup_factor);
+
+        assert_eq!(
+            verifying_key.column_log_sizes.0,
+            [[LOG_N_ROWS], [LOG_N_ROWS]]
+        );
+        verify_copy(&air, &verifying_key, proof).unwrap();
+    }
+
+    #[test]
+    fn test_prove_and_verify_with_larger_preprocessed_blowup_factor() {
+        let log_blowup_factor = PcsConfig::default().fri_config.log_blowup_factor;
+        let (air, verifying_key, proof) = prove_copy(log_blowup_factor + 2);
+
+        assert_eq!(
+            verifying_key.column_log_blowup_factors.0,
+            [[log_blowup_factor], [log_blowup_factor + 2]]
+        );
+        verify_copy(&air, &verifying_key, proof).unwrap();
+    }
+
+    #[test]
+    fn test_verify_with_deserialized_verifying_key() {
+        let log_blowup_factor = PcsConfig::default().fri_config.log_blowup_factor;
+        let (air, verifying_key, proof) = prove_copy(log_blowup_factor + 1);
+
+        let serialized = serde_json::to_string(&verifying_key).unwrap();
+        let deserialized: VerifyingKey<Blake2sMerkleHasher> =
+            serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(
+            deserialized.column_log_blowup_factors,
+            verifying_key.column_log_blowup_factors
+        );
+        verify_copy(&air, &deserialized, proof).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "tree 0 has more columns than blowup factors")]
+    fn test_tree_builder_with_extra_column_fails() {
+        let config = PcsConfig::default();
+        let (proving_key, _) = setup(
+            &CopyAir::new(),
+            config,
+            [(1, preprocessed(1, config.fri_config.log_blowup_factor))],
+        );
+        let commitment_scheme = &mut proving_key.commitment_scheme(TwiddleCache::default());
+
+        proving_key
+            .tree_builder(commitment_scheme)
+            .extend_evals([column(), column()]);
+    }
+
+    #[test]
+    fn test_verify_with_wrong_preprocessed_root_fails() {
+        let (air, verifying_key, mut proof) =
+            prove_copy(PcsConfig::default().fri_config.log_blowup_factor);
+        proof.commitments[1] = vec![Blake2sHash::default()];
+
+        assert!(matches!(
+            verify_copy(&air, &verifying_key, proof),
+            Err(VerificationError::PreprocessedRootMismatch(1))
+        ));
+    }
+
+    #[test]
+    fn test_verify_with_wrong_components_fails() {
+        let (_, verifying_key, _) = prove_copy(PcsConfig::default().fri_config.log_blowup_factor);
+
+        assert!(verifying_key.check_components(&[]).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "preprocessed tree 1 doesn't match the components")]
+    fn test_setup_with_wrong_preprocessed_tree_fails() {
+        let config = PcsConfig::default();
+
+        setup(
+            &CopyAir::new(),
+            config,
+            [(1, preprocessed(2, config.fri_config.log_blowup_factor))],
+        );
+    }
+}
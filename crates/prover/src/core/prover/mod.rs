@@ -1,9 +1,14 @@
+mod compressed;
+mod keys;
+
 use std::{array, mem};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::{info, instrument, span, Level};
 
+pub use self::compressed::CompressedStarkProof;
+pub use self::keys::{setup, ComponentDescription, ProvingKey, VerifyingKey};
 use super::air::{Component, ComponentProver, ComponentProvers, Components};
 use super::backend::BackendForChannel;
 use super::channel::MerkleChannel;
@@ -23,7 +28,8 @@ use crate::core::vcs::verifier::MerkleVerificationError;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StarkProof<H: MerkleHasher> {
-    pub commitments: TreeVec<H::Hash>,
+    /// The Merkle caps of the committed trees.
+    pub commitments: TreeVec<Vec<H::Hash>>,
     pub commitment_scheme_proof: CommitmentSchemeProof<H>,
 }
 
@@ -31,7 +37,7 @@ pub struct StarkProof<H: MerkleHasher> {
 pub fn prove<B: BackendForChannel<MC>, MC: MerkleChannel>(
     components: &[&dyn ComponentProver<B>],
     channel: &mut MC::C,
-    commitment_scheme: &mut CommitmentSchemeProver<'_, B, MC>,
+    commitment_scheme: &mut CommitmentSchemeProver<B, MC>,
 ) -> Result<StarkProof<MC::H>, ProvingError> {
     let component_provers = ComponentProvers(components.to_vec());
     let trace = commitment_scheme.trace();
@@ -74,7 +80,7 @@ pub fn prove<B: BackendForChannel<MC>, MC: MerkleChannel>(
     }
 
     let proof = StarkProof {
-        commitments: commitment_scheme.roots(),
+        commitments: commitment_scheme.commitments(),
         commitment_scheme_proof,
     };
     info!(proof_size_estimate = proof.size_estimate());
@@ -92,7 +98,7 @@ pub fn verify<MC: MerkleChannel>(
 
     // Read composition polynomial commitment.
     commitment_scheme.commit(
-        *proof.commitments.last().unwrap(),
+        proof.commitments.last().unwrap().clone(),
         &[components.composition_log_degree_bound(); SECURE_EXTENSION_DEGREE],
         channel,
     );
@@ -170,6 +176,10 @@ pub enum VerificationError {
     Fri(#[from] FriVerificationError),
     #[error("Proof of work verification failed.")]
     ProofOfWork,
+    #[error("Opened values do not match the decommitted values.")]
+    OpenedValuesNotMatching,
+    #[error("The commitment of preprocessed tree {0} does not match the verifying key.")]
+    PreprocessedRootMismatch(usize),
 }
 
 impl<H: MerkleHasher> StarkProof<H> {
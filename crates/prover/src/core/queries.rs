@@ -2,6 +2,7 @@ use std::collections::BTreeSet;
 use std::ops::Deref;
 
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use super::channel::Channel;
 use super::circle::Coset;
@@ -10,6 +11,18 @@ use super::utils::bit_reverse_index;
 
 pub const UPPER_BOUND_QUERY_BYTES: usize = 4;
 
+/// Strategy used to sample query positions from the channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuerySampling {
+    /// Draws exactly `n_queries` positions. Duplicate positions are merged, so the effective
+    /// number of queries may be lower than `n_queries`.
+    #[default]
+    WithReplacement,
+    /// Keeps drawing positions until `n_queries` distinct positions are obtained (or the whole
+    /// domain is queried).
+    Distinct,
+}
+
 /// An ordered set of query indices over a bit reversed [CircleDomain].
 #[derive(Debug, Clone)]
 pub struct Queries {
@@ -19,27 +32,91 @@ pub struct Queries {
 
 impl Queries {
     /// Randomizes a set of query indices uniformly over the range [0, 2^`log_query_size`).
+    ///
+    /// Exactly `n_queries` indices are drawn. Since duplicates are merged, the number of resulting
+    /// positions may be smaller than `n_queries`. See [`Queries::generate_distinct`].
     pub fn generate(channel: &mut impl Channel, log_domain_size: u32, n_queries: usize) -> Self {
         let mut queries = BTreeSet::new();
         let mut query_cnt = 0;
-        let max_query = (1 << log_domain_size) - 1;
+        Self::draw_positions(channel, log_domain_size, |query| {
+            queries.insert(query);
+            query_cnt += 1;
+            query_cnt == n_queries
+        });
+        Self {
+            positions: queries.into_iter().collect(),
+            log_domain_size,
+        }
+    }
+
+    /// Randomizes a set of `n_queries` distinct query indices uniformly over the range
+    /// [0, 2^`log_query_size`).
+    ///
+    /// Indices are drawn until enough distinct ones are obtained. If `n_queries` exceeds the domain
+    /// size, every position of the domain is returned.
+    pub fn generate_distinct(
+        channel: &mut impl Channel,
+        log_domain_size: u32,
+        n_queries: usize,
+    ) -> Self {
+        let n_queries = n_queries.min(1 << log_domain_size);
+        if n_queries == 0 {
+            return Self {
+                positions: vec![],
+                log_domain_size,
+            };
+        }
+        let mut queries = BTreeSet::new();
+        Self::draw_positions(channel, log_domain_size, |query| {
+            queries.insert(query);
+            queries.len() == n_queries
+        });
+        Self {
+            positions: queries.into_iter().collect(),
+            log_domain_size,
+        }
+    }
+
+    /// Randomizes a set of query indices using the given [`QuerySampling`] strategy.
+    pub fn sample(
+        channel: &mut impl Channel,
+        log_domain_size: u32,
+        n_queries: usize,
+        sampling: QuerySampling,
+    ) -> Self {
+        match sampling {
+            QuerySampling::WithReplacement => Self::generate(channel, log_domain_size, n_queries),
+            QuerySampling::Distinct => Self::generate_distinct(channel, log_domain_size, n_queries),
+        }
+    }
+
+    /// Feeds uniformly random positions in [0, 2^`log_domain_size`) to `consume` until it returns
+    /// `true`.
+    ///
+    /// Positions are obtained by masking the words of [Channel::draw_u32s]. Since the domain size
+    /// is a power of two, the masked value is unbiased.
+    fn draw_positions(
+        channel: &mut impl Channel,
+        log_domain_size: u32,
+        mut consume: impl FnMut(usize) -> bool,
+    ) {
+        assert!(log_domain_size <= u32::BITS);
+        let max_query = ((1u64 << log_domain_size) - 1) as u32;
         loop {
-            let random_bytes = channel.draw_random_bytes();
-            for chunk in random_bytes.chunks_exact(UPPER_BOUND_QUERY_BYTES) {
-                let query_bits = u32::from_le_bytes(chunk.try_into().unwrap());
-                let quotient_query = query_bits & max_query;
-                queries.insert(quotient_query as usize);
-                query_cnt += 1;
-                if query_cnt == n_queries {
-                    return Self {
-                        positions: queries.into_iter().collect(),
-                        log_domain_size,
-                    };
+            for query_bits in channel.draw_u32s() {
+                if consume((query_bits & max_query) as usize) {
+                    return;
                 }
             }
         }
     }
 
+    /// Returns the number of distinct query positions, i.e. the number of queries that
+    /// effectively contribute to soundness.
+    pub fn n_effective_queries(&self) -> usize {
+        self.positions.len()
+    }
+
     // TODO docs
     #[allow(clippy::missing_safety_doc)]
     pub fn from_positions(positions: Vec<usize>, log_domain_size: u32) -> Self {
@@ -134,7 +211,7 @@ impl SubCircleDomain {
 mod tests {
     use crate::core::channel::Blake2sChannel;
     use crate::core::poly::circle::CanonicCoset;
-    use crate::core::queries::Queries;
+    use crate::core::queries::{Queries, QuerySampling};
     use crate::core::utils::bit_reverse;
 
     #[test]
@@ -151,6 +228,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_distinct_queries() {
+        let log_domain_size = 5;
+        let n_queries = 20;
+        let mut channel = Blake2sChannel::default();
+        let mut verifier_channel = Blake2sChannel::default();
+
+        let queries = Queries::generate_distinct(&mut channel, log_domain_size, n_queries);
+        let verifier_queries = Queries::sample(
+            &mut verifier_channel,
+            log_domain_size,
+            n_queries,
+            QuerySampling::Distinct,
+        );
+
+        assert_eq!(queries.n_effective_queries(), n_queries);
+        assert_eq!(queries.positions, verifier_queries.positions);
+        assert_eq!(channel.digest(), verifier_channel.digest());
+    }
+
+    #[test]
+    fn test_generate_distinct_queries_exceeding_domain_size() {
+        let channel = &mut Blake2sChannel::default();
+        let log_domain_size = 3;
+
+        let queries = Queries::generate_distinct(channel, log_domain_size, 100);
+
+        assert_eq!(*queries, (0..1 << log_domain_size).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_generate_distinct_no_queries() {
+        let mut channel = Blake2sChannel::default();
+        let initial_digest = channel.digest();
+
+        let queries = Queries::generate_distinct(&mut channel, 5, 0);
+
+        assert!(queries.is_empty());
+        assert_eq!(channel.digest(), initial_digest);
+    }
+
     #[test]
     pub fn test_folded_queries() {
         let log_domain_size = 7;
@@ -130,6 +130,44 @@ pub fn coset_index_to_circle_domain_index(coset_index: usize, log_domain_size: u
     }
 }
 
+/// Converts an index within a [`CircleDomain`] to the corresponding index in its canonic
+/// [`Coset`]. This is the inverse of [`coset_index_to_circle_domain_index`].
+///
+/// [`CircleDomain`]: crate::core::poly::circle::CircleDomain
+/// [`Coset`]: crate::core::circle::Coset
+pub fn circle_domain_index_to_coset_index(circle_index: usize, log_domain_size: u32) -> usize {
+    let half_size = 1 << (log_domain_size - 1);
+    if circle_index < half_size {
+        circle_index * 2
+    } else {
+        (2 << log_domain_size) - 2 * circle_index - 1
+    }
+}
+
+/// Converts an index within a [`Coset`] (i.e. a trace row) to the corresponding index in a
+/// bit-reversed [`CircleDomain`] evaluation.
+///
+/// [`CircleDomain`]: crate::core::poly::circle::CircleDomain
+/// [`Coset`]: crate::core::circle::Coset
+pub fn coset_index_to_bit_reversed_index(coset_index: usize, log_domain_size: u32) -> usize {
+    bit_reverse_index(
+        coset_index_to_circle_domain_index(coset_index, log_domain_size),
+        log_domain_size,
+    )
+}
+
+/// Converts an index within a bit-reversed [`CircleDomain`] evaluation to the corresponding index
+/// in its canonic [`Coset`]. This is the inverse of [`coset_index_to_bit_reversed_index`].
+///
+/// [`CircleDomain`]: crate::core::poly::circle::CircleDomain
+/// [`Coset`]: crate::core::circle::Coset
+pub fn bit_reversed_index_to_coset_index(bit_reversed_index: usize, log_domain_size: u32) -> usize {
+    circle_domain_index_to_coset_index(
+        bit_reverse_index(bit_reversed_index, log_domain_size),
+        log_domain_size,
+    )
+}
+
 /// Performs a naive bit-reversal permutation inplace.
 ///
 /// # Panics
@@ -176,6 +214,7 @@ mod tests {
     use num_traits::One;
 
     use super::{
+        bit_reversed_index_to_coset_index, coset_index_to_bit_reversed_index,
         offset_bit_reversed_circle_domain_index, previous_bit_reversed_circle_domain_index,
     };
     use crate::core::backend::cpu::CpuCircleEvaluation;
@@ -183,7 +222,7 @@ mod tests {
     use crate::core::fields::FieldExpOps;
     use crate::core::poly::circle::CanonicCoset;
     use crate::core::poly::NaturalOrder;
-    use crate::core::utils::bit_reverse;
+    use crate::core::utils::{bit_reverse, bit_reverse_index};
     use crate::{m31, qm31};
 
     #[test]
@@ -305,4 +344,24 @@ mod tests {
 
         assert_eq!(neighbor_pairs, expected_neighbor_pairs);
     }
+
+    #[test]
+    fn coset_index_to_bit_reversed_index_works() {
+        let log_size = 5;
+        let coset = CanonicCoset::new(log_size).coset();
+        let domain = CanonicCoset::new(log_size).circle_domain();
+
+        for coset_index in 0..coset.size() {
+            let bit_reversed_index = coset_index_to_bit_reversed_index(coset_index, log_size);
+
+            assert_eq!(
+                domain.at(bit_reverse_index(bit_reversed_index, log_size)),
+                coset.at(coset_index)
+            );
+            assert_eq!(
+                bit_reversed_index_to_coset_index(bit_reversed_index, log_size),
+                coset_index
+            );
+        }
+    }
 }
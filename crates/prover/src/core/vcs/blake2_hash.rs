@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 
 // Wrapper for the blake2s hash type.
 #[repr(C, align(32))]
-#[derive(Clone, Copy, PartialEq, Default, Eq, Pod, Zeroable, Deserialize, Serialize)]
+#[derive(Clone, Copy, PartialEq, Default, Eq, Hash, Pod, Zeroable, Deserialize, Serialize)]
 pub struct Blake2sHash(pub [u8; 32]);
 
 impl From<Blake2sHash> for Vec<u8> {
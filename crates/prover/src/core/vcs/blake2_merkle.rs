@@ -11,11 +11,9 @@ use crate::core::fields::m31::BaseField;
 pub struct Blake2sMerkleHasher;
 impl MerkleHasher for Blake2sMerkleHasher {
     type Hash = Blake2sHash;
+    type NodeState = Blake2sNodeState;
 
-    fn hash_node(
-        children_hashes: Option<(Self::Hash, Self::Hash)>,
-        column_values: &[BaseField],
-    ) -> Self::Hash {
+    fn start_node(children_hashes: Option<(Self::Hash, Self::Hash)>) -> Self::NodeState {
         let mut state = [0; 8];
         if let Some((left, right)) = children_hashes {
             state = compress(
@@ -27,15 +25,53 @@ impl MerkleHasher for Blake2sMerkleHasher {
                 0,
             );
         }
-        let rem = 15 - ((column_values.len() + 15) % 16);
-        let padded_values = column_values
-            .iter()
-            .copied()
-            .chain(std::iter::repeat(BaseField::zero()).take(rem));
-        for chunk in padded_values.array_chunks::<16>() {
-            state = compress(state, unsafe { std::mem::transmute(chunk) }, 0, 0, 0, 0);
+        Blake2sNodeState {
+            state,
+            pending: [BaseField::zero(); 16],
+            n_pending: 0,
         }
-        state.map(|x| x.to_le_bytes()).flatten().into()
+    }
+
+    fn update_node(state: &mut Self::NodeState, column_values: &[BaseField]) {
+        for &value in column_values {
+            state.pending[state.n_pending] = value;
+            state.n_pending += 1;
+            if state.n_pending == 16 {
+                state.compress_pending();
+            }
+        }
+    }
+
+    fn finalize_node(mut state: Self::NodeState) -> Self::Hash {
+        // The last chunk of column values is padded with zeros.
+        if state.n_pending > 0 {
+            state.pending[state.n_pending..].fill(BaseField::zero());
+            state.compress_pending();
+        }
+        state.state.map(|x| x.to_le_bytes()).flatten().into()
+    }
+}
+
+/// A partially hashed node of a [Blake2sMerkleHasher] tree: the compression state, and the column
+/// values that don't fill a chunk of 16 yet.
+#[derive(Clone, Debug)]
+pub struct Blake2sNodeState {
+    state: [u32; 8],
+    pending: [BaseField; 16],
+    n_pending: usize,
+}
+
+impl Blake2sNodeState {
+    fn compress_pending(&mut self) {
+        self.state = compress(
+            self.state,
+            unsafe { std::mem::transmute(self.pending) },
+            0,
+            0,
+            0,
+            0,
+        );
+        self.n_pending = 0;
     }
 }
 
@@ -62,7 +98,7 @@ mod tests {
     use crate::core::channel::{Blake2sChannel, MerkleChannel};
     use crate::core::fields::m31::BaseField;
     use crate::core::vcs::blake2_merkle::{Blake2sHash, Blake2sMerkleHasher};
-    use crate::core::vcs::test_utils::prepare_merkle;
+    use crate::core::vcs::test_utils::{prepare_merkle, prepare_merkle_with_cap};
     use crate::core::vcs::verifier::MerkleVerificationError;
 
     #[test]
@@ -138,11 +174,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_merkle_cap_success() {
+        let (_, full_decommitment, ..) = prepare_merkle::<Blake2sMerkleHasher>();
+        for log_cap_size in 1..=3 {
+            let (queries, decommitment, values, verifier) =
+                prepare_merkle_with_cap::<Blake2sMerkleHasher>(log_cap_size);
+            assert_eq!(verifier.cap.len(), 1 << log_cap_size);
+            // The paths end at the cap.
+            assert!(decommitment.hash_witness.len() <= full_decommitment.hash_witness.len());
+
+            verifier.verify(queries, values, decommitment).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_merkle_cap_larger_than_smallest_column() {
+        // The columns are of log size at least 3, so the cap is lowered to 8 nodes.
+        let (queries, decommitment, values, verifier) =
+            prepare_merkle_with_cap::<Blake2sMerkleHasher>(10);
+        let (_, lowered_cap_decommitment, _, lowered_cap_verifier) =
+            prepare_merkle_with_cap::<Blake2sMerkleHasher>(3);
+        assert_eq!(decommitment, lowered_cap_decommitment);
+        assert_eq!(verifier.cap, lowered_cap_verifier.cap);
+
+        verifier.verify(queries, values, decommitment).unwrap();
+    }
+
+    #[test]
+    fn test_merkle_invalid_cap() {
+        let (queries, decommitment, values, mut verifier) =
+            prepare_merkle_with_cap::<Blake2sMerkleHasher>(3);
+        verifier.cap.fill(Blake2sHash::default());
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::RootMismatch
+        );
+    }
+
+    #[test]
+    fn test_merkle_cap_too_short() {
+        let (queries, decommitment, values, mut verifier) =
+            prepare_merkle_with_cap::<Blake2sMerkleHasher>(3);
+        verifier.cap.pop();
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::InvalidCapSize
+        );
+    }
+
+    #[test]
+    fn test_merkle_cap_too_long() {
+        let (queries, decommitment, values, mut verifier) =
+            prepare_merkle_with_cap::<Blake2sMerkleHasher>(3);
+        verifier.cap.push(Blake2sHash::default());
+
+        assert_eq!(
+            verifier.verify(queries, values, decommitment).unwrap_err(),
+            MerkleVerificationError::InvalidCapSize
+        );
+    }
+
     #[test]
     fn test_merkle_channel() {
         let mut channel = Blake2sChannel::default();
         let (_queries, _decommitment, _values, verifier) = prepare_merkle::<Blake2sMerkleHasher>();
-        Blake2sMerkleChannel::mix_root(&mut channel, verifier.root);
+        Blake2sMerkleChannel::mix_root(&mut channel, verifier.cap[0]);
         assert_eq!(channel.channel_time.n_challenges, 1);
+
+        // A cap of a single node is mixed like its root.
+        let mut cap_channel = Blake2sChannel::default();
+        Blake2sMerkleChannel::mix_cap(&mut cap_channel, &verifier.cap);
+        assert_eq!(cap_channel.digest(), channel.digest());
     }
 }
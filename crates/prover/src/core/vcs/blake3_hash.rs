@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::core::vcs::hash::Hash;
 
 // Wrapper for the blake3 hash type.
-#[derive(Clone, Copy, PartialEq, Default, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Default, Eq, Hash, Serialize, Deserialize)]
 pub struct Blake3Hash([u8; 32]);
 
 impl From<Blake3Hash> for Vec<u8> {
@@ -8,6 +8,7 @@ pub trait Hash:
     + Display
     + Debug
     + Eq
+    + std::hash::Hash
     + Send
     + Sync
     + 'static
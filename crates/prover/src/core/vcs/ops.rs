@@ -1,10 +1,14 @@
 use std::fmt::Debug;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::core::backend::{Col, ColumnOps};
+use crate::core::backend::{Col, Column, ColumnOps};
 use crate::core::fields::m31::BaseField;
 use crate::core::vcs::hash::Hash;
+use crate::core::MaybeSync;
+use crate::parallel_iter;
 
 /// A Merkle node hash is a hash of:
 ///   [left_child_hash, right_child_hash], column0_value, column1_value, ...
@@ -13,19 +17,46 @@ use crate::core::vcs::hash::Hash;
 /// children hashes.
 /// At each layer, the tree may have multiple columns of the same length as the layer.
 /// Each node in that layer contains one value from each column.
+///
+/// A node can also be hashed incrementally, adding its column values in any number of parts.
 pub trait MerkleHasher: Debug + Default + Clone {
     type Hash: Hash;
+    /// The partial hash of a node, to which more column values can be added.
+    type NodeState: Send;
+
+    /// Starts hashing a node with the given children hashes.
+    fn start_node(children_hashes: Option<(Self::Hash, Self::Hash)>) -> Self::NodeState;
+
+    /// Adds the next column values to a partially hashed node.
+    fn update_node(state: &mut Self::NodeState, column_values: &[BaseField]);
+
+    /// Returns the hash of a node after all its column values were added.
+    fn finalize_node(state: Self::NodeState) -> Self::Hash;
+
     /// Hashes a single Merkle node. See [MerkleHasher] for more details.
     fn hash_node(
         children_hashes: Option<(Self::Hash, Self::Hash)>,
         column_values: &[BaseField],
-    ) -> Self::Hash;
+    ) -> Self::Hash {
+        let mut state = Self::start_node(children_hashes);
+        Self::update_node(&mut state, column_values);
+        Self::finalize_node(state)
+    }
 }
 
 /// Trait for performing Merkle operations on a commitment scheme.
 pub trait MerkleOps<H: MerkleHasher>:
     ColumnOps<BaseField> + ColumnOps<H::Hash> + for<'de> Deserialize<'de> + Serialize
 {
+    /// The partial hashes of the nodes of a layer, to which the columns of the layer are added
+    /// incrementally. See [Self::start_layer].
+    ///
+    /// It is kept in memory while the layer is hashed, so its size per node bounds the memory of
+    /// streamed commitments: 32 to 92 bytes per node for
+    /// [SimdBackend](crate::core::backend::simd::SimdBackend) with Blake2s, and a
+    /// [MerkleHasher::NodeState] per node for [NodeStates].
+    type LayerState;
+
     /// Commits on an entire layer of the Merkle tree.
     /// See [MerkleHasher] for more details.
     ///
@@ -44,4 +75,90 @@ pub trait MerkleOps<H: MerkleHasher>:
         prev_layer: Option<&Col<Self, H::Hash>>,
         columns: &[&Col<Self, BaseField>],
     ) -> Col<Self, H::Hash>;
+
+    /// Starts hashing the layer of 2^`log_size` nodes incrementally, for columns that are not all
+    /// available at once. Returns the partial hashes of the nodes.
+    ///
+    /// `prev_layer` is as in [Self::commit_on_layer].
+    fn start_layer(log_size: u32, prev_layer: Option<&Col<Self, H::Hash>>) -> Self::LayerState;
+
+    /// Adds the next `columns` of a layer to the partial hashes of its nodes.
+    fn update_layer(state: &mut Self::LayerState, columns: &[&Col<Self, BaseField>]);
+
+    /// Finishes hashing a layer, returning the same hashes as [Self::commit_on_layer] on all the
+    /// columns added to it.
+    fn finish_layer(state: Self::LayerState) -> Col<Self, H::Hash>;
+
+    /// Returns the values of `columns` at each of the `rows`, by row.
+    ///
+    /// Backends whose columns can be shared between threads override it to read the rows in
+    /// parallel when the `parallel` feature is enabled.
+    fn read_rows(columns: &[&Col<Self, BaseField>], rows: &[usize]) -> Vec<Vec<BaseField>> {
+        rows.iter()
+            .map(|&row| columns.iter().map(|column| column.at(row)).collect())
+            .collect()
+    }
+}
+
+/// Reads the rows of [MerkleOps::read_rows] in parallel.
+#[cfg(feature = "parallel")]
+pub fn par_read_rows<C: Column<BaseField> + Sync>(
+    columns: &[&C],
+    rows: &[usize],
+) -> Vec<Vec<BaseField>> {
+    rows.par_iter()
+        .map(|&row| columns.iter().map(|column| column.at(row)).collect())
+        .collect()
+}
+
+/// A [MerkleOps::LayerState] that keeps a [MerkleHasher::NodeState] per node of the layer, for
+/// backends that hash the nodes one at a time.
+///
+/// It takes `size_of::<H::NodeState>()` bytes per node, which is 104 bytes for
+/// [Blake2sMerkleHasher](super::blake2_merkle::Blake2sMerkleHasher).
+pub struct NodeStates<H: MerkleHasher>(Vec<H::NodeState>);
+
+impl<H: MerkleHasher> NodeStates<H> {
+    /// See [MerkleOps::start_layer].
+    pub fn start<C: Column<H::Hash> + MaybeSync>(log_size: u32, prev_layer: Option<&C>) -> Self {
+        Self(
+            parallel_iter!(0..1 << log_size)
+                .map(|i| {
+                    H::start_node(
+                        prev_layer
+                            .map(|prev_layer| (prev_layer.at(2 * i), prev_layer.at(2 * i + 1))),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// See [MerkleOps::update_layer].
+    pub fn update<C: Column<BaseField> + MaybeSync>(&mut self, columns: &[&C]) {
+        let update_node = |values: &mut Vec<BaseField>, (i, state): (usize, &mut H::NodeState)| {
+            values.clear();
+            values.extend(columns.iter().map(|column| column.at(i)));
+            H::update_node(state, values);
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        {
+            let mut values = Vec::with_capacity(columns.len());
+            self.0
+                .iter_mut()
+                .enumerate()
+                .for_each(|node| update_node(&mut values, node));
+        }
+
+        #[cfg(feature = "parallel")]
+        self.0
+            .par_iter_mut()
+            .enumerate()
+            .for_each_init(|| Vec::with_capacity(columns.len()), update_node);
+    }
+
+    /// See [MerkleOps::finish_layer].
+    pub fn finish<C: FromIterator<H::Hash>>(self) -> C {
+        self.0.into_iter().map(H::finalize_node).collect()
+    }
 }
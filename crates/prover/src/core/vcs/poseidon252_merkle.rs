@@ -1,6 +1,6 @@
 use num_traits::Zero;
 use serde::{Deserialize, Serialize};
-use starknet_crypto::{poseidon_hash, poseidon_hash_many};
+use starknet_crypto::{poseidon_hash, PoseidonHasher};
 use starknet_ff::FieldElement as FieldElement252;
 
 use super::ops::MerkleHasher;
@@ -8,39 +8,65 @@ use crate::core::channel::{MerkleChannel, Poseidon252Channel};
 use crate::core::fields::m31::BaseField;
 use crate::core::vcs::hash::Hash;
 
-const ELEMENTS_IN_BLOCK: usize = 8;
+pub(crate) const ELEMENTS_IN_BLOCK: usize = 8;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default, Deserialize, Serialize)]
 pub struct Poseidon252MerkleHasher;
 impl MerkleHasher for Poseidon252MerkleHasher {
     type Hash = FieldElement252;
+    type NodeState = Poseidon252NodeState;
 
-    fn hash_node(
-        children_hashes: Option<(Self::Hash, Self::Hash)>,
-        column_values: &[BaseField],
-    ) -> Self::Hash {
-        let n_column_blocks = column_values.len().div_ceil(ELEMENTS_IN_BLOCK);
-        let values_len = 2 + n_column_blocks;
-        let mut values = Vec::with_capacity(values_len);
-
+    fn start_node(children_hashes: Option<(Self::Hash, Self::Hash)>) -> Self::NodeState {
+        let mut hasher = PoseidonHasher::new();
         if let Some((left, right)) = children_hashes {
-            values.push(left);
-            values.push(right);
+            hasher.update(left);
+            hasher.update(right);
+        }
+        Poseidon252NodeState {
+            hasher,
+            pending: [BaseField::zero(); ELEMENTS_IN_BLOCK],
+            n_pending: 0,
         }
+    }
 
-        let padding_length = ELEMENTS_IN_BLOCK * n_column_blocks - column_values.len();
-        let padded_values = column_values
-            .iter()
-            .copied()
-            .chain(std::iter::repeat(BaseField::zero()).take(padding_length));
-        for chunk in padded_values.array_chunks::<ELEMENTS_IN_BLOCK>() {
-            let mut word = FieldElement252::default();
-            for x in chunk {
-                word = word * FieldElement252::from(2u64.pow(31)) + FieldElement252::from(x.0);
+    fn update_node(state: &mut Self::NodeState, column_values: &[BaseField]) {
+        for &value in column_values {
+            state.pending[state.n_pending] = value;
+            state.n_pending += 1;
+            if state.n_pending == ELEMENTS_IN_BLOCK {
+                state.absorb_pending();
             }
-            values.push(word);
         }
-        poseidon_hash_many(&values)
+    }
+
+    fn finalize_node(mut state: Self::NodeState) -> Self::Hash {
+        // The last block of column values is padded with zeros.
+        if state.n_pending > 0 {
+            state.pending[state.n_pending..].fill(BaseField::zero());
+            state.absorb_pending();
+        }
+        state.hasher.finalize()
+    }
+}
+
+/// A partially hashed node of a [Poseidon252MerkleHasher] tree: the sponge, and the column values
+/// that don't fill a block yet.
+#[derive(Debug)]
+pub struct Poseidon252NodeState {
+    hasher: PoseidonHasher,
+    pending: [BaseField; ELEMENTS_IN_BLOCK],
+    n_pending: usize,
+}
+
+impl Poseidon252NodeState {
+    /// Packs the pending block of column values into a single field element and absorbs it.
+    fn absorb_pending(&mut self) {
+        let mut word = FieldElement252::default();
+        for x in self.pending {
+            word = word * FieldElement252::from(2u64.pow(31)) + FieldElement252::from(x.0);
+        }
+        self.hasher.update(word);
+        self.n_pending = 0;
     }
 }
 
@@ -60,6 +86,7 @@ impl MerkleChannel for Poseidon252MerkleChannel {
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
     use num_traits::Zero;
     use starknet_ff::FieldElement as FieldElement252;
 
@@ -92,6 +119,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hash_node_in_parts() {
+        let children = Some((FieldElement252::from(1u32), FieldElement252::from(2u32)));
+        let values = (0..21).map(BaseField::from).collect_vec();
+
+        let mut state = Poseidon252MerkleHasher::start_node(children);
+        for part in [&values[..3], &values[3..3], &values[3..12], &values[12..]] {
+            Poseidon252MerkleHasher::update_node(&mut state, part);
+        }
+
+        assert_eq!(
+            Poseidon252MerkleHasher::finalize_node(state),
+            Poseidon252MerkleHasher::hash_node(children, &values)
+        );
+    }
+
     #[test]
     fn test_merkle_success() {
         let (queries, decommitment, values, verifier) = prepare_merkle::<Poseidon252MerkleHasher>();
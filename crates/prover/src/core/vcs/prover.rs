@@ -1,15 +1,19 @@
 use std::cmp::Reverse;
 use std::collections::BTreeMap;
+use std::iter::zip;
 
 use itertools::Itertools;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use super::ops::{MerkleHasher, MerkleOps};
-use super::utils::{next_decommitment_node, option_flatten_peekable};
+use super::utils::{cap_log_size, next_decommitment_node, option_flatten_peekable};
 use crate::core::backend::{Col, Column};
 use crate::core::fields::m31::BaseField;
 use crate::core::utils::PeekableExt;
-use crate::core::ColumnVec;
+use crate::core::{ColumnVec, MaybeSync};
+use crate::parallel_iter;
 
 pub struct MerkleProver<B: MerkleOps<H>, H: MerkleHasher> {
     /// Layers of the Merkle tree.
@@ -44,22 +48,19 @@ impl<B: MerkleOps<H>, H: MerkleHasher> MerkleProver<B, H> {
             .into_iter()
             .sorted_by_key(|c| Reverse(c.len()))
             .peekable();
-        let mut layers: Vec<Col<B, H::Hash>> = Vec::new();
-
-        let max_log_size = columns.peek().unwrap().len().ilog2();
-        for log_size in (0..=max_log_size).rev() {
+        let mut builder = MerkleProverBuilder::new();
+        while let Some(column) = columns.peek() {
+            let log_size = column.len().ilog2();
             // Take columns of the current log_size.
             let layer_columns = columns
                 .peek_take_while(|column| column.len().ilog2() == log_size)
                 .collect_vec();
-
-            layers.push(B::commit_on_layer(log_size, layers.last(), &layer_columns));
+            builder.commit_layer(log_size, &layer_columns);
         }
-        layers.reverse();
-        Self { layers }
+        builder.finish()
     }
 
-    /// Decommits to columns on the given queries.
+    /// Decommits to columns on the given queries, with paths up to the root.
     /// Queries are given as indices to the largest column.
     ///
     /// # Arguments
@@ -78,6 +79,94 @@ impl<B: MerkleOps<H>, H: MerkleHasher> MerkleProver<B, H> {
         queries_per_log_size: BTreeMap<u32, Vec<usize>>,
         columns: Vec<&Col<B, BaseField>>,
     ) -> (ColumnVec<Vec<BaseField>>, MerkleDecommitment<H>) {
+        self.decommit_to_cap(queries_per_log_size, columns, 0)
+    }
+
+    /// Decommits to columns on the given queries, like [Self::decommit], with paths up to the
+    /// layer of `2^log_cap_size` nodes only. That layer, the cap (see [Self::cap]), is the
+    /// commitment the verifier holds, so the paths are `log_cap_size` hashes shorter.
+    ///
+    /// The cap is lowered to the smallest column if needed (see [cap_log_size]).
+    pub fn decommit_to_cap(
+        &self,
+        queries_per_log_size: BTreeMap<u32, Vec<usize>>,
+        columns: Vec<&Col<B, BaseField>>,
+        log_cap_size: u32,
+    ) -> (ColumnVec<Vec<BaseField>>, MerkleDecommitment<H>) {
+        let column_log_sizes = columns.iter().map(|c| c.len().ilog2()).collect_vec();
+        self.decommit_rows_to_cap(
+            queries_per_log_size,
+            &column_log_sizes,
+            |layer_columns, rows| {
+                let layer_columns = layer_columns
+                    .iter()
+                    .map(|&column| columns[column])
+                    .collect_vec();
+                B::read_rows(&layer_columns, rows)
+            },
+            log_cap_size,
+        )
+    }
+
+    /// Returns the rows of the columns of each log size that [Self::decommit_to_cap] reads, in
+    /// increasing order.
+    pub fn decommitment_positions(
+        &self,
+        queries_per_log_size: &BTreeMap<u32, Vec<usize>>,
+        column_log_sizes: &[u32],
+        log_cap_size: u32,
+    ) -> BTreeMap<u32, Vec<usize>> {
+        let log_cap_size = cap_log_size(log_cap_size, column_log_sizes.iter().copied());
+        let nodes_by_layer = self.decommitment_nodes(queries_per_log_size, log_cap_size);
+        zip(
+            (log_cap_size..self.layers.len() as u32).rev(),
+            nodes_by_layer,
+        )
+        .filter(|(log_size, _)| column_log_sizes.contains(log_size))
+        .map(|(log_size, nodes)| (log_size, nodes.into_iter().map(|(node, _)| node).collect()))
+        .collect()
+    }
+
+    /// Decommits like [Self::decommit_to_cap], on columns of log sizes `column_log_sizes` whose
+    /// values are read with `value(column, row)`. Only the rows of
+    /// [Self::decommitment_positions] are read, so the columns don't need to be held whole.
+    ///
+    /// The rows of each layer are read in parallel when the `parallel` feature is enabled.
+    pub fn decommit_values_to_cap(
+        &self,
+        queries_per_log_size: BTreeMap<u32, Vec<usize>>,
+        column_log_sizes: &[u32],
+        value: impl Fn(usize, usize) -> BaseField + MaybeSync,
+        log_cap_size: u32,
+    ) -> (ColumnVec<Vec<BaseField>>, MerkleDecommitment<H>) {
+        self.decommit_rows_to_cap(
+            queries_per_log_size,
+            column_log_sizes,
+            |layer_columns, rows| {
+                parallel_iter!(rows)
+                    .map(|&row| {
+                        layer_columns
+                            .iter()
+                            .map(|&column| value(column, row))
+                            .collect()
+                    })
+                    .collect()
+            },
+            log_cap_size,
+        )
+    }
+
+    /// Decommits like [Self::decommit_values_to_cap], where `read_rows(layer_columns, rows)`
+    /// returns the values of the columns of a layer, given by index, at each of the rows.
+    fn decommit_rows_to_cap(
+        &self,
+        queries_per_log_size: BTreeMap<u32, Vec<usize>>,
+        column_log_sizes: &[u32],
+        mut read_rows: impl FnMut(&[usize], &[usize]) -> Vec<Vec<BaseField>>,
+        log_cap_size: u32,
+    ) -> (ColumnVec<Vec<BaseField>>, MerkleDecommitment<H>) {
+        let log_cap_size = cap_log_size(log_cap_size, column_log_sizes.iter().copied());
+
         // Check that queries are sorted and deduped.
         // TODO(andrew): Consider using a Queries struct to prevent this.
         for queries in queries_per_log_size.values() {
@@ -91,86 +180,106 @@ impl<B: MerkleOps<H>, H: MerkleHasher> MerkleProver<B, H> {
         let mut queried_values_by_layer = vec![];
         let mut decommitment = MerkleDecommitment::empty();
 
-        // Sort columns by layer.
-        let mut columns_by_layer = columns
-            .iter()
-            .sorted_by_key(|c| Reverse(c.len()))
-            .peekable();
+        // Group the columns by layer, keeping their order.
+        let mut columns_by_layer = BTreeMap::<u32, Vec<usize>>::new();
+        for (column, &log_size) in column_log_sizes.iter().enumerate() {
+            columns_by_layer.entry(log_size).or_default().push(column);
+        }
 
-        let mut last_layer_queries = vec![];
-        for layer_log_size in (0..self.layers.len() as u32).rev() {
+        let nodes_by_layer = self.decommitment_nodes(&queries_per_log_size, log_cap_size);
+        let mut last_layer_nodes: &[(usize, bool)] = &[];
+        for (layer_log_size, layer_nodes) in zip(
+            (log_cap_size..self.layers.len() as u32).rev(),
+            &nodes_by_layer,
+        ) {
             // Prepare write buffer for queried values to the current layer.
             let mut layer_queried_values = vec![];
 
-            // Prepare write buffer for queries to the current layer. This will propagate to the
-            // next layer.
-            let mut layer_total_queries = vec![];
-
             // Each layer node is a hash of column values as previous layer hashes.
             // Prepare the relevant columns and previous layer hashes to read from.
             let layer_columns = columns_by_layer
-                .peek_take_while(|column| column.len().ilog2() == layer_log_size)
-                .collect_vec();
+                .get(&layer_log_size)
+                .map_or(&[][..], |columns| columns);
             let previous_layer_hashes = self.layers.get(layer_log_size as usize + 1);
 
-            // Queries to this layer come from queried node in the previous layer and queried
-            // columns in this one.
-            let mut prev_layer_queries = last_layer_queries.into_iter().peekable();
-            let mut layer_column_queries =
-                option_flatten_peekable(queries_per_log_size.get(&layer_log_size));
-
-            // Merge previous layer queries and column queries.
-            while let Some(node_index) =
-                next_decommitment_node(&mut prev_layer_queries, &mut layer_column_queries)
-            {
-                if let Some(previous_layer_hashes) = previous_layer_hashes {
-                    // If the left child was not computed, add it to the witness.
-                    if prev_layer_queries.next_if_eq(&(2 * node_index)).is_none() {
-                        decommitment
-                            .hash_witness
-                            .push(previous_layer_hashes.at(2 * node_index));
-                    }
-
-                    // If the right child was not computed, add it to the witness.
-                    if prev_layer_queries
-                        .next_if_eq(&(2 * node_index + 1))
-                        .is_none()
-                    {
-                        decommitment
-                            .hash_witness
-                            .push(previous_layer_hashes.at(2 * node_index + 1));
+            // The children on the paths are computed by the verifier, the others are witnesses.
+            if let Some(previous_layer_hashes) = previous_layer_hashes {
+                let mut prev_layer_queries = last_layer_nodes.iter().map(|&(n, _)| n).peekable();
+                for &(node_index, _) in layer_nodes {
+                    for child_index in [2 * node_index, 2 * node_index + 1] {
+                        if prev_layer_queries.next_if_eq(&child_index).is_none() {
+                            decommitment
+                                .hash_witness
+                                .push(previous_layer_hashes.at(child_index));
+                        }
                     }
                 }
+            }
 
+            let rows = layer_nodes.iter().map(|&(node, _)| node).collect_vec();
+            let layer_node_values = read_rows(layer_columns, &rows);
+            for (&(_, is_queried), node_values) in zip(layer_nodes, layer_node_values) {
                 // If the column values were queried, return them.
-                let node_values = layer_columns.iter().map(|c| c.at(node_index));
-                if layer_column_queries.next_if_eq(&node_index).is_some() {
-                    layer_queried_values.push(node_values.collect_vec());
+                if is_queried {
+                    layer_queried_values.push(node_values);
                 } else {
                     // Otherwise, add them to the witness.
                     decommitment.column_witness.extend(node_values);
                 }
-
-                layer_total_queries.push(node_index);
             }
 
             queried_values_by_layer.push(layer_queried_values);
-
-            // Propagate queries to the next layer.
-            last_layer_queries = layer_total_queries;
+            last_layer_nodes = layer_nodes;
         }
+        // There are no columns below the cap.
+        queried_values_by_layer.extend((0..log_cap_size).map(|_| vec![]));
         queried_values_by_layer.reverse();
 
         // Rearrange returned queried values according to input, and not by layer.
-        let queried_values = Self::rearrange_queried_values(queried_values_by_layer, columns);
+        let queried_values =
+            Self::rearrange_queried_values(queried_values_by_layer, column_log_sizes);
 
         (queried_values, decommitment)
     }
 
+    /// Returns the nodes on the decommitment paths of each layer, from the largest layer down to
+    /// the layer of `2^log_cap_size` nodes, and whether their column values are queried.
+    fn decommitment_nodes(
+        &self,
+        queries_per_log_size: &BTreeMap<u32, Vec<usize>>,
+        log_cap_size: u32,
+    ) -> Vec<Vec<(usize, bool)>> {
+        let mut nodes_by_layer = vec![];
+        let mut last_layer_queries = vec![];
+        for layer_log_size in (log_cap_size..self.layers.len() as u32).rev() {
+            // Queries to this layer come from queried node in the previous layer and queried
+            // columns in this one.
+            let mut prev_layer_queries = last_layer_queries.into_iter().peekable();
+            let mut layer_column_queries =
+                option_flatten_peekable(queries_per_log_size.get(&layer_log_size));
+
+            // Merge previous layer queries and column queries.
+            let mut layer_nodes = vec![];
+            while let Some(node_index) =
+                next_decommitment_node(&mut prev_layer_queries, &mut layer_column_queries)
+            {
+                prev_layer_queries.next_if_eq(&(2 * node_index));
+                prev_layer_queries.next_if_eq(&(2 * node_index + 1));
+                let is_queried = layer_column_queries.next_if_eq(&node_index).is_some();
+                layer_nodes.push((node_index, is_queried));
+            }
+
+            // Propagate queries to the next layer.
+            last_layer_queries = layer_nodes.iter().map(|&(n, _)| n).collect_vec();
+            nodes_by_layer.push(layer_nodes);
+        }
+        nodes_by_layer
+    }
+
     /// Given queried values by layer, rearranges in the order of input columns.
     fn rearrange_queried_values(
         queried_values_by_layer: Vec<Vec<Vec<BaseField>>>,
-        columns: Vec<&Col<B, BaseField>>,
+        column_log_sizes: &[u32],
     ) -> Vec<Vec<BaseField>> {
         // Turn each column queried values into an iterator.
         let mut queried_values_by_layer = queried_values_by_layer
@@ -184,11 +293,11 @@ impl<B: MerkleOps<H>, H: MerkleHasher> MerkleProver<B, H> {
             .collect_vec();
 
         // For each input column, fetch the queried values from the corresponding layer.
-        let queried_values = columns
+        let queried_values = column_log_sizes
             .iter()
-            .map(|column| {
+            .map(|&log_size| {
                 queried_values_by_layer
-                    .get_mut(column.len().ilog2() as usize)
+                    .get_mut(log_size as usize)
                     .unwrap()
                     .iter_mut()
                     .map(|x| x.next().unwrap())
@@ -201,6 +310,125 @@ impl<B: MerkleOps<H>, H: MerkleHasher> MerkleProver<B, H> {
     pub fn root(&self) -> H::Hash {
         self.layers.first().unwrap().at(0)
     }
+
+    /// Returns the layer of `2^log_cap_size` nodes, from which the root is hashed. It commits to
+    /// the columns of log size at least `log_cap_size`, and is the root alone if `log_cap_size` is
+    /// 0.
+    pub fn cap(&self, log_cap_size: u32) -> Vec<H::Hash> {
+        self.layers[log_cap_size as usize].to_cpu()
+    }
+}
+
+/// Builds a [MerkleProver] one layer at a time, from the largest layer to the root.
+///
+/// The columns of a layer are only needed while that layer is hashed, so they can be generated
+/// just before [Self::commit_layer] and dropped right after. The columns of a layer may also be
+/// added in several parts with [Self::add_columns], which keeps a partial hash of each node
+/// instead of the columns (see [MerkleOps::LayerState] for its size). The resulting tree is the
+/// same as [MerkleProver::commit] on all the columns.
+pub struct MerkleProverBuilder<B: MerkleOps<H>, H: MerkleHasher> {
+    /// The committed layers, from the largest layer up.
+    layers: Vec<Col<B, H::Hash>>,
+    /// The log size and the partial node hashes of the layer that columns are being added to.
+    current: Option<(u32, B::LayerState)>,
+}
+
+impl<B: MerkleOps<H>, H: MerkleHasher> MerkleProverBuilder<B, H> {
+    pub fn new() -> Self {
+        Self {
+            layers: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// Commits on the layer of log size `log_size`, with all the columns of that size. The layers
+    /// between the previously committed layer and this one have no columns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `log_size` is not smaller than the log size of the previously committed layer, or
+    /// if a column is not of size `2^log_size`.
+    pub fn commit_layer(&mut self, log_size: u32, columns: &[&Col<B, BaseField>]) {
+        self.finish_current_layer();
+        self.push_empty_layers(log_size, columns);
+        self.push_layer(log_size, columns);
+    }
+
+    /// Adds columns to the layer of log size `log_size`, which is committed once columns of a
+    /// smaller log size are added, or on [Self::finish]. Only a partial hash of each node of the
+    /// layer is kept, so the columns can be dropped right after.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `log_size` is larger than the log size of the layer columns were last added to,
+    /// or not smaller than the log size of the previously committed layer, or if a column is not
+    /// of size `2^log_size`.
+    pub fn add_columns(&mut self, log_size: u32, columns: &[&Col<B, BaseField>]) {
+        if !matches!(self.current, Some((current_log_size, _)) if current_log_size == log_size) {
+            self.finish_current_layer();
+            self.push_empty_layers(log_size, columns);
+            let state = B::start_layer(log_size, self.layers.last());
+            self.current = Some((log_size, state));
+        }
+        let (_, state) = self.current.as_mut().unwrap();
+        B::update_layer(state, columns);
+    }
+
+    /// Commits on the remaining layers up to the root, which have no columns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no layer was committed.
+    pub fn finish(mut self) -> MerkleProver<B, H> {
+        self.finish_current_layer();
+        let last_log_size = self.last_log_size().expect("no layer was committed");
+        for log_size in (0..last_log_size).rev() {
+            self.push_layer(log_size, &[]);
+        }
+        self.layers.reverse();
+        MerkleProver {
+            layers: self.layers,
+        }
+    }
+
+    fn last_log_size(&self) -> Option<u32> {
+        self.layers.last().map(|layer| layer.len().ilog2())
+    }
+
+    /// Checks that `columns` can be committed in a layer of log size `log_size`, and commits on
+    /// the layers above it, which have no columns.
+    fn push_empty_layers(&mut self, log_size: u32, columns: &[&Col<B, BaseField>]) {
+        assert!(
+            columns.iter().all(|column| column.len() == 1 << log_size),
+            "columns are not of log size {log_size}"
+        );
+        if let Some(last_log_size) = self.last_log_size() {
+            assert!(
+                log_size < last_log_size,
+                "layers must be committed in decreasing log size"
+            );
+            for empty_log_size in (log_size + 1..last_log_size).rev() {
+                self.push_layer(empty_log_size, &[]);
+            }
+        }
+    }
+
+    fn push_layer(&mut self, log_size: u32, columns: &[&Col<B, BaseField>]) {
+        let layer = B::commit_on_layer(log_size, self.layers.last(), columns);
+        self.layers.push(layer);
+    }
+
+    fn finish_current_layer(&mut self) {
+        if let Some((_, state)) = self.current.take() {
+            self.layers.push(B::finish_layer(state));
+        }
+    }
+}
+
+impl<B: MerkleOps<H>, H: MerkleHasher> Default for MerkleProverBuilder<B, H> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd)]
@@ -222,3 +450,74 @@ impl<H: MerkleHasher> MerkleDecommitment<H> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::MerkleProver;
+    use crate::core::backend::simd::column::BaseColumn;
+    use crate::core::backend::simd::SimdBackend;
+    use crate::core::backend::Column;
+    use crate::core::fields::m31::BaseField;
+    use crate::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+
+    #[test]
+    fn test_parallel_decommit_matches_sequential() {
+        const LOG_CAP_SIZE: u32 = 2;
+        const N_QUERIES: usize = 200;
+        let mut rng = SmallRng::seed_from_u64(0);
+        let columns = (0..20)
+            .map(|_| {
+                let log_size = rng.gen_range(4..10);
+                (0..1 << log_size)
+                    .map(|_| rng.gen())
+                    .collect::<BaseColumn>()
+            })
+            .collect_vec();
+        let column_log_sizes = columns.iter().map(|c| c.len().ilog2()).collect_vec();
+        let queries = (4..10)
+            .map(|log_size| {
+                let layer_queries = (0..N_QUERIES)
+                    .map(|_| rng.gen_range(0..1 << log_size))
+                    .sorted()
+                    .dedup()
+                    .collect_vec();
+                (log_size, layer_queries)
+            })
+            .collect::<BTreeMap<_, _>>();
+        let merkle =
+            MerkleProver::<SimdBackend, Blake2sMerkleHasher>::commit(columns.iter().collect());
+
+        let sequential = merkle.decommit_rows_to_cap(
+            queries.clone(),
+            &column_log_sizes,
+            |layer_columns, rows| {
+                rows.iter()
+                    .map(|&row| {
+                        layer_columns
+                            .iter()
+                            .map(|&column| columns[column].at(row))
+                            .collect::<Vec<BaseField>>()
+                    })
+                    .collect()
+            },
+            LOG_CAP_SIZE,
+        );
+        let parallel_columns =
+            merkle.decommit_to_cap(queries.clone(), columns.iter().collect(), LOG_CAP_SIZE);
+        let parallel_values = merkle.decommit_values_to_cap(
+            queries,
+            &column_log_sizes,
+            |column, row| columns[column].at(row),
+            LOG_CAP_SIZE,
+        );
+
+        assert_eq!(parallel_columns, sequential);
+        assert_eq!(parallel_values, sequential);
+    }
+}
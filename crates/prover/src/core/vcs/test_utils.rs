@@ -6,8 +6,9 @@ use rand::{Rng, SeedableRng};
 
 use super::ops::{MerkleHasher, MerkleOps};
 use super::prover::MerkleDecommitment;
+use super::utils::cap_log_size;
 use super::verifier::MerkleVerifier;
-use crate::core::backend::CpuBackend;
+use crate::core::backend::{Col, Column, CpuBackend};
 use crate::core::fields::m31::BaseField;
 use crate::core::vcs::prover::MerkleProver;
 
@@ -19,6 +20,14 @@ pub type TestData<H> = (
 );
 
 pub fn prepare_merkle<H: MerkleHasher>() -> TestData<H>
+where
+    CpuBackend: MerkleOps<H>,
+{
+    prepare_merkle_with_cap(0)
+}
+
+/// Like [prepare_merkle], with decommitment paths up to a cap of `2^log_cap_size` nodes.
+pub fn prepare_merkle_with_cap<H: MerkleHasher>(log_cap_size: u32) -> TestData<H>
 where
     CpuBackend: MerkleOps<H>,
 {
@@ -50,11 +59,108 @@ where
         queries.insert(log_size, layer_queries);
     }
 
-    let (values, decommitment) = merkle.decommit(queries.clone(), cols.iter().collect_vec());
+    let (values, decommitment) =
+        merkle.decommit_to_cap(queries.clone(), cols.iter().collect_vec(), log_cap_size);
 
-    let verifier = MerkleVerifier {
-        root: merkle.root(),
-        column_log_sizes: log_sizes,
-    };
+    let cap = merkle.cap(cap_log_size(log_cap_size, log_sizes.iter().copied()));
+    let verifier = MerkleVerifier::with_cap(cap, log_sizes, log_cap_size);
     (queries, decommitment, values, verifier)
 }
+
+/// Commits to and decommits random columns with `B`, and checks that the tree and the
+/// decommitment are the same as with [CpuBackend].
+pub fn assert_merkle_matches_cpu<B: MerkleOps<H>, H: MerkleHasher>()
+where
+    CpuBackend: MerkleOps<H>,
+{
+    const N_COLS: usize = 20;
+    const N_QUERIES: usize = 10;
+    // Includes layers smaller than a SIMD vector.
+    let log_size_range = 0..10;
+
+    let mut rng = SmallRng::seed_from_u64(0);
+    let cols = (0..N_COLS)
+        .map(|_| {
+            let log_size = rng.gen_range(log_size_range.clone());
+            (0..1 << log_size)
+                .map(|_| BaseField::from(rng.gen_range(0..(1 << 30))))
+                .collect_vec()
+        })
+        .collect_vec();
+    let queries = log_size_range
+        .map(|log_size| {
+            let layer_queries = (0..N_QUERIES)
+                .map(|_| rng.gen_range(0..1 << log_size))
+                .sorted()
+                .dedup()
+                .collect_vec();
+            (log_size, layer_queries)
+        })
+        .collect::<BTreeMap<_, _>>();
+    let backend_cols = cols
+        .iter()
+        .map(|col| col.iter().copied().collect::<Col<B, BaseField>>())
+        .collect_vec();
+
+    let cpu_merkle = MerkleProver::<CpuBackend, H>::commit(cols.iter().collect_vec());
+    let merkle = MerkleProver::<B, H>::commit(backend_cols.iter().collect_vec());
+
+    assert_eq!(
+        merkle
+            .layers
+            .iter()
+            .map(|layer| layer.to_cpu())
+            .collect_vec(),
+        cpu_merkle
+            .layers
+            .iter()
+            .map(|layer| layer.to_cpu())
+            .collect_vec()
+    );
+    let (values, decommitment) =
+        merkle.decommit(queries.clone(), backend_cols.iter().collect_vec());
+    let (cpu_values, cpu_decommitment) = cpu_merkle.decommit(queries, cols.iter().collect_vec());
+    assert_eq!(values, cpu_values);
+    assert_eq!(decommitment.hash_witness, cpu_decommitment.hash_witness);
+    assert_eq!(decommitment.column_witness, cpu_decommitment.column_witness);
+}
+
+/// Hashes layers of random columns incrementally with `B`, adding the columns in parts of various
+/// sizes, and checks that the layers are the same as with [MerkleOps::commit_on_layer].
+pub fn assert_layer_ops_match_commit_on_layer<B: MerkleOps<H>, H: MerkleHasher>() {
+    // The sizes of the parts the columns are added in, one after the other.
+    const PART_SIZES: [usize; 6] = [3, 0, 16, 1, 15, 5];
+    let n_cols = PART_SIZES.iter().sum();
+
+    let mut rng = SmallRng::seed_from_u64(0);
+    // Includes layers smaller than a SIMD vector.
+    for log_size in [0, 3, 4, 7] {
+        let mut random_column = |log_size: u32| {
+            (0..1 << log_size)
+                .map(|_| BaseField::from(rng.gen_range(0..(1 << 30))))
+                .collect::<Col<B, BaseField>>()
+        };
+        let leaf_column = random_column(log_size + 1);
+        let columns = (0..n_cols).map(|_| random_column(log_size)).collect_vec();
+        let leaf_layer = B::commit_on_layer(log_size + 1, None, &[&leaf_column]);
+
+        for prev_layer in [None, Some(&leaf_layer)] {
+            let expected = B::commit_on_layer(log_size, prev_layer, &columns.iter().collect_vec());
+
+            let mut state = B::start_layer(log_size, prev_layer);
+            let mut columns_iter = columns.iter();
+            for part_size in PART_SIZES {
+                let part = columns_iter.by_ref().take(part_size).collect_vec();
+                B::update_layer(&mut state, &part);
+            }
+            let layer = B::finish_layer(state);
+
+            assert_eq!(
+                layer.to_cpu(),
+                expected.to_cpu(),
+                "log size {log_size}, with children: {}",
+                prev_layer.is_some()
+            );
+        }
+    }
+}
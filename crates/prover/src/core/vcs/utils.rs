@@ -13,6 +13,17 @@ pub fn next_decommitment_node(
         .min()
 }
 
+/// Returns the log size of the cap of a tree with columns of log sizes `column_log_sizes`, given
+/// the requested `log_cap_size`.
+///
+/// The cap can't be below the smallest column, whose values would not be committed by the cap.
+pub fn cap_log_size(log_cap_size: u32, column_log_sizes: impl IntoIterator<Item = u32>) -> u32 {
+    column_log_sizes
+        .into_iter()
+        .min()
+        .map_or(0, |min_log_size| log_cap_size.min(min_log_size))
+}
+
 pub fn option_flatten_peekable<'a, I: IntoIterator<Item = &'a usize>>(
     a: Option<I>,
 ) -> Peekable<std::iter::Copied<std::iter::Flatten<<Option<I> as IntoIterator>::IntoIter>>> {
@@ -6,22 +6,37 @@ use thiserror::Error;
 
 use super::ops::MerkleHasher;
 use super::prover::MerkleDecommitment;
-use super::utils::{next_decommitment_node, option_flatten_peekable};
+use super::utils::{cap_log_size, next_decommitment_node, option_flatten_peekable};
 use crate::core::fields::m31::BaseField;
 use crate::core::utils::PeekableExt;
 use crate::core::ColumnVec;
 
 pub struct MerkleVerifier<H: MerkleHasher> {
-    pub root: H::Hash,
+    /// The commitment: the layer of `2^log_cap_size` nodes the decommitment paths end at, which is
+    /// the root alone if `log_cap_size` is 0.
+    pub cap: Vec<H::Hash>,
     pub column_log_sizes: Vec<u32>,
+    /// The log size of the cap, before it is lowered to the layer of the smallest column (see
+    /// [cap_log_size]).
+    pub log_cap_size: u32,
 }
 impl<H: MerkleHasher> MerkleVerifier<H> {
     pub fn new(root: H::Hash, column_log_sizes: Vec<u32>) -> Self {
+        Self::with_cap(vec![root], column_log_sizes, 0)
+    }
+
+    /// Creates a verifier of decommitments with paths up to `cap`, the layer of `2^log_cap_size`
+    /// nodes (see [MerkleProver::decommit_to_cap]).
+    ///
+    /// [MerkleProver::decommit_to_cap]: super::prover::MerkleProver::decommit_to_cap
+    pub fn with_cap(cap: Vec<H::Hash>, column_log_sizes: Vec<u32>, log_cap_size: u32) -> Self {
         Self {
-            root,
+            cap,
             column_log_sizes,
+            log_cap_size,
         }
     }
+
     /// Verifies the decommitment of the columns.
     ///
     /// # Arguments
@@ -40,7 +55,11 @@ impl<H: MerkleHasher> MerkleVerifier<H> {
     /// * The witness is too short (missing values).
     /// * The column values are too long (not fully consumed).
     /// * The column values are too short (missing values).
-    /// * The computed root does not match the expected root.
+    /// * The cap is not of the expected size.
+    /// * The computed cap nodes do not match the cap.
+    ///
+    /// The cap is lowered to the layer of the smallest column if it is larger, as the layers below
+    /// it are not committed by the cap.
     ///
     /// # Panics
     ///
@@ -57,6 +76,10 @@ impl<H: MerkleHasher> MerkleVerifier<H> {
         decommitment: MerkleDecommitment<H>,
     ) -> Result<(), MerkleVerificationError> {
         let max_log_size = self.column_log_sizes.iter().max().copied().unwrap_or(0);
+        let log_cap_size = cap_log_size(self.log_cap_size, self.column_log_sizes.iter().copied());
+        if self.cap.len() != 1 << log_cap_size {
+            return Err(MerkleVerificationError::InvalidCapSize);
+        }
 
         // Prepare read buffers.
         let mut queried_values_by_layer = self
@@ -74,7 +97,7 @@ impl<H: MerkleHasher> MerkleVerifier<H> {
         let mut column_witness = decommitment.column_witness.into_iter();
 
         let mut last_layer_hashes: Option<Vec<(usize, H::Hash)>> = None;
-        for layer_log_size in (0..=max_log_size).rev() {
+        for layer_log_size in (log_cap_size..=max_log_size).rev() {
             // Prepare read buffer for queried values to the current layer.
             let mut layer_queried_values = queried_values_by_layer
                 .peek_take_while(|(log_size, _)| *log_size == layer_log_size)
@@ -169,9 +192,11 @@ impl<H: MerkleHasher> MerkleVerifier<H> {
             return Err(MerkleVerificationError::WitnessTooLong);
         }
 
-        let [(_, computed_root)] = last_layer_hashes.unwrap().try_into().unwrap();
-        if computed_root != self.root {
-            return Err(MerkleVerificationError::RootMismatch);
+        // The paths end at the cap.
+        for (node_index, hash) in last_layer_hashes.unwrap() {
+            if hash != self.cap[node_index] {
+                return Err(MerkleVerificationError::RootMismatch);
+            }
         }
 
         Ok(())
@@ -188,6 +213,8 @@ pub enum MerkleVerificationError {
     ColumnValuesTooLong,
     #[error("Column values are too short.")]
     ColumnValuesTooShort,
+    #[error("Cap has an invalid size.")]
+    InvalidCapSize,
     #[error("Root mismatch.")]
     RootMismatch,
 }
@@ -8,7 +8,6 @@ use tracing::{span, Level};
 use super::round::{blake_round_info, BlakeRoundComponent, BlakeRoundEval};
 use super::scheduler::{BlakeSchedulerComponent, BlakeSchedulerEval};
 use super::xor_table::{XorTableComponent, XorTableEval};
-use crate::constraint_framework::constant_columns::gen_is_first;
 use crate::constraint_framework::TraceLocationAllocator;
 use crate::core::air::{Component, ComponentProver};
 use crate::core::backend::simd::m31::LOG_N_LANES;
@@ -16,8 +15,12 @@ use crate::core::backend::simd::SimdBackend;
 use crate::core::backend::BackendForChannel;
 use crate::core::channel::{Channel, MerkleChannel};
 use crate::core::fields::qm31::SecureField;
-use crate::core::pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig, TreeVec};
+use crate::core::pcs::{
+    CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig, PreprocessedColumns,
+    PreprocessedTrace, TreeVec,
+};
 use crate::core::poly::circle::{CanonicCoset, PolyOps};
+use crate::core::poly::twiddles::TwiddleCache;
 use crate::core::prover::{prove, verify, StarkProof, VerificationError};
 use crate::core::vcs::ops::MerkleHasher;
 use crate::examples::blake::round::RoundElements;
@@ -148,38 +151,38 @@ impl BlakeComponents {
                 .collect(),
             xor12: XorTableComponent::new(
                 tree_span_provider,
-                XorTableEval {
-                    lookup_elements: all_elements.xor_elements.xor12.clone(),
-                    claimed_sum: stmt1.xor12_claimed_sum,
-                },
+                XorTableEval::new(
+                    all_elements.xor_elements.xor12.clone(),
+                    stmt1.xor12_claimed_sum,
+                ),
             ),
             xor9: XorTableComponent::new(
                 tree_span_provider,
-                XorTableEval {
-                    lookup_elements: all_elements.xor_elements.xor9.clone(),
-                    claimed_sum: stmt1.xor9_claimed_sum,
-                },
+                XorTableEval::new(
+                    all_elements.xor_elements.xor9.clone(),
+                    stmt1.xor9_claimed_sum,
+                ),
             ),
             xor8: XorTableComponent::new(
                 tree_span_provider,
-                XorTableEval {
-                    lookup_elements: all_elements.xor_elements.xor8.clone(),
-                    claimed_sum: stmt1.xor8_claimed_sum,
-                },
+                XorTableEval::new(
+                    all_elements.xor_elements.xor8.clone(),
+                    stmt1.xor8_claimed_sum,
+                ),
             ),
             xor7: XorTableComponent::new(
                 tree_span_provider,
-                XorTableEval {
-                    lookup_elements: all_elements.xor_elements.xor7.clone(),
-                    claimed_sum: stmt1.xor7_claimed_sum,
-                },
+                XorTableEval::new(
+                    all_elements.xor_elements.xor7.clone(),
+                    stmt1.xor7_claimed_sum,
+                ),
             ),
             xor4: XorTableComponent::new(
                 tree_span_provider,
-                XorTableEval {
-                    lookup_elements: all_elements.xor_elements.xor4.clone(),
-                    claimed_sum: stmt1.xor4_claimed_sum,
-                },
+                XorTableEval::new(
+                    all_elements.xor_elements.xor4.clone(),
+                    stmt1.xor4_claimed_sum,
+                ),
             ),
         }
     }
@@ -216,8 +219,34 @@ impl BlakeComponents {
     }
 }
 
+/// Commits to the constant columns of the xor tables, which are the same for every proof.
+#[allow(unused)]
+pub fn preprocess_blake<MC: MerkleChannel>(config: PcsConfig) -> PreprocessedTrace<SimdBackend, MC>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    let _span = span!(Level::INFO, "Preprocessing").entered();
+    let mut columns = PreprocessedColumns::new();
+    for (table, trace) in [
+        ("xor12", xor_table::generate_constant_trace::<12, 4>()),
+        ("xor9", xor_table::generate_constant_trace::<9, 2>()),
+        ("xor8", xor_table::generate_constant_trace::<8, 2>()),
+        ("xor7", xor_table::generate_constant_trace::<7, 2>()),
+        ("xor4", xor_table::generate_constant_trace::<4, 0>()),
+    ] {
+        for (column, eval) in ["a", "c"].into_iter().zip_eq(trace) {
+            columns.insert(format!("{table}_{column}"), eval);
+        }
+    }
+    PreprocessedTrace::commit(columns, config, &TwiddleCache::default())
+}
+
 #[allow(unused)]
-pub fn prove_blake<MC: MerkleChannel>(log_size: u32, config: PcsConfig) -> (BlakeProof<MC::H>)
+pub fn prove_blake<MC: MerkleChannel>(
+    log_size: u32,
+    config: PcsConfig,
+    preprocessed: &PreprocessedTrace<SimdBackend, MC>,
+) -> (BlakeProof<MC::H>)
 where
     SimdBackend: BackendForChannel<MC>,
 {
@@ -250,7 +279,7 @@ where
 
     // Setup protocol.
     let channel = &mut MC::C::default();
-    let commitment_scheme = &mut CommitmentSchemeProver::new(config, &twiddles);
+    let commitment_scheme = &mut CommitmentSchemeProver::new(config, TwiddleCache::from(twiddles));
 
     let span = span!(Level::INFO, "Trace").entered();
 
@@ -363,26 +392,7 @@ where
     span.exit();
 
     // Constant trace.
-    // TODO(ShaharS): share is_first column between components when constant columns support this.
-    let span = span!(Level::INFO, "Constant Trace").entered();
-    let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(
-        chain![
-            vec![gen_is_first(log_size)],
-            ROUND_LOG_SPLIT
-                .iter()
-                .map(|l| gen_is_first(log_size + l))
-                .collect_vec(),
-            xor_table::generate_constant_trace::<12, 4>(),
-            xor_table::generate_constant_trace::<9, 2>(),
-            xor_table::generate_constant_trace::<8, 2>(),
-            xor_table::generate_constant_trace::<7, 2>(),
-            xor_table::generate_constant_trace::<4, 0>(),
-        ]
-        .collect_vec(),
-    );
-    tree_builder.commit(channel);
-    span.exit();
+    commitment_scheme.commit_preprocessed(preprocessed, channel);
 
     assert_eq!(
         commitment_scheme
@@ -412,6 +422,7 @@ pub fn verify_blake<MC: MerkleChannel>(
         stark_proof,
     }: BlakeProof<MC::H>,
     config: PcsConfig,
+    preprocessed_cap: Vec<<MC::H as MerkleHasher>::Hash>,
 ) -> Result<(), VerificationError> {
     let channel = &mut MC::C::default();
     let commitment_scheme = &mut CommitmentSchemeVerifier::<MC>::new(config);
@@ -420,17 +431,17 @@ pub fn verify_blake<MC: MerkleChannel>(
 
     // Trace.
     stmt0.mix_into(channel);
-    commitment_scheme.commit(stark_proof.commitments[0], &log_sizes[0], channel);
+    commitment_scheme.commit(stark_proof.commitments[0].clone(), &log_sizes[0], channel);
 
     // Draw interaction elements.
     let all_elements = AllElements::draw(channel);
 
     // Interaction trace.
     stmt1.mix_into(channel);
-    commitment_scheme.commit(stark_proof.commitments[1], &log_sizes[1], channel);
+    commitment_scheme.commit(stark_proof.commitments[1].clone(), &log_sizes[1], channel);
 
-    // Constant trace.
-    commitment_scheme.commit(stark_proof.commitments[2], &log_sizes[2], channel);
+    // Constant trace, whose cap is known in advance.
+    commitment_scheme.commit(preprocessed_cap, &log_sizes[2], channel);
 
     let components = BlakeComponents::new(&stmt0, &all_elements, &stmt1);
 
@@ -460,7 +471,7 @@ mod tests {
 
     use crate::core::pcs::PcsConfig;
     use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
-    use crate::examples::blake::air::{prove_blake, verify_blake};
+    use crate::examples::blake::air::{preprocess_blake, prove_blake, verify_blake, BlakeProof};
 
     // Note: this test is slow. Only run in release.
     #[cfg_attr(not(feature = "slow-tests"), ignore)]
@@ -478,10 +489,23 @@ mod tests {
             .unwrap();
         let config = PcsConfig::default();
 
+        // Preprocess.
+        let preprocessed = preprocess_blake::<Blake2sMerkleChannel>(config);
+
         // Prove.
-        let proof = prove_blake::<Blake2sMerkleChannel>(log_n_instances, config);
+        let proof = prove_blake::<Blake2sMerkleChannel>(log_n_instances, config, &preprocessed);
+
+        // Some columns of the trace are identical, so their queried values are sent once.
+        let proof_size = proof.stark_proof.size_estimate();
+        let compressed_proof = proof.stark_proof.compress();
+        assert!(compressed_proof.size_estimate() < proof_size);
+        let proof = BlakeProof {
+            stark_proof: compressed_proof.decompress().unwrap(),
+            ..proof
+        };
 
         // Verify.
-        verify_blake::<Blake2sMerkleChannel>(proof, config).unwrap();
+        let preprocessed_cap = preprocessed.cap(config.fri_config.log_merkle_cap_size);
+        verify_blake::<Blake2sMerkleChannel>(proof, config, preprocessed_cap).unwrap();
     }
 }
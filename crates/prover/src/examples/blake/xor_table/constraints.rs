@@ -17,10 +17,10 @@ impl<'a, E: EvalAtRow, const ELEM_BITS: u32, const EXPAND_BITS: u32>
 {
     pub fn eval(mut self) -> E {
         // al, bl are the constant columns for the inputs: All pairs of elements in [0,
-        // 2^LIMB_BITS).
+        // 2^LIMB_BITS). bl is periodic.
         // cl is the constant column for the xor: al ^ bl.
         let [al] = self.eval.next_interaction_mask(2, [0]);
-        let [bl] = self.eval.next_interaction_mask(2, [0]);
+        let bl = self.eval.next_periodic_column();
         let [cl] = self.eval.next_interaction_mask(2, [0]);
 
         let frac_chunks = (0..(1 << (2 * EXPAND_BITS)))
@@ -3,11 +3,11 @@ use std::simd::u32x16;
 use itertools::Itertools;
 use tracing::{span, Level};
 
-use super::{column_bits, limb_bits, XorAccumulator, XorElements};
-use crate::constraint_framework::constant_columns::gen_is_first;
+use super::{column_bits, limb_bits, table_entry, XorAccumulator, XorElements};
 use crate::constraint_framework::logup::{LogupTraceGenerator, LookupElements};
+use crate::constraint_framework::PeriodicColumn;
 use crate::core::backend::simd::column::BaseColumn;
-use crate::core::backend::simd::m31::{PackedBaseField, LOG_N_LANES};
+use crate::core::backend::simd::m31::{PackedBaseField, LOG_N_LANES, N_LANES};
 use crate::core::backend::simd::qm31::PackedSecureField;
 use crate::core::backend::simd::SimdBackend;
 use crate::core::fields::m31::BaseField;
@@ -53,7 +53,6 @@ pub fn generate_interaction_trace<const ELEM_BITS: u32, const EXPAND_BITS: u32>(
 ) {
     let limb_bits = limb_bits::<ELEM_BITS, EXPAND_BITS>();
     let _span = span!(Level::INFO, "Xor interaction trace").entered();
-    let offsets_vec = u32x16::from_array(std::array::from_fn(|i| i as u32));
     let mut logup_gen = LogupTraceGenerator::new(column_bits::<ELEM_BITS, EXPAND_BITS>());
 
     // Iterate each pair of columns, to batch their lookup together.
@@ -76,17 +75,13 @@ pub fn generate_interaction_trace<const ELEM_BITS: u32, const EXPAND_BITS: u32>(
         // Each column has 2^(2*LIMB_BITS) rows, packed in N_LANES.
         #[allow(clippy::needless_range_loop)]
         for vec_row in 0..(1 << (column_bits::<ELEM_BITS, EXPAND_BITS>() - LOG_N_LANES)) {
-            // vec_row is LIMB_BITS of al and LIMB_BITS - LOG_N_LANES of bl.
-            // Extract al, blh from vec_row.
-            let al = vec_row >> (limb_bits - LOG_N_LANES);
-            let blh = vec_row & ((1 << (limb_bits - LOG_N_LANES)) - 1);
+            let (al, bl) = table_entries::<ELEM_BITS, EXPAND_BITS>(vec_row);
 
             // Construct the 3 vectors a, b, c.
-            let a0 = u32x16::splat((ah0 << limb_bits) | al);
-            let a1 = u32x16::splat((ah1 << limb_bits) | al);
-            // bll is just the consecutive numbers 0 .. N_LANES-1.
-            let b0 = u32x16::splat((bh0 << limb_bits) | (blh << LOG_N_LANES)) | offsets_vec;
-            let b1 = u32x16::splat((bh1 << limb_bits) | (blh << LOG_N_LANES)) | offsets_vec;
+            let a0 = u32x16::splat(ah0 << limb_bits) | al;
+            let a1 = u32x16::splat(ah1 << limb_bits) | al;
+            let b0 = u32x16::splat(bh0 << limb_bits) | bl;
+            let b1 = u32x16::splat(bh1 << limb_bits) | bl;
 
             let c0 = a0 ^ b0;
             let c1 = a1 ^ b1;
@@ -112,11 +107,9 @@ pub fn generate_interaction_trace<const ELEM_BITS: u32, const EXPAND_BITS: u32>(
 
             #[allow(clippy::needless_range_loop)]
             for vec_row in 0..(1 << (column_bits::<ELEM_BITS, EXPAND_BITS>() - LOG_N_LANES)) {
-                // vec_row is LIMB_BITS of a, and LIMB_BITS - LOG_N_LANES of b.
-                let al = vec_row >> (limb_bits - LOG_N_LANES);
-                let a = u32x16::splat((ah << limb_bits) | al);
-                let bm = vec_row & ((1 << (limb_bits - LOG_N_LANES)) - 1);
-                let b = u32x16::splat((bh << limb_bits) | (bm << LOG_N_LANES)) | offsets_vec;
+                let (al, bl) = table_entries::<ELEM_BITS, EXPAND_BITS>(vec_row);
+                let a = u32x16::splat(ah << limb_bits) | al;
+                let b = u32x16::splat(bh << limb_bits) | bl;
 
                 let c = a ^ b;
 
@@ -143,28 +136,48 @@ pub fn generate_constant_trace<const ELEM_BITS: u32, const EXPAND_BITS: u32>(
     let limb_bits = limb_bits::<ELEM_BITS, EXPAND_BITS>();
     let _span = span!(Level::INFO, "Xor constant trace").entered();
 
-    // Generate the constant columns. In reality, these should be generated before the proof
-    // even began.
-    let a_col: BaseColumn = (0..(1 << (column_bits::<ELEM_BITS, EXPAND_BITS>())))
-        .map(|i| BaseField::from_u32_unchecked((i >> limb_bits) as u32))
-        .collect();
-    let b_col: BaseColumn = (0..(1 << (column_bits::<ELEM_BITS, EXPAND_BITS>())))
-        .map(|i| BaseField::from_u32_unchecked((i & ((1 << limb_bits) - 1)) as u32))
+    // Generate the constant columns. These are committed once, before any proof.
+    let entries = (0..1 << column_bits::<ELEM_BITS, EXPAND_BITS>())
+        .map(table_entry::<ELEM_BITS, EXPAND_BITS>)
+        .collect_vec();
+    let a_col: BaseColumn = entries
+        .iter()
+        .map(|&(al, _)| BaseField::from_u32_unchecked(al))
         .collect();
-    let c_col: BaseColumn = (0..(1 << (column_bits::<ELEM_BITS, EXPAND_BITS>())))
-        .map(|i| {
-            BaseField::from_u32_unchecked(((i >> limb_bits) ^ (i & ((1 << limb_bits) - 1))) as u32)
-        })
+    let c_col: BaseColumn = entries
+        .iter()
+        .map(|&(al, bl)| BaseField::from_u32_unchecked(al ^ bl))
         .collect();
 
-    let mut constant_trace = [a_col, b_col, c_col]
+    [a_col, c_col]
         .map(|x| {
             CircleEvaluation::new(
                 CanonicCoset::new(column_bits::<ELEM_BITS, EXPAND_BITS>()).circle_domain(),
                 x,
             )
         })
-        .to_vec();
-    constant_trace.insert(0, gen_is_first(column_bits::<ELEM_BITS, EXPAND_BITS>()));
-    constant_trace
+        .to_vec()
+}
+
+/// Returns the low parts `(al, bl)` of the table entries at the packed bit-reversed index
+/// `vec_row`.
+fn table_entries<const ELEM_BITS: u32, const EXPAND_BITS: u32>(vec_row: u32) -> (u32x16, u32x16) {
+    let entries: [_; N_LANES] = std::array::from_fn(|i| {
+        table_entry::<ELEM_BITS, EXPAND_BITS>(((vec_row as usize) << LOG_N_LANES) + i)
+    });
+    (
+        u32x16::from_array(entries.map(|(al, _)| al)),
+        u32x16::from_array(entries.map(|(_, bl)| bl)),
+    )
+}
+
+/// Generates the periodic column of the xor table's second input, `b_l`, which takes all values in
+/// [0, 2^limb_bits) in every period.
+pub(super) fn generate_b_column<const ELEM_BITS: u32, const EXPAND_BITS: u32>() -> PeriodicColumn {
+    let limb_bits = limb_bits::<ELEM_BITS, EXPAND_BITS>();
+    PeriodicColumn::new(
+        (0..1 << limb_bits)
+            .map(BaseField::from_u32_unchecked)
+            .collect(),
+    )
 }
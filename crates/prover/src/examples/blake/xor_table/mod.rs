@@ -7,7 +7,8 @@
 //! The component itself will hold 2^(2*EXPAND_BITS) multiplicity columns, each of size
 //! 2^(ELEM_BITS - EXPAND_BITS).
 //! The constant columns correspond only to the smaller table of the lower `ELEM_BITS - EXPAND_BITS`
-//! xors: (a_l, b_l, a_l^b_l).
+//! xors: (a_l, b_l, a_l^b_l). b_l repeats every 2^(ELEM_BITS - EXPAND_BITS) rows, so it is a
+//! periodic column rather than a committed one.
 //! The rest of the lookups are computed based on these constant columns.
 
 mod constraints;
@@ -17,20 +18,24 @@ use std::simd::u32x16;
 
 use itertools::Itertools;
 use num_traits::Zero;
+use r#gen::generate_b_column;
 pub use r#gen::{generate_constant_trace, generate_interaction_trace, generate_trace};
 
 use crate::constraint_framework::logup::{LogupAtRow, LookupElements};
-use crate::constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval, InfoEvaluator};
+use crate::constraint_framework::{
+    EvalAtRow, FrameworkComponent, FrameworkEval, InfoEvaluator, PeriodicColumn, Selector,
+};
 use crate::core::backend::simd::column::BaseColumn;
 use crate::core::backend::Column;
 use crate::core::fields::qm31::SecureField;
 use crate::core::pcs::{TreeSubspan, TreeVec};
+use crate::core::utils::{bit_reversed_index_to_coset_index, coset_index_to_bit_reversed_index};
 
 pub fn trace_sizes<const ELEM_BITS: u32, const EXPAND_BITS: u32>() -> TreeVec<Vec<u32>> {
-    let component = XorTableEval::<ELEM_BITS, EXPAND_BITS> {
-        lookup_elements: LookupElements::<3>::dummy(),
-        claimed_sum: SecureField::zero(),
-    };
+    let component = XorTableEval::<ELEM_BITS, EXPAND_BITS>::new(
+        LookupElements::<3>::dummy(),
+        SecureField::zero(),
+    );
     let info = component.evaluate(InfoEvaluator::default());
     info.mask_offsets
         .as_cols_ref()
@@ -44,10 +49,28 @@ pub const fn column_bits<const ELEM_BITS: u32, const EXPAND_BITS: u32>() -> u32
     2 * limb_bits::<ELEM_BITS, EXPAND_BITS>()
 }
 
+/// Returns the bit-reversed index in the table columns of the low parts `(al, bl)`. The table holds
+/// them at row `al||bl`, so `bl` repeats every 2^LIMB_BITS rows.
+fn table_index<const ELEM_BITS: u32, const EXPAND_BITS: u32>(al: u32, bl: u32) -> usize {
+    let row = ((al << limb_bits::<ELEM_BITS, EXPAND_BITS>()) | bl) as usize;
+    coset_index_to_bit_reversed_index(row, column_bits::<ELEM_BITS, EXPAND_BITS>())
+}
+
+/// Returns the low parts `(al, bl)` at the bit-reversed index `index` of the table columns. Inverse
+/// of [table_index].
+fn table_entry<const ELEM_BITS: u32, const EXPAND_BITS: u32>(index: usize) -> (u32, u32) {
+    let row = bit_reversed_index_to_coset_index(index, column_bits::<ELEM_BITS, EXPAND_BITS>());
+    let limb_bits = limb_bits::<ELEM_BITS, EXPAND_BITS>();
+    (
+        (row >> limb_bits) as u32,
+        (row & ((1 << limb_bits) - 1)) as u32,
+    )
+}
+
 /// Accumulator that keeps track of the number of times each input has been used.
 pub struct XorAccumulator<const ELEM_BITS: u32, const EXPAND_BITS: u32> {
-    /// 2^(2*EXPAND_BITS) multiplicity columns. Index (al, bl) of column (ah, bh) is the number of
-    /// times ah||al ^ bh||bl has been used.
+    /// 2^(2*EXPAND_BITS) multiplicity columns. Index [table_index] (al, bl) of column (ah, bh) is
+    /// the number of times ah||al ^ bh||bl has been used.
     pub mults: Vec<BaseColumn>,
 }
 impl<const ELEM_BITS: u32, const EXPAND_BITS: u32> Default
@@ -71,12 +94,17 @@ impl<const ELEM_BITS: u32, const EXPAND_BITS: u32> XorAccumulator<ELEM_BITS, EXP
         let bl = b & u32x16::splat((1 << limb_bits::<ELEM_BITS, EXPAND_BITS>()) - 1);
         let bh = b >> limb_bits::<ELEM_BITS, EXPAND_BITS>();
         let column_idx = (ah << EXPAND_BITS) + bh;
-        let offset = (al << limb_bits::<ELEM_BITS, EXPAND_BITS>()) + bl;
 
         // Since the indices may collide, we cannot use scatter simd operations here.
         // Instead, loop over packed values.
-        for (column_idx, offset) in column_idx.as_array().iter().zip(offset.as_array().iter()) {
-            self.mults[*column_idx as usize].as_mut_slice()[*offset as usize].0 += 1;
+        for ((column_idx, al), bl) in column_idx
+            .as_array()
+            .iter()
+            .zip(al.as_array())
+            .zip(bl.as_array())
+        {
+            let offset = table_index::<ELEM_BITS, EXPAND_BITS>(*al, *bl);
+            self.mults[*column_idx as usize].as_mut_slice()[offset].0 += 1;
         }
     }
 }
@@ -91,6 +119,17 @@ pub type XorElements = LookupElements<3>;
 pub struct XorTableEval<const ELEM_BITS: u32, const EXPAND_BITS: u32> {
     pub lookup_elements: XorElements,
     pub claimed_sum: SecureField,
+    b_column: [PeriodicColumn; 1],
+}
+
+impl<const ELEM_BITS: u32, const EXPAND_BITS: u32> XorTableEval<ELEM_BITS, EXPAND_BITS> {
+    pub fn new(lookup_elements: XorElements, claimed_sum: SecureField) -> Self {
+        Self {
+            lookup_elements,
+            claimed_sum,
+            b_column: [generate_b_column::<ELEM_BITS, EXPAND_BITS>()],
+        }
+    }
 }
 
 impl<const ELEM_BITS: u32, const EXPAND_BITS: u32> FrameworkEval
@@ -102,8 +141,11 @@ impl<const ELEM_BITS: u32, const EXPAND_BITS: u32> FrameworkEval
     fn max_constraint_log_degree_bound(&self) -> u32 {
         column_bits::<ELEM_BITS, EXPAND_BITS>() + 1
     }
+    fn periodic_columns(&self) -> &[PeriodicColumn] {
+        &self.b_column
+    }
     fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
-        let [is_first] = eval.next_interaction_mask(2, [0]);
+        let is_first = eval.selector(Selector::IsFirst);
         let xor_eval = constraints::XorTableEval::<'_, _, ELEM_BITS, EXPAND_BITS> {
             eval,
             lookup_elements: &self.lookup_elements,
@@ -118,7 +160,7 @@ mod tests {
     use std::simd::u32x16;
 
     use crate::constraint_framework::logup::LookupElements;
-    use crate::constraint_framework::{assert_constraints, FrameworkEval};
+    use crate::constraint_framework::{assert_constraints_with_periodic_columns, FrameworkEval};
     use crate::core::poly::circle::CanonicCoset;
     use crate::examples::blake::xor_table::r#gen::{
         generate_constant_trace, generate_interaction_trace, generate_trace,
@@ -144,13 +186,11 @@ mod tests {
         let trace = TreeVec::new(vec![trace, interaction_trace, constant_trace]);
         let trace_polys = trace.map_cols(|c| c.interpolate());
 
-        let component = XorTableEval::<ELEM_BITS, EXPAND_BITS> {
-            lookup_elements,
-            claimed_sum,
-        };
-        assert_constraints(
+        let component = XorTableEval::<ELEM_BITS, EXPAND_BITS>::new(lookup_elements, claimed_sum);
+        assert_constraints_with_periodic_columns(
             &trace_polys,
             CanonicCoset::new(column_bits::<ELEM_BITS, EXPAND_BITS>()),
+            component.periodic_columns(),
             |eval| {
                 component.evaluate(eval);
             },
@@ -2,12 +2,12 @@ use itertools::Itertools;
 use num_traits::One;
 use tracing::{span, Level};
 
-use crate::constraint_framework::constant_columns::gen_is_first;
 use crate::constraint_framework::logup::{
     ClaimedPrefixSum, LogupAtRow, LogupTraceGenerator, LookupElements,
 };
 use crate::constraint_framework::{
-    assert_constraints, EvalAtRow, FrameworkComponent, FrameworkEval, TraceLocationAllocator,
+    assert_constraints, EvalAtRow, FrameworkComponent, FrameworkEval, Selector,
+    TraceLocationAllocator,
 };
 use crate::core::backend::simd::column::BaseColumn;
 use crate::core::backend::simd::m31::LOG_N_LANES;
@@ -18,8 +18,11 @@ use crate::core::channel::Blake2sChannel;
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
 use crate::core::lookups::utils::Fraction;
-use crate::core::pcs::{CommitmentSchemeProver, PcsConfig, TreeSubspan};
-use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, PolyOps};
+use crate::core::pcs::{
+    CommitmentSchemeProver, PcsConfig, PreprocessedColumns, PreprocessedTrace, TreeSubspan,
+};
+use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
+use crate::core::poly::twiddles::TwiddleCache;
 use crate::core::poly::BitReversedOrder;
 use crate::core::prover::{prove, StarkProof};
 use crate::core::vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher};
@@ -48,7 +51,7 @@ impl FrameworkEval for PlonkEval {
     }
 
     fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
-        let [is_first] = eval.next_interaction_mask(2, [0]);
+        let is_first = eval.selector(Selector::IsFirst);
         let mut logup = LogupAtRow::<_>::new(1, self.total_sum, Some(self.claimed_sum), is_first);
 
         let [a_wire] = eval.next_interaction_mask(2, [0]);
@@ -151,19 +154,12 @@ pub fn gen_interaction_trace(
     logup_gen.finalize_at([(1 << log_size) - 1, padding_offset])
 }
 
-#[allow(unused)]
-pub fn prove_fibonacci_plonk(
-    log_n_rows: u32,
-    config: PcsConfig,
-) -> (PlonkComponent, StarkProof<Blake2sMerkleHasher>) {
-    assert!(log_n_rows >= LOG_N_LANES);
-
-    // Prepare a fibonacci circuit.
+/// Generates a fibonacci circuit of `2^log_n_rows` gates.
+fn fibonacci_circuit(log_n_rows: u32) -> PlonkCircuitTrace {
     let mut fib_values = vec![BaseField::one(), BaseField::one()];
     for _ in 0..(1 << log_n_rows) {
         fib_values.push(fib_values[fib_values.len() - 1] + fib_values[fib_values.len() - 2]);
     }
-    let padding_offset = 17;
     let range = 0..(1 << log_n_rows);
     let mut circuit = PlonkCircuitTrace {
         mult: range.clone().map(|_| 2.into()).collect(),
@@ -177,20 +173,48 @@ pub fn prove_fibonacci_plonk(
     };
     circuit.mult.set((1 << log_n_rows) - 1, 0.into());
     circuit.mult.set((1 << log_n_rows) - 2, 1.into());
+    circuit
+}
 
-    // Precompute twiddles.
-    let span = span!(Level::INFO, "Precompute twiddles").entered();
-    let twiddles = SimdBackend::precompute_twiddles(
-        CanonicCoset::new(log_n_rows + config.fri_config.log_blowup_factor + 1)
-            .circle_domain()
-            .half_coset,
-    );
-    span.exit();
+/// Commits to the wiring and gate columns of the fibonacci circuit, which are the same for every
+/// proof of that circuit.
+pub fn preprocess_fibonacci_plonk(
+    log_n_rows: u32,
+    config: PcsConfig,
+) -> PreprocessedTrace<SimdBackend, Blake2sMerkleChannel> {
+    let _span = span!(Level::INFO, "Preprocessing").entered();
+    let circuit = fibonacci_circuit(log_n_rows);
+    let mut columns = PreprocessedColumns::new();
+    for (name, col) in [
+        ("a_wire", circuit.a_wire),
+        ("b_wire", circuit.b_wire),
+        ("c_wire", circuit.c_wire),
+        ("op", circuit.op),
+    ] {
+        let domain = CanonicCoset::new(log_n_rows).circle_domain();
+        columns.insert(name, CircleEvaluation::new(domain, col));
+    }
+    PreprocessedTrace::commit(columns, config, &TwiddleCache::default())
+}
+
+#[allow(unused)]
+pub fn prove_fibonacci_plonk(
+    log_n_rows: u32,
+    config: PcsConfig,
+    preprocessed: &PreprocessedTrace<SimdBackend, Blake2sMerkleChannel>,
+) -> (PlonkComponent, StarkProof<Blake2sMerkleHasher>) {
+    assert!(log_n_rows >= LOG_N_LANES);
+
+    // Prepare a fibonacci circuit.
+    let circuit = fibonacci_circuit(log_n_rows);
+    let padding_offset = 17;
 
     // Setup protocol.
     let channel = &mut Blake2sChannel::default();
-    let commitment_scheme =
-        &mut CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(config, &twiddles);
+    let commitment_scheme = &mut CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(
+        config,
+        TwiddleCache::default(),
+    );
 
     // Trace.
     let span = span!(Level::INFO, "Trace").entered();
@@ -213,22 +237,7 @@ pub fn prove_fibonacci_plonk(
     span.exit();
 
     // Constant trace.
-    let span = span!(Level::INFO, "Constant").entered();
-    let mut tree_builder = commitment_scheme.tree_builder();
-    let is_first = gen_is_first(log_n_rows);
-    let mut constant_trace = [circuit.a_wire, circuit.b_wire, circuit.c_wire, circuit.op]
-        .into_iter()
-        .map(|col| {
-            CircleEvaluation::<SimdBackend, _, BitReversedOrder>::new(
-                CanonicCoset::new(log_n_rows).circle_domain(),
-                col,
-            )
-        })
-        .collect_vec();
-    constant_trace.insert(0, is_first);
-    let constants_trace_location = tree_builder.extend_evals(constant_trace);
-    tree_builder.commit(channel);
-    span.exit();
+    let constants_trace_location = commitment_scheme.commit_preprocessed(preprocessed, channel);
 
     // Prove constraints.
     let component = PlonkComponent::new(
@@ -269,7 +278,7 @@ mod tests {
     use crate::core::pcs::{CommitmentSchemeVerifier, PcsConfig};
     use crate::core::prover::verify;
     use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
-    use crate::examples::plonk::prove_fibonacci_plonk;
+    use crate::examples::plonk::{preprocess_fibonacci_plonk, prove_fibonacci_plonk};
 
     #[test_log::test]
     fn test_simd_plonk_prove() {
@@ -284,7 +293,8 @@ mod tests {
         };
 
         // Prove.
-        let (component, proof) = prove_fibonacci_plonk(log_n_instances, config);
+        let preprocessed = preprocess_fibonacci_plonk(log_n_instances, config);
+        let (component, proof) = prove_fibonacci_plonk(log_n_instances, config, &preprocessed);
 
         // Verify.
         // TODO: Create Air instance independently.
@@ -295,14 +305,15 @@ mod tests {
         // Retrieve the expected column sizes in each commitment interaction, from the AIR.
         let sizes = component.trace_log_degree_bounds();
         // Trace columns.
-        commitment_scheme.commit(proof.commitments[0], &sizes[0], channel);
+        commitment_scheme.commit(proof.commitments[0].clone(), &sizes[0], channel);
         // Draw lookup element.
         let lookup_elements = LookupElements::<2>::draw(channel);
         assert_eq!(lookup_elements, component.lookup_elements);
         // Interaction columns.
-        commitment_scheme.commit(proof.commitments[1], &sizes[1], channel);
-        // Constant columns.
-        commitment_scheme.commit(proof.commitments[2], &sizes[2], channel);
+        commitment_scheme.commit(proof.commitments[1].clone(), &sizes[1], channel);
+        // Constant columns, whose cap is known in advance.
+        let preprocessed_cap = preprocessed.cap(config.fri_config.log_merkle_cap_size);
+        commitment_scheme.commit(preprocessed_cap, &sizes[2], channel);
 
         verify(&[&component], channel, commitment_scheme, proof).unwrap();
     }
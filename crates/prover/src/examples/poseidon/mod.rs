@@ -2,13 +2,12 @@
 
 use std::ops::{Add, AddAssign, Mul, Sub};
 
-use itertools::Itertools;
+use itertools::{chain, Itertools};
 use tracing::{span, Level};
 
-use crate::constraint_framework::constant_columns::gen_is_first;
 use crate::constraint_framework::logup::{LogupAtRow, LogupTraceGenerator, LookupElements};
 use crate::constraint_framework::{
-    EvalAtRow, FrameworkComponent, FrameworkEval, TraceLocationAllocator,
+    EvalAtRow, FrameworkComponent, FrameworkEval, PeriodicColumn, Selector, TraceLocationAllocator,
 };
 use crate::core::backend::simd::column::BaseColumn;
 use crate::core::backend::simd::m31::{PackedBaseField, LOG_N_LANES};
@@ -21,7 +20,8 @@ use crate::core::fields::qm31::SecureField;
 use crate::core::fields::FieldExpOps;
 use crate::core::lookups::utils::Reciprocal;
 use crate::core::pcs::{CommitmentSchemeProver, PcsConfig};
-use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, PolyOps};
+use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
+use crate::core::poly::twiddles::TwiddleCache;
 use crate::core::poly::BitReversedOrder;
 use crate::core::prover::{prove, StarkProof};
 use crate::core::vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher};
@@ -51,6 +51,8 @@ pub struct PoseidonEval {
     pub log_n_rows: u32,
     pub lookup_elements: PoseidonElements,
     pub total_sum: SecureField,
+    /// See [round_constant_columns].
+    pub round_constants: Vec<PeriodicColumn>,
 }
 impl FrameworkEval for PoseidonEval {
     fn log_size(&self) -> u32 {
@@ -59,8 +61,11 @@ impl FrameworkEval for PoseidonEval {
     fn max_constraint_log_degree_bound(&self) -> u32 {
         self.log_n_rows + LOG_EXPAND
     }
+    fn periodic_columns(&self) -> &[PeriodicColumn] {
+        &self.round_constants
+    }
     fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
-        let [is_first] = eval.next_interaction_mask(2, [0]);
+        let is_first = eval.selector(Selector::IsFirst);
         let logup = LogupAtRow::new(1, self.total_sum, None, is_first);
         eval_poseidon_constraints(&mut eval, logup, &self.lookup_elements);
         eval
@@ -140,11 +145,27 @@ fn pow5<F: FieldExpOps>(x: F) -> F {
     x4 * x.clone()
 }
 
+/// Returns the round constants as periodic columns: the external round constants by round, then
+/// the internal ones. Every row applies all rounds, so each constant has a period of one row.
+pub fn round_constant_columns() -> Vec<PeriodicColumn> {
+    chain![
+        EXTERNAL_ROUND_CONSTS.into_iter().flatten(),
+        INTERNAL_ROUND_CONSTS
+    ]
+    .map(|constant| PeriodicColumn::new(vec![constant]))
+    .collect()
+}
+
 pub fn eval_poseidon_constraints<E: EvalAtRow>(
     eval: &mut E,
     mut logup: LogupAtRow<E>,
     lookup_elements: &PoseidonElements,
 ) {
+    let external_round_consts: [[E::F; N_STATE]; FULL_ROUNDS] =
+        std::array::from_fn(|_| std::array::from_fn(|_| eval.next_periodic_column()));
+    let internal_round_consts: [E::F; N_PARTIAL_ROUNDS] =
+        std::array::from_fn(|_| eval.next_periodic_column());
+
     for _ in 0..N_INSTANCES_PER_ROW {
         let mut state: [_; N_STATE] = std::array::from_fn(|_| eval.next_trace_mask());
 
@@ -154,7 +175,7 @@ pub fn eval_poseidon_constraints<E: EvalAtRow>(
         // 4 full rounds.
         (0..N_HALF_FULL_ROUNDS).for_each(|round| {
             (0..N_STATE).for_each(|i| {
-                state[i] += EXTERNAL_ROUND_CONSTS[round][i];
+                state[i] += external_round_consts[round][i].clone();
             });
             apply_external_round_matrix(&mut state);
             // TODO(andrew) Apply round matrix after the pow5, as is the order in the paper.
@@ -168,7 +189,7 @@ pub fn eval_poseidon_constraints<E: EvalAtRow>(
 
         // Partial rounds.
         (0..N_PARTIAL_ROUNDS).for_each(|round| {
-            state[0] += INTERNAL_ROUND_CONSTS[round];
+            state[0] += internal_round_consts[round].clone();
             apply_internal_round_matrix(&mut state);
             state[0] = pow5(state[0].clone());
             let m = eval.next_trace_mask();
@@ -179,7 +200,7 @@ pub fn eval_poseidon_constraints<E: EvalAtRow>(
         // 4 full rounds.
         (0..N_HALF_FULL_ROUNDS).for_each(|round| {
             (0..N_STATE).for_each(|i| {
-                state[i] += EXTERNAL_ROUND_CONSTS[round + N_HALF_FULL_ROUNDS][i];
+                state[i] += external_round_consts[round + N_HALF_FULL_ROUNDS][i].clone();
             });
             apply_external_round_matrix(&mut state);
             state = std::array::from_fn(|i| pow5(state[i].clone()));
@@ -336,19 +357,12 @@ pub fn prove_poseidon(
     assert!(log_n_instances >= N_LOG_INSTANCES_PER_ROW as u32);
     let log_n_rows = log_n_instances - N_LOG_INSTANCES_PER_ROW as u32;
 
-    // Precompute twiddles.
-    let span = span!(Level::INFO, "Precompute twiddles").entered();
-    let twiddles = SimdBackend::precompute_twiddles(
-        CanonicCoset::new(log_n_rows + LOG_EXPAND + config.fri_config.log_blowup_factor)
-            .circle_domain()
-            .half_coset,
-    );
-    span.exit();
-
     // Setup protocol.
     let channel = &mut Blake2sChannel::default();
-    let commitment_scheme =
-        &mut CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(config, &twiddles);
+    let commitment_scheme = &mut CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(
+        config,
+        TwiddleCache::default(),
+    );
 
     // Trace.
     let span = span!(Level::INFO, "Trace").entered();
@@ -369,14 +383,6 @@ pub fn prove_poseidon(
     tree_builder.commit(channel);
     span.exit();
 
-    // Constant trace.
-    let span = span!(Level::INFO, "Constant").entered();
-    let mut tree_builder = commitment_scheme.tree_builder();
-    let constant_trace = vec![gen_is_first(log_n_rows)];
-    tree_builder.extend_evals(constant_trace);
-    tree_builder.commit(channel);
-    span.exit();
-
     // Prove constraints.
     let component = PoseidonComponent::new(
         &mut TraceLocationAllocator::default(),
@@ -384,6 +390,7 @@ pub fn prove_poseidon(
             log_n_rows,
             lookup_elements,
             total_sum,
+            round_constants: round_constant_columns(),
         },
     );
     let proof = prove(&[&component], channel, commitment_scheme).unwrap();
@@ -398,9 +405,10 @@ mod tests {
     use itertools::Itertools;
     use num_traits::One;
 
-    use crate::constraint_framework::constant_columns::gen_is_first;
     use crate::constraint_framework::logup::{LogupAtRow, LookupElements};
-    use crate::constraint_framework::{assert_constraints, EvalAtRow};
+    use crate::constraint_framework::{
+        assert_constraints_with_periodic_columns, EvalAtRow, Selector,
+    };
     use crate::core::air::Component;
     use crate::core::channel::Blake2sChannel;
     use crate::core::fields::m31::BaseField;
@@ -411,7 +419,7 @@ mod tests {
     use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
     use crate::examples::poseidon::{
         apply_internal_round_matrix, apply_m4, eval_poseidon_constraints, gen_interaction_trace,
-        gen_trace, prove_poseidon, PoseidonElements,
+        gen_trace, prove_poseidon, round_constant_columns, PoseidonElements,
     };
     use crate::math::matrix::{RowMajorMatrix, SquareMatrix};
 
@@ -475,17 +483,22 @@ mod tests {
         let (trace1, total_sum) =
             gen_interaction_trace(LOG_N_ROWS, interaction_data, &lookup_elements);
 
-        let traces = TreeVec::new(vec![trace0, trace1, vec![gen_is_first(LOG_N_ROWS)]]);
+        let traces = TreeVec::new(vec![trace0, trace1]);
         let trace_polys =
             traces.map(|trace| trace.into_iter().map(|c| c.interpolate()).collect_vec());
-        assert_constraints(&trace_polys, CanonicCoset::new(LOG_N_ROWS), |mut eval| {
-            let [is_first] = eval.next_interaction_mask(2, [0]);
-            eval_poseidon_constraints(
-                &mut eval,
-                LogupAtRow::new(1, total_sum, None, is_first),
-                &lookup_elements,
-            );
-        });
+        assert_constraints_with_periodic_columns(
+            &trace_polys,
+            CanonicCoset::new(LOG_N_ROWS),
+            &round_constant_columns(),
+            |mut eval| {
+                let is_first = eval.selector(Selector::IsFirst);
+                eval_poseidon_constraints(
+                    &mut eval,
+                    LogupAtRow::new(1, total_sum, None, is_first),
+                    &lookup_elements,
+                );
+            },
+        );
     }
 
     #[test_log::test]
@@ -508,6 +521,12 @@ mod tests {
         // Prove.
         let (component, proof) = prove_poseidon(log_n_instances, config);
 
+        // Some columns of the trace are identical, so their queried values are sent once.
+        let proof_size = proof.size_estimate();
+        let compressed_proof = proof.compress();
+        assert!(compressed_proof.size_estimate() < proof_size);
+        let proof = compressed_proof.decompress().unwrap();
+
         // Verify.
         // TODO: Create Air instance independently.
         let channel = &mut Blake2sChannel::default();
@@ -517,15 +536,12 @@ mod tests {
         // Retrieve the expected column sizes in each commitment interaction, from the AIR.
         let sizes = component.trace_log_degree_bounds();
         // Trace columns.
-        commitment_scheme.commit(proof.commitments[0], &sizes[0], channel);
+        commitment_scheme.commit(proof.commitments[0].clone(), &sizes[0], channel);
         // Draw lookup element.
         let lookup_elements = PoseidonElements::draw(channel);
         assert_eq!(lookup_elements, component.lookup_elements);
         // Interaction columns.
-        commitment_scheme.commit(proof.commitments[1], &sizes[1], channel);
-
-        // Constant columns.
-        commitment_scheme.commit(proof.commitments[2], &sizes[2], channel);
+        commitment_scheme.commit(proof.commitments[1].clone(), &sizes[1], channel);
 
         verify(&[&component], channel, commitment_scheme, proof).unwrap();
     }
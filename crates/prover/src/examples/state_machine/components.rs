@@ -1,14 +1,15 @@
-use num_traits::{One, Zero};
+use num_traits::One;
 
 use crate::constraint_framework::logup::{LogupAtRow, LookupElements};
-use crate::constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval, InfoEvaluator};
-use crate::core::air::{Component, ComponentProver};
+use crate::constraint_framework::{
+    EvalAtRow, FrameworkComponent, FrameworkEval, Selector, TraceLocationAllocator,
+};
+use crate::core::air::{Air, AirProver, Component, ComponentProver};
 use crate::core::backend::simd::SimdBackend;
 use crate::core::channel::Channel;
 use crate::core::fields::m31::M31;
 use crate::core::fields::qm31::{SecureField, QM31};
 use crate::core::lookups::utils::Fraction;
-use crate::core::pcs::TreeVec;
 use crate::core::prover::StarkProof;
 use crate::core::vcs::ops::MerkleHasher;
 
@@ -38,7 +39,7 @@ impl<const COORDINATE: usize> FrameworkEval for StateTransitionEval<COORDINATE>
         self.log_n_rows + LOG_CONSTRAINT_DEGREE
     }
     fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
-        let [is_first] = eval.next_interaction_mask(2, [0]);
+        let is_first = eval.selector(Selector::IsFirst);
         let mut logup: LogupAtRow<E> = LogupAtRow::new(1, self.total_sum, None, is_first);
 
         let input_state: [_; STATE_SIZE] = std::array::from_fn(|_| eval.next_trace_mask());
@@ -64,19 +65,6 @@ pub struct StateMachineStatement0 {
     pub m: u32,
 }
 impl StateMachineStatement0 {
-    pub fn log_sizes(&self) -> TreeVec<Vec<u32>> {
-        let sizes = vec![
-            state_transition_info::<0>()
-                .mask_offsets
-                .as_cols_ref()
-                .map_cols(|_| self.n),
-            state_transition_info::<1>()
-                .mask_offsets
-                .as_cols_ref()
-                .map_cols(|_| self.m),
-        ];
-        TreeVec::concat_cols(sizes.into_iter())
-    }
     pub fn mix_into(&self, channel: &mut impl Channel) {
         channel.mix_u64(self.n as u64);
         channel.mix_u64(self.m as u64);
@@ -93,29 +81,50 @@ impl StateMachineStatement1 {
     }
 }
 
-fn state_transition_info<const INDEX: usize>() -> InfoEvaluator {
-    let component = StateTransitionEval::<INDEX> {
-        log_n_rows: 1,
-        lookup_elements: StateMachineElements::dummy(),
-        total_sum: QM31::zero(),
-    };
-    component.evaluate(InfoEvaluator::default())
-}
-
 pub struct StateMachineComponents {
     pub component0: StateMachineOp0Component,
     pub component1: StateMachineOp1Component,
 }
 
 impl StateMachineComponents {
-    pub fn components(&self) -> Vec<&dyn Component> {
+    pub fn new(
+        stmt0: &StateMachineStatement0,
+        lookup_elements: &StateMachineElements,
+        stmt1: &StateMachineStatement1,
+    ) -> Self {
+        let tree_span_provider = &mut TraceLocationAllocator::default();
+        Self {
+            component0: StateMachineOp0Component::new(
+                tree_span_provider,
+                StateTransitionEval {
+                    log_n_rows: stmt0.n,
+                    lookup_elements: lookup_elements.clone(),
+                    total_sum: stmt1.x_axis_claimed_sum,
+                },
+            ),
+            component1: StateMachineOp1Component::new(
+                tree_span_provider,
+                StateTransitionEval {
+                    log_n_rows: stmt0.m,
+                    lookup_elements: lookup_elements.clone(),
+                    total_sum: stmt1.y_axis_claimed_sum,
+                },
+            ),
+        }
+    }
+}
+
+impl Air for StateMachineComponents {
+    fn components(&self) -> Vec<&dyn Component> {
         vec![
             &self.component0 as &dyn Component,
             &self.component1 as &dyn Component,
         ]
     }
+}
 
-    pub fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
+impl AirProver<SimdBackend> for StateMachineComponents {
+    fn component_provers(&self) -> Vec<&dyn ComponentProver<SimdBackend>> {
         vec![
             &self.component0 as &dyn ComponentProver<SimdBackend>,
             &self.component1 as &dyn ComponentProver<SimdBackend>,
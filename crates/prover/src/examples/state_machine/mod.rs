@@ -2,30 +2,52 @@ pub mod components;
 pub mod gen;
 
 use components::{
-    State, StateMachineComponents, StateMachineElements, StateMachineOp0Component,
-    StateMachineOp1Component, StateMachineProof, StateMachineStatement0, StateMachineStatement1,
-    StateTransitionEval,
+    State, StateMachineComponents, StateMachineElements, StateMachineProof, StateMachineStatement0,
+    StateMachineStatement1,
 };
 use gen::{gen_interaction_trace, gen_trace};
 use itertools::{chain, Itertools};
+use num_traits::Zero;
 
-use crate::constraint_framework::constant_columns::gen_is_first;
-use crate::constraint_framework::TraceLocationAllocator;
+use crate::core::air::{Air, AirProver};
 use crate::core::backend::simd::m31::LOG_N_LANES;
 use crate::core::backend::simd::SimdBackend;
 use crate::core::channel::Blake2sChannel;
 use crate::core::fields::m31::M31;
 use crate::core::fields::qm31::QM31;
-use crate::core::pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig};
-use crate::core::poly::circle::{CanonicCoset, PolyOps};
-use crate::core::prover::{prove, verify, VerificationError};
+use crate::core::pcs::PcsConfig;
+use crate::core::poly::twiddles::TwiddleCache;
+use crate::core::prover::{prove, setup, verify, ProvingKey, VerificationError, VerifyingKey};
 use crate::core::vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher};
 
+/// Produces the keys of the state machine with `2^log_n_rows` transitions along the x axis.
+pub fn setup_state_machine(
+    log_n_rows: u32,
+    config: PcsConfig,
+) -> (
+    ProvingKey<SimdBackend, Blake2sMerkleChannel>,
+    VerifyingKey<Blake2sMerkleHasher>,
+) {
+    // Only the shape of the components matters, so the elements and sums are dummies.
+    let components = StateMachineComponents::new(
+        &StateMachineStatement0 {
+            n: log_n_rows,
+            m: log_n_rows - 1,
+        },
+        &StateMachineElements::dummy(),
+        &StateMachineStatement1 {
+            x_axis_claimed_sum: QM31::zero(),
+            y_axis_claimed_sum: QM31::zero(),
+        },
+    );
+    setup(&components, config, [])
+}
+
 #[allow(unused)]
 pub fn prove_state_machine(
     log_n_rows: u32,
     initial_state: State,
-    config: PcsConfig,
+    proving_key: &ProvingKey<SimdBackend, Blake2sMerkleChannel>,
     channel: &mut Blake2sChannel,
 ) -> (
     StateMachineComponents,
@@ -40,16 +62,8 @@ pub fn prove_state_machine(
     let mut final_state = intermediate_state;
     final_state[1] += M31::from_u32_unchecked(1 << y_axis_log_rows);
 
-    // Precompute twiddles.
-    let twiddles = SimdBackend::precompute_twiddles(
-        CanonicCoset::new(log_n_rows + config.fri_config.log_blowup_factor + 1)
-            .circle_domain()
-            .half_coset,
-    );
-
     // Setup protocol.
-    let commitment_scheme =
-        &mut CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(config, &twiddles);
+    let commitment_scheme = &mut proving_key.commitment_scheme(TwiddleCache::default());
 
     // Trace.
     let trace_op0 = gen_trace(x_axis_log_rows, initial_state, 0);
@@ -61,7 +75,7 @@ pub fn prove_state_machine(
     };
     stmt0.mix_into(channel);
 
-    let mut tree_builder = commitment_scheme.tree_builder();
+    let mut tree_builder = proving_key.tree_builder(commitment_scheme);
     tree_builder.extend_evals(chain![trace_op0.clone(), trace_op1.clone()]);
     tree_builder.commit(channel);
 
@@ -80,40 +94,12 @@ pub fn prove_state_machine(
     };
     stmt1.mix_into(channel);
 
-    let mut tree_builder = commitment_scheme.tree_builder();
+    let mut tree_builder = proving_key.tree_builder(commitment_scheme);
     tree_builder.extend_evals(chain![interaction_trace_op0, interaction_trace_op1].collect_vec());
     tree_builder.commit(channel);
 
-    // Constant trace.
-    let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(vec![
-        gen_is_first(x_axis_log_rows),
-        gen_is_first(y_axis_log_rows),
-    ]);
-    tree_builder.commit(channel);
-
     // Prove constraints.
-    let mut tree_span_provider = &mut TraceLocationAllocator::default();
-    let component0 = StateMachineOp0Component::new(
-        tree_span_provider,
-        StateTransitionEval {
-            log_n_rows: x_axis_log_rows,
-            lookup_elements: lookup_elements.clone(),
-            total_sum: total_sum_op0,
-        },
-    );
-    let component1 = StateMachineOp1Component::new(
-        tree_span_provider,
-        StateTransitionEval {
-            log_n_rows: y_axis_log_rows,
-            lookup_elements,
-            total_sum: total_sum_op1,
-        },
-    );
-    let components = StateMachineComponents {
-        component0,
-        component1,
-    };
+    let components = StateMachineComponents::new(&stmt0, &lookup_elements, &stmt1);
     let stark_proof = prove(&components.component_provers(), channel, commitment_scheme).unwrap();
     let proof = StateMachineProof {
         public_input: [initial_state, final_state],
@@ -125,18 +111,15 @@ pub fn prove_state_machine(
 }
 
 pub fn verify_state_machine(
-    config: PcsConfig,
+    verifying_key: &VerifyingKey<Blake2sMerkleHasher>,
     channel: &mut Blake2sChannel,
-    components: StateMachineComponents,
     proof: StateMachineProof<Blake2sMerkleHasher>,
 ) -> Result<(), VerificationError> {
-    let commitment_scheme = &mut CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+    let commitment_scheme = &mut verifying_key.commitment_scheme::<Blake2sMerkleChannel>();
     // Decommit.
-    // Retrieve the expected column sizes in each commitment interaction, from the AIR.
-    let sizes = proof.stmt0.log_sizes();
-    // Trace columns.
+    // Trace columns, with the column sizes from the verifying key.
     proof.stmt0.mix_into(channel);
-    commitment_scheme.commit(proof.stark_proof.commitments[0], &sizes[0], channel);
+    verifying_key.commit(commitment_scheme, &proof.stark_proof, channel)?;
 
     // Assert state machine statement.
     let lookup_elements = StateMachineElements::draw(channel);
@@ -151,10 +134,10 @@ pub fn verify_state_machine(
 
     // Interaction columns.
     proof.stmt1.mix_into(channel);
-    commitment_scheme.commit(proof.stark_proof.commitments[1], &sizes[1], channel);
-    // Constant columns.
-    commitment_scheme.commit(proof.stark_proof.commitments[2], &sizes[2], channel);
+    verifying_key.commit(commitment_scheme, &proof.stark_proof, channel)?;
 
+    let components = StateMachineComponents::new(&proof.stmt0, &lookup_elements, &proof.stmt1);
+    verifying_key.check_components(&components.components())?;
     verify(
         &components.components(),
         channel,
@@ -171,8 +154,7 @@ mod tests {
         StateMachineElements, StateMachineOp0Component, StateTransitionEval, STATE_SIZE,
     };
     use super::gen::{gen_interaction_trace, gen_trace};
-    use super::{prove_state_machine, verify_state_machine};
-    use crate::constraint_framework::constant_columns::gen_is_first;
+    use super::{prove_state_machine, setup_state_machine, verify_state_machine};
     use crate::constraint_framework::{assert_constraints, FrameworkEval, TraceLocationAllocator};
     use crate::core::channel::Blake2sChannel;
     use crate::core::fields::m31::M31;
@@ -202,11 +184,7 @@ mod tests {
             },
         );
 
-        let trace = TreeVec::new(vec![
-            trace,
-            interaction_trace,
-            vec![gen_is_first(log_n_rows)],
-        ]);
+        let trace = TreeVec::new(vec![trace, interaction_trace]);
         let trace_polys = trace.map_cols(|c| c.interpolate());
         assert_constraints(&trace_polys, CanonicCoset::new(log_n_rows), |eval| {
             component.evaluate(eval);
@@ -227,7 +205,8 @@ mod tests {
 
         // Setup protocol.
         let channel = &mut Blake2sChannel::default();
-        let (component, _) = prove_state_machine(log_n_rows, initial_state, config, channel);
+        let (proving_key, _) = setup_state_machine(log_n_rows, config);
+        let (component, _) = prove_state_machine(log_n_rows, initial_state, &proving_key, channel);
 
         let interaction_elements = component.component0.lookup_elements.clone();
         let initial_state_comb: QM31 = interaction_elements.combine(&initial_state);
@@ -247,9 +226,10 @@ mod tests {
         let prover_channel = &mut Blake2sChannel::default();
         let verifier_channel = &mut Blake2sChannel::default();
 
-        let (components, proof) =
-            prove_state_machine(log_n_rows, initial_state, config, prover_channel);
+        let (proving_key, verifying_key) = setup_state_machine(log_n_rows, config);
+        let (_, proof) =
+            prove_state_machine(log_n_rows, initial_state, &proving_key, prover_channel);
 
-        verify_state_machine(config, verifier_channel, components, proof).unwrap();
+        verify_state_machine(&verifying_key, verifier_channel, proof).unwrap();
     }
 }
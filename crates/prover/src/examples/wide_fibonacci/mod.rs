@@ -77,6 +77,8 @@ mod tests {
         assert_constraints, AssertEvaluator, FrameworkEval, TraceLocationAllocator,
     };
     use crate::core::air::Component;
+    #[cfg(not(target_arch = "wasm32"))]
+    use crate::core::backend::disk::{DiskBackend, Spill};
     use crate::core::backend::simd::m31::{PackedBaseField, LOG_N_LANES};
     use crate::core::backend::simd::SimdBackend;
     use crate::core::backend::Column;
@@ -84,8 +86,11 @@ mod tests {
     #[cfg(not(target_arch = "wasm32"))]
     use crate::core::channel::Poseidon252Channel;
     use crate::core::fields::m31::BaseField;
-    use crate::core::pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig, TreeVec};
-    use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, PolyOps};
+    use crate::core::pcs::{
+        CommitmentSchemeProver, CommitmentSchemeVerifier, EvaluationStorage, PcsConfig, TreeVec,
+    };
+    use crate::core::poly::circle::{CanonicCoset, CircleEvaluation};
+    use crate::core::poly::twiddles::TwiddleCache;
     use crate::core::poly::BitReversedOrder;
     use crate::core::prover::{prove, verify};
     use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
@@ -171,18 +176,13 @@ mod tests {
     fn test_wide_fib_prove_with_blake() {
         for log_n_instances in 2..=6 {
             let config = PcsConfig::default();
-            // Precompute twiddles.
-            let twiddles = SimdBackend::precompute_twiddles(
-                CanonicCoset::new(log_n_instances + 1 + config.fri_config.log_blowup_factor)
-                    .circle_domain()
-                    .half_coset,
-            );
 
             // Setup protocol.
             let prover_channel = &mut Blake2sChannel::default();
             let commitment_scheme =
                 &mut CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
-                    config, &twiddles,
+                    config,
+                    TwiddleCache::default(),
                 );
 
             // Trace.
@@ -213,28 +213,126 @@ mod tests {
 
             // Retrieve the expected column sizes in each commitment interaction, from the AIR.
             let sizes = component.trace_log_degree_bounds();
-            commitment_scheme.commit(proof.commitments[0], &sizes[0], verifier_channel);
+            commitment_scheme.commit(proof.commitments[0].clone(), &sizes[0], verifier_channel);
+            verify(&[&component], verifier_channel, commitment_scheme, proof).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_wide_fib_prove_with_recomputed_evaluations() {
+        // The small trace falls back to the CPU domain evaluator.
+        for log_n_instances in [3, 6] {
+            let config = PcsConfig::default();
+
+            // Setup protocol.
+            let prover_channel = &mut Blake2sChannel::default();
+            let commitment_scheme =
+                &mut CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+                    config,
+                    TwiddleCache::default(),
+                )
+                .with_evaluation_storage(EvaluationStorage::Recompute);
+
+            // Trace.
+            let trace = generate_test_trace(log_n_instances);
+            let mut tree_builder = commitment_scheme.tree_builder();
+            tree_builder.extend_evals(trace);
+            tree_builder.commit(prover_channel);
+            assert!(commitment_scheme.trees[0].evaluations.is_none());
+
+            // Prove constraints.
+            let component = WideFibonacciComponent::new(
+                &mut TraceLocationAllocator::default(),
+                WideFibonacciEval::<FIB_SEQUENCE_LENGTH> {
+                    log_n_rows: log_n_instances,
+                },
+            );
+            let proof = prove::<SimdBackend, Blake2sMerkleChannel>(
+                &[&component],
+                prover_channel,
+                commitment_scheme,
+            )
+            .unwrap();
+
+            // Verify.
+            let verifier_channel = &mut Blake2sChannel::default();
+            let commitment_scheme =
+                &mut CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+            let sizes = component.trace_log_degree_bounds();
+            commitment_scheme.commit(proof.commitments[0].clone(), &sizes[0], verifier_channel);
             verify(&[&component], verifier_channel, commitment_scheme, proof).unwrap();
         }
     }
 
     #[test]
     #[cfg(not(target_arch = "wasm32"))]
-    fn test_wide_fib_prove_with_poseidon() {
+    fn test_wide_fib_prove_with_disk_backend() {
         const LOG_N_INSTANCES: u32 = 6;
         let config = PcsConfig::default();
-        // Precompute twiddles.
-        let twiddles = SimdBackend::precompute_twiddles(
-            CanonicCoset::new(LOG_N_INSTANCES + 1 + config.fri_config.log_blowup_factor)
-                .circle_domain()
-                .half_coset,
+        let component = WideFibonacciComponent::new(
+            &mut TraceLocationAllocator::default(),
+            WideFibonacciEval::<FIB_SEQUENCE_LENGTH> {
+                log_n_rows: LOG_N_INSTANCES,
+            },
         );
+        let trace = generate_test_trace(LOG_N_INSTANCES);
+
+        // Prove with the SIMD backend, for reference.
+        let prover_channel = &mut Blake2sChannel::default();
+        let commitment_scheme =
+            &mut CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+                config,
+                TwiddleCache::default(),
+            );
+        let mut tree_builder = commitment_scheme.tree_builder();
+        tree_builder.extend_evals(trace.clone());
+        tree_builder.commit(prover_channel);
+        let simd_proof = prove::<SimdBackend, Blake2sMerkleChannel>(
+            &[&component],
+            prover_channel,
+            commitment_scheme,
+        )
+        .unwrap();
+
+        // Prove with the disk backend, streaming the trace and recomputing its evaluations.
+        let prover_channel = &mut Blake2sChannel::default();
+        let commitment_scheme =
+            &mut CommitmentSchemeProver::<DiskBackend, Blake2sMerkleChannel>::new(
+                config,
+                TwiddleCache::default(),
+            )
+            .with_evaluation_storage(EvaluationStorage::Recompute);
+        let mut tree_builder = commitment_scheme.streaming_tree_builder();
+        tree_builder.extend_evals(trace.iter().map(Spill::spill));
+        tree_builder.commit(prover_channel);
+        let proof = prove::<DiskBackend, Blake2sMerkleChannel>(
+            &[&component],
+            prover_channel,
+            commitment_scheme,
+        )
+        .unwrap();
+        assert_eq!(proof.commitments, simd_proof.commitments);
+
+        // Verify.
+        let verifier_channel = &mut Blake2sChannel::default();
+        let commitment_scheme = &mut CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+        let sizes = component.trace_log_degree_bounds();
+        commitment_scheme.commit(proof.commitments[0].clone(), &sizes[0], verifier_channel);
+        verify(&[&component], verifier_channel, commitment_scheme, proof).unwrap();
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_wide_fib_prove_with_poseidon() {
+        const LOG_N_INSTANCES: u32 = 6;
+        let config = PcsConfig::default();
 
         // Setup protocol.
         let prover_channel = &mut Poseidon252Channel::default();
         let commitment_scheme =
             &mut CommitmentSchemeProver::<SimdBackend, Poseidon252MerkleChannel>::new(
-                config, &twiddles,
+                config,
+                TwiddleCache::default(),
             );
 
         // Trace.
@@ -264,7 +362,7 @@ mod tests {
 
         // Retrieve the expected column sizes in each commitment interaction, from the AIR.
         let sizes = component.trace_log_degree_bounds();
-        commitment_scheme.commit(proof.commitments[0], &sizes[0], verifier_channel);
+        commitment_scheme.commit(proof.commitments[0].clone(), &sizes[0], verifier_channel);
         verify(&[&component], verifier_channel, commitment_scheme, proof).unwrap();
     }
 }
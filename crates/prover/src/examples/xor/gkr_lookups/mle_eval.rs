@@ -2,6 +2,7 @@
 // TODO(andrew): Remove in downstream PR.
 #![allow(dead_code)]
 
+use std::borrow::Cow;
 use std::iter::zip;
 
 use itertools::{chain, zip_eq, Itertools};
@@ -25,19 +26,18 @@ use crate::core::circle::{CirclePoint, Coset};
 use crate::core::constraints::{coset_vanishing, point_vanishing};
 use crate::core::fields::m31::BaseField;
 use crate::core::fields::qm31::SecureField;
-use crate::core::fields::secure_column::SecureColumnByCoords;
+use crate::core::fields::secure_column::{SecureColumnByCoords, SECURE_EXTENSION_DEGREE};
 use crate::core::fields::{Field, FieldExpOps};
 use crate::core::lookups::gkr_prover::GkrOps;
 use crate::core::lookups::mle::Mle;
 use crate::core::lookups::utils::eq;
 use crate::core::pcs::{TreeSubspan, TreeVec};
 use crate::core::poly::circle::{
-    CanonicCoset, CircleEvaluation, SecureCirclePoly, SecureEvaluation,
+    CanonicCoset, CircleEvaluation, NaturalOrderTrace, SecureCirclePoly, SecureEvaluation,
 };
 use crate::core::poly::twiddles::TwiddleTree;
 use crate::core::poly::BitReversedOrder;
-use crate::core::utils::{self, bit_reverse_index, coset_index_to_circle_domain_index};
-use crate::core::ColumnVec;
+use crate::core::{utils, ColumnVec};
 
 /// Prover component that carries out a univariate IOP for multilinear eval at point.
 ///
@@ -191,10 +191,17 @@ impl<'twiddles, 'oracle, O: MleCoeffColumnOracle> ComponentProver<SimdBackend>
         let eval_domain = CanonicCoset::new(self.max_constraint_log_degree_bound()).circle_domain();
         let trace_domain = CanonicCoset::new(self.log_size());
 
-        let mut component_trace = trace.evals.sub_tree(&self.trace_locations).map_cols(|c| *c);
-
-        // Build auxiliary trace.
+        // Build auxiliary trace, and recompute the evaluations that were dropped after commitment.
         let span = span!(Level::INFO, "Extension").entered();
+        let component_evals = trace
+            .evals
+            .sub_tree(&self.trace_locations)
+            .zip_cols(trace.polys.sub_tree(&self.trace_locations))
+            .map_cols(|(eval, poly)| match eval {
+                Some(eval) => Cow::Borrowed(*eval),
+                None => Cow::Owned(poly.evaluate_with_twiddles(eval_domain, self.twiddles)),
+            });
+        let mut component_trace = component_evals.as_cols_ref().map_cols(|c| &**c);
         let mle_coeffs_column_lde = self
             .mle_coeff_column_poly
             .evaluate_with_twiddles(eval_domain, self.twiddles)
@@ -580,27 +587,27 @@ fn gen_carry_quotient_col(
         gen_half_coset_carry_quotients(&mle_eval_point);
 
     let log_size = mle_eval_point.n_variables() as u32;
-    let size = 1 << log_size;
-    let half_coset_size = size / 2;
-    let mut col = SecureColumnByCoords::<SimdBackend>::zeros(size);
+    let mut trace = NaturalOrderTrace::<SimdBackend>::new(log_size, SECURE_EXTENSION_DEGREE);
 
     // TODO(andrew): Optimize.
-    for i in 0..half_coset_size {
-        let half_coset0_index = coset_index_to_circle_domain_index(i * 2, log_size);
-        let half_coset1_index = coset_index_to_circle_domain_index(i * 2 + 1, log_size);
-        let half_coset0_index_bit_rev = bit_reverse_index(half_coset0_index, log_size);
-        let half_coset1_index_bit_rev = bit_reverse_index(half_coset1_index, log_size);
-
+    for i in 0..trace.n_rows() / 2 {
         let n_trailing_ones = i.trailing_ones() as usize;
         let half_coset0_carry_quotient = half_coset0_carry_quotients[n_trailing_ones];
         let half_coset1_carry_quotient = half_coset1_carry_quotients[n_trailing_ones];
 
-        col.set(half_coset0_index_bit_rev, half_coset0_carry_quotient);
-        col.set(half_coset1_index_bit_rev, half_coset1_carry_quotient);
+        trace.set_row(i * 2, &half_coset0_carry_quotient.to_m31_array());
+        trace.set_row(i * 2 + 1, &half_coset1_carry_quotient.to_m31_array());
     }
 
-    let domain = CanonicCoset::new(log_size).circle_domain();
-    SecureEvaluation::new(domain, col)
+    let evals = trace.into_evaluations();
+    let domain = evals[0].domain;
+    let columns = evals.into_iter().map(|eval| eval.values).collect_vec();
+    SecureEvaluation::new(
+        domain,
+        SecureColumnByCoords {
+            columns: columns.try_into().unwrap(),
+        },
+    )
 }
 
 /// Evaluates the succinct Eq carry quotients column at point `p`.
@@ -713,6 +720,7 @@ fn hadamard_product(
 mod tests {
     use std::array;
     use std::iter::{repeat, zip};
+    use std::sync::Arc;
 
     use itertools::{chain, Itertools};
     use mle_coeff_column::{MleCoeffColumnComponent, MleCoeffColumnEval};
@@ -735,13 +743,12 @@ mod tests {
     use crate::core::circle::SECURE_FIELD_CIRCLE_GEN;
     use crate::core::fields::m31::BaseField;
     use crate::core::fields::qm31::SecureField;
-    use crate::core::fields::secure_column::SecureColumnByCoords;
+    use crate::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
     use crate::core::lookups::mle::Mle;
     use crate::core::pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig, TreeVec};
-    use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, PolyOps};
+    use crate::core::poly::circle::{CanonicCoset, CircleEvaluation, NaturalOrderTrace, PolyOps};
     use crate::core::poly::BitReversedOrder;
     use crate::core::prover::{prove, verify, VerificationError};
-    use crate::core::utils::{bit_reverse, coset_order_to_circle_domain_order};
     use crate::core::vcs::blake2_merkle::Blake2sMerkleChannel;
     use crate::examples::xor::gkr_lookups::accumulation::MIN_LOG_BLOWUP_FACTOR;
     use crate::examples::xor::gkr_lookups::mle_eval::eval_step_selector_with_offset;
@@ -761,14 +768,16 @@ mod tests {
         let eval_point: [SecureField; N_VARIABLES] = array::from_fn(|_| rng.gen());
         let claim = mle.eval_at_point(&eval_point);
         // Setup protocol.
-        let twiddles = SimdBackend::precompute_twiddles(
+        let twiddles = Arc::new(SimdBackend::precompute_twiddles(
             CanonicCoset::new(log_size + LOG_EXPAND + MIN_LOG_BLOWUP_FACTOR)
                 .circle_domain()
                 .half_coset,
-        );
+        ));
         let config = PcsConfig::default();
-        let commitment_scheme =
-            &mut CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(config, &twiddles);
+        let commitment_scheme = &mut CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(
+            config,
+            twiddles.clone().into(),
+        );
         let channel = &mut Blake2sChannel::default();
         // Build trace.
         // 1. MLE coeffs trace.
@@ -804,8 +813,8 @@ mod tests {
         let log_sizes = components.column_log_sizes();
         let channel = &mut Blake2sChannel::default();
         let commitment_scheme = &mut CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
-        commitment_scheme.commit(proof.commitments[0], &log_sizes[0], channel);
-        commitment_scheme.commit(proof.commitments[1], &log_sizes[1], channel);
+        commitment_scheme.commit(proof.commitments[0].clone(), &log_sizes[0], channel);
+        commitment_scheme.commit(proof.commitments[1].clone(), &log_sizes[1], channel);
         verify(&components.0, channel, commitment_scheme, proof)
     }
 
@@ -825,14 +834,16 @@ mod tests {
         let eval_point: [SecureField; N_VARIABLES] = array::from_fn(|_| rng.gen());
         let claim = mle.eval_at_point(&eval_point);
         // Setup protocol.
-        let twiddles = SimdBackend::precompute_twiddles(
+        let twiddles = Arc::new(SimdBackend::precompute_twiddles(
             CanonicCoset::new(log_size + LOG_EXPAND + MIN_LOG_BLOWUP_FACTOR)
                 .circle_domain()
                 .half_coset,
-        );
+        ));
         let config = PcsConfig::default();
-        let commitment_scheme =
-            &mut CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(config, &twiddles);
+        let commitment_scheme = &mut CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(
+            config,
+            twiddles.clone().into(),
+        );
         let channel = &mut Blake2sChannel::default();
         // Build trace.
         // 1. MLE coeffs trace.
@@ -880,8 +891,8 @@ mod tests {
         let log_sizes = components.column_log_sizes();
         let channel = &mut Blake2sChannel::default();
         let commitment_scheme = &mut CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
-        commitment_scheme.commit(proof.commitments[0], &log_sizes[0], channel);
-        commitment_scheme.commit(proof.commitments[1], &log_sizes[1], channel);
+        commitment_scheme.commit(proof.commitments[0].clone(), &log_sizes[0], channel);
+        commitment_scheme.commit(proof.commitments[1].clone(), &log_sizes[1], channel);
         verify(&components.0, channel, commitment_scheme, proof)
     }
 
@@ -1083,12 +1094,18 @@ mod tests {
     ) -> Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         assert!(values.len().is_power_of_two());
 
-        let vals_circle_domain_order = coset_order_to_circle_domain_order(&values);
-        let mut vals_bit_rev_circle_domain_order = vals_circle_domain_order;
-        bit_reverse(&mut vals_bit_rev_circle_domain_order);
-        let vals_secure_col: SecureColumnByCoords<SimdBackend> =
-            vals_bit_rev_circle_domain_order.into_iter().collect();
-        let vals_cols = vals_secure_col.columns;
+        let log_size = values.len().ilog2();
+        let mut trace = NaturalOrderTrace::<SimdBackend>::new(log_size, SECURE_EXTENSION_DEGREE);
+        for (row, value) in values.iter().enumerate() {
+            trace.set_row(row, &value.to_m31_array());
+        }
+        let vals_cols: [_; SECURE_EXTENSION_DEGREE] = trace
+            .into_evaluations()
+            .into_iter()
+            .map(|eval| eval.values)
+            .collect_vec()
+            .try_into()
+            .unwrap();
 
         let cumulative_sum = values.iter().sum::<SecureField>();
         let cumulative_sum_shift = cumulative_sum / BaseField::from(values.len());
@@ -1099,7 +1116,6 @@ mod tests {
             .for_each(|(col, packed_shift)| col.data.iter_mut().for_each(|v| *v -= packed_shift));
         let shifted_prefix_sum_cols = shifted_cols.map(inclusive_prefix_sum);
 
-        let log_size = values.len().ilog2();
         let trace_domain = CanonicCoset::new(log_size).circle_domain();
 
         chain![vals_cols, shifted_prefix_sum_cols]
@@ -1196,7 +1212,7 @@ mod tests {
             let log_size = mle.n_variables() as u32;
             let trace_domain = CanonicCoset::new(log_size).circle_domain();
             let mle_coeffs_col_by_coords = mle.clone().into_evals().into_secure_column_by_coords();
-            SecureEvaluation::new(trace_domain, mle_coeffs_col_by_coords)
+            SecureEvaluation::<_, BitReversedOrder>::new(trace_domain, mle_coeffs_col_by_coords)
                 .into_coordinate_evals()
                 .into_iter()
                 .collect()